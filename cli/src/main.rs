@@ -1,7 +1,24 @@
 extern crate fitch_proof;
 
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
 const DEFAULT_ALLOWED_VARIABLE_NAMES: &str = "x,y,z,u,v,w";
 
+/// How much is printed about a checked proof, see [report].
+#[derive(PartialEq, Clone, Copy)]
+enum Verbosity {
+    /// Only the verdict (`correct`, `incorrect`, `unfinished`, `no_proof`, `fatal` or
+    /// `internal_error`, see [fitch_proof::check_proof_with_verdict]), for scripts. Set with `-q`.
+    Quiet,
+    /// The result of the check, with the error messages.
+    Normal,
+    /// The result of the check, the warnings about the proof, an outline of the proof and how
+    /// long checking took. Set with `-v`.
+    Verbose,
+}
+
 /// Print an error message that file could not be found and exit.
 fn fail_open_file(filename: &str) -> ! {
     println!(
@@ -48,24 +65,369 @@ fn find_txt_file() -> String {
     }
 }
 
+/// Reads a proof template: one sentence per line.
+fn read_template(text: &str) -> Vec<String> {
+    text.lines().map(|s| s.trim().to_string()).collect()
+}
+
+/// Turns a string into one CSV field, with double quotes around it if that is needed.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Prints the result of checking a proof, with as much detail as the [Verbosity] says. The verdict
+/// is the one that comes with the result, see [fitch_proof::check_proof_with_verdict].
+fn report(proof: &str, (result, verdict): (&str, &str), elapsed: Duration, verbosity: Verbosity) {
+    match verbosity {
+        Verbosity::Quiet => println!("{verdict}"),
+        Verbosity::Normal => println!("{result}"),
+        Verbosity::Verbose => {
+            println!("{result}");
+            let warnings = fitch_proof::lint_proof(proof);
+            if !warnings.is_empty() {
+                println!("\nWarnings:\n\n{warnings}");
+            }
+            println!("\n{}", fitch_proof::summarize_proof(proof));
+            println!("\nChecked in {:.1} ms.", elapsed.as_secs_f64() * 1000.0);
+        }
+    }
+}
+
+/// Batch mode: checks many proofs against many proof templates in one go, and prints a gradebook
+/// (in CSV format) with one row per proof.
+///
+/// The manifest is a text file in which every line has the form
+///
+/// `<proof file> <template file> [<options>]`
+///
+/// where the options are given like in [fitch_proof::check_proof_with_options] (without spaces),
+/// for example `symbols=ascii,derived_rules=on`. Empty lines and lines starting with `#` are
+/// ignored. The paths of the files are relative to the directory of the manifest. Each template
+/// file is only read once, even if many proofs use it.
+///
+/// If a `cache_file` is given, then the results are looked up in (and afterwards saved to) that
+/// file, so that proofs that have been checked before are not checked again (see
+/// [fitch_proof::CheckCache]). The file is created if it does not exist yet.
+///
+/// With `golf`, the gradebook is the ranking of a proof-golf competition instead: every row has
+/// the length and nesting depth of the proof, its golf score (see [fitch_proof::golf_proof]) and
+/// its rank among the correct proofs for the same template. The cache does not keep golf scores,
+/// so it cannot be used together with `golf`.
+fn batch(manifest_file: &str, cache_file: Option<&str>, golf: bool) {
+    if golf && cache_file.is_some() {
+        println!("A cache cannot be used in golf mode. Aborting.");
+        std::process::exit(1);
+    }
+    let Ok(manifest) = std::fs::read_to_string(manifest_file) else {
+        fail_open_file(manifest_file)
+    };
+    let mut cache = match cache_file.map(std::fs::read_to_string) {
+        Some(Ok(text)) => fitch_proof::CheckCache::from_text(&text).unwrap_or_else(|err| {
+            println!("The cache file could not be read: {err}. Aborting.");
+            std::process::exit(1)
+        }),
+        Some(Err(err)) if err.kind() != std::io::ErrorKind::NotFound => {
+            println!("The cache file could not be read: {err}. Aborting.");
+            std::process::exit(1)
+        }
+        Some(Err(_)) | None => fitch_proof::CheckCache::new(),
+    };
+    let base_dir = Path::new(manifest_file).parent().unwrap_or(Path::new(""));
+    let mut templates: HashMap<String, Vec<String>> = HashMap::new();
+
+    // in golf mode, the rows are only printed at the end, when the ranks are known
+    let mut golf_rows: Vec<(&str, &str, &str, Option<fitch_proof::GolfScore>)> = vec![];
+    if !golf {
+        println!("submission,template,status,message");
+    }
+    for (i, line) in manifest.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (proof_file, template_file, options) = match fields[..] {
+            [proof_file, template_file] => (proof_file, template_file, ""),
+            [proof_file, template_file, options] => (proof_file, template_file, options),
+            _ => {
+                println!(
+                    "Line {} of the manifest should be of the form \
+                    '<proof file> <template file> [<options>]'. Aborting.",
+                    i + 1
+                );
+                std::process::exit(1);
+            }
+        };
+        if !templates.contains_key(template_file) {
+            let path = base_dir.join(template_file).display().to_string();
+            let Ok(text) = std::fs::read_to_string(&path) else {
+                fail_open_file(&path)
+            };
+            templates.insert(template_file.to_string(), read_template(&text));
+        }
+        let template = &templates[template_file];
+
+        let path = base_dir.join(proof_file).display().to_string();
+        if golf {
+            let (status, score) = match std::fs::read_to_string(&path) {
+                Err(_) => ("missing", None),
+                Ok(proof) => {
+                    let (_, verdict, score) = fitch_proof::golf_proof(
+                        &proof,
+                        template,
+                        DEFAULT_ALLOWED_VARIABLE_NAMES,
+                        options,
+                    );
+                    (verdict, score)
+                }
+            };
+            golf_rows.push((proof_file, template_file, status, score));
+            continue;
+        }
+        let (status, message) = match std::fs::read_to_string(&path) {
+            Err(_) => ("missing", "The proof file could not be opened.".to_string()),
+            Ok(proof) => {
+                let (result, verdict) = cache.check_proof_with_verdict(
+                    &proof,
+                    template,
+                    DEFAULT_ALLOWED_VARIABLE_NAMES,
+                    options,
+                );
+                (verdict, result)
+            }
+        };
+        println!(
+            "{},{},{},{}",
+            csv_field(proof_file),
+            csv_field(template_file),
+            status,
+            csv_field(&message)
+        );
+    }
+
+    if golf {
+        print_golf_ranking(&golf_rows);
+    }
+
+    if let Some(cache_file) = cache_file {
+        if std::fs::write(cache_file, cache.to_text()).is_err() {
+            println!("The cache could not be saved to {cache_file}.");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Prints the ranking of a proof-golf competition (see [batch]) in CSV format: for every proof, its
+/// file, its template file, its status, and, if it has a golf score, its length, nesting depth,
+/// golf score and rank among the proofs for the same template (see
+/// [fitch_proof::rank_golf_scores]).
+fn print_golf_ranking(rows: &[(&str, &str, &str, Option<fitch_proof::GolfScore>)]) {
+    // the rows with a golf score, grouped by template, and their ranks within the group
+    let mut competitions: HashMap<&str, (Vec<usize>, Vec<fitch_proof::GolfScore>)> = HashMap::new();
+    for (i, (_, template_file, _, score)) in rows.iter().enumerate() {
+        if let Some(score) = score {
+            let (indices, scores) = competitions.entry(template_file).or_default();
+            indices.push(i);
+            scores.push(score.clone());
+        }
+    }
+    let mut ranks: Vec<usize> = vec![0; rows.len()];
+    for (indices, scores) in competitions.values() {
+        for (i, rank) in indices.iter().zip(fitch_proof::rank_golf_scores(scores)) {
+            ranks[*i] = rank;
+        }
+    }
+
+    println!("submission,template,status,lines,nesting_depth,score,rank");
+    for ((proof_file, template_file, status, score), rank) in rows.iter().zip(ranks) {
+        let columns = match score {
+            Some(score) => format!(
+                "{},{},{:.1},{rank}",
+                score.lines,
+                score.nesting_depth,
+                score.score()
+            ),
+            None => ",,,".to_string(),
+        };
+        println!(
+            "{},{},{status},{columns}",
+            csv_field(proof_file),
+            csv_field(template_file)
+        );
+    }
+}
+
+/// Checks that formatting the proofs in the given files does not change their meaning (see
+/// [fitch_proof::roundtrip_check]), and prints the result for every file. Exits with exit code 1
+/// if there is a problem with one of the files, so that this can be used in CI.
+fn roundtrip_check(files: &[String]) {
+    let mut all_ok = true;
+    for file in files {
+        let Ok(proof) = std::fs::read_to_string(file) else {
+            fail_open_file(file)
+        };
+        match fitch_proof::roundtrip_check(&proof) {
+            Ok(()) => println!("{file}: ok"),
+            Err(err) => {
+                println!("{file}: {err}");
+                all_ok = false;
+            }
+        }
+    }
+    if !all_ok {
+        std::process::exit(1);
+    }
+}
+
+/// Checks that the exercise bundle in the given file is consistent (see
+/// [fitch_proof::ExerciseBundle::validate]), and prints the problems with it. Exits with exit
+/// code 1 if there are problems, so that this can be used in CI.
+fn validate_bundle(bundle_file: &str) {
+    let Ok(bundle) = std::fs::read_to_string(bundle_file) else {
+        fail_open_file(bundle_file)
+    };
+    let problems = fitch_proof::validate_bundle(&bundle);
+    if problems.is_empty() {
+        println!("{bundle_file}: ok");
+    } else {
+        println!("{problems}");
+        std::process::exit(1);
+    }
+}
+
+/// Hides the solutions in the exercise bundle in the given file with a key (see
+/// [fitch_proof::hide_solutions]), and prints the new bundle, which can be given to students.
+fn hide_solutions(bundle_file: &str, key: &str) {
+    let Ok(bundle) = std::fs::read_to_string(bundle_file) else {
+        fail_open_file(bundle_file)
+    };
+    match fitch_proof::hide_solutions(&bundle, key) {
+        Ok(bundle) => print!("{bundle}"),
+        Err(err) => {
+            println!("The exercise bundle could not be read: {err}. Aborting.");
+            std::process::exit(1);
+        }
+    }
+}
+
 /// The *proof* itself (what the student wrote) should be in some .txt file in the same directory
 /// as the executable. The executable will detect this .txt file itself.
 ///
 /// The *proof template* should be given via `stdin`.
 ///
+/// Alternatively, with `--batch <manifest> [--cache <cache file>]`, many proofs are checked at
+/// once, see [batch] (with `--golf` instead of `--cache`, the proofs are ranked like in a
+/// proof-golf competition). With `--roundtrip-check <file>...`, it is checked that formatting the
+/// proofs in the files does not change them, see [roundtrip_check]. With
+/// `--validate-bundle <file>`, an exercise bundle is validated, see [validate_bundle], and with
+/// `--hide-solutions <file> <key>`, the solutions in it are hidden, see [hide_solutions].
+///
+/// With `--bundle <bundle file> <exercise id>`, the template, allowed variable names and options
+/// are taken from that exercise in the exercise bundle (see [fitch_proof::ExerciseBundle]),
+/// instead of reading the template from `stdin`.
+///
+/// With `-q`, only the verdict is printed (`correct`, `incorrect`, `unfinished`, `no_proof`,
+/// `fatal` or `internal_error`), and with `-v`, also the warnings about the proof, an outline of
+/// it and how long checking took, see [Verbosity].
+///
+/// With `--format=<renderer>`, the result of checking the proof against the template from `stdin`
+/// is printed in another format, like `json` or `sarif` (see
+/// [fitch_proof::check_proof_with_renderer]), and `-q` and `-v` are ignored.
+///
+/// With `--profile`, the result of checking the proof against the template from `stdin` is
+/// followed by how long each phase of the check, each rule and the slowest lines took (see
+/// [fitch_proof::CheckProfile]).
+///
 /// Currently, there is NO SUPPORT for a custom set of allowed variable names over the command
-/// line (it is only in the web GUI).
+/// line (it is only in the web GUI, and in exercise bundles).
 fn main() {
+    let mut args: Vec<String> = std::env::args().collect();
+    let mut verbosity = Verbosity::Normal;
+    let mut format: Option<String> = None;
+    let mut profile = false;
+    let mut golf = false;
+    args.retain(|arg| match arg.as_str() {
+        "-q" | "--quiet" => {
+            verbosity = Verbosity::Quiet;
+            false
+        }
+        "-v" | "--verbose" => {
+            verbosity = Verbosity::Verbose;
+            false
+        }
+        "--profile" => {
+            profile = true;
+            false
+        }
+        "--golf" => {
+            golf = true;
+            false
+        }
+        _ => match arg.strip_prefix("--format=") {
+            Some(name) => {
+                format = Some(name.to_string());
+                false
+            }
+            None => true,
+        },
+    });
+    match &args[..] {
+        [_, flag, manifest_file] if flag == "--batch" => return batch(manifest_file, None, golf),
+        [_, flag, manifest_file, cache_flag, cache_file]
+            if flag == "--batch" && cache_flag == "--cache" =>
+        {
+            return batch(manifest_file, Some(cache_file), golf)
+        }
+        [_, flag, files @ ..] if flag == "--roundtrip-check" => return roundtrip_check(files),
+        [_, flag, bundle_file] if flag == "--validate-bundle" => {
+            return validate_bundle(bundle_file)
+        }
+        [_, flag, bundle_file, key] if flag == "--hide-solutions" => {
+            return hide_solutions(bundle_file, key)
+        }
+        _ => {}
+    }
+
     let proof_file = find_txt_file();
     let Ok(proof) = std::fs::read_to_string(&proof_file) else {
         fail_open_file(&proof_file)
     };
-    let template: Vec<String> = std::io::stdin()
-        .lines()
-        .map(|s| s.unwrap().trim().to_string())
-        .collect();
+    if let [_, flag, bundle_file, exercise_id] = &args[..] {
+        if flag == "--bundle" {
+            let Ok(bundle) = std::fs::read_to_string(bundle_file) else {
+                fail_open_file(bundle_file)
+            };
+            let bundle = fitch_proof::ExerciseBundle::from_json(&bundle).unwrap_or_else(|err| {
+                println!("The exercise bundle could not be read: {err}. Aborting.");
+                std::process::exit(1)
+            });
+            let start = Instant::now();
+            let (result, verdict) = bundle.check_proof_with_verdict(exercise_id, &proof);
+            return report(&proof, (&result, verdict), start.elapsed(), verbosity);
+        }
+    }
+    let template: Vec<String> = read_template(&std::io::read_to_string(std::io::stdin()).unwrap());
     let variables = DEFAULT_ALLOWED_VARIABLE_NAMES.to_string();
 
-    let result: String = fitch_proof::check_proof_with_template(&proof, template, &variables);
-    println!("{}", result);
+    if let Some(format) = format {
+        let result = fitch_proof::check_proof_with_template_and_renderer(
+            &proof, template, &variables, "", &format,
+        );
+        return println!("{result}");
+    }
+    if profile {
+        let (result, verdict, profile) =
+            fitch_proof::profile_proof(&proof, &template, &variables, "");
+        let elapsed = Duration::from_secs_f64(profile.total() / 1000.0);
+        report(&proof, (&result, verdict), elapsed, verbosity);
+        return println!("\n{}", profile.to_text());
+    }
+    let start = Instant::now();
+    let (result, verdict) =
+        fitch_proof::check_proof_with_verdict(&proof, Some(&template), &variables, "");
+    report(&proof, (&result, verdict), start.elapsed(), verbosity);
 }
@@ -0,0 +1,552 @@
+use crate::parser;
+use crate::util::{self, JsonValue};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::ChaCha20Poly1305;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::{BTreeMap, HashSet};
+
+/// An exercise bundle: a single `.fitchpack` file with everything that is needed to check the
+/// proofs for a set of exercises, so that the statement, template, options and solutions of an
+/// exercise cannot drift out of sync. A bundle is a JSON document like this:
+///
+/// ```text
+/// {
+///   "title": "Week 3",
+///   "exercises": [
+///     {
+///       "id": "3.1",
+///       "statement": "Show that A follows from A ∧ B.",
+///       "template": ["A ∧ B", "# minimal proof length: 2", "A"],
+///       "variables": "x,y,z",
+///       "options": "derived_rules=on",
+///       "solutions": ["1 | A ∧ B\n  |----\n2 | A  ∧Elim: 1"]
+///     },
+///     {
+///       "id": "3.2",
+///       "template": ["A", "A ∨ B"],
+///       "hidden_solutions": ["6c0f3e..."],
+///       "unlock_after": "2026-11-01T23:59:00Z"
+///     }
+///   ]
+/// }
+/// ```
+///
+/// Only `id` and `template` are required for an exercise. The template is written like a
+/// template file (see [crate::parser::parse_template]), one line per string. The allowed
+/// variable names default to `x,y,z,u,v,w` and the options (see
+/// [crate::check_proof_with_options]) default to no options.
+///
+/// The solutions can be hidden with a key (see [ExerciseBundle::hide_solutions]), so that the same
+/// bundle can be given to students and to graders. Hidden solutions are encrypted, and can only be
+/// revealed with the same key (see [Exercise::reveal_solutions]), so the key should be long and
+/// random. The `unlock_after` time (in UTC) is not protected by the encryption: it is checked
+/// against the time that the caller gives, so anyone who has the key can reveal the solutions at
+/// any time. Only give out the key when the solutions may be revealed.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct ExerciseBundle {
+    pub title: String,
+    pub exercises: Vec<Exercise>,
+}
+
+/// One exercise in an [ExerciseBundle].
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Exercise {
+    /// The name by which the exercise is found in the bundle, see [ExerciseBundle::exercise].
+    pub id: String,
+    /// The text of the exercise, as it is shown to students.
+    pub statement: String,
+    /// The premises and the conclusion, possibly with comments.
+    pub template: Vec<String>,
+    pub allowed_variable_names: String,
+    pub options: String,
+    /// Correct proofs, which are never shown to students, but are checked by
+    /// [ExerciseBundle::validate].
+    pub solutions: Vec<String>,
+    /// Solutions that are encrypted with a key, as hexadecimal strings, see
+    /// [ExerciseBundle::hide_solutions].
+    pub hidden_solutions: Vec<String>,
+    /// The time after which the hidden solutions may be revealed, like `2026-11-01T23:59:00Z`
+    /// (see [Exercise::reveal_solutions] for what this does and does not protect).
+    pub unlock_after: Option<String>,
+}
+
+impl ExerciseBundle {
+    /// Reads a bundle from its JSON text. If the text is not valid JSON, or does not have the
+    /// form of a bundle (e.g. because a field is missing, has the wrong type, or is unknown), an
+    /// error message is returned.
+    ///
+    /// This does not check whether the contents make sense, see [ExerciseBundle::validate].
+    pub fn from_json(text: &str) -> Result<ExerciseBundle, String> {
+        let json = util::parse_json(text)?;
+        let mut fields = object_fields(json, "the bundle", &["title", "exercises"])?;
+        let exercises = match fields.remove("exercises") {
+            Some(JsonValue::Array(exercises)) => exercises,
+            Some(_) => return Err("the exercises of the bundle should be an array".to_string()),
+            None => return Err("the bundle does not have any exercises".to_string()),
+        };
+        Ok(ExerciseBundle {
+            title: string_field(&mut fields, "title", "the bundle")?.unwrap_or_default(),
+            exercises: exercises
+                .into_iter()
+                .enumerate()
+                .map(|(i, exercise)| Exercise::from_json(exercise, i + 1))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Returns the exercise with the given id, if there is one.
+    pub fn exercise(&self, id: &str) -> Option<&Exercise> {
+        self.exercises.iter().find(|exercise| exercise.id == id)
+    }
+
+    /// Checks that the bundle is consistent: the ids of the exercises are unique, the templates,
+    /// variable names and options can be parsed, every solution is a correct proof for its
+    /// exercise, and no solution is shorter than the minimal proof length in the template.
+    ///
+    /// All problems that are found are returned; if there are none, the bundle is fine.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = vec![];
+        let mut ids: HashSet<&str> = HashSet::new();
+        for exercise in &self.exercises {
+            if !ids.insert(&exercise.id) {
+                problems.push(format!("there is more than one exercise '{}'", exercise.id));
+            }
+            problems.extend(exercise.validate());
+        }
+        problems
+    }
+
+    /// Checks a proof for the exercise with the given id, and returns the same message as
+    /// [crate::check_proof_with_template_and_options] (with the template, variable names and
+    /// options of the exercise).
+    pub fn check_proof(&self, exercise_id: &str, proof: &str) -> String {
+        self.check_proof_with_verdict(exercise_id, proof).0
+    }
+
+    /// Same as [ExerciseBundle::check_proof], but the verdict is returned as well, see
+    /// [crate::check_proof_with_verdict].
+    pub fn check_proof_with_verdict(
+        &self,
+        exercise_id: &str,
+        proof: &str,
+    ) -> (String, &'static str) {
+        match self.exercise(exercise_id) {
+            Some(exercise) => exercise.check_proof_with_verdict(proof),
+            None => {
+                let err = format!("there is no exercise '{exercise_id}' in the bundle");
+                (format!("Fatal error: {err}"), "fatal")
+            }
+        }
+    }
+
+    /// Hides the solutions of all exercises with a key: they are removed from
+    /// [Exercise::solutions] and added (encrypted with the key) to [Exercise::hidden_solutions].
+    ///
+    /// The solutions are encrypted with ChaCha20-Poly1305, with a key that is derived from `key`
+    /// and the id of the exercise with PBKDF2-HMAC-SHA256. Since an exercise's hidden solutions
+    /// are tied to its id, the id should not be changed afterwards.
+    pub fn hide_solutions(&mut self, key: &str) {
+        for exercise in self.exercises.iter_mut().filter(|e| !e.solutions.is_empty()) {
+            let key = solution_key(key, &exercise.id);
+            let cipher = ChaCha20Poly1305::new(&key.into());
+            let nonce_key = hmac_sha256(&key, b"nonce");
+            for solution in exercise.solutions.drain(..) {
+                // the nonce depends on the solution, so that it is never used for another solution
+                let mut nonce = [0; 12];
+                nonce.copy_from_slice(&hmac_sha256(&nonce_key, solution.as_bytes())[..12]);
+                let payload = Payload {
+                    msg: solution.as_bytes(),
+                    aad: exercise.id.as_bytes(),
+                };
+                let sealed = cipher
+                    .encrypt(&nonce.into(), payload)
+                    .expect("only messages of more than 256 GiB cannot be encrypted");
+                let mut data = nonce.to_vec();
+                data.extend(sealed);
+                exercise.hidden_solutions.push(data.iter().map(|b| format!("{b:02x}")).collect());
+            }
+        }
+    }
+
+    /// Returns the bundle as JSON text, which can be read again with [ExerciseBundle::from_json].
+    /// Fields that have their default value are left out.
+    pub fn to_json(&self) -> String {
+        let exercises: Vec<String> = self.exercises.iter().map(Exercise::to_json).collect();
+        format!(
+            "{{\n  \"title\": {},\n  \"exercises\": [\n{}\n  ]\n}}\n",
+            util::json_string(&self.title),
+            exercises.join(",\n")
+        )
+    }
+}
+
+impl Exercise {
+    /// Checks a proof for this exercise, see [ExerciseBundle::check_proof].
+    pub fn check_proof(&self, proof: &str) -> String {
+        self.check_proof_with_verdict(proof).0
+    }
+
+    /// Same as [Exercise::check_proof], but the verdict is returned as well, see
+    /// [crate::check_proof_with_verdict].
+    pub fn check_proof_with_verdict(&self, proof: &str) -> (String, &'static str) {
+        crate::check_proof_with_verdict(
+            proof,
+            Some(&self.template),
+            &self.allowed_variable_names,
+            &self.options,
+        )
+    }
+
+    /// Returns the hidden solutions of this exercise (see [ExerciseBundle::hide_solutions]),
+    /// revealed with the key. `now` is the current time, in seconds since the Unix epoch.
+    ///
+    /// An error message is returned if it is not after the `unlock_after` time yet, or if the key
+    /// is not correct. Note that the time lock is only checked here, against the `now` that the
+    /// caller gives, e.g. the clock of the student's computer. It does not protect the solutions
+    /// from anyone who has the key; only the key does.
+    pub fn reveal_solutions(&self, key: &str, now: u64) -> Result<Vec<String>, String> {
+        if let Some(unlock_after) = &self.unlock_after {
+            match unix_time(unlock_after) {
+                Some(time) if now > time => {}
+                Some(_) => {
+                    return Err(format!(
+                        "the solutions of exercise '{}' cannot be revealed before {unlock_after}",
+                        self.id
+                    ))
+                }
+                None => return Err(format!("'{unlock_after}' is not a valid time")),
+            }
+        }
+        if self.hidden_solutions.is_empty() {
+            return Ok(vec![]);
+        }
+        let wrong_key = || "the key is not correct".to_string();
+        let cipher = ChaCha20Poly1305::new(&solution_key(key, &self.id).into());
+        let mut solutions = vec![];
+        for hidden in &self.hidden_solutions {
+            let data = hex_to_bytes(hidden).ok_or_else(wrong_key)?;
+            let (Some(nonce), Some(sealed)) = (data.first_chunk::<12>(), data.get(12..)) else {
+                return Err(wrong_key());
+            };
+            let payload = Payload {
+                msg: sealed,
+                aad: self.id.as_bytes(),
+            };
+            let solution = cipher.decrypt(nonce.into(), payload).map_err(|_| wrong_key())?;
+            solutions.push(String::from_utf8(solution).map_err(|_| wrong_key())?);
+        }
+        Ok(solutions)
+    }
+
+    /// Reads the `n`th exercise of a bundle, see [ExerciseBundle::from_json].
+    fn from_json(json: JsonValue, n: usize) -> Result<Exercise, String> {
+        let what = format!("exercise {n} of the bundle");
+        let mut fields = object_fields(
+            json,
+            &what,
+            &[
+                "id",
+                "statement",
+                "template",
+                "variables",
+                "options",
+                "solutions",
+                "hidden_solutions",
+                "unlock_after",
+            ],
+        )?;
+        let Some(id) = string_field(&mut fields, "id", &what)? else {
+            return Err(format!("{what} does not have an id"));
+        };
+        let what = format!("exercise '{id}'");
+        let Some(template) = string_array_field(&mut fields, "template", &what)? else {
+            return Err(format!("{what} does not have a template"));
+        };
+        let hidden_solutions =
+            string_array_field(&mut fields, "hidden_solutions", &what)?.unwrap_or_default();
+        if hidden_solutions.iter().any(|hidden| hex_to_bytes(hidden).is_none()) {
+            return Err(format!("the hidden solutions of {what} should be hexadecimal strings"));
+        }
+        let unlock_after = string_field(&mut fields, "unlock_after", &what)?;
+        if let Some(time) = unlock_after.as_ref().filter(|time| unix_time(time).is_none()) {
+            return Err(format!(
+                "the unlock_after of {what} should be a time like 2026-11-01T23:59:00Z, not '{time}'"
+            ));
+        }
+        Ok(Exercise {
+            id,
+            statement: string_field(&mut fields, "statement", &what)?.unwrap_or_default(),
+            template,
+            allowed_variable_names: string_field(&mut fields, "variables", &what)?
+                .unwrap_or(default_variable_names!().to_string()),
+            options: string_field(&mut fields, "options", &what)?.unwrap_or_default(),
+            solutions: string_array_field(&mut fields, "solutions", &what)?.unwrap_or_default(),
+            hidden_solutions,
+            unlock_after,
+        })
+    }
+
+    /// Returns the exercise as a JSON object, see [ExerciseBundle::to_json].
+    fn to_json(&self) -> String {
+        let strings = |strings: &[String]| {
+            strings.iter().map(|s| util::json_string(s)).collect::<Vec<String>>().join(", ")
+        };
+        let mut fields = vec![format!("\"id\": {}", util::json_string(&self.id))];
+        if !self.statement.is_empty() {
+            fields.push(format!("\"statement\": {}", util::json_string(&self.statement)));
+        }
+        fields.push(format!("\"template\": [{}]", strings(&self.template)));
+        if self.allowed_variable_names != default_variable_names!() {
+            let variables = util::json_string(&self.allowed_variable_names);
+            fields.push(format!("\"variables\": {variables}"));
+        }
+        if !self.options.is_empty() {
+            fields.push(format!("\"options\": {}", util::json_string(&self.options)));
+        }
+        if !self.solutions.is_empty() {
+            fields.push(format!("\"solutions\": [{}]", strings(&self.solutions)));
+        }
+        if !self.hidden_solutions.is_empty() {
+            fields.push(format!("\"hidden_solutions\": [{}]", strings(&self.hidden_solutions)));
+        }
+        if let Some(unlock_after) = &self.unlock_after {
+            fields.push(format!("\"unlock_after\": {}", util::json_string(unlock_after)));
+        }
+        format!("    {{\n      {}\n    }}", fields.join(",\n      "))
+    }
+
+    /// Returns the problems with this exercise, see [ExerciseBundle::validate].
+    fn validate(&self) -> Vec<String> {
+        let id = &self.id;
+        let mut problems = vec![];
+        if let Err(err) = parser::parse_allowed_variable_names(&self.allowed_variable_names) {
+            problems.push(format!("exercise '{id}': {err}"));
+        }
+        if let Err(err) = parser::parse_check_options(&self.options) {
+            problems.push(format!("exercise '{id}': {err}"));
+        }
+        let minimal_length = match parser::parse_template(&self.template) {
+            Ok(template) if template.sentences.is_empty() => {
+                problems.push(format!("exercise '{id}': the template does not have a conclusion"));
+                None
+            }
+            Ok(template) => template.minimal_length,
+            Err(err) => {
+                problems.push(format!("exercise '{id}': {err}"));
+                None
+            }
+        };
+        if !problems.is_empty() {
+            // the solutions cannot be checked
+            return problems;
+        }
+        for (i, solution) in self.solutions.iter().enumerate() {
+            let res = self.check_proof(solution);
+            if res != "The proof is correct!" {
+                problems
+                    .push(format!("exercise '{id}': solution {} is not correct:\n{res}", i + 1));
+                continue;
+            }
+            // unwrap works, since the solution is correct
+            let lines = parser::parse_fitch_proof(solution).unwrap();
+            let length = lines.iter().filter(|line| line.line_num.is_some()).count();
+            match minimal_length {
+                Some(min) if length < min => problems.push(format!(
+                    "exercise '{id}': solution {} has {length} lines, but the template says that \
+                    the shortest proof has {min} lines",
+                    i + 1
+                )),
+                _ => {}
+            }
+        }
+        problems
+    }
+}
+
+/* ------------------ PRIVATE -------------------- */
+
+/// The number of iterations of PBKDF2 with which the keys of hidden solutions are derived, which
+/// makes it slow to try many guesses of a key.
+const KEY_DERIVATION_ITERATIONS: u32 = 100_000;
+
+/// Derives the key with which the hidden solutions of an exercise are encrypted from the key that
+/// the solutions were hidden with, see [ExerciseBundle::hide_solutions].
+fn solution_key(key: &str, exercise_id: &str) -> [u8; 32] {
+    let salt = format!("fitch-proof hidden solutions\0{exercise_id}");
+    pbkdf2::pbkdf2_hmac_array::<Sha256, 32>(
+        key.as_bytes(),
+        salt.as_bytes(),
+        KEY_DERIVATION_ITERATIONS,
+    )
+}
+
+/// The HMAC-SHA256 (RFC 2104) of a message with a key.
+fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut mac =
+        <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
+/// Turns a string of hexadecimal digits (two per byte) into bytes. Returns [None] if that is not
+/// possible.
+fn hex_to_bytes(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok()).collect()
+}
+
+/// Turns a UTC time like `2026-11-01T23:59:00Z` into the number of seconds since the Unix epoch.
+/// Returns [None] if the time is not of this form, if the date does not exist, or if the year is
+/// not between 1970 and 9999.
+fn unix_time(time: &str) -> Option<u64> {
+    let (date, time) = time.strip_suffix('Z')?.split_once('T')?;
+    let numbers = |s: &str, sep: char| -> Option<Vec<u64>> {
+        s.split(sep).map(|n| n.parse::<u64>().ok().filter(|_| !n.starts_with('+'))).collect()
+    };
+    let (&[year, month, day], &[hour, minute, second]) =
+        (&numbers(date, '-')?[..], &numbers(time, ':')?[..])
+    else {
+        return None;
+    };
+    let is_leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        2 if is_leap_year => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+    if !(1970..=9999).contains(&year) || !(1..=12).contains(&month) {
+        return None;
+    }
+    if !(1..=days_in_month).contains(&day) {
+        return None;
+    }
+    if hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    // the number of days since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html
+    let (y, m) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let day_of_year = (153 * m + 2) / 5 + day - 1;
+    let day_of_era = (y % 400) * 365 + (y % 400) / 4 - (y % 400) / 100 + day_of_year;
+    let days = (y / 400) * 146097 + day_of_era - 719468;
+    Some(days * 86400 + hour * 3600 + minute * 60 + second)
+}
+
+/// Returns the fields of a JSON object, or an error message if `json` is not an object, or has a
+/// field that is not in `known_fields`. `what` describes the object in the error messages.
+fn object_fields(
+    json: JsonValue,
+    what: &str,
+    known_fields: &[&str],
+) -> Result<BTreeMap<String, JsonValue>, String> {
+    let JsonValue::Object(fields) = json else {
+        return Err(format!("{what} should be a JSON object"));
+    };
+    if let Some(field) = fields.keys().find(|field| !known_fields.contains(&field.as_str())) {
+        return Err(format!("{what} has an unknown field '{field}'"));
+    }
+    Ok(fields)
+}
+
+/// Removes a field from the fields of a JSON object, and returns it if it is a string.
+fn string_field(
+    fields: &mut BTreeMap<String, JsonValue>,
+    field: &str,
+    what: &str,
+) -> Result<Option<String>, String> {
+    match fields.remove(field) {
+        Some(JsonValue::String(s)) => Ok(Some(s)),
+        Some(_) => Err(format!("the {field} of {what} should be a string")),
+        None => Ok(None),
+    }
+}
+
+/// Removes a field from the fields of a JSON object, and returns it if it is an array of
+/// strings.
+fn string_array_field(
+    fields: &mut BTreeMap<String, JsonValue>,
+    field: &str,
+    what: &str,
+) -> Result<Option<Vec<String>>, String> {
+    let err = || format!("the {field} of {what} should be an array of strings");
+    match fields.remove(field) {
+        Some(JsonValue::Array(values)) => values
+            .into_iter()
+            .map(|value| match value {
+                JsonValue::String(s) => Ok(s),
+                _ => Err(err()),
+            })
+            .collect::<Result<_, _>>()
+            .map(Some),
+        Some(_) => Err(err()),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_from_json() {
+        let bundle = ExerciseBundle::from_json(
+            r#"{"exercises": [{"id": "1", "template": ["A", "A"], "options": "drafting=on"}]}"#,
+        );
+        assert_eq!(
+            bundle,
+            Ok(ExerciseBundle {
+                title: "".to_string(),
+                exercises: vec![Exercise {
+                    id: "1".to_string(),
+                    statement: "".to_string(),
+                    template: vec!["A".to_string(), "A".to_string()],
+                    allowed_variable_names: "x,y,z,u,v,w".to_string(),
+                    options: "drafting=on".to_string(),
+                    solutions: vec![],
+                    hidden_solutions: vec![],
+                    unlock_after: None,
+                }],
+            })
+        );
+        assert!(ExerciseBundle::from_json(r#"{"title": "no exercises"}"#).is_err());
+        assert!(ExerciseBundle::from_json(r#"{"exercises": [{"template": ["A"]}]}"#).is_err());
+        assert!(ExerciseBundle::from_json(r#"{"exercises": [{"id": "1"}]}"#).is_err());
+        assert!(ExerciseBundle::from_json(r#"{"exercises": [{"id": 1, "template": []}]}"#).is_err());
+        assert!(ExerciseBundle::from_json(
+            r#"{"exercises": [{"id": "1", "template": ["A"], "solution": ""}]}"#
+        )
+        .is_err());
+        assert!(ExerciseBundle::from_json(
+            r#"{"exercises": [{"id": "1", "template": ["A"], "hidden_solutions": ["a"]}]}"#
+        )
+        .is_err());
+        assert!(ExerciseBundle::from_json(
+            r#"{"exercises": [{"id": "1", "template": ["A"], "unlock_after": "tomorrow"}]}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_unix_time() {
+        assert_eq!(unix_time("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(unix_time("2000-03-01T12:30:15Z"), Some(951913815));
+        assert_eq!(unix_time("2026-11-01T23:59:00Z"), Some(1793577540));
+        assert_eq!(unix_time("2026-11-01T23:59:00"), None);
+        assert_eq!(unix_time("2026-13-01T23:59:00Z"), None);
+        assert_eq!(unix_time("2026-11-01 23:59:00Z"), None);
+        assert_eq!(unix_time("9999-12-31T23:59:59Z"), Some(253402300799));
+        assert_eq!(unix_time("999999999999999999-01-01T00:00:00Z"), None);
+        assert_eq!(unix_time("2024-02-29T00:00:00Z"), Some(1709164800));
+        assert_eq!(unix_time("2026-02-29T00:00:00Z"), None);
+        assert_eq!(unix_time("2100-02-29T00:00:00Z"), None);
+        assert_eq!(unix_time("2026-02-31T00:00:00Z"), None);
+        assert_eq!(unix_time("2026-04-31T00:00:00Z"), None);
+    }
+}
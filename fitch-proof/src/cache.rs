@@ -0,0 +1,226 @@
+use crate::formatter;
+use crate::parser;
+use crate::render;
+use crate::util;
+use std::collections::HashMap;
+
+/// A cache of results of checking proofs, so that identical submissions (which happens a lot when
+/// students resubmit) do not have to be checked again.
+///
+/// The results are stored by two fingerprints: one of the proof, and one of everything else that
+/// influences the result (the template, the allowed variable names, the options and the version of
+/// this crate). The proof is formatted before it is fingerprinted, so proofs that only differ in
+/// whitespace get the same fingerprint. Since a fingerprint is not collision-resistant, the
+/// formatted proof is stored as well, and a result is only used for the same formatted proof.
+///
+/// A cache can be saved to (and loaded from) a text file, see [CheckCache::to_text].
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct CheckCache {
+    results: HashMap<(u64, u64), CachedResult>,
+}
+
+/// A result in a [CheckCache], with the formatted proof that it is the result for.
+#[derive(PartialEq, Debug, Clone)]
+struct CachedResult {
+    proof: String,
+    result: String,
+    verdict: &'static str,
+}
+
+impl CheckCache {
+    /// Returns an empty cache.
+    pub fn new() -> CheckCache {
+        CheckCache::default()
+    }
+
+    /// Returns the same as [crate::check_proof_with_template_and_options] (or as
+    /// [crate::check_proof_with_options], if the template is empty), but only checks the proof if
+    /// the result is not in the cache yet.
+    pub fn check_proof(
+        &mut self,
+        proof: &str,
+        template: &[String],
+        allowed_variable_names: &str,
+        options: &str,
+    ) -> String {
+        self.check_proof_with_verdict(proof, template, allowed_variable_names, options).0
+    }
+
+    /// Same as [CheckCache::check_proof], but the verdict is returned as well, see
+    /// [crate::check_proof_with_verdict].
+    pub fn check_proof_with_verdict(
+        &mut self,
+        proof: &str,
+        template: &[String],
+        allowed_variable_names: &str,
+        options: &str,
+    ) -> (String, &'static str) {
+        let normalized = normalized_proof(proof);
+        let key = (
+            util::fnv1a_hash(normalized.as_bytes()),
+            configuration_fingerprint(proof, template, allowed_variable_names, options),
+        );
+        match self.results.get(&key) {
+            Some(cached) if cached.proof == normalized => (cached.result.clone(), cached.verdict),
+            _ => {
+                let template = (!template.is_empty()).then_some(template);
+                let (result, verdict) = crate::check_proof_with_verdict(
+                    proof,
+                    template,
+                    allowed_variable_names,
+                    options,
+                );
+                let cached = CachedResult {
+                    proof: normalized,
+                    result: result.clone(),
+                    verdict,
+                };
+                self.results.insert(key, cached);
+                (result, verdict)
+            }
+        }
+    }
+
+    /// Returns the number of results in the cache.
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Returns whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// Returns the cache as text, with one result per line, like this:
+    ///
+    /// `<proof fingerprint> <configuration fingerprint> <verdict> <proof> <result>`
+    ///
+    /// where the fingerprints are written as 16 hexadecimal digits, the verdict is like in
+    /// [crate::check_proof_with_verdict], and the formatted proof and the result are JSON strings.
+    /// The lines are sorted, so the same cache always gives the same text.
+    pub fn to_text(&self) -> String {
+        let mut lines: Vec<String> = self
+            .results
+            .iter()
+            .map(|((proof_fp, config_fp), cached)| {
+                format!(
+                    "{proof_fp:016x} {config_fp:016x} {} {} {}\n",
+                    cached.verdict,
+                    util::json_string(&cached.proof),
+                    util::json_string(&cached.result)
+                )
+            })
+            .collect();
+        lines.sort();
+        lines.concat()
+    }
+
+    /// Reads a cache from text that was made with [CheckCache::to_text]. If a line cannot be
+    /// read, an error message is returned.
+    pub fn from_text(text: &str) -> Result<CheckCache, String> {
+        let mut cache = CheckCache::new();
+        for (i, line) in text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            let wrong_form = || format!("line {} of the cache is not of the right form", i + 1);
+            let mut parts = line.splitn(4, ' ');
+            let (Some(proof_fp), Some(config_fp), Some(verdict), Some(rest)) =
+                (parts.next(), parts.next(), parts.next(), parts.next())
+            else {
+                return Err(wrong_form());
+            };
+            let (proof, result) = util::split_json_string(rest).ok_or_else(wrong_form)?;
+            match (
+                u64::from_str_radix(proof_fp, 16),
+                u64::from_str_radix(config_fp, 16),
+                render::VERDICTS.iter().find(|v| **v == verdict),
+                util::parse_json_string(proof),
+                result.strip_prefix(' ').and_then(util::parse_json_string),
+            ) {
+                (Ok(proof_fp), Ok(config_fp), Some(verdict), Some(proof), Some(result)) => {
+                    let cached = CachedResult {
+                        proof,
+                        result,
+                        verdict,
+                    };
+                    cache.results.insert((proof_fp, config_fp), cached);
+                }
+                _ => return Err(wrong_form()),
+            }
+        }
+        Ok(cache)
+    }
+}
+
+/* ------------------ PRIVATE -------------------- */
+
+/// The proof as it is stored in the cache: the formatted proof if it can be parsed, so that
+/// differences in whitespace do not matter, and otherwise the proof itself.
+fn normalized_proof(proof: &str) -> String {
+    match parser::parse_fitch_proof(proof) {
+        Ok(lines) if !lines.is_empty() => formatter::format_proof(lines),
+        _ => proof.to_string(),
+    }
+}
+
+/// The fingerprint of everything besides the (formatted) proof that influences the result of
+/// checking it. Since the symbols in the messages may depend on the symbols that are used in the
+/// proof, those are included as well.
+fn configuration_fingerprint(
+    proof: &str,
+    template: &[String],
+    allowed_variable_names: &str,
+    options: &str,
+) -> u64 {
+    let configuration = [
+        env!("CARGO_PKG_VERSION").to_string(),
+        template.join("\n"),
+        allowed_variable_names.to_string(),
+        options.to_string(),
+        format!("{:?}", formatter::detect_symbol_set(proof)),
+    ];
+    util::fnv1a_hash(configuration.join("\0").as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache() {
+        let mut cache = CheckCache::new();
+        let template = ["A ∧ B".to_string(), "A".to_string()];
+        let res = cache.check_proof("1 | A ∧ B\n  |---\n2 | A  ∧Elim: 1", &template, "x", "");
+        assert_eq!(res, "The proof is correct!");
+        let res = cache.check_proof("1|A ∧ B\n|----\n\n2|A ∧Elim:1", &template, "x", "");
+        assert_eq!(res, "The proof is correct!");
+        assert_eq!(cache.len(), 1);
+        cache.check_proof("1 | A ∧ B\n  |---\n2 | A  ∧Elim: 1", &template, "x", "symbols=ascii");
+        cache.check_proof("1 | A & B\n  |---\n2 | A  &Elim: 1", &template, "x", "");
+        cache.check_proof("garbage", &template, "x", "");
+        assert_eq!(cache.len(), 4);
+        let (res, verdict) = cache.check_proof_with_verdict("garbage", &template, "x", "");
+        assert!(res.starts_with("Fatal error"), "{res}");
+        assert_eq!(verdict, "fatal");
+
+        let text = cache.to_text();
+        assert_eq!(CheckCache::from_text(&text), Ok(cache));
+        assert!(CheckCache::from_text("0123 4567").is_err());
+        assert!(CheckCache::from_text("0123 xyz correct \"\" \"\"").is_err());
+        assert!(CheckCache::from_text("0123 4567 \"\" \"\"").is_err());
+        assert!(CheckCache::from_text("0123 4567 wrong \"\" \"\"").is_err());
+        assert!(CheckCache::from_text("0123 4567 correct \"a b\"").is_err());
+        assert!(CheckCache::from_text("0123 4567 correct \"a b\" \"c\"").is_ok());
+
+        // a result is only used for the same proof, even if the fingerprints are the same
+        let wrong = "1 | A ∧ B\n  |---\n2 | B  ∧Elim: 1";
+        let collision = format!(
+            "{:016x} {:016x} correct {} \"The proof is correct!\"",
+            util::fnv1a_hash(normalized_proof(wrong).as_bytes()),
+            configuration_fingerprint(wrong, &template, "x", ""),
+            util::json_string(&normalized_proof("1 | A ∧ B\n  |---\n2 | A  ∧Elim: 1")),
+        );
+        let mut cache = CheckCache::from_text(&collision).unwrap();
+        let (res, verdict) = cache.check_proof_with_verdict(wrong, &template, "x", "");
+        assert!(res.starts_with("The conclusion of your proof does not match"), "{res}");
+        assert_eq!(verdict, "incorrect");
+    }
+}
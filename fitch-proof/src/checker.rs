@@ -1,7 +1,8 @@
 use crate::data::*;
+use crate::error_codes;
 use crate::formatter;
+use crate::profile::{self, CheckProfile};
 use crate::proof::*;
-use crate::util;
 use std::collections::{HashMap, HashSet};
 use std::iter::zip;
 
@@ -13,13 +14,16 @@ use std::iter::zip;
 /// For example, if this is the set ["x", "y", "z"], then something like ∀x P(x) will be accepted,
 /// but something like ∀a P(a) will not be accepted, because "a" is not listed as a string
 /// that should be seen as a variable.
+///
+/// The third argument contains the [CheckOptions], e.g. whether derived rules may be used.
 pub fn check_proof(
     proof_lines: Vec<ProofLine>,
     allowed_variable_names: HashSet<String>,
+    options: CheckOptions,
 ) -> ProofResult {
-    match Proof::construct(proof_lines, allowed_variable_names) {
-        Err(err) => ProofResult::FatalError(err),
-        Ok(proof) => proof.is_fully_correct(),
+    match construct_proof(proof_lines, allowed_variable_names, options) {
+        Err(res) => res,
+        Ok(proof) => proof.is_fully_correct(None),
     }
 }
 
@@ -36,19 +40,398 @@ pub fn check_proof(
 /// For example, if this is the set ["x", "y", "z"], then something like ∀x P(x) will be accepted,
 /// but something like ∀a P(a) will not be accepted, because "a" is not listed as a string
 /// that should be seen as a variable.
+///
+/// The fourth argument contains the [CheckOptions] (see [check_proof]).
 pub fn check_proof_with_template(
     proof_lines: Vec<ProofLine>,
     template: Vec<Wff>,
     allowed_variable_names: HashSet<String>,
+    options: CheckOptions,
+) -> ProofResult {
+    match construct_proof(proof_lines, allowed_variable_names, options) {
+        Err(res) => res,
+        Ok(proof) => proof.is_fully_correct_and_matches_template(template, None),
+    }
+}
+
+/// This function checks a proof like [check_proof] (or like [check_proof_with_template], if a
+/// template is given), and records in `profile` how long each phase of the check took, and how
+/// long checking each line took.
+pub fn check_proof_with_profile(
+    proof_lines: Vec<ProofLine>,
+    template: Option<Vec<Wff>>,
+    allowed_variable_names: HashSet<String>,
+    options: CheckOptions,
+    profile: &mut CheckProfile,
+) -> ProofResult {
+    let start = profile::now();
+    let proof = construct_proof(proof_lines, allowed_variable_names, options);
+    profile.add_phase("structure and scope", profile::now() - start);
+    match (proof, template) {
+        (Err(res), _) => res,
+        (Ok(proof), Some(template)) => {
+            proof.is_fully_correct_and_matches_template(template, Some(profile))
+        }
+        (Ok(proof), None) => proof.is_fully_correct(Some(profile)),
+    }
+}
+
+/// This function checks only the structure (the "skeleton") of a proof, and treats the sentences
+/// as opaque: it checks the line numbers, the nesting of the subproofs, that every inference has a
+/// justification, and that all lines and subproofs that are cited are accessible. Whether the
+/// rules are applied correctly is not checked. The number of cited lines of each rule is already
+/// checked by the parser.
+///
+/// The arguments are the same as for [check_proof]. The proof lines would typically come from
+/// [crate::parser::parse_fitch_proof_skeleton].
+pub fn check_proof_skeleton(
+    proof_lines: Vec<ProofLine>,
+    allowed_variable_names: HashSet<String>,
+    options: CheckOptions,
+) -> ProofResult {
+    match construct_proof(proof_lines, allowed_variable_names, options) {
+        Err(res) => res,
+        Ok(proof) => {
+            let mut errors = proof.structure_errors();
+            for line in &proof.lines {
+                errors.extend(proof.citation_errors(line));
+            }
+            errors_to_proof_result(errors, &proof)
+        }
+    }
+}
+
+/// This function checks whether a single inference (a [Sequent]) is correct, that is, whether its
+/// rule leads from its premises to its conclusion.
+///
+/// Under the hood, the sequent is turned into a tiny proof: the premises become lines `1..=n`,
+/// and the conclusion becomes line `n+1`, which is justified by the rule of the sequent. In the
+/// error messages, the lines are then referred to as "premise 1", ..., and "the conclusion".
+///
+/// For rules that cite two premises (such as →Elim), the order in which the premises are written
+/// does not matter.
+///
+/// The second argument is the set of strings that should be seen as a variable (see
+/// [check_proof]).
+pub fn check_sequent(sequent: Sequent, allowed_variable_names: HashSet<String>) -> ProofResult {
+    let num_premises = sequent.premises.len();
+    let swapped_rule = match sequent.rule {
+        Justification::ImpliesElim(n, m) => Some(Justification::ImpliesElim(m, n)),
+        Justification::BicondElim(n, m) => Some(Justification::BicondElim(m, n)),
+        Justification::BottomIntro(n, m) => Some(Justification::BottomIntro(m, n)),
+        Justification::EqualsElim(n, m) => Some(Justification::EqualsElim(m, n)),
+        _ => None,
+    };
+
+    let to_proof_lines = |rule: Justification| -> Vec<ProofLine> {
+        let mut lines: Vec<ProofLine> = sequent
+            .premises
+            .iter()
+            .enumerate()
+            .map(|(i, premise)| ProofLine {
+                line_num: Some(i + 1),
+                depth: 1,
+                is_fitch_bar_line: false,
+                sentence: Some(premise.clone()),
+                justification: None,
+                constant_between_square_brackets: None,
+                cites_premises: false,
+            })
+            .collect();
+        lines.push(ProofLine {
+            line_num: None,
+            depth: 1,
+            is_fitch_bar_line: true,
+            sentence: None,
+            justification: None,
+            constant_between_square_brackets: None,
+            cites_premises: false,
+        });
+        lines.push(ProofLine {
+            line_num: Some(num_premises + 1),
+            depth: 1,
+            is_fitch_bar_line: false,
+            sentence: Some(sequent.conclusion.clone()),
+            justification: Some(rule),
+            constant_between_square_brackets: None,
+            cites_premises: false,
+        });
+        lines
+    };
+
+    let check = |rule: Justification| -> ProofResult {
+        let options = CheckOptions::default();
+        match construct_proof(to_proof_lines(rule), allowed_variable_names.clone(), options) {
+            Err(res) => res,
+            Ok(mut proof) => {
+                proof.sequent_premises = Some(num_premises);
+                proof.is_fully_correct(None)
+            }
+        }
+    };
+
+    if let Some(swapped_rule) = swapped_rule {
+        let res = check(swapped_rule);
+        if let ProofResult::Correct = res {
+            return res;
+        }
+    }
+    check(sequent.rule)
+}
+
+/// This function checks whether a proof is fully correct, just like [check_proof], but it also
+/// reports the status of every line with a justification as soon as that line has been checked,
+/// by calling `on_line_checked` with the line number and the [LineStatus] of that line.
+///
+/// This function looks for the first line of a proof that needs a (different) justification, and
+/// tries to find one that makes that line correct. The hints are based on this, see
+/// [crate::hints::hint].
+///
+/// The line that is looked for is the first inference whose rule is not applied correctly, or
+/// that does not have a justification at all. If there is no such line, then [None] is returned.
+/// Otherwise, its line number is returned, together with a justification that makes the line
+/// correct (if one is found). First, the rule that is used in the line is tried (citing other
+/// lines), and then the other rules, citing all lines and subproofs that are accessible from the
+/// line. Chains of equalities are never suggested.
+///
+/// The arguments are the same as for [check_proof]. If the proof is not even
+/// half-well-structured, then an error message is returned.
+#[cfg(feature = "tutoring")]
+pub fn suggest_justification(
+    proof_lines: Vec<ProofLine>,
+    allowed_variable_names: HashSet<String>,
+    options: CheckOptions,
+) -> Result<Option<(usize, Option<Justification>)>, String> {
+    let proof = Proof::construct(proof_lines, allowed_variable_names.clone(), options.clone())
+        .map_err(|err| err.to_string())?;
+    let missing_justification = proof.line_numbers_missing_justification();
+    let Some(index) = proof.lines.iter().position(|line| match line.line_num {
+        Some(n) => missing_justification.contains(&n) || proof.check_line(line).is_err(),
+        None => false,
+    }) else {
+        return Ok(None);
+    };
+    // unwrap works, since only lines with a line number are looked at above
+    let line_num = proof.lines[index].line_num.unwrap();
+    let used = proof.lines[index].justification.clone();
+
+    // a line without a justification has no scope, so the proof is constructed again with a
+    // placeholder justification in that line
+    let mut lines = proof.lines;
+    lines[index].justification = Some(Justification::EqualsIntro);
+    let proof =
+        Proof::construct(lines, allowed_variable_names, options).map_err(|err| err.to_string())?;
+    let mut candidates = proof.candidate_justifications(&proof.lines[index]);
+    if let Some(used) = used {
+        candidates
+            .sort_by_key(|just| std::mem::discriminant(just) != std::mem::discriminant(&used));
+    }
+    let found = candidates.into_iter().find(|just| {
+        let line = ProofLine {
+            justification: Some(just.clone()),
+            ..proof.lines[index].clone()
+        };
+        proof.check_line(&line).is_ok()
+    });
+    Ok(Some((line_num, found)))
+}
+
+/// This is useful for very long proofs, for which a user interface wants to show a progress bar,
+/// or wants to show errors as they are found rather than after the whole run.
+///
+/// Note that `on_line_checked` is never called if the proof is not even half-well-structured (in
+/// that case, a [ProofResult::FatalError] is returned), and that errors about the proof as a
+/// whole (not about one particular line) are only part of the returned [ProofResult].
+pub fn check_proof_with_progress(
+    proof_lines: Vec<ProofLine>,
+    allowed_variable_names: HashSet<String>,
+    options: CheckOptions,
+    on_line_checked: &mut dyn FnMut(usize, &LineStatus),
 ) -> ProofResult {
-    match Proof::construct(proof_lines, allowed_variable_names) {
-        Err(err) => ProofResult::FatalError(err),
-        Ok(proof) => proof.is_fully_correct_and_matches_template(template),
+    match IncrementalCheck::new(proof_lines, allowed_variable_names, options) {
+        Err(res) => res,
+        Ok(mut check) => {
+            check.check_next_lines(usize::MAX, on_line_checked);
+            check.finish()
+        }
+    }
+}
+
+/// An [IncrementalCheck] is a proof that is being checked a few lines at a time. This allows the
+/// caller to do other things between checking two chunks of the proof (e.g. update a progress
+/// bar in a web page).
+///
+/// Checking a proof with an [IncrementalCheck] gives exactly the same [ProofResult] as
+/// [check_proof].
+pub struct IncrementalCheck {
+    /// The proof that is being checked.
+    proof: Proof,
+    /// The index (in `proof.lines`) of the next line that should be checked.
+    next_index: usize,
+    /// The errors that were found so far.
+    errors: Vec<ProofError>,
+}
+
+impl IncrementalCheck {
+    /// Starts checking a proof. If there are no proof lines, or the proof is not even
+    /// half-well-structured, then the [ProofResult] of the whole check is returned instead
+    /// ([ProofResult::NoProof] or a [ProofResult::FatalError]).
+    pub fn new(
+        proof_lines: Vec<ProofLine>,
+        allowed_variable_names: HashSet<String>,
+        options: CheckOptions,
+    ) -> Result<IncrementalCheck, ProofResult> {
+        Ok(IncrementalCheck {
+            proof: construct_proof(proof_lines, allowed_variable_names, options)?,
+            next_index: 0,
+            errors: vec![],
+        })
+    }
+
+    /// Checks (at most) the next `max_lines` lines of the proof, and calls `on_line_checked` for
+    /// each of those lines that has a justification.
+    pub fn check_next_lines(
+        &mut self,
+        max_lines: usize,
+        on_line_checked: &mut dyn FnMut(usize, &LineStatus),
+    ) {
+        let end_index = self.next_index.saturating_add(max_lines).min(self.proof.lines.len());
+        for line in &self.proof.lines[self.next_index..end_index] {
+            let (Some(line_num), Some(_)) = (line.line_num, &line.justification) else {
+                continue;
+            };
+            let status = match self.proof.check_line(line) {
+                Ok(()) => LineStatus::Correct,
+                Err(err) => {
+                    let status = LineStatus::Error(err.to_string());
+                    self.errors.push(err);
+                    status
+                }
+            };
+            on_line_checked(line_num, &status);
+        }
+        self.next_index = end_index;
+    }
+
+    /// Returns the total number of lines of the proof (including Fitch bar lines and empty lines).
+    pub fn num_lines(&self) -> usize {
+        self.proof.lines.len()
+    }
+
+    /// Returns the number of lines of the proof that have already been checked.
+    pub fn num_checked_lines(&self) -> usize {
+        self.next_index
+    }
+
+    /// Returns whether all lines of the proof have been checked.
+    pub fn is_done(&self) -> bool {
+        self.next_index >= self.proof.lines.len()
+    }
+
+    /// Checks any lines that were not yet checked, and then returns the evaluation of the proof.
+    pub fn finish(mut self) -> ProofResult {
+        self.check_next_lines(usize::MAX, &mut |_, _| {});
+        self.errors.extend(self.proof.proof_level_errors());
+        errors_to_proof_result(self.errors, &self.proof)
+    }
+}
+
+/// Returns the line numbers of the lines and the subproofs that are cited in a justification.
+pub fn cited_lines_and_subproofs(just: &Justification) -> (Vec<usize>, Vec<(usize, usize)>) {
+    match just {
+        Justification::AndIntro(ns) | Justification::EqualsChain(ns) => (ns.clone(), vec![]),
+        Justification::AndElim(n)
+        | Justification::OrIntro(n)
+        | Justification::NotElim(n)
+        | Justification::BottomElim(n)
+        | Justification::ForallElim(n)
+        | Justification::ExistsIntro(n)
+        | Justification::Reit(n)
+        | Justification::QuantifierExchange(n)
+        | Justification::Contraposition(n)
+        | Justification::MaterialImplication(n) => (vec![*n], vec![]),
+        Justification::BottomIntro(n, m)
+        | Justification::ImpliesElim(n, m)
+        | Justification::BicondElim(n, m)
+        | Justification::EqualsElim(n, m) => (vec![*n, *m], vec![]),
+        Justification::OrElim(n, subproofs) => (vec![*n], subproofs.clone()),
+        Justification::NotIntro(subproof)
+        | Justification::ImpliesIntro(subproof)
+        | Justification::ForallIntro(subproof) => (vec![], vec![*subproof]),
+        Justification::BicondIntro(subproof1, subproof2) => (vec![], vec![*subproof1, *subproof2]),
+        Justification::ExistsElim(n, subproof) => (vec![*n], vec![*subproof]),
+        Justification::EqualsIntro => (vec![], vec![]),
     }
 }
 
 /* ------------------ PRIVATE -------------------- */
 
+/// Constructs a [Proof] (see [Proof::construct]). If that is not possible, then the result of
+/// checking the proof is returned instead: [ProofResult::NoProof] if there are no proof lines at
+/// all (see [crate::parser::is_empty_proof]), and a [ProofResult::FatalError] otherwise.
+fn construct_proof(
+    proof_lines: Vec<ProofLine>,
+    allowed_variable_names: HashSet<String>,
+    options: CheckOptions,
+) -> Result<Proof, ProofResult> {
+    if proof_lines.is_empty() {
+        return Err(ProofResult::NoProof);
+    }
+    Proof::construct(proof_lines, allowed_variable_names, options).map_err(ProofResult::FatalError)
+}
+
+/// Turns the list of all errors found in a proof into a [ProofResult]. The errors are sorted in the
+/// order of [CheckOptions::error_order] (see [ErrorKey]). If there are lines that are not checked
+/// because they do not have a justification yet (see [Proof::unchecked_draft_lines]), then the
+/// result is [ProofResult::Unfinished].
+fn errors_to_proof_result(errors: Vec<ProofError>, proof: &Proof) -> ProofResult {
+    let mut keyed_errors: Vec<(ErrorKey, ProofError)> =
+        errors.into_iter().map(|err| (ErrorKey::new(&err, proof), err)).collect();
+    let by_line =
+        |a: &ErrorKey, b: &ErrorKey| (a.real_line, a.fitch_line).cmp(&(b.real_line, b.fitch_line));
+    // the sort is stable, so errors with the same key stay in the order in which they were found
+    keyed_errors.sort_by(|(a, _), (b, _)| match proof.options.error_order {
+        ErrorOrder::Line => by_line(a, b).then(a.code.cmp(b.code)),
+        ErrorOrder::Severity => {
+            error_codes::severity(a.code).cmp(&error_codes::severity(b.code)).then(by_line(a, b))
+        }
+        ErrorOrder::Code => a.code.cmp(b.code).then(by_line(a, b)),
+    });
+    let errors: Vec<ProofError> = keyed_errors.into_iter().map(|(_, err)| err).collect();
+    let unchecked_lines = proof.unchecked_draft_lines();
+    if !unchecked_lines.is_empty() {
+        ProofResult::Unfinished(errors, unchecked_lines)
+    } else if errors.is_empty() {
+        ProofResult::Correct
+    } else {
+        ProofResult::Error(errors)
+    }
+}
+
+/// The key on which the errors in a proof are sorted, see [errors_to_proof_result].
+struct ErrorKey {
+    /// The position (counting all lines, like Fitch bar lines) in the proof of the line that the
+    /// error is about, or [usize::MAX] if the error is not about one line.
+    real_line: usize,
+    /// The line number of the line that the error is about, or [usize::MAX].
+    fitch_line: usize,
+    /// The error code of the error, see [ProofError::code].
+    code: &'static str,
+}
+
+impl ErrorKey {
+    fn new(err: &ProofError, proof: &Proof) -> ErrorKey {
+        let fitch_line = err.line;
+        let real_line =
+            fitch_line.and_then(|n| proof.lines.iter().position(|l| l.line_num == Some(n)));
+        ErrorKey {
+            real_line: real_line.unwrap_or(usize::MAX),
+            fitch_line: fitch_line.unwrap_or(usize::MAX),
+            code: err.code,
+        }
+    }
+}
+
 impl Proof {
     /// Given a [Proof], this function checks if it is fully correct, AND that it matches the given
     /// proof template. A template is a vector of [Wff]s, containing (in order) all the premises,
@@ -57,15 +440,32 @@ impl Proof {
     /// When you want to fully assess the validity of a proof, and
     /// check that it matches the template, you should first
     /// [Proof::construct] the proof, and then run this function.
-    fn is_fully_correct_and_matches_template(&self, template: Vec<Wff>) -> ProofResult {
+    ///
+    /// If a [CheckProfile] is given, then the time of each phase of the check is recorded in it.
+    fn is_fully_correct_and_matches_template(
+        &self,
+        template: Vec<Wff>,
+        mut profile: Option<&mut CheckProfile>,
+    ) -> ProofResult {
         // Note: don't remove this check on the length of `template`. It would cause some panics
         // below if the length is zero.
         if template.is_empty() {
-            return ProofResult::FatalError("The proof template is empty. This should not be! If you see this on Themis as a student, please contact the course staff as soon as possible. Something is wrong on our side. Thanks!".to_owned());
+            return ProofResult::FatalError(FatalError::TemplateError(
+                "The proof template is empty.".to_owned(),
+            ));
         }
 
+        // the template is read with the same negation convention as the proof
+        let template: Vec<Wff> = match self.options.negation {
+            NegationConvention::Primitive => template,
+            NegationConvention::DefinedByBottom => {
+                template.iter().map(Proof::expand_negations).collect()
+            }
+        };
+
+        let start = profile::now();
         // template matching errors that we will be accumulating.
-        let mut template_errors: Vec<String> = vec![];
+        let mut template_errors: Vec<ProofError> = vec![];
 
         // check premises
         {
@@ -78,40 +478,72 @@ impl Proof {
 
             // index is within bounds
             if premises_in_proof != template[0..template.len() - 1] {
-                template_errors.push(
+                template_errors.push(ProofError::about_proof(
+                    error_codes::TEMPLATE_MISMATCH,
                     "The premises of your proof do not match the premises in the proof template."
                         .to_owned(),
-                );
+                ));
             }
         }
 
         // check conclusion
         {
-            let conclusion_in_proof = self.lines.iter().rev().find(|l| l.sentence.is_some());
-            match conclusion_in_proof {
-                None => {
-                    template_errors
-                        .push("It seems that your proof has no sentences in it.".to_owned());
-                }
-                Some(concl) => {
-                    // both unwraps work (note that we checked the length of `template`)
-                    if concl.sentence.as_ref().unwrap() != template.last().unwrap() {
-                        template_errors.push("The conclusion of your proof does not match the conclusion in the proof template.".to_owned());
+            // unwrap works (note that we checked the length of `template`)
+            let conclusion = template.last().unwrap();
+            // the first top-level line (a premise or an inference) in which the conclusion is
+            // reached
+            let num_premises = self.lines.iter().take_while(|l| !l.is_fitch_bar_line).count();
+            let conclusion_reached = self.lines.iter().enumerate().find_map(|(i, l)| {
+                let is_reached = l.depth == 1
+                    && (l.justification.is_some() || i < num_premises)
+                    && l.sentence.as_ref() == Some(conclusion);
+                is_reached.then_some(l)
+            });
+            let last_line = self.lines.iter().rev().find(|l| l.sentence.is_some());
+            match (last_line, self.options.conclusion_placement) {
+                (None, _) => {
+                    template_errors.push(ProofError::about_proof(
+                        error_codes::TEMPLATE_MISMATCH,
+                        "It seems that your proof has no sentences in it.".to_owned(),
+                    ));
+                }
+                (Some(_), ConclusionPlacement::AnyTopLevelLine) => {
+                    if conclusion_reached.is_none() {
+                        template_errors.push(ProofError::about_proof(error_codes::TEMPLATE_MISMATCH, "The conclusion in the proof template is not reached in your proof (outside of subproofs).".to_owned()));
+                    }
+                }
+                (Some(last), ConclusionPlacement::LastLine) => {
+                    // unwrap works, since `last` has a sentence
+                    if last.sentence.as_ref().unwrap() != conclusion {
+                        match conclusion_reached.and_then(|l| l.line_num) {
+                            Some(n) => template_errors.push(self.error_at(n, error_codes::TEMPLATE_MISMATCH, "the conclusion is reached in this line, but the proof \
+                                continues after it. The conclusion should be the last line of the \
+                                proof.".to_string())),
+                            None => template_errors.push(ProofError::about_proof(error_codes::TEMPLATE_MISMATCH, "The conclusion of your proof does not match the conclusion in the proof template.".to_owned())),
+                        }
                     }
                 }
             }
         }
 
-        let result_without_template_check = self.is_fully_correct();
+        if let Some(profile) = profile.as_deref_mut() {
+            profile.add_phase("template", profile::now() - start);
+        }
+
+        let result_without_template_check = self.is_fully_correct(profile);
         match result_without_template_check {
             // If the proof generates a fatal error by itself, the user is not interested in
             // template matching errors.
-            ProofResult::FatalError(_) => result_without_template_check,
+            ProofResult::NoProof | ProofResult::FatalError(_) => result_without_template_check,
             // If there were already errors, just append any template matching errors.
             ProofResult::Error(mut errs) => {
                 errs.append(&mut template_errors);
                 ProofResult::Error(errs)
             }
+            ProofResult::Unfinished(mut errs, unchecked_lines) => {
+                errs.append(&mut template_errors);
+                ProofResult::Unfinished(errs, unchecked_lines)
+            }
             // If there were any template mathing errors, change the Correct into Error. Otherwise
             // it stays Correct.
             ProofResult::Correct => {
@@ -126,37 +558,41 @@ impl Proof {
     /// Given a [Proof], this function checks if it is fully correct.
     ///
     /// When you want to fully assess the validity of a proof, you should first [Proof::construct] the proof, and then run this function.
-    fn is_fully_correct(&self) -> ProofResult {
-        let mut errors: Vec<String> = vec![]; // here we accumulate all errors
+    ///
+    /// If a [CheckProfile] is given, then the time of each phase of the check, and of each line,
+    /// is recorded in it.
+    fn is_fully_correct(&self, mut profile: Option<&mut CheckProfile>) -> ProofResult {
+        let mut errors: Vec<ProofError> = vec![]; // here we accumulate all errors
 
         // check that user applied proof rule correctly everywhere
+        let start = profile::now();
         for line in &self.lines {
+            let line_start = profile::now();
             if let Err(err) = self.check_line(line) {
-                errors.push(err.to_string());
+                errors.push(err);
+            }
+            if let (Some(profile), Some(line_num)) = (profile.as_deref_mut(), line.line_num) {
+                let rule = line.justification.as_ref().map(formatter::rule_name);
+                profile.add_line(line_num, rule, profile::now() - line_start);
             }
         }
+        if let Some(profile) = profile.as_deref_mut() {
+            profile.add_phase("rules", profile::now() - start);
+        }
 
-        // check that proof starts with zero or more premises, followed by a Fitch bar
-        if !self.units.iter().any(|u| *u == ProofUnit::FitchBarLine)
-            || !self.units.iter().take_while(|u| **u != ProofUnit::FitchBarLine).all(|u| {
-                matches!(
-                    *u,
-                    ProofUnit::NumberedProofLineWithoutJustificationWithoutBoxedConstant(..)
-                )
-            })
-        {
-            errors.push(
-                "Each proof should start start with zero or more premises, followed by a Fitch bar"
-                    .to_string(),
-            );
+        let start = profile::now();
+        errors.extend(self.proof_level_errors());
+        if let Some(profile) = profile {
+            profile.add_phase("proof-level checks", profile::now() - start);
         }
+        errors_to_proof_result(errors, self)
+    }
 
-        // check that all inferences have justification
-        errors.extend(
-            self.line_numbers_missing_justification()
-                .iter()
-                .map(|n| format!("Line {n}: missing justification").to_string()),
-        );
+    /// This function returns all errors that are not about the application of a proof rule in a
+    /// single line, but about the proof as a whole (the structure of the proof, variable scoping,
+    /// arities, boxed constants and so on).
+    fn proof_level_errors(&self) -> Vec<ProofError> {
+        let mut errors = self.structure_errors(); // here we accumulate all errors
 
         // check that all variables are bound, that user doesn't have nested quantifiers over the
         // same variable and that users don't quantify over a constant, and that the user does not make
@@ -186,17 +622,63 @@ impl Proof {
             errors.extend(errs);
         }
 
+        errors
+    }
+
+    /// This function returns the errors about the structure of the proof that do not depend on
+    /// the sentences in it (see [check_proof_skeleton]).
+    fn structure_errors(&self) -> Vec<ProofError> {
+        let mut errors: Vec<ProofError> = vec![];
+
+        // check that proof starts with zero or more premises, followed by a Fitch bar
+        if !self.units.contains(&ProofUnit::FitchBarLine)
+            || !self.units.iter().take_while(|u| **u != ProofUnit::FitchBarLine).all(|u| {
+                matches!(
+                    *u,
+                    ProofUnit::NumberedProofLineWithoutJustificationWithoutBoxedConstant(..)
+                )
+            })
+        {
+            errors.push(ProofError::about_proof(
+                error_codes::STRUCTURE,
+                "Each proof should start with zero or more premises, followed by a Fitch bar"
+                    .to_string(),
+            ));
+        }
+
+        // check that all inferences have justification (unless this is a draft, see
+        // `unchecked_draft_lines`)
+        if !self.options.drafting {
+            errors.extend(self.line_numbers_missing_justification().into_iter().map(|n| {
+                self.error_at(
+                    n,
+                    error_codes::MISSING_JUSTIFICATION,
+                    "missing justification".to_string(),
+                )
+            }));
+        }
+
         // check that last line is top-level
         if self.last_line_is_inside_subproof() {
             let lln = self.last_line_num();
-            errors.push(format!("Line {lln}: last line of proof should not be inside subproof"));
+            errors.push(self.error_at(
+                lln,
+                error_codes::STRUCTURE,
+                "last line of proof should not be inside subproof".to_string(),
+            ));
         }
 
-        if errors.is_empty() {
-            ProofResult::Correct
+        errors
+    }
+
+    /// If [CheckOptions::drafting] is set, this function returns the line numbers of the inferences
+    /// that do not have a justification yet (which are not checked). Otherwise, it returns an
+    /// empty vector, since such lines are simply mistakes.
+    fn unchecked_draft_lines(&self) -> Vec<usize> {
+        if self.options.drafting {
+            self.line_numbers_missing_justification()
         } else {
-            util::natural_sort(&mut errors);
-            ProofResult::Error(errors)
+            vec![]
         }
     }
 
@@ -228,6 +710,312 @@ impl Proof {
         res
     }
 
+    /// This function returns the errors about the lines and subproofs that are cited in the
+    /// justification of a line, which are not accessible from that line (or do not exist).
+    fn citation_errors(&self, line: &ProofLine) -> Vec<ProofError> {
+        let (Some(line_num), Some(just)) = (line.line_num, &line.justification) else {
+            return vec![];
+        };
+        let (cited_lines, cited_subproofs) = cited_lines_and_subproofs(just);
+        cited_lines
+            .into_iter()
+            .filter_map(|n| self.get_wff_at_line(line_num, n).err())
+            .chain(
+                cited_subproofs
+                    .into_iter()
+                    .filter_map(|subproof| self.get_subproof_at_lines(line_num, subproof).err()),
+            )
+            .chain(self.reiteration_error(line_num, just))
+            .collect()
+    }
+
+    /// If [CheckOptions::reiteration] is [ReiterationPolicy::Explicit], this function returns an
+    /// error message if the justification of line `line_num` is not Reit, but cites a line that is
+    /// accessible from a subproof around the subproof of line `line_num` (or from the premises).
+    /// Lines that are not accessible at all are left to [Proof::get_wff_at_line].
+    fn reiteration_error(&self, line_num: usize, just: &Justification) -> Option<ProofError> {
+        if self.options.reiteration == ReiterationPolicy::Direct
+            || matches!(just, Justification::Reit(_))
+        {
+            return None;
+        }
+        let depth = |n: usize| self.lines.iter().find(|l| l.line_num == Some(n)).map(|l| l.depth);
+        let n = cited_lines_and_subproofs(just)
+            .0
+            .into_iter()
+            .find(|n| self.can_reference(line_num, *n) && depth(*n) < depth(line_num))?;
+        Some(self.error_at(
+            line_num,
+            error_codes::SCOPE_VIOLATION,
+            format!(
+                "{line_n} is cited, but it is outside the subproof that \
+            {curr_line} is in, and here lines outside the subproof can only be cited with Reit. \
+            First repeat the sentence of {line_n} inside the subproof with Reit: {n}, and then \
+            cite that line instead.",
+                line_n = self.line_name(n),
+                curr_line = self.line_name(line_num)
+            ),
+        ))
+    }
+
+    /// If [CheckOptions::propositional_quantifiers] is on, this function checks the quantifier
+    /// rules for quantifiers over sentences (like `∀P (P → P)`): ∀Elim and ∃Intro substitute a
+    /// sentence for the bound atomic sentence, and ∀Intro and ∃Elim use a subproof with a new
+    /// atomic sentence as boxed constant, like `[Q]`. [None] is returned if the rule is not about a
+    /// quantifier over sentences; it is then checked as usual.
+    fn check_propositional_quantifier_rule(
+        &self,
+        curr_line_num: usize,
+        curr_wff: &Wff,
+        just: &Justification,
+    ) -> Option<Result<(), ProofError>> {
+        if !self.options.propositional_quantifiers {
+            return None;
+        }
+        let boxed_sentence = |s_begin: &ProofLine| match &s_begin.constant_between_square_brackets {
+            Some(Term::Atomic(name)) if is_sentence_letter(name) => Some(Wff::Atomic(name.clone())),
+            _ => None,
+        };
+        let res = match just {
+            Justification::ForallElim(n) => {
+                let Ok(Wff::Forall(var, ref_wff)) = self.get_wff_at_line(curr_line_num, *n) else {
+                    return None;
+                };
+                if !is_sentence_letter(var) {
+                    return None;
+                }
+                match find_sentence_substitution(ref_wff, var, curr_wff) {
+                    Some(_) => Ok(()),
+                    None => Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "the rule ∀Elim:{n} is used, but the sentence in \
+                        this line cannot be obtained by replacing every free {var} in the \
+                        quantified part of {line_n} by the same sentence",
+                            line_n = self.line_name(*n)
+                        ),
+                    )),
+                }
+            }
+            Justification::ExistsIntro(n) => {
+                let Wff::Exists(var, exists_curr_wff) = curr_wff else {
+                    return None;
+                };
+                if !is_sentence_letter(var) {
+                    return None;
+                }
+                let ref_wff = match self.get_wff_at_line(curr_line_num, *n) {
+                    Ok(ref_wff) => ref_wff,
+                    Err(err) => return Some(Err(err)),
+                };
+                match find_sentence_substitution(exists_curr_wff, var, ref_wff) {
+                    Some(_) => Ok(()),
+                    None => Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "the rule ∃Intro:{n} is used, but the sentence in \
+                        {line_n} cannot be obtained by replacing every free {var} in the \
+                        quantified part of this line by the same sentence",
+                            line_n = self.line_name(*n)
+                        ),
+                    )),
+                }
+            }
+            Justification::ForallIntro((sb, se)) => {
+                let Wff::Forall(var, forall_curr_wff) = curr_wff else {
+                    return None;
+                };
+                if !is_sentence_letter(var) {
+                    return None;
+                }
+                let (s_begin, s_end) = match self.get_subproof_at_lines(curr_line_num, (*sb, *se)) {
+                    Ok(subproof) => subproof,
+                    Err(err) => return Some(Err(err)),
+                };
+                let Some(new_sentence) = boxed_sentence(s_begin) else {
+                    return Some(Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "the rule ∀Intro:{sb}-{se} is used for a \
+                        quantifier over sentences, but the referenced subproof does not \
+                        introduce a new atomic sentence (like [Q]) in {line_sb}",
+                            line_sb = self.line_name(*sb)
+                        ),
+                    )));
+                };
+                if s_begin.sentence.is_some() {
+                    return Some(Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        "when using ∀Intro, the premise of the referenced \
+                        subproof should consist of solely a boxed atomic sentence, without a \
+                        sentence"
+                            .to_string(),
+                    )));
+                }
+                let substitution =
+                    find_sentence_substitution(forall_curr_wff, var, s_end.sentence.as_ref()?);
+                match substitution {
+                    Some(None) => Ok(()),
+                    Some(Some(wff)) if *wff == new_sentence => Ok(()),
+                    _ => Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "the rule ∀Intro:{sb}-{se} is used, but if all \
+                        free occurrences of {var} in the quantified part of \
+                        {curr_line} are replaced by {}, one does not obtain the sentence in \
+                        {line_se}",
+                            formatter::format_wff(&new_sentence),
+                            line_se = self.line_name(*se),
+                            curr_line = self.line_name(curr_line_num)
+                        ),
+                    )),
+                }
+            }
+            Justification::ExistsElim(n, (sb, se)) => {
+                let Ok(Wff::Exists(var, exists_ref_wff)) = self.get_wff_at_line(curr_line_num, *n)
+                else {
+                    return None;
+                };
+                if !is_sentence_letter(var) {
+                    return None;
+                }
+                let (s_begin, s_end) = match self.get_subproof_at_lines(curr_line_num, (*sb, *se)) {
+                    Ok(subproof) => subproof,
+                    Err(err) => return Some(Err(err)),
+                };
+                let Some(new_sentence) = boxed_sentence(s_begin) else {
+                    return Some(Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "the rule ∃Elim:{n},{sb}-{se} is used for a \
+                        quantifier over sentences, but the referenced subproof does not \
+                        introduce a new atomic sentence (like [Q]) in {line_sb}",
+                            line_sb = self.line_name(*sb)
+                        ),
+                    )));
+                };
+                let Some(assumption) = &s_begin.sentence else {
+                    return Some(Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "the rule ∃Elim:{n},{sb}-{se} is used, but {line_sb} \
+                        contains only a boxed atomic sentence; when using ∃Elim, it should \
+                        contain both a boxed atomic sentence and a sentence",
+                            line_sb = self.line_name(*sb)
+                        ),
+                    )));
+                };
+                let substitution = find_sentence_substitution(exists_ref_wff, var, assumption);
+                if substitution != Some(None) && substitution != Some(Some(&new_sentence)) {
+                    Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "the rule ∃Elim:{n},{sb}-{se} is used, but the \
+                        sentence in {line_sb} is not obtained by replacing every free {var} in \
+                        the quantified part of {line_n} by {}",
+                            formatter::format_wff(&new_sentence),
+                            line_sb = self.line_name(*sb),
+                            line_n = self.line_name(*n)
+                        ),
+                    ))
+                } else if s_end.sentence.as_ref() != Some(curr_wff) {
+                    Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "the rule ∃Elim:{n},{sb}-{se} is used, but the \
+                        sentence in {line_se} is not the same as the sentence in \
+                        {curr_line} ({})",
+                            formatter::format_wff(curr_wff),
+                            line_se = self.line_name(*se),
+                            curr_line = self.line_name(curr_line_num)
+                        ),
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+            _ => return None,
+        };
+        Some(res)
+    }
+
+    /// Returns the justifications that could be tried for a line (see [suggest_justification]):
+    /// every rule, citing the lines and subproofs that are accessible from the line. For ∧Intro
+    /// and ∨Elim, only the lines and subproofs that fit the conjuncts and disjuncts are cited.
+    #[cfg(feature = "tutoring")]
+    fn candidate_justifications(&self, line: &ProofLine) -> Vec<Justification> {
+        let Some(line_num) = line.line_num else {
+            return vec![];
+        };
+        let (lines, subproofs) = &self.scope[line_num];
+        let wff_at = |n: usize| self.get_wff_at_line(line_num, n).ok();
+        let assumption_of = |(begin, _): (usize, usize)| {
+            self.lines.iter().find(|l| l.line_num == Some(begin)).and_then(|l| l.sentence.as_ref())
+        };
+        let mut res = vec![Justification::EqualsIntro];
+        for &n in lines {
+            res.extend([
+                Justification::Reit(n),
+                Justification::AndElim(n),
+                Justification::OrIntro(n),
+                Justification::NotElim(n),
+                Justification::BottomElim(n),
+                Justification::ForallElim(n),
+                Justification::ExistsIntro(n),
+                Justification::QuantifierExchange(n),
+                Justification::Contraposition(n),
+                Justification::MaterialImplication(n),
+            ]);
+            for &m in lines {
+                res.extend([
+                    Justification::BottomIntro(n, m),
+                    Justification::ImpliesElim(n, m),
+                    Justification::BicondElim(n, m),
+                    Justification::EqualsElim(n, m),
+                ]);
+            }
+            for &subproof in subproofs {
+                res.push(Justification::ExistsElim(n, subproof));
+            }
+            if let Some(Wff::Or(disjuncts)) = wff_at(n) {
+                let cases = disjuncts.iter().map(|disjunct| {
+                    subproofs.iter().copied().find(|s| assumption_of(*s) == Some(disjunct))
+                });
+                if let Some(cases) = cases.collect::<Option<Vec<(usize, usize)>>>() {
+                    res.push(Justification::OrElim(n, cases));
+                }
+            }
+        }
+        if let Some(Wff::And(conjuncts)) = &line.sentence {
+            let cited = conjuncts
+                .iter()
+                .map(|conjunct| lines.iter().copied().find(|n| wff_at(*n) == Some(conjunct)));
+            if let Some(cited) = cited.collect::<Option<Vec<usize>>>() {
+                res.push(Justification::AndIntro(cited));
+            }
+        }
+        for &subproof in subproofs {
+            res.extend([
+                Justification::NotIntro(subproof),
+                Justification::ImpliesIntro(subproof),
+                Justification::ForallIntro(subproof),
+            ]);
+            for &other in subproofs {
+                res.push(Justification::BicondIntro(subproof, other));
+            }
+        }
+        res
+    }
+
     /// This function returns true if and only if the last line (that has a line number) is inside a subproof
     fn last_line_is_inside_subproof(&self) -> bool {
         // unwrap should work, since this proof is half-well-structured, so it should contain some
@@ -252,8 +1040,8 @@ impl Proof {
     /// This function checks that no boxed constants are used outside the subproof. If no boxed
     /// constants are used outside the corresponding subproof, `Ok(())` is returned. Otherwise, a
     /// vector or relevant error messages will be returned, wrapped in an `Err`.
-    fn check_boxed_constant_outside_subproof(&self) -> Result<(), Vec<String>> {
-        let mut errors: Vec<String> = vec![];
+    fn check_boxed_constant_outside_subproof(&self) -> Result<(), Vec<ProofError>> {
+        let mut errors: Vec<ProofError> = vec![];
 
         // step 1: check which boxed constants exist within the proof
         let boxed_consts: HashSet<_> = self
@@ -262,18 +1050,39 @@ impl Proof {
             .filter_map(|line| line.constant_between_square_brackets.clone())
             .collect();
 
-        // step 2: let's also give warnings if the user puts a variable in a box (not a constant)
+        // step 2: let's also give warnings if the user puts a variable in a box (not a constant),
+        // or a new atomic sentence without the option for quantifiers over sentences
         errors.extend(
             self.lines
                 .iter()
                 .filter(|line| line.constant_between_square_brackets.is_some())
                 .filter_map(|line| {
+                    let line_num = line.line_num?;
+                    if let Some(Term::Atomic(name)) = &line.constant_between_square_brackets {
+                        if !self.options.propositional_quantifiers && is_sentence_letter(name) {
+                            return Some(
+                                self.error_at(
+                                    line_num,
+                                    error_codes::VARIABLES,
+                                    "a boxed constant must be a constant; it should start with a \
+                                lowercase letter."
+                                        .to_string(),
+                                ),
+                            );
+                        }
+                    }
                     if self.term_is_constant(
                         line.constant_between_square_brackets.as_ref().unwrap().clone(),
                     ) {
                         None
                     } else {
-                        Some(format!("Line {}: a boxed constant cannot be a variable (should not have the name of a variable).", line.line_num.unwrap()))
+                        Some(self.error_at(
+                            line_num,
+                            error_codes::VARIABLES,
+                            "a boxed constant cannot be a variable (should not have the name of a \
+                            variable)."
+                                .to_string(),
+                        ))
                     }
                 }),
         );
@@ -291,7 +1100,7 @@ impl Proof {
                     // before we add the new variable to current scope,
                     // test that it was not already in scope:
                     if currently_in_scope.contains(new_boxed_const) {
-                        errors.push(format!("Line {num}: you cannot introduce the same boxed constant twice in nested subproofs"));
+                        errors.push(self.error_at(num, error_codes::VARIABLES, "you cannot introduce the same boxed constant twice in nested subproofs".to_string()));
                     }
 
                     currently_in_scope.push(new_boxed_const.clone());
@@ -301,10 +1110,11 @@ impl Proof {
                             wff,
                             &currently_in_scope,
                             &boxed_consts,
-                            num,
                         ) {
                             Ok(_) => {}
-                            Err(err) => errors.push(err),
+                            Err(err) => {
+                                errors.push(self.error_at(num, error_codes::SCOPE_VIOLATION, err))
+                            }
                         }
                     }
                 }
@@ -316,9 +1126,8 @@ impl Proof {
                         prfln.sentence.as_ref().unwrap(),
                         &currently_in_scope,
                         &boxed_consts,
-                        num,
                     ) {
-                        errors.push(err);
+                        errors.push(self.error_at(num, error_codes::SCOPE_VIOLATION, err));
                     }
                 }
                 ProofUnit::NumberedProofLineWithJustification(num) => {
@@ -327,9 +1136,8 @@ impl Proof {
                         prfln.sentence.as_ref().unwrap(),
                         &currently_in_scope,
                         &boxed_consts,
-                        num,
                     ) {
-                        errors.push(err);
+                        errors.push(self.error_at(num, error_codes::SCOPE_VIOLATION, err));
                     }
                 }
                 ProofUnit::SubproofClose => {
@@ -345,66 +1153,56 @@ impl Proof {
             wff: &Wff,
             curr_scope: &[Option<Term>],
             all_boxeds: &HashSet<Term>,
-            line_num: usize,
         ) -> Result<(), String> {
             fn check_term_not_contain_out_of_scope_boxed_consts(
                 term: &Term,
                 curr_scope: &[Option<Term>],
                 all_boxeds: &HashSet<Term>,
-                line_num: usize,
             ) -> Result<(), String> {
                 match term {
                     Term::Atomic(_) => {
                         if all_boxeds.contains(term)
                             && !curr_scope.iter().filter_map(|x| x.as_ref()).any(|t| t == term)
                         {
-                            Err(format!("Line {line_num}: it is not allowed to use a boxed constant outside the subproof that defines it"))
+                            Err("it is not allowed to use a boxed constant outside the subproof that defines it".to_string())
                         } else {
                             Ok(())
                         }
                     }
                     Term::FuncApp(_, args) => args.iter().try_for_each(|a| {
-                        check_term_not_contain_out_of_scope_boxed_consts(
-                            a, curr_scope, all_boxeds, line_num,
-                        )
+                        check_term_not_contain_out_of_scope_boxed_consts(a, curr_scope, all_boxeds)
                     }),
                 }
             }
             match wff {
                 Wff::Bottom => Ok(()),
                 Wff::And(li) | Wff::Or(li) => li.iter().try_for_each(|w| {
-                    check_wff_not_contain_out_of_scope_boxed_consts(
-                        w, curr_scope, all_boxeds, line_num,
-                    )
+                    check_wff_not_contain_out_of_scope_boxed_consts(w, curr_scope, all_boxeds)
                 }),
                 Wff::Implies(a, b) | Wff::Bicond(a, b) => {
-                    check_wff_not_contain_out_of_scope_boxed_consts(
-                        a, curr_scope, all_boxeds, line_num,
+                    check_wff_not_contain_out_of_scope_boxed_consts(a, curr_scope, all_boxeds).and(
+                        check_wff_not_contain_out_of_scope_boxed_consts(b, curr_scope, all_boxeds),
                     )
-                    .and(check_wff_not_contain_out_of_scope_boxed_consts(
-                        b, curr_scope, all_boxeds, line_num,
-                    ))
                 }
-                Wff::Not(w) => check_wff_not_contain_out_of_scope_boxed_consts(
-                    w, curr_scope, all_boxeds, line_num,
-                ),
+                Wff::Not(w) => {
+                    check_wff_not_contain_out_of_scope_boxed_consts(w, curr_scope, all_boxeds)
+                }
                 Wff::Forall(_, w) | Wff::Exists(_, w) => {
-                    check_wff_not_contain_out_of_scope_boxed_consts(
-                        w, curr_scope, all_boxeds, line_num,
-                    )
+                    check_wff_not_contain_out_of_scope_boxed_consts(w, curr_scope, all_boxeds)
                 }
-                Wff::Atomic(_) => Ok(()),
+                // a new atomic sentence can be boxed, see CheckOptions::propositional_quantifiers
+                Wff::Atomic(name) => check_term_not_contain_out_of_scope_boxed_consts(
+                    &Term::Atomic(name.to_string()),
+                    curr_scope,
+                    all_boxeds,
+                ),
                 Wff::PredApp(_, args) => args.iter().try_for_each(|t| {
-                    check_term_not_contain_out_of_scope_boxed_consts(
-                        t, curr_scope, all_boxeds, line_num,
-                    )
+                    check_term_not_contain_out_of_scope_boxed_consts(t, curr_scope, all_boxeds)
                 }),
                 Wff::Equals(s, t) => check_term_not_contain_out_of_scope_boxed_consts(
-                    s, curr_scope, all_boxeds, line_num,
+                    s, curr_scope, all_boxeds,
                 )
-                .and(check_term_not_contain_out_of_scope_boxed_consts(
-                    t, curr_scope, all_boxeds, line_num,
-                )),
+                .and(check_term_not_contain_out_of_scope_boxed_consts(t, curr_scope, all_boxeds)),
             }
         }
 
@@ -423,16 +1221,24 @@ impl Proof {
         &self,
         wff: &Wff,
         line_num: usize,
-    ) -> Result<(), String> {
+    ) -> Result<(), ProofError> {
         fn check_variable_scoping_naming_issues_helper(
             proof: &Proof,
             wff: &Wff,
             line_num: usize,
             bound_vars_in_scope: &mut Vec<String>,
-        ) -> Result<(), String> {
+        ) -> Result<(), ProofError> {
             match wff {
                 Wff::Bottom => Ok(()),
                 Wff::Atomic(_) => Ok(()),
+                Wff::PredApp(name, _) if bound_vars_in_scope.contains(name) => Err(proof.error_at(
+                    line_num,
+                    error_codes::VARIABLES,
+                    format!(
+                        "{name} is bound by a quantifier over sentences, so it can \
+                    only be used as an atomic sentence, not as a predicate."
+                    ),
+                )),
                 Wff::PredApp(_, args) => args.iter().try_for_each(|a| {
                     check_variable_scoping_naming_issues_helper_term(
                         proof,
@@ -482,12 +1288,31 @@ impl Proof {
                     bound_vars_in_scope,
                 )),
                 Wff::Forall(var, wff) | Wff::Exists(var, wff) => {
-                    if !proof.allowed_variable_names.contains(var) {
-                        Err(format!("Line {line_num}: you can only quantify over a variable, not over a constant."))
+                    if is_sentence_letter(var) && !proof.options.propositional_quantifiers {
+                        Err(proof.error_at(
+                            line_num,
+                            error_codes::VARIABLES,
+                            format!(
+                                "{var} is an atomic sentence, and you can only \
+                            quantify over sentences with the option propositional_quantifiers=on."
+                            ),
+                        ))
+                    } else if !proof.allowed_variable_names.contains(var)
+                        && !is_sentence_letter(var)
+                    {
+                        Err(proof.error_at(
+                            line_num,
+                            error_codes::VARIABLES,
+                            "you can only quantify over a variable, not over a constant."
+                                .to_string(),
+                        ))
                     } else if bound_vars_in_scope.contains(var) {
-                        Err(format!(
-                            "Line {line_num}: this line contains \
+                        Err(proof.error_at(
+                            line_num,
+                            error_codes::VARIABLES,
+                            "this line contains \
                                        two nested quantifiers over the same variable."
+                                .to_string(),
                         ))
                     } else {
                         bound_vars_in_scope.push(var.to_string());
@@ -509,13 +1334,26 @@ impl Proof {
             term: &Term,
             line_num: usize,
             bound_vars_in_scope: &mut Vec<String>,
-        ) -> Result<(), String> {
+        ) -> Result<(), ProofError> {
             match term {
                 Term::Atomic(str) => {
                     if proof.allowed_variable_names.contains(str)
                         && !bound_vars_in_scope.contains(str)
                     {
-                        Err(format!("Line {line_num}: this line contains unbound variables."))
+                        Err(proof.error_at(
+                            line_num,
+                            error_codes::VARIABLES,
+                            "this line contains unbound variables.".to_string(),
+                        ))
+                    } else if is_sentence_letter(str) && bound_vars_in_scope.contains(str) {
+                        Err(proof.error_at(
+                            line_num,
+                            error_codes::VARIABLES,
+                            format!(
+                                "{str} is bound by a quantifier over sentences, so \
+                            it can only be used as an atomic sentence, not as a term."
+                            ),
+                        ))
                     } else {
                         Ok(())
                     }
@@ -531,9 +1369,13 @@ impl Proof {
                         )
                     })
                     .and(if proof.allowed_variable_names.contains(name) {
-                        Err(format!(
-                            "Line {line_num}: you cannot have a function called \
+                        Err(proof.error_at(
+                            line_num,
+                            error_codes::VARIABLES,
+                            format!(
+                                "you cannot have a function called \
                                      {name}, because {name} is a reserved name for variables."
+                            ),
                         ))
                     } else {
                         Ok(())
@@ -547,33 +1389,28 @@ impl Proof {
     /// error messages, if for example from the arity set it can be determined that the user has
     /// functions of inconsistent arity throughout the proof (e.g. they use both f(x) and f(x,x)) or
     /// if for example the user uses some letter both as a constant name and a function name.
-    fn generate_arity_errors(&self) -> Vec<String> {
-        let mut errors: Vec<String> = vec![];
+    fn generate_arity_errors(&self) -> Vec<ProofError> {
+        let mut errors: Vec<ProofError> = vec![];
         let mut arity_map: HashMap<String, Vec<usize>> = HashMap::from([]);
         for (name, arity) in self.get_arity_set() {
-            if !arity_map.contains_key(&name) {
-                arity_map.insert(name.to_string(), vec![]);
-            }
-            if !arity_map.get(&name).unwrap().contains(&arity) {
-                arity_map.get_mut(&name).unwrap().push(arity);
+            let arities = arity_map.entry(name).or_default();
+            if !arities.contains(&arity) {
+                arities.push(arity);
             }
         }
         for (name, mut arities) in arity_map {
-            if arities.is_empty() {
-                panic!();
-            }
             if arities.len() > 1 {
                 arities.sort();
                 if arities.contains(&0) {
-                    if name.chars().next().unwrap().is_lowercase() {
-                        errors.push(format!("Error: it seems like you use the name \'{name}\' both to denote a constant, and to denote a function symbol"));
+                    if name.starts_with(char::is_lowercase) {
+                        errors.push(ProofError::about_proof(error_codes::ARITY, format!("Error: it seems like you use the name \'{name}\' both to denote a constant, and to denote a function symbol")));
                     } else {
-                        errors.push(format!("Error: it seems like you use the name \'{name}\' both to denote a nullary predicate (\'no inputs\'), and to denote a non-nullary predicate"));
+                        errors.push(ProofError::about_proof(error_codes::ARITY, format!("Error: it seems like you use the name \'{name}\' both to denote a nullary predicate (\'no inputs\'), and to denote a non-nullary predicate")));
                     }
-                } else if name.chars().next().unwrap().is_lowercase() {
-                    errors.push(format!("Error: it seems like \'{name}\' is meant to denote a function symbol, but throughout the proof, its arity is inconsistent. The found arities are {arities:?}"))
+                } else if name.starts_with(char::is_lowercase) {
+                    errors.push(ProofError::about_proof(error_codes::ARITY, format!("Error: it seems like \'{name}\' is meant to denote a function symbol, but throughout the proof, its arity is inconsistent. The found arities are {arities:?}")))
                 } else {
-                    errors.push(format!("Error: it seems like \'{name}\' is meant to denote a predicate, but throughout the proof, its arity is inconsistent. The found arities are {arities:?}"))
+                    errors.push(ProofError::about_proof(error_codes::ARITY, format!("Error: it seems like \'{name}\' is meant to denote a predicate, but throughout the proof, its arity is inconsistent. The found arities are {arities:?}")))
                 }
             }
         }
@@ -642,12 +1479,34 @@ impl Proof {
                     .filter_map(|line| line.constant_between_square_brackets.as_ref())
                     .map(|c| match c {
                         Term::Atomic(str) => (str.to_owned(), 0),
-                        Term::FuncApp(..) => panic!("boxed constant cannot be FuncApp"),
+                        Term::FuncApp(name, args) => (name.to_owned(), args.len()),
                     }),
             )
             .collect()
     }
 
+    /// Returns an error with code `code` about line `line_num`, whose message is `message` after
+    /// the line number, like `Line 3: the rule ...`. In a proof that is made from a sequent, the
+    /// message starts with `Premise 1: ...` instead, or just with `message` for the conclusion.
+    fn error_at(&self, line_num: usize, code: &'static str, message: String) -> ProofError {
+        let message = match self.sequent_premises {
+            Some(n) if (1..=n).contains(&line_num) => format!("Premise {line_num}: {message}"),
+            Some(n) if line_num == n + 1 => message,
+            _ => format!("Line {line_num}: {message}"),
+        };
+        ProofError::at_line(line_num, code, message)
+    }
+
+    /// Returns how line `line_num` is referred to in an error message: `line 3`, or `premise 1`
+    /// and `the conclusion` in a proof that is made from a sequent (see [check_sequent]).
+    fn line_name(&self, line_num: usize) -> String {
+        match self.sequent_premises {
+            Some(n) if (1..=n).contains(&line_num) => format!("premise {line_num}"),
+            Some(n) if line_num == n + 1 => "the conclusion".to_string(),
+            _ => format!("line {line_num}"),
+        }
+    }
+
     /// This function returns whether line n1 can reference line n2.
     ///
     /// For example, it will return `false` if line `n2` comes after line `n1` or if line `n2` is
@@ -666,23 +1525,129 @@ impl Proof {
         &self,
         referencing_line: usize,
         requested_line: usize,
-    ) -> Result<&Wff, String> {
+    ) -> Result<&Wff, ProofError> {
         let li = self.lines.iter().find(|l| l.line_num == Some(requested_line));
         if let Some(l) = li {
             if let Some(wff) = &l.sentence {
                 if self.can_reference(referencing_line, requested_line) {
                     Ok(wff)
                 } else if requested_line < referencing_line {
-                    Err(format!("Line {referencing_line}: line {requested_line} is referenced in the justification, but this is not allowed, because line {requested_line} is inside an already closed subproof."))
+                    Err(self.error_at(referencing_line, error_codes::SCOPE_VIOLATION, format!("{requested} is referenced in the justification, but this is not allowed, because {requested} is inside an already closed subproof.{}", self.closed_subproof_diagram(referencing_line, requested_line), requested = self.line_name(requested_line))))
                 } else {
-                    Err(format!("Line {referencing_line}: line {requested_line} is referenced in the justification, but this is not allowed, because line {requested_line} does not come before line {referencing_line}."))
+                    Err(self.error_at(referencing_line, error_codes::SCOPE_VIOLATION, format!("{requested} is referenced in the justification, but this is not allowed, because {requested} does not come before {referencing}.", requested = self.line_name(requested_line), referencing = self.line_name(referencing_line))))
                 }
             } else {
-                Err(format!("Line {referencing_line}: line {requested_line} is being referenced in the justification, but that line does not contain a sentence."))
+                Err(self.error_at(referencing_line, error_codes::BAD_CITATION, format!("{requested} is being referenced in the justification, but that line does not contain a sentence.", requested = self.line_name(requested_line))))
             }
         } else {
-            Err(format!("Line {referencing_line}: line {requested_line} is being referenced in the justification, but that line does not exist."))
+            Err(self.error_at(referencing_line, error_codes::BAD_CITATION, format!("{requested} is being referenced in the justification, but that line does not exist.", requested = self.line_name(requested_line))))
+        }
+    }
+
+    /// Returns a small diagram (preceded by a newline) of the part of the proof that explains why
+    /// line `requested_line` cannot be referenced from line `referencing_line`: the subproof that
+    /// contains `requested_line` is closed before `referencing_line`. For example:
+    ///
+    /// ```notrust
+    ///   2 | | B         <-- the subproof starts here
+    ///   3 | | A         <-- line 3 is inside the subproof
+    ///     | | ⋮
+    ///   5 | | C         <-- the subproof ends here
+    ///   6 | B → C       <-- line 6 is outside the subproof, so it cannot reference line 3
+    /// ```
+    ///
+    /// If there is no such closed subproof, an empty string is returned.
+    fn closed_subproof_diagram(&self, referencing_line: usize, requested_line: usize) -> String {
+        let position = |n: usize| self.lines.iter().position(|l| l.line_num == Some(n));
+        let (Some(requested_index), Some(referencing_index)) =
+            (position(requested_line), position(referencing_line))
+        else {
+            return "".to_string();
+        };
+        if requested_index >= referencing_index {
+            return "".to_string();
+        }
+        // the outermost subproof that contains the requested line, but not the referencing line
+        let depth = 1 + self.lines[requested_index..=referencing_index]
+            .iter()
+            .map(|l| l.depth)
+            .min()
+            .unwrap();
+        if self.lines[requested_index].depth < depth {
+            return "".to_string();
+        }
+        let inside = |l: &&ProofLine| l.depth >= depth;
+        let begin_index =
+            requested_index - self.lines[..requested_index].iter().rev().take_while(inside).count();
+        let end_index =
+            requested_index + self.lines[requested_index + 1..].iter().take_while(inside).count();
+        let (Some(begin_line), Some(end_line)) = (
+            self.lines[begin_index..].iter().find_map(|l| l.line_num),
+            self.lines[..=end_index].iter().rev().find_map(|l| l.line_num),
+        ) else {
+            return "".to_string();
+        };
+
+        let shown_lines: Vec<(usize, String)> = vec![
+            (begin_line, "the subproof starts here".to_string()),
+            (
+                requested_line,
+                format!(
+                    "{requested} is inside the subproof",
+                    requested = self.line_name(requested_line)
+                ),
+            ),
+            (end_line, "the subproof ends here".to_string()),
+            (
+                referencing_line,
+                format!(
+                    "{referencing} is outside the subproof, so it cannot reference \
+                    {requested}",
+                    referencing = self.line_name(referencing_line),
+                    requested = self.line_name(requested_line)
+                ),
+            ),
+        ];
+        let number_width = referencing_line.to_string().len();
+        let mut rows: Vec<(String, Option<String>)> = vec![];
+        let mut prev_index: Option<usize> = None;
+        for (line_num, note) in shown_lines {
+            let index = position(line_num).unwrap();
+            if prev_index == Some(index) {
+                // e.g. the requested line is the first line of the subproof
+                rows.last_mut().unwrap().1 = Some(note);
+                continue;
+            }
+            let line = &self.lines[index];
+            let first_skipped_line = prev_index
+                .and_then(|prev| self.lines[prev + 1..index].iter().find(|l| l.line_num.is_some()));
+            if let Some(skipped_line) = first_skipped_line {
+                let bars = "| ".repeat(skipped_line.depth);
+                rows.push((format!("{:number_width$} {bars}⋮", ""), None));
+            }
+            let mut content = "".to_string();
+            if let Some(Term::Atomic(c)) = &line.constant_between_square_brackets {
+                content.push_str(&format!("[{c}] "));
+            }
+            if let Some(wff) = &line.sentence {
+                content.push_str(&formatter::format_wff(wff));
+            }
+            rows.push((
+                format!("{line_num:>number_width$} {}{content}", "| ".repeat(line.depth)),
+                Some(note),
+            ));
+            prev_index = Some(index);
         }
+
+        let width = rows.iter().map(|(row, _)| row.chars().count()).max().unwrap_or(0);
+        rows.iter()
+            .map(|(row, note)| match note {
+                Some(note) => {
+                    format!("\n{row}{}  <-- {note}", " ".repeat(width - row.chars().count()))
+                }
+                None => format!("\n{row}"),
+            })
+            .collect()
     }
 
     /// This function gets the subproof that runs from line `subproof_begin` to line
@@ -693,6 +1658,11 @@ impl Proof {
     /// `referencing_line` is not allowed to reference this subproof and an error message will be
     /// returned.
     ///
+    /// If `subproof_begin == subproof_end` but there is no such one-line subproof, then the
+    /// subproof has probably been cited by its first line only. In that case, the subproof that
+    /// starts at that line is returned if [CheckOptions::allow_subproof_shorthand] is set, and an
+    /// error message that tells how to cite it properly otherwise.
+    ///
     /// Precondition: referencing_line is an existing line (and the scope needs to be computed
     /// already, but that is always the case since we are working on an already-instantiated Proof
     /// instance, and those cannot be created if their scope cannot be determined)
@@ -700,18 +1670,46 @@ impl Proof {
         &self,
         referencing_line: usize,
         (subproof_begin, subproof_end): (usize, usize),
-    ) -> Result<(&ProofLine, &ProofLine), String> {
+    ) -> Result<(&ProofLine, &ProofLine), ProofError> {
+        let mut subproof_end = subproof_end;
+        if subproof_begin == subproof_end
+            && !self.scope[referencing_line].1.contains(&(subproof_begin, subproof_end))
+        {
+            // the subproof may have been cited by its first line only
+            if let Some((_, end)) =
+                self.scope[referencing_line].1.iter().find(|(begin, _)| *begin == subproof_begin)
+            {
+                if !self.options.allow_subproof_shorthand {
+                    return Err(self.error_at(
+                        referencing_line,
+                        error_codes::BAD_CITATION,
+                        format!(
+                            "the subproof that starts at \
+                        {line_begin} is referenced, but it should be referenced by its first \
+                        and its last line, like this: {subproof_begin}-{end}.",
+                            line_begin = self.line_name(subproof_begin)
+                        ),
+                    ));
+                }
+                subproof_end = *end;
+            }
+        }
         if self.scope[referencing_line].1.contains(&(subproof_begin, subproof_end)) {
             let s_begin = self.lines.iter().find(|l| l.line_num == Some(subproof_begin)).unwrap();
             // the unwrap should work, since `scope` should refer only to valid line numbers
             let s_end = self.lines.iter().find(|l| l.line_num == Some(subproof_end)).unwrap();
             Ok((s_begin, s_end))
         } else {
-            Err(format!(
-                "Line {referencing_line}: the referenced \
+            Err(self.error_at(
+                referencing_line,
+                error_codes::SCOPE_VIOLATION,
+                format!(
+                    "the referenced \
                         subproof {subproof_begin}-{subproof_end} is \
-                        not in the scope of line {referencing_line}, \
-                        or it does not exist."
+                        not in the scope of {referencing}, \
+                        or it does not exist.",
+                    referencing = self.line_name(referencing_line)
+                ),
             ))
         }
     }
@@ -724,7 +1722,7 @@ impl Proof {
     /// line, or an empty line, since in those cases there is no justification to check.
     ///
     /// Note that the provided [ProofLine] should exist in the proof!
-    fn check_line(&self, line: &ProofLine) -> Result<(), String> {
+    fn check_line(&self, line: &ProofLine) -> Result<(), ProofError> {
         // this function only checks lines that have a justification...
         if line.justification.is_none() {
             return Ok(());
@@ -737,49 +1735,81 @@ impl Proof {
 
         let (curr_wff, just) =
             (line.sentence.as_ref().unwrap(), line.justification.as_ref().unwrap());
+        if self.options.negation == NegationConvention::DefinedByBottom
+            && matches!(just, Justification::NotIntro(_))
+        {
+            return Err(self.error_at(
+                curr_line_num,
+                error_codes::RULE_NOT_ALLOWED,
+                "the rule ¬Intro is used, but here ¬φ is short for φ → ⊥, so there is no rule \
+                ¬Intro. Use →Intro with a subproof that ends with ⊥ instead."
+                    .to_string(),
+            ));
+        }
+        if let Some(err) = self.reiteration_error(curr_line_num, just) {
+            return Err(err);
+        }
+        if let Some(res) = self.check_propositional_quantifier_rule(curr_line_num, curr_wff, just) {
+            return res;
+        }
         match just {
             Justification::Reit(n) => {
                 let ref_wff = self.get_wff_at_line(curr_line_num, *n)?;
                 if curr_wff == ref_wff {
                     Ok(())
                 } else {
-                    Err(format!(
-                        "Line {curr_line_num}: the \
+                    Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        "the \
                                            proof rule Reit is used, but the sentence \
                                            in this line is not the same as the sentence \
                                            in the referenced line."
+                            .to_string(),
                     ))
                 }
             }
             Justification::AndIntro(ns) => {
                 if let Wff::And(conjs) = curr_wff {
                     if ns.len() != conjs.len() {
-                        return Err(format!(
-                            "Line {curr_line_num}: the rule ∧Intro is used, but the number of \
-                            conjuncts ({}) of the sentence in line {curr_line_num} is not equal \
+                        return Err(self.error_at(
+                            curr_line_num,
+                            error_codes::RULE_MISAPPLIED,
+                            format!(
+                                "the rule ∧Intro is used, but the number of \
+                            conjuncts ({}) of the sentence in {curr_line} is not equal \
                             to the number of referenced proof lines ({}).",
-                            conjs.len(),
-                            ns.len()
+                                conjs.len(),
+                                ns.len(),
+                                curr_line = self.line_name(curr_line_num)
+                            ),
                         ));
                     }
                     for i in 0..ns.len() {
                         if &conjs[i] != self.get_wff_at_line(curr_line_num, ns[i])? {
-                            return Err(format!(
-                                "Line {curr_line_num}: the rule ∧Intro is used, but the {}\'th \
+                            return Err(self.error_at(
+                                curr_line_num,
+                                error_codes::RULE_MISAPPLIED,
+                                format!(
+                                    "the rule ∧Intro is used, but the {}\'th \
                                 conjunct of the sentence in that line is not the same as \
-                                the sentence found in line {} (the {}\'th \
+                                the sentence found in {} (the {}\'th \
                                 line referenced in the justification).",
-                                i + 1,
-                                ns[i],
-                                i + 1
+                                    i + 1,
+                                    self.line_name(ns[i]),
+                                    i + 1
+                                ),
                             ));
                         }
                     }
                     Ok(())
                 } else {
-                    Err(format!(
-                        "Line {curr_line_num}: the justification ∧Intro is \
+                    Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        "the justification ∧Intro is \
                                 used, but the top-level connective of this line is not ∧."
+                            .to_string(),
                     ))
                 }
             }
@@ -789,18 +1819,29 @@ impl Proof {
                     if conjs.iter().any(|conj| conj == curr_wff) {
                         Ok(())
                     } else {
-                        Err(format!(
-                            "Line {curr_line_num}: the justification \
+                        Err(self.error_at(
+                            curr_line_num,
+                            error_codes::RULE_MISAPPLIED,
+                            format!(
+                                "the justification \
                             ∧Elim: {n} is used, but none of the \
-                            conjuncts in line {n} is identical \
-                            to the sentence found in line {curr_line_num}."
+                            conjuncts in {line_n} is identical \
+                            to the sentence found in {curr_line}.",
+                                line_n = self.line_name(*n),
+                                curr_line = self.line_name(curr_line_num)
+                            ),
                         ))
                     }
                 } else {
-                    Err(format!(
-                        "Line {curr_line_num}: the justification \
+                    Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "the justification \
                         ∧Elim: {n} is used, but the top-level \
-                        connective of line {n} is not a conjunction."
+                        connective of {line_n} is not a conjunction.",
+                            line_n = self.line_name(*n)
+                        ),
                     ))
                 }
             }
@@ -810,37 +1851,65 @@ impl Proof {
                     if disjs.iter().any(|disj| disj == ref_wff) {
                         Ok(())
                     } else {
-                        Err(format!(
-                            "Line {curr_line_num}: the justification \
+                        Err(self.error_at(
+                            curr_line_num,
+                            error_codes::RULE_MISAPPLIED,
+                            format!(
+                                "the justification \
                             ∨Intro: {n} is used, but none of the \
-                            disjuncts in line {curr_line_num} is identical \
-                            to the sentence found in line {n}."
+                            disjuncts in {curr_line} is identical \
+                            to the sentence found in {line_n}.",
+                                curr_line = self.line_name(curr_line_num),
+                                line_n = self.line_name(*n)
+                            ),
                         ))
                     }
                 } else {
-                    Err(format!(
-                        "Line {curr_line_num}: the justification \
+                    Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        "the justification \
                         ∨Intro is used, but the top-level \
                         connective of this line is not a disjunction."
+                            .to_string(),
                     ))
                 }
             }
             Justification::OrElim(n, subproofs) => {
                 let Wff::Or(disjs) = self.get_wff_at_line(curr_line_num, *n)? else {
-                    return Err(format!(
-                        "Line {curr_line_num}: ∨Elim: {n}, ..... \
+                    return Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "∨Elim: {n}, ..... \
                         is used, but the top-level connective of \
-                        the sentence at line {n} is not ∨."
+                        the sentence at {line_n} is not ∨.",
+                            line_n = self.line_name(*n)
+                        ),
                     ));
                 };
+                // a case split over A ∨ (B ∨ C) may also consist of three subproofs (one for each
+                // of A, B and C) instead of two
+                let flattened_disjs = flatten_disjunction(disjs);
+                let disjs: Vec<&Wff> =
+                    if disjs.len() != subproofs.len() && flattened_disjs.len() == subproofs.len() {
+                        flattened_disjs
+                    } else {
+                        disjs.iter().collect()
+                    };
                 if disjs.len() != subproofs.len() {
-                    return Err(format!(
-                        "Line {curr_line_num}: the rule ∨Elim: {n}, ..... \
+                    return Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "the rule ∨Elim: {n}, ..... \
                             is used, but the number of disjuncts ({}) \
-                            of the sentence in line {n} is not equal to \
+                            of the sentence in {line_n} is not equal to \
                             the number of referenced subproofs ({}).",
-                        disjs.len(),
-                        subproofs.len()
+                            disjs.len(),
+                            subproofs.len(),
+                            line_n = self.line_name(*n)
+                        ),
                     ));
                 }
                 for (disj, subprf) in zip(disjs, subproofs) {
@@ -850,28 +1919,41 @@ impl Proof {
                         &s_end.sentence,
                         &s_begin.constant_between_square_brackets,
                     ) else {
-                        return Err(format!(
-                            "Line {curr_line_num}: when using ∨Elim, \
+                        return Err(self.error_at(
+                            curr_line_num,
+                            error_codes::RULE_MISAPPLIED,
+                            "when using ∨Elim, \
                             you cannot reference subproofs which \
                             introduce a boxed constant."
+                                .to_string(),
                         ));
                     };
                     if disj != s_begin_wff {
-                        return Err(format!(
-                            "Line {curr_line_num}: ∨Elim: {n}, ..... \
+                        return Err(self.error_at(
+                            curr_line_num,
+                            error_codes::RULE_MISAPPLIED,
+                            format!(
+                                "∨Elim: {n}, ..... \
                             is used, but the premise of one of the \
                             referenced subproofs does not match the \
-                            corresponding disjunct of the sentence at line {n}. \
+                            corresponding disjunct of the sentence at {line_n}. \
                             Note that the subproofs should be referenced in the \
                             order in which their corresponding premises \
-                            appear as disjuncts in the sentence at line {n}."
+                            appear as disjuncts in the sentence at {line_n}.",
+                                line_n = self.line_name(*n)
+                            ),
                         ));
                     }
                     if s_end_wff != curr_wff {
-                        return Err(format!(
-                            "Line {curr_line_num}: ∨Elim \
+                        return Err(self.error_at(
+                            curr_line_num,
+                            error_codes::RULE_MISAPPLIED,
+                            format!(
+                                "∨Elim \
                             is used, but not all referenced subproofs end with \
-                            the same sentence as the sentence in line {curr_line_num}."
+                            the same sentence as the sentence in {curr_line}.",
+                                curr_line = self.line_name(curr_line_num)
+                            ),
                         ));
                     }
                 }
@@ -879,10 +1961,13 @@ impl Proof {
             }
             Justification::ImpliesIntro((n, m)) => {
                 let Wff::Implies(a, b) = curr_wff else {
-                    return Err(format!(
-                        "Line {curr_line_num}: →Intro is used, but \
+                    return Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        "→Intro is used, but \
                             the top-level connective of the sentence at this line \
                             is not an implication."
+                            .to_string(),
                     ));
                 };
                 let (s_begin, s_end) = self.get_subproof_at_lines(curr_line_num, (*n, *m))?;
@@ -890,31 +1975,49 @@ impl Proof {
                     (&s_begin.sentence, &s_end.sentence, &s_begin.constant_between_square_brackets)
                 {
                     if **a != *s_begin_wff && **b == *s_end_wff {
-                        Err(format!(
-                            "Line {curr_line_num}: →Intro is used, but \
+                        Err(self.error_at(
+                            curr_line_num,
+                            error_codes::RULE_MISAPPLIED,
+                            format!(
+                                "→Intro is used, but \
                                 the premise of the referenced subproof does not match the \
-                                antecedent of the implication found in line {curr_line_num}."
+                                antecedent of the implication found in {curr_line}.",
+                                curr_line = self.line_name(curr_line_num)
+                            ),
                         ))
                     } else if **a == *s_begin_wff && **b != *s_end_wff {
-                        Err(format!(
-                            "Line {curr_line_num}: →Intro is used, but \
+                        Err(self.error_at(
+                            curr_line_num,
+                            error_codes::RULE_MISAPPLIED,
+                            format!(
+                                "→Intro is used, but \
                                 the last sentence of the referenced subproof does not match the \
-                                consequent of the implication found in line {curr_line_num}."
+                                consequent of the implication found in {curr_line}.",
+                                curr_line = self.line_name(curr_line_num)
+                            ),
                         ))
                     } else if **a != *s_begin_wff && **b != *s_end_wff {
-                        Err(format!(
-                            "Line {curr_line_num}: →Intro is used, but \
+                        Err(self.error_at(
+                            curr_line_num,
+                            error_codes::RULE_MISAPPLIED,
+                            format!(
+                                "→Intro is used, but \
                                 the premise and last sentence of the referenced subproof \
                                 do not match the antecedent and the consequent, respectively, \
-                                of the implication found in line {curr_line_num}."
+                                of the implication found in {curr_line}.",
+                                curr_line = self.line_name(curr_line_num)
+                            ),
                         ))
                     } else {
                         Ok(())
                     }
                 } else {
-                    Err(format!(
-                        "Line {curr_line_num}: when using →Intro, you \
+                    Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        "when using →Intro, you \
                         cannot reference a subproof that introduces a boxed constant."
+                            .to_string(),
                     ))
                 }
             }
@@ -924,16 +2027,24 @@ impl Proof {
                     if *wff_m == **wff1 && **wff2 == *curr_wff {
                         Ok(())
                     } else {
-                        Err(format!(
-                            "Line {curr_line_num}: the rule \
+                        Err(self.error_at(
+                            curr_line_num,
+                            error_codes::RULE_MISAPPLIED,
+                            "the rule \
                                                →Elim is wrongly used."
+                                .to_string(),
                         ))
                     }
                 } else {
-                    Err(format!(
-                        "Line {curr_line_num}: the rule \
+                    Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "the rule \
                                            →Elim: {n}, {m} is used, but the top-level \
-                                           connective of line {n} is not an implication."
+                                           connective of {line_n} is not an implication.",
+                            line_n = self.line_name(*n)
+                        ),
                     ))
                 }
             }
@@ -958,34 +2069,117 @@ impl Proof {
                         &s_begin1.constant_between_square_brackets,
                         &s_begin2.constant_between_square_brackets,
                     ) {
-                        if **p == *s_begin_wff1
-                            && **q == *s_end_wff1
-                            && **p == *s_end_wff2
-                            && **q == *s_begin_wff2
+                        let goes_from = |from: &Wff, to: &Wff, begin: &Wff, end: &Wff| {
+                            *from == *begin && *to == *end
+                        };
+                        if goes_from(p, q, s_begin_wff1, s_end_wff1)
+                            && goes_from(q, p, s_begin_wff2, s_end_wff2)
                         {
                             Ok(())
+                        } else if goes_from(q, p, s_begin_wff1, s_end_wff1)
+                            && goes_from(p, q, s_begin_wff2, s_end_wff2)
+                        {
+                            if self.options.allow_any_bicond_order {
+                                Ok(())
+                            } else {
+                                Err(self.error_at(
+                                    curr_line_num,
+                                    error_codes::RULE_MISAPPLIED,
+                                    format!(
+                                        "↔Intro is used with the subproofs in \
+                                    the wrong order. To infer P↔Q, first cite the subproof that \
+                                    goes from P to Q, and then the subproof that goes from Q to P, \
+                                    like this: ↔Intro: {sb2}-{se2}, {sb1}-{se1}"
+                                    ),
+                                ))
+                            }
                         } else {
-                            Err(format!("Line {curr_line_num}: when using ↔Intro to infer P↔Q, you must first cite the subproof that proves P→Q, and then the subproof that proves Q→P."))
+                            let from_to = |begin: &Wff, end: &Wff| {
+                                goes_from(p, q, begin, end) || goes_from(q, p, begin, end)
+                            };
+                            let (b, e, begin, end) = match from_to(s_begin_wff1, s_end_wff1) {
+                                true => (sb2, se2, s_begin_wff2, s_end_wff2),
+                                false => (sb1, se1, s_begin_wff1, s_end_wff1),
+                            };
+                            Err(self.error_at(
+                                curr_line_num,
+                                error_codes::RULE_MISAPPLIED,
+                                format!(
+                                    "↔Intro is used, but the subproof {b}-{e} \
+                                goes from {} to {}. To infer {}, cite a subproof that goes from \
+                                {} to {} and a subproof that goes from {} to {}.",
+                                    formatter::format_wff(begin),
+                                    formatter::format_wff(end),
+                                    formatter::format_wff(curr_wff),
+                                    formatter::format_wff(p),
+                                    formatter::format_wff(q),
+                                    formatter::format_wff(q),
+                                    formatter::format_wff(p),
+                                ),
+                            ))
                         }
                     } else {
-                        Err(format!("Line {curr_line_num}: when using ↔Intro, you cannot reference a subproof that introduces a boxed constant."))
+                        Err(self.error_at(curr_line_num, error_codes::RULE_MISAPPLIED, "when using ↔Intro, you cannot reference a subproof that introduces a boxed constant.".to_string()))
                     }
                 } else {
-                    Err(format!("Line {curr_line_num}: ↔Intro is used, but the top-level connective of this sentence is not a bi-implication."))
+                    Err(self.error_at(curr_line_num, error_codes::RULE_MISAPPLIED, "↔Intro is used, but the top-level connective of this sentence is not a bi-implication.".to_string()))
                 }
             }
             Justification::BicondElim(n, m) => {
-                if let Wff::Bicond(wff1, wff2) = self.get_wff_at_line(curr_line_num, *n)? {
-                    let wff_m = self.get_wff_at_line(curr_line_num, *m)?;
-                    if (*wff_m == **wff1 && **wff2 == *curr_wff)
-                        || (*wff_m == **wff2 && **wff1 == *curr_wff)
-                    {
+                let (mut n, mut m) = (*n, *m);
+                let is_bicond = |k: usize| -> Result<bool, ProofError> {
+                    Ok(matches!(self.get_wff_at_line(curr_line_num, k)?, Wff::Bicond(..)))
+                };
+                if !is_bicond(n)? && is_bicond(m)? {
+                    if !self.options.allow_any_bicond_order {
+                        return Err(self.error_at(
+                            curr_line_num,
+                            error_codes::RULE_MISAPPLIED,
+                            format!(
+                                "the rule ↔Elim: {n}, {m} is used, but the \
+                            bi-implication should be cited first, like this: ↔Elim: {m}, {n}"
+                            ),
+                        ));
+                    }
+                    (n, m) = (m, n);
+                }
+                if let Wff::Bicond(wff1, wff2) = self.get_wff_at_line(curr_line_num, n)? {
+                    let wff_m = self.get_wff_at_line(curr_line_num, m)?;
+                    let (direction, from, to) = if *wff_m == **wff1 {
+                        ("left to right", wff1, wff2)
+                    } else if *wff_m == **wff2 {
+                        ("right to left", wff2, wff1)
+                    } else {
+                        return Err(self.error_at(
+                            curr_line_num,
+                            error_codes::RULE_MISAPPLIED,
+                            format!(
+                                "the rule ↔Elim: {n}, {m} is used, but the \
+                            sentence in {line_m} is neither side of the bi-implication in \
+                            {line_n}.",
+                                line_m = self.line_name(m),
+                                line_n = self.line_name(n)
+                            ),
+                        ));
+                    };
+                    if **to == *curr_wff {
                         Ok(())
                     } else {
-                        Err(format!("Line {curr_line_num}: the rule ↔Elim is wrongly used."))
+                        Err(self.error_at(
+                            curr_line_num,
+                            error_codes::RULE_MISAPPLIED,
+                            format!(
+                                "the rule ↔Elim: {n}, {m} is used from {direction} \
+                            (from {} in {line_m}), so it gives {}, which is not the sentence in \
+                            this line.",
+                                formatter::format_wff(from),
+                                formatter::format_wff(to),
+                                line_m = self.line_name(m)
+                            ),
+                        ))
                     }
                 } else {
-                    Err(format!("Line {curr_line_num}: the rule ↔Elim: {n}, {m} is used, but the top-level connective of line {n} is not a bi-implication."))
+                    Err(self.error_at(curr_line_num, error_codes::RULE_MISAPPLIED, format!("the rule ↔Elim: {n}, {m} is used, but the top-level connective of {line_n} is not a bi-implication.", line_n = self.line_name(n))))
                 }
             }
             Justification::NotIntro((n, m)) => {
@@ -998,35 +2192,73 @@ impl Proof {
                             if *s_end_wff == Wff::Bottom {
                                 Ok(())
                             } else {
-                                Err(format!(
-                                    "Line {curr_line_num}: ¬Intro is used, \
+                                Err(self.error_at(
+                                    curr_line_num,
+                                    error_codes::RULE_MISAPPLIED,
+                                    "¬Intro is used, \
                                     but the last sentence in the referenced \
                                     subproof is not ⊥."
+                                        .to_string(),
                                 ))
                             }
                         } else {
-                            Err(format!(
-                                "Line {curr_line_num}: ¬Intro is \
+                            Err(self.error_at(
+                                curr_line_num,
+                                error_codes::RULE_MISAPPLIED,
+                                "¬Intro is \
                                             used, but the negation of the premise \
                                             of the referenced subproof does \
                                             not match this line."
+                                    .to_string(),
                             ))
                         }
                     } else {
-                        Err(format!(
-                            "Line {curr_line_num}: ¬Intro is \
+                        Err(self.error_at(
+                            curr_line_num,
+                            error_codes::RULE_MISAPPLIED,
+                            "¬Intro is \
                             used, but the referenced subproof is not \
                             of the proper form. You cannot use ¬Intro \
                             on a subproof that introduces a boxed constant."
+                                .to_string(),
                         ))
                     }
                 } else {
-                    Err(format!(
-                        "Line {curr_line_num}: ¬Intro is used, \
+                    Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        "¬Intro is used, \
                         but the top-level connective of the sentence in this line is not ¬."
+                            .to_string(),
                     ))
                 }
             }
+            Justification::NotElim(n)
+                if self.options.negation == NegationConvention::DefinedByBottom =>
+            {
+                // ¬¬φ is (φ → ⊥) → ⊥ here
+                let negated =
+                    |wff: &Wff| Wff::Implies(Box::new(wff.clone()), Box::new(Wff::Bottom));
+                let cited = self.get_wff_at_line(curr_line_num, *n)?;
+                if *cited == negated(&negated(curr_wff)) {
+                    return Ok(());
+                }
+                if let Wff::Implies(negd_wff, bottom) = curr_wff {
+                    if **bottom == Wff::Bottom && **negd_wff == negated(cited) {
+                        return Err(self.error_at(
+                            curr_line_num,
+                            error_codes::RULE_MISAPPLIED,
+                            "¬Elim can only be used to go from ¬¬P to P, not the other way around"
+                                .to_string(),
+                        ));
+                    }
+                }
+                Err(self.error_at(
+                    curr_line_num,
+                    error_codes::RULE_MISAPPLIED,
+                    "¬Elim is used improperly".to_string(),
+                ))
+            }
             Justification::NotElim(n) => {
                 if let Wff::Not(negd_wff) = self.get_wff_at_line(curr_line_num, *n)? {
                     if let Wff::Not(negd_negd_wff) = &**negd_wff {
@@ -1038,33 +2270,52 @@ impl Proof {
                 if let Wff::Not(negd_wff) = curr_wff {
                     if let Wff::Not(negd_negd_wff) = &**negd_wff {
                         if *self.get_wff_at_line(curr_line_num, *n)? == **negd_negd_wff {
-                            return Err(format!("Line {curr_line_num}: ¬Elim can only be used to go from ¬¬P to P, not the other way around"));
+                            return Err(self.error_at(curr_line_num, error_codes::RULE_MISAPPLIED, "¬Elim can only be used to go from ¬¬P to P, not the other way around".to_string()));
                         }
                     }
                 }
-                Err(format!("Line {curr_line_num}: ¬Elim is used improperly"))
+                Err(self.error_at(
+                    curr_line_num,
+                    error_codes::RULE_MISAPPLIED,
+                    "¬Elim is used improperly".to_string(),
+                ))
             }
             Justification::BottomIntro(n, m) => {
                 let wff1 = self.get_wff_at_line(curr_line_num, *n)?;
                 let wff2 = self.get_wff_at_line(curr_line_num, *m)?;
-                if let Wff::Not(negd_wff) = wff2 {
-                    if *wff1 == **negd_wff {
-                        return Ok(());
+                let negated_wff1 = match self.options.negation {
+                    NegationConvention::Primitive => Wff::Not(Box::new(wff1.clone())),
+                    NegationConvention::DefinedByBottom => {
+                        Wff::Implies(Box::new(wff1.clone()), Box::new(Wff::Bottom))
                     }
+                };
+                if *wff2 == negated_wff1 {
+                    return Ok(());
                 }
-                Err(format!(
-                    "Line {curr_line_num}: ⊥Intro: {n}, {m} is used, \
-                    but the sentence at line {m} is not the negation \
-                    of the sentence at line {n}"
+                Err(self.error_at(
+                    curr_line_num,
+                    error_codes::RULE_MISAPPLIED,
+                    format!(
+                        "⊥Intro: {n}, {m} is used, \
+                    but the sentence at {line_m} is not the negation \
+                    of the sentence at {line_n}",
+                        line_m = self.line_name(*m),
+                        line_n = self.line_name(*n)
+                    ),
                 ))
             }
             Justification::BottomElim(n) => {
                 if let Wff::Bottom = self.get_wff_at_line(curr_line_num, *n)? {
                     Ok(())
                 } else {
-                    Err(format!(
-                        "Line {curr_line_num}: ⊥Elim: {n} is \
-                        used, but the sentence at line {n} is not ⊥."
+                    Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "⊥Elim: {n} is \
+                        used, but the sentence at {line_n} is not ⊥.",
+                            line_n = self.line_name(*n)
+                        ),
                     ))
                 }
             }
@@ -1074,14 +2325,23 @@ impl Proof {
                         return Ok(());
                     }
                 }
-                Err(format!("Line {curr_line_num}: =Intro is wrongly used"))
+                Err(self.error_at(
+                    curr_line_num,
+                    error_codes::RULE_MISAPPLIED,
+                    "=Intro is wrongly used".to_string(),
+                ))
             }
             Justification::EqualsElim(n, m) => {
                 let Wff::Equals(subst_old, subst_new) = self.get_wff_at_line(curr_line_num, *m)?
                 else {
-                    return Err(format!(
-                        "Line {curr_line_num}: the rule =Elim:{n},{m} \
-                        is used, but line {m} is not of the form (term1) = (term2)"
+                    return Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "the rule =Elim:{n},{m} \
+                        is used, but {line_m} is not of the form (term1) = (term2)",
+                            line_m = self.line_name(*m)
+                        ),
                     ));
                 };
 
@@ -1092,35 +2352,116 @@ impl Proof {
                 ) {
                     Ok(())
                 } else {
-                    Err(format!(
-                        "Line {curr_line_num}: the rule =Elim:{n},{m} \
-                            is used, but is is impossible to obtain line {curr_line_num} \
-                            from line {n} by changing one or more occurrences of {} to {}",
-                        formatter::format_term(subst_old),
-                        formatter::format_term(subst_new),
+                    Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "the rule =Elim:{n},{m} \
+                            is used, but is is impossible to obtain {curr_line} \
+                            from {line_n} by changing one or more occurrences of {} to {}",
+                            formatter::format_term(subst_old),
+                            formatter::format_term(subst_new),
+                            curr_line = self.line_name(curr_line_num),
+                            line_n = self.line_name(*n)
+                        ),
+                    ))
+                }
+            }
+            Justification::EqualsChain(ns) => {
+                if !self.options.allow_equality_chains {
+                    return Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_NOT_ALLOWED,
+                        "the rule =Chain is used, but chains of equalities \
+                        are not allowed here. Use =Elim (one equality at a time) instead."
+                            .to_string(),
+                    ));
+                }
+                let Wff::Equals(first_term, last_term) = curr_wff else {
+                    return Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        "the rule =Chain is used, but the sentence in this \
+                        line is not of the form (term1) = (term2)"
+                            .to_string(),
+                    ));
+                };
+                // walk along the chain, starting at the left-hand side of the current line
+                let mut term = first_term;
+                for n in ns {
+                    let Wff::Equals(t1, t2) = self.get_wff_at_line(curr_line_num, *n)? else {
+                        return Err(self.error_at(
+                            curr_line_num,
+                            error_codes::RULE_MISAPPLIED,
+                            format!(
+                                "the rule =Chain is used, but {line_n} is not \
+                            of the form (term1) = (term2)",
+                                line_n = self.line_name(*n)
+                            ),
+                        ));
+                    };
+                    term = if t1 == term {
+                        t2
+                    } else if t2 == term {
+                        t1
+                    } else {
+                        return Err(self.error_at(
+                            curr_line_num,
+                            error_codes::RULE_MISAPPLIED,
+                            format!(
+                                "the rule =Chain is used, but the equality in \
+                            {line_n} does not continue the chain, because it does not contain {}",
+                                formatter::format_term(term),
+                                line_n = self.line_name(*n)
+                            ),
+                        ));
+                    };
+                }
+                if term == last_term {
+                    Ok(())
+                } else {
+                    Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "the rule =Chain is used, but the chain of \
+                        equalities leads from {} to {}, not to {}",
+                            formatter::format_term(first_term),
+                            formatter::format_term(term),
+                            formatter::format_term(last_term)
+                        ),
                     ))
                 }
             }
             Justification::ForallIntro((sb, se)) => {
                 let Wff::Forall(var, forall_curr_wff) = curr_wff else {
-                    return Err(format!(
-                        "Line {curr_line_num}: the rule ∀Intro is used, \
+                    return Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        "the rule ∀Intro is used, \
                     but the sentence at this line is not universally quantified at the top-level"
+                            .to_string(),
                     ));
                 };
                 let (s_begin, s_end) = self.get_subproof_at_lines(curr_line_num, (*sb, *se))?;
                 let Some(boxed_const @ Term::Atomic(bc)) =
                     &s_begin.constant_between_square_brackets
                 else {
-                    return Err(format!(
-                        "Line {curr_line_num}: the rule ∀Intro is used, but the \
+                    return Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        "the rule ∀Intro is used, but the \
                         referenced subproof does not introduce a boxed constant"
+                            .to_string(),
                     ));
                 };
                 if s_begin.sentence.is_some() {
-                    return Err(format!(
-                            "Line {curr_line_num}: when using ∀Intro, the premise of the referenced subproof \
+                    return Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        "when using ∀Intro, the premise of the referenced subproof \
                             should consist of solely a boxed constant, without a sentence"
+                            .to_string(),
                     ));
                 }
                 if apply_trivial_substitution_everywhere_to_wff(
@@ -1128,17 +2469,22 @@ impl Proof {
                     (&Term::Atomic(var.to_string()), boxed_const),
                 ) != *s_end.sentence.as_ref().unwrap()
                 {
-                    return Err(format!("Line {curr_line_num}: the rule ∀Intro:{sb}-{se} is used, but if all occurrences of {var} in the quantified part of line {curr_line_num} are replaced by {bc}, one does not obtain the sentence in line {se}"));
+                    return Err(self.error_at(curr_line_num, error_codes::RULE_MISAPPLIED, format!("the rule ∀Intro:{sb}-{se} is used, but if all occurrences of {var} in the quantified part of {curr_line} are replaced by {bc}, one does not obtain the sentence in {line_se}", curr_line = self.line_name(curr_line_num), line_se = self.line_name(*se))));
                 }
 
                 Ok(())
             }
             Justification::ForallElim(n) => {
                 let Wff::Forall(var, ref_wff) = self.get_wff_at_line(curr_line_num, *n)? else {
-                    return Err(format!(
-                        "Line {curr_line_num}: the justification \
-                        ∀Elim:{n} is used, but the sentence at line {n} is not a \
-                        universally quantified sentence at the top level"
+                    return Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "the justification \
+                        ∀Elim:{n} is used, but the sentence at {line_n} is not a \
+                        universally quantified sentence at the top level",
+                            line_n = self.line_name(*n)
+                        ),
                     ));
                 };
                 if let Some((term1, term2)) =
@@ -1151,13 +2497,17 @@ impl Proof {
                         return if self.is_closed_term(&term2) {
                             Ok(())
                         } else {
-                            Err(format!(
-                                "Line {curr_line_num}: the rule ∀Elim:{n} is used, \
+                            Err(self.error_at(
+                                curr_line_num,
+                                error_codes::RULE_MISAPPLIED,
+                                format!(
+                                    "the rule ∀Elim:{n} is used, \
                                  but {} is not a closed term (so you cannot substitute {}
-                                 for all occurences of {var} in line {})",
-                                formatter::format_term(&term2),
-                                formatter::format_term(&term2),
-                                *n
+                                 for all occurences of {var} in {})",
+                                    formatter::format_term(&term2),
+                                    formatter::format_term(&term2),
+                                    self.line_name(*n)
+                                ),
                             ))
                         };
                     }
@@ -1165,17 +2515,28 @@ impl Proof {
                 if &**ref_wff == curr_wff {
                     return Ok(());
                 }
-                Err(format!(
-                    "Line {curr_line_num}: the rule ∀Elim:{n} is used, but there is no \
-                    appropriate substitution between line {n} and line {curr_line_num}"
+                Err(self.error_at(
+                    curr_line_num,
+                    error_codes::RULE_MISAPPLIED,
+                    format!(
+                        "the rule ∀Elim:{n} is used, but there is no \
+                    appropriate substitution between {line_n} and {curr_line}",
+                        line_n = self.line_name(*n),
+                        curr_line = self.line_name(curr_line_num)
+                    ),
                 ))
             }
             Justification::ExistsIntro(n) => {
                 let Wff::Exists(var, exists_curr_wff) = curr_wff else {
-                    return Err(format!(
-                        "Line {curr_line_num}: the justification \
-                        ∃Intro:{n} is used, but the sentence at line {curr_line_num} is not an \
-                        existentially quantified sentence at the top level"
+                    return Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "the justification \
+                        ∃Intro:{n} is used, but the sentence at {curr_line} is not an \
+                        existentially quantified sentence at the top level",
+                            curr_line = self.line_name(curr_line_num)
+                        ),
                     ));
                 };
                 let ref_wff = self.get_wff_at_line(curr_line_num, *n)?;
@@ -1191,11 +2552,15 @@ impl Proof {
                         return if self.is_closed_term(&term2) {
                             Ok(())
                         } else {
-                            Err(format!(
-                                "Line {curr_line_num}: the rule ∃Intro:{n} is \
-                                used, but {} in line {} is not a closed term",
-                                formatter::format_term(&term2),
-                                *n
+                            Err(self.error_at(
+                                curr_line_num,
+                                error_codes::RULE_MISAPPLIED,
+                                format!(
+                                    "the rule ∃Intro:{n} is \
+                                used, but {} in {} is not a closed term",
+                                    formatter::format_term(&term2),
+                                    self.line_name(*n)
+                                ),
                             ))
                         };
                     }
@@ -1203,30 +2568,41 @@ impl Proof {
                 if **exists_curr_wff == *ref_wff {
                     return Ok(());
                 }
-                Err(format!(
-                    "Line {curr_line_num}: the rule ∃Intro:{n} is used, but there is no \
-                    appropriate substitution between line {n} and line {curr_line_num}"
+                Err(self.error_at(
+                    curr_line_num,
+                    error_codes::RULE_MISAPPLIED,
+                    format!(
+                        "the rule ∃Intro:{n} is used, but there is no \
+                    appropriate substitution between {line_n} and {curr_line}",
+                        line_n = self.line_name(*n),
+                        curr_line = self.line_name(curr_line_num)
+                    ),
                 ))
             }
             Justification::ExistsElim(n, (sb, se)) => {
                 let ref_wff = self.get_wff_at_line(curr_line_num, *n)?;
                 let (s_begin, s_end) = self.get_subproof_at_lines(curr_line_num, (*sb, *se))?;
                 let Wff::Exists(var, exists_ref_wff) = ref_wff else {
-                    return Err(format!(
-                        "Line {curr_line_num}: the rule ∃Elim:{n},{sb}-{se} \
-                    is used, but the sentence at line {n} ({}) is not an existentially \
+                    return Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "the rule ∃Elim:{n},{sb}-{se} \
+                    is used, but the sentence at {line_n} ({}) is not an existentially \
                     quantified sentence at the top-level",
-                        formatter::format_wff(ref_wff)
+                            formatter::format_wff(ref_wff),
+                            line_n = self.line_name(*n)
+                        ),
                     ));
                 };
 
                 let Some(bc_term @ Term::Atomic(bc)) = &s_begin.constant_between_square_brackets
                 else {
-                    return Err(format!("Line {curr_line_num}: the rule ∃Elim:{n},{sb}-{se} is used, but the referenced subproof does not introduce a boxed constant in line {sb}."));
+                    return Err(self.error_at(curr_line_num, error_codes::RULE_MISAPPLIED, format!("the rule ∃Elim:{n},{sb}-{se} is used, but the referenced subproof does not introduce a boxed constant in {line_sb}.", line_sb = self.line_name(*sb))));
                 };
 
                 if s_begin.sentence.is_none() {
-                    return Err(format!("Line {curr_line_num}: the rule ∃Elim:{n},{sb}-{se} is used, but line {sb} contains only a boxed constant; when using ∃Elim, it should contain both a boxed constant and a sentence"));
+                    return Err(self.error_at(curr_line_num, error_codes::RULE_MISAPPLIED, format!("the rule ∃Elim:{n},{sb}-{se} is used, but {line_sb} contains only a boxed constant; when using ∃Elim, it should contain both a boxed constant and a sentence", line_sb = self.line_name(*sb))));
                 }
                 if apply_trivial_substitution_everywhere_to_wff(
                     exists_ref_wff,
@@ -1236,27 +2612,95 @@ impl Proof {
                     if s_end.sentence.as_ref().unwrap() == curr_wff {
                         Ok(())
                     } else {
-                        Err(format!(
-                            "Line {curr_line_num}: the rule ∃Elim:{n},{sb}-{se} \
-                        is used, but the sentence in line {se} ({}) is not the same as \
-                        the sentence in line {curr_line_num} ({})",
-                            formatter::format_wff(s_end.sentence.as_ref().unwrap()),
-                            formatter::format_wff(curr_wff),
+                        Err(self.error_at(
+                            curr_line_num,
+                            error_codes::RULE_MISAPPLIED,
+                            format!(
+                                "the rule ∃Elim:{n},{sb}-{se} \
+                        is used, but the sentence in {line_se} ({}) is not the same as \
+                        the sentence in {curr_line} ({})",
+                                formatter::format_wff(s_end.sentence.as_ref().unwrap()),
+                                formatter::format_wff(curr_wff),
+                                line_se = self.line_name(*se),
+                                curr_line = self.line_name(curr_line_num)
+                            ),
                         ))
                     }
                 } else {
-                    Err(format!(
-                        "Line {curr_line_num}: the rule ∃Elim:{n},{sb}-{se} \
+                    Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "the rule ∃Elim:{n},{sb}-{se} \
                         is used, but if one substitutes {bc} for all free \
                         occurences of {var} in the quantified part of the sentence \
-                        in line {n} ({}), one obtains {}, but this is not equal to the \
-                        sentence found in line {sb} ({})",
-                        formatter::format_wff(ref_wff),
-                        formatter::format_wff(&apply_trivial_substitution_everywhere_to_wff(
-                            exists_ref_wff,
-                            (&Term::Atomic(var.to_string()), bc_term)
-                        )),
-                        formatter::format_wff(s_begin.sentence.as_ref().unwrap())
+                        in {line_n} ({}), one obtains {}, but this is not equal to the \
+                        sentence found in {line_sb} ({})",
+                            formatter::format_wff(ref_wff),
+                            formatter::format_wff(&apply_trivial_substitution_everywhere_to_wff(
+                                exists_ref_wff,
+                                (&Term::Atomic(var.to_string()), bc_term)
+                            )),
+                            formatter::format_wff(s_begin.sentence.as_ref().unwrap()),
+                            line_n = self.line_name(*n),
+                            line_sb = self.line_name(*sb)
+                        ),
+                    ))
+                }
+            }
+            Justification::QuantifierExchange(n)
+            | Justification::Contraposition(n)
+            | Justification::MaterialImplication(n) => {
+                let (rule_name, equivalence, are_equivalent): (&str, &str, fn(&Wff, &Wff) -> bool) =
+                    match just {
+                        Justification::QuantifierExchange(_) => (
+                            "QE",
+                            "¬∀x φ is equivalent to ∃x ¬φ, and ¬∃x φ is equivalent to ∀x ¬φ",
+                            is_quantifier_exchange,
+                        ),
+                        Justification::Contraposition(_) => {
+                            ("Contra", "φ → ψ is equivalent to ¬ψ → ¬φ", is_contraposition)
+                        }
+                        _ => ("Impl", "φ → ψ is equivalent to ¬φ ∨ ψ", is_material_implication),
+                    };
+                if !self.options.allow_derived_rules {
+                    return Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_NOT_ALLOWED,
+                        format!(
+                            "the derived rule {rule_name} is used, but derived \
+                        rules are not allowed here. Use the basic Intro and Elim rules instead."
+                        ),
+                    ));
+                }
+                let ref_wff = self.get_wff_at_line(curr_line_num, *n)?;
+                let replaced = match self.options.negation {
+                    NegationConvention::Primitive => {
+                        differs_by_one_replacement(ref_wff, curr_wff, are_equivalent)
+                    }
+                    // the equivalences are about ¬, so the abbreviations are used again
+                    NegationConvention::DefinedByBottom => differs_by_one_replacement(
+                        &contract_negations(ref_wff),
+                        &contract_negations(curr_wff),
+                        are_equivalent,
+                    ),
+                };
+                if replaced {
+                    Ok(())
+                } else {
+                    Err(self.error_at(
+                        curr_line_num,
+                        error_codes::RULE_MISAPPLIED,
+                        format!(
+                            "the rule {rule_name}: {n} is used, but the \
+                        sentence in {curr_line} ({}) cannot be obtained from the \
+                        sentence in {line_n} ({}) by replacing one subformula by an equivalent \
+                        one, where {equivalence}.",
+                            formatter::format_wff(curr_wff),
+                            formatter::format_wff(ref_wff),
+                            curr_line = self.line_name(curr_line_num),
+                            line_n = self.line_name(*n)
+                        ),
                     ))
                 }
             }
@@ -1459,6 +2903,190 @@ fn wffs_are_syntactically_equal_except_possibly_the_terms(wff1: &Wff, wff2: &Wff
     }
 }
 
+/// Returns `true` iff `name` is the name of an atomic sentence (or a predicate), that is, if it
+/// starts with an uppercase letter. A quantifier over such a name quantifies over sentences (see
+/// [CheckOptions::propositional_quantifiers]).
+fn is_sentence_letter(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_uppercase())
+}
+
+/// Returns the sentence ψ such that `wff` is obtained from `pattern` by replacing every free
+/// occurrence of the atomic sentence `var` by ψ, or `None` if there is no such sentence. If `var`
+/// does not occur free in `pattern`, then `Some(None)` is returned if `wff` equals `pattern`.
+///
+/// A sentence ψ is not accepted if a quantifier in `pattern` would capture one of its free names.
+/// For example, if `pattern` is ∀x (P → Q(x)), then ψ cannot be R(x).
+fn find_sentence_substitution<'a>(
+    pattern: &Wff,
+    var: &str,
+    wff: &'a Wff,
+) -> Option<Option<&'a Wff>> {
+    fn helper<'a>(
+        pattern: &Wff,
+        var: &str,
+        wff: &'a Wff,
+        bound: &mut Vec<String>,
+        res: &mut Option<&'a Wff>,
+    ) -> bool {
+        match (pattern, wff) {
+            (Wff::Atomic(name), _) if name == var => {
+                if bound.iter().any(|b| name_occurs_free(wff, b)) {
+                    return false;
+                }
+                match res {
+                    Some(psi) => *psi == wff,
+                    None => {
+                        *res = Some(wff);
+                        true
+                    }
+                }
+            }
+            (Wff::And(li1), Wff::And(li2)) | (Wff::Or(li1), Wff::Or(li2)) => {
+                li1.len() == li2.len()
+                    && li1.iter().zip(li2).all(|(p, w)| helper(p, var, w, bound, res))
+            }
+            (Wff::Implies(p1, p2), Wff::Implies(w1, w2))
+            | (Wff::Bicond(p1, p2), Wff::Bicond(w1, w2)) => {
+                helper(p1, var, w1, bound, res) && helper(p2, var, w2, bound, res)
+            }
+            (Wff::Not(p), Wff::Not(w)) => helper(p, var, w, bound, res),
+            (Wff::Forall(v1, p), Wff::Forall(v2, w)) | (Wff::Exists(v1, p), Wff::Exists(v2, w))
+                if v1 == v2 =>
+            {
+                // below a quantifier over `var` itself, there are no free occurrences of `var`
+                if v1 == var {
+                    return p == w;
+                }
+                bound.push(v1.to_string());
+                let matches = helper(p, var, w, bound, res);
+                bound.pop();
+                matches
+            }
+            _ => pattern == wff,
+        }
+    }
+    let mut res = None;
+    helper(pattern, var, wff, &mut vec![], &mut res).then_some(res)
+}
+
+/// Returns `true` iff `name` occurs free in `wff`: as an atomic sentence, a predicate, a function
+/// or a term.
+fn name_occurs_free(wff: &Wff, name: &str) -> bool {
+    fn occurs_in_term(term: &Term, name: &str) -> bool {
+        match term {
+            Term::Atomic(n) => n == name,
+            Term::FuncApp(f, args) => f == name || args.iter().any(|a| occurs_in_term(a, name)),
+        }
+    }
+    match wff {
+        Wff::Bottom => false,
+        Wff::Atomic(n) => n == name,
+        Wff::PredApp(p, args) => p == name || args.iter().any(|a| occurs_in_term(a, name)),
+        Wff::Equals(t1, t2) => occurs_in_term(t1, name) || occurs_in_term(t2, name),
+        Wff::And(li) | Wff::Or(li) => li.iter().any(|w| name_occurs_free(w, name)),
+        Wff::Implies(w1, w2) | Wff::Bicond(w1, w2) => {
+            name_occurs_free(w1, name) || name_occurs_free(w2, name)
+        }
+        Wff::Not(w) => name_occurs_free(w, name),
+        Wff::Forall(v, w) | Wff::Exists(v, w) => v != name && name_occurs_free(w, name),
+    }
+}
+
+/// Returns the disjuncts of a disjunction, where disjuncts that are disjunctions themselves are
+/// (recursively) replaced by their disjuncts. For example, the disjuncts of A ∨ ((B ∨ C) ∨ D) are
+/// A, B, C and D.
+fn flatten_disjunction(disjs: &[Wff]) -> Vec<&Wff> {
+    disjs
+        .iter()
+        .flat_map(|disj| match disj {
+            Wff::Or(inner_disjs) => flatten_disjunction(inner_disjs),
+            _ => vec![disj],
+        })
+        .collect()
+}
+
+/// Returns `true` iff [Wff] `wff2` can be obtained from [Wff] `wff1` by replacing exactly one
+/// occurrence of a subformula (possibly `wff1` itself) by another formula, such that the old and
+/// the new subformula are `equivalent`.
+fn differs_by_one_replacement(wff1: &Wff, wff2: &Wff, equivalent: fn(&Wff, &Wff) -> bool) -> bool {
+    if equivalent(wff1, wff2) {
+        return true;
+    }
+    match (wff1, wff2) {
+        (Wff::And(li1), Wff::And(li2)) | (Wff::Or(li1), Wff::Or(li2)) if li1.len() == li2.len() => {
+            let differing: Vec<(&Wff, &Wff)> = zip(li1, li2).filter(|(w1, w2)| w1 != w2).collect();
+            matches!(differing[..], [(w1, w2)] if differs_by_one_replacement(w1, w2, equivalent))
+        }
+        (Wff::Implies(w11, w12), Wff::Implies(w21, w22))
+        | (Wff::Bicond(w11, w12), Wff::Bicond(w21, w22)) => {
+            (w11 == w21 && differs_by_one_replacement(w12, w22, equivalent))
+                || (w12 == w22 && differs_by_one_replacement(w11, w21, equivalent))
+        }
+        (Wff::Not(w1), Wff::Not(w2)) => differs_by_one_replacement(w1, w2, equivalent),
+        (Wff::Forall(x1, w1), Wff::Forall(x2, w2)) | (Wff::Exists(x1, w1), Wff::Exists(x2, w2)) => {
+            x1 == x2 && differs_by_one_replacement(w1, w2, equivalent)
+        }
+        _ => false,
+    }
+}
+
+/// Returns `true` iff the two [Wff]s are the two sides (in any order) of ¬∀x φ ⇄ ∃x ¬φ or of
+/// ¬∃x φ ⇄ ∀x ¬φ.
+fn is_quantifier_exchange(wff1: &Wff, wff2: &Wff) -> bool {
+    let one_way = |w1: &Wff, w2: &Wff| match (w1, w2) {
+        (Wff::Not(negated), Wff::Exists(x2, w)) | (Wff::Not(negated), Wff::Forall(x2, w)) => {
+            match (&**negated, w2) {
+                (Wff::Forall(x1, inner), Wff::Exists(..))
+                | (Wff::Exists(x1, inner), Wff::Forall(..)) => {
+                    x1 == x2 && **w == Wff::Not(inner.clone())
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    };
+    one_way(wff1, wff2) || one_way(wff2, wff1)
+}
+
+/// Returns `true` iff the two [Wff]s are the two sides (in any order) of (φ → ψ) ⇄ (¬ψ → ¬φ).
+fn is_contraposition(wff1: &Wff, wff2: &Wff) -> bool {
+    let one_way = |w1: &Wff, w2: &Wff| match (w1, w2) {
+        (Wff::Implies(phi, psi), Wff::Implies(not_psi, not_phi)) => {
+            **not_psi == Wff::Not(psi.clone()) && **not_phi == Wff::Not(phi.clone())
+        }
+        _ => false,
+    };
+    one_way(wff1, wff2) || one_way(wff2, wff1)
+}
+
+/// Returns `true` iff the two [Wff]s are the two sides (in any order) of (φ → ψ) ⇄ (¬φ ∨ ψ).
+fn is_material_implication(wff1: &Wff, wff2: &Wff) -> bool {
+    let one_way = |w1: &Wff, w2: &Wff| match (w1, w2) {
+        (Wff::Implies(phi, psi), Wff::Or(disjs)) => {
+            matches!(&disjs[..], [not_phi, disj2]
+                if *not_phi == Wff::Not(phi.clone()) && disj2 == &**psi)
+        }
+        _ => false,
+    };
+    one_way(wff1, wff2) || one_way(wff2, wff1)
+}
+
+/// Replaces every `φ → ⊥` in a sentence by `¬φ`, which undoes [Proof::expand_negations].
+fn contract_negations(wff: &Wff) -> Wff {
+    let contract = |wff: &Wff| Box::new(contract_negations(wff));
+    match wff {
+        Wff::Implies(wff1, wff2) if **wff2 == Wff::Bottom => Wff::Not(contract(wff1)),
+        Wff::Implies(wff1, wff2) => Wff::Implies(contract(wff1), contract(wff2)),
+        Wff::And(wffs) => Wff::And(wffs.iter().map(contract_negations).collect()),
+        Wff::Or(wffs) => Wff::Or(wffs.iter().map(contract_negations).collect()),
+        Wff::Bicond(wff1, wff2) => Wff::Bicond(contract(wff1), contract(wff2)),
+        Wff::Not(wff) => Wff::Not(contract(wff)),
+        Wff::Forall(var, wff) => Wff::Forall(var.clone(), contract(wff)),
+        Wff::Exists(var, wff) => Wff::Exists(var.clone(), contract(wff)),
+        Wff::Bottom | Wff::Atomic(_) | Wff::PredApp(..) | Wff::Equals(..) => wff.clone(),
+    }
+}
+
 // This is a macro to duplicate the same code for both mutable and immutable references...
 // It's really cursed, but I saw no better way, other than having duplicate code, since Rust doesn't
 // have an inbuilt way of parametrizing over mutability (yet?).
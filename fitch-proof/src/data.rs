@@ -15,7 +15,7 @@
 /// Each line of the user's input must correspond to exactly one of the above types. If the user
 /// writes garbage, then it is not possible to convert it into [ProofLine]s and a fatal error will
 /// be given to the user.
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct ProofLine {
     /// The line number of the proof line. This is *not* the index at which the current line
     /// occured in the input string that the user gave, but it is the line number inside a Fitch
@@ -51,6 +51,10 @@ pub struct ProofLine {
     /// If the current proof line is a premise that introduces a constant in a box, then this field
     /// contains it.
     pub constant_between_square_brackets: Option<Term>,
+    /// Whether the justification cites all premises with the keyword `Prem`, like `∧Intro: Prem`,
+    /// instead of listing them. In [ProofLine::justification], the premises are then cited like
+    /// any other lines, but the formatter and the exporters write `Prem` again.
+    pub cites_premises: bool,
 }
 
 /// This a logical term. A term can be either a constant, a variable, or a function application
@@ -106,7 +110,7 @@ pub enum Wff {
 
 /// This enum represents the justification rules for an inference. The associated [usize]s denote
 /// the line numbers being represented.
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub enum Justification {
     AndIntro(Vec<usize>),
     AndElim(usize),
@@ -127,6 +131,170 @@ pub enum Justification {
     ExistsIntro(usize),
     ExistsElim(usize, (usize, usize)),
     Reit(usize),
+    /// Derived rule: quantifier exchange (¬∀x φ ⇄ ∃x ¬φ and ¬∃x φ ⇄ ∀x ¬φ), applied to one
+    /// subformula. Only allowed if [CheckOptions::allow_derived_rules] is set.
+    QuantifierExchange(usize),
+    /// Derived rule: contraposition ((φ → ψ) ⇄ (¬ψ → ¬φ)), applied to one subformula. Only
+    /// allowed if [CheckOptions::allow_derived_rules] is set.
+    Contraposition(usize),
+    /// Derived rule: material implication ((φ → ψ) ⇄ (¬φ ∨ ψ)), applied to one subformula. Only
+    /// allowed if [CheckOptions::allow_derived_rules] is set.
+    MaterialImplication(usize),
+    /// A chain of equalities: if the cited lines are t1 = t2, t2 = t3, ..., then t1 = tn follows
+    /// (each cited equality may also be used from right to left). Only allowed if
+    /// [CheckOptions::allow_equality_chains] is set.
+    EqualsChain(Vec<usize>),
+}
+
+/// A single inference, without any Fitch scaffolding around it: some premises, a conclusion, and
+/// the rule that should lead from the premises to the conclusion. See
+/// [crate::parser::parse_sequent].
+#[derive(PartialEq, Debug)]
+pub struct Sequent {
+    /// The premises, in the order in which they were written. In the [Sequent::rule], the premises
+    /// are referred to by their position, starting at 1.
+    pub premises: Vec<Wff>,
+    /// The sentence that should follow from the premises.
+    pub conclusion: Wff,
+    /// The rule that is applied. It cites the premises `1..=premises.len()`, in order.
+    pub rule: Justification,
+}
+
+/// A proof template: the premises and the conclusion that a proof should have, and possibly some
+/// information about the exercise. See [crate::parser::parse_template].
+#[derive(PartialEq, Debug)]
+pub struct ProofTemplate {
+    /// The premises, followed by the conclusion.
+    pub sentences: Vec<Wff>,
+    /// The number of lines (with a line number) of the shortest known proof, if it is given.
+    pub minimal_length: Option<usize>,
+    /// The nesting depth of the subproofs (0 if there are no subproofs) of the shortest known
+    /// proof, if it is given. Together with [ProofTemplate::minimal_length], this is the reference
+    /// for a proof-golf score, see [crate::golf_proof].
+    pub minimal_depth: Option<usize>,
+    /// Whether the exercise is propositional-only, see [CheckOptions::propositional_only].
+    pub propositional_only: bool,
+}
+
+/// Options for exporting a proof in two columns (the sentences and their justifications), see
+/// [crate::export_two_column].
+///
+/// The options can be given as a string like `"format=html, numbering=parens"`, see
+/// [crate::parser::parse_two_column_options].
+#[cfg(feature = "export")]
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct TwoColumnOptions {
+    /// Whether a LaTeX tabular or an HTML table is made.
+    pub format: TwoColumnFormat,
+    /// How the line numbers are written.
+    pub numbering: Numbering,
+    /// The width of the column with the sentences, e.g. `8cm`. If it is [None], the column is as
+    /// wide as its contents.
+    pub formula_width: Option<String>,
+    /// The width of the column with the justifications, like [TwoColumnOptions::formula_width].
+    pub justification_width: Option<String>,
+}
+
+/// The output format of a two-column export (see [TwoColumnOptions::format]).
+#[cfg(feature = "export")]
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum TwoColumnFormat {
+    /// A LaTeX `tabular` environment.
+    #[default]
+    Latex,
+    /// An HTML `<table>` element.
+    Html,
+}
+
+/// How the line numbers are written in a two-column export (see [TwoColumnOptions::numbering]).
+#[cfg(feature = "export")]
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum Numbering {
+    /// Like `1.`
+    #[default]
+    Plain,
+    /// Like `(1)`
+    Parenthesized,
+    /// The line numbers are left out.
+    None,
+}
+
+/// The directives in a proof: lines starting with `%`, which are ignored when the proof is checked,
+/// but which can be used by exercise authors, e.g. to say which mistakes a deliberately broken
+/// proof should have. See [crate::parser::parse_directives].
+#[derive(PartialEq, Debug, Default)]
+pub struct ProofDirectives {
+    /// The mistakes that the proof should have, from `%expect-error: ...` directives.
+    pub expected_errors: Vec<ExpectedError>,
+    /// The rules that may be used in the proof (besides the basic rules), from `%allow: ...`
+    /// directives. These are derived rules (`QE`, `Contra` or `Impl`) or `=Chain`.
+    pub allowed_rules: Vec<String>,
+}
+
+/// A mistake that a proof should have (see [ProofDirectives]).
+#[derive(PartialEq, Debug, Clone)]
+pub struct ExpectedError {
+    /// The code of the error, like `E_SCOPE_VIOLATION` (see [crate::error_codes]).
+    pub code: String,
+    /// The line that the error is about, if it is given.
+    pub line: Option<usize>,
+}
+
+/// The status of one proof line with a justification, as reported while a proof is being checked
+/// (see [crate::checker::check_proof_with_progress]).
+#[derive(PartialEq, Debug, Clone)]
+pub enum LineStatus {
+    /// The proof rule in this line has been applied correctly.
+    Correct,
+    /// The proof rule in this line has not been applied correctly. The associated [String] is the
+    /// error message.
+    Error(String),
+}
+
+/// A mistake in a proof, as found by the checker (see [ProofResult::Error]).
+#[derive(PartialEq, Debug, Clone)]
+pub struct ProofError {
+    /// The line number of the line that the error is about, or [None] if the error is about the
+    /// proof as a whole.
+    pub line: Option<usize>,
+    /// The kind of mistake, one of the codes in [crate::error_codes].
+    pub code: &'static str,
+    /// The error message as it is shown to the student, like `Line 3: the rule ...`.
+    pub message: String,
+}
+
+impl ProofError {
+    /// Returns an error about line `line`. The message should say which line it is about.
+    pub fn at_line(line: usize, code: &'static str, message: String) -> ProofError {
+        ProofError {
+            line: Some(line),
+            code,
+            message,
+        }
+    }
+
+    /// Returns an error about the proof as a whole, not about one line.
+    pub fn about_proof(code: &'static str, message: String) -> ProofError {
+        ProofError {
+            line: None,
+            code,
+            message,
+        }
+    }
+
+    /// Applies `f` to the error message.
+    pub fn map_message(self, f: impl FnOnce(&str) -> String) -> ProofError {
+        ProofError {
+            message: f(&self.message),
+            ..self
+        }
+    }
+}
+
+impl std::fmt::Display for ProofError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
 }
 
 pub enum ProofResult {
@@ -135,13 +303,300 @@ pub enum ProofResult {
     /// An 'error' is a mistake that makes the proof wrong, but still allows
     /// the checker to go on and find other mistakes. This [ProofResult::Error]
     /// variant denotes the list of errors that was obtained during analysis.
-    Error(Vec<String>),
+    Error(Vec<ProofError>),
     /// A mistake that is so severe that the checker cannot continue its analysis.
     /// When a fatal error occurs, this fatal error will be returned to the user,
     /// with no other error messages along it.
     ///
-    /// Note that when the user checks some proof that should match to some proof template,
-    /// a [ProofResult::FatalError] will be returned if the proof does
-    /// not match the template.
-    FatalError(String),
+    /// The [FatalError] says whose mistake it is (the student's, the instructor's, the caller's or
+    /// the checker's), so that the message can be sent to the right person.
+    FatalError(FatalError),
+    /// No proof was found: the input is empty, or it only contains whitespace and directives (see
+    /// [crate::parser::is_empty_proof]). This is the same for every function that checks a proof,
+    /// so that an empty submission can be told apart from a proof with a fatal error.
+    NoProof,
+    /// The proof is a draft (see [CheckOptions::drafting]) in which some lines do not have a
+    /// justification yet. The first vector contains the errors in the rest of the proof (like in
+    /// [ProofResult::Error], it may be empty), and the second vector contains the line numbers of
+    /// the lines without a justification, which have not been checked.
+    Unfinished(Vec<ProofError>, Vec<usize>),
+}
+
+/// The kinds of [ProofResult::FatalError]. The associated [String] is always the error message.
+#[derive(PartialEq, Debug, Clone)]
+pub enum FatalError {
+    /// The proof cannot be parsed, or it is not a well-formed Fitch proof (e.g. a subproof is
+    /// never closed). This is the student's mistake. The [TextSpan] is the part of the text of the
+    /// proof that could not be parsed, if it is known.
+    ParseError(String, Option<TextSpan>),
+    /// The options, the allowed variable names or some other setting that is given by the caller
+    /// of the checker (not by the student) are not valid.
+    ConfigError(String),
+    /// The proof template or the exercise cannot be used, e.g. because it cannot be parsed. This
+    /// is the instructor's mistake, not the student's.
+    TemplateError(String),
+    /// The proof is too large for the checker, e.g. a line number is too big.
+    LimitExceeded(String),
+    /// The checker got into a state that should be impossible, so there is a bug in the checker
+    /// (not necessarily a mistake in the proof). The associated [String] is a (minimized) input
+    /// with which the bug can be reproduced, see [crate::internal_error::catch_internal_errors].
+    Internal(String),
+}
+
+impl FatalError {
+    /// Returns the kind of the fatal error as a string: `parse_error`, `config_error`,
+    /// `template_error`, `limit_exceeded` or `internal`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FatalError::ParseError(..) => "parse_error",
+            FatalError::ConfigError(_) => "config_error",
+            FatalError::TemplateError(_) => "template_error",
+            FatalError::LimitExceeded(_) => "limit_exceeded",
+            FatalError::Internal(_) => "internal",
+        }
+    }
+
+    /// Applies `f` to the error message (but not to the input of an [FatalError::Internal], which
+    /// is not a message).
+    pub fn map_message(self, f: impl FnOnce(&str) -> String) -> FatalError {
+        match self {
+            FatalError::ParseError(err, span) => FatalError::ParseError(f(&err), span),
+            FatalError::ConfigError(err) => FatalError::ConfigError(f(&err)),
+            FatalError::TemplateError(err) => FatalError::TemplateError(f(&err)),
+            FatalError::LimitExceeded(err) => FatalError::LimitExceeded(f(&err)),
+            FatalError::Internal(input) => FatalError::Internal(input),
+        }
+    }
+}
+
+/// The message of a fatal error, as it is shown after `Fatal error: `. The message of a
+/// [FatalError::TemplateError] says that it is not the student's mistake.
+impl std::fmt::Display for FatalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FatalError::ParseError(err, _)
+            | FatalError::ConfigError(err)
+            | FatalError::LimitExceeded(err) => write!(f, "{err}"),
+            FatalError::TemplateError(err) => write!(
+                f,
+                "{}. This is a mistake in the exercise, not in your proof. If you see this as a \
+                student, please contact the course staff.",
+                err.trim_end_matches('.')
+            ),
+            FatalError::Internal(input) => write!(
+                f,
+                "something went wrong inside the proof checker. This is a bug in the proof \
+                checker, which is not necessarily caused by a mistake in your proof. Please report \
+                it, together with this input: {}",
+                crate::util::json_string(input)
+            ),
+        }
+    }
+}
+
+/// A part of a line in the text of a proof, e.g. where a [FatalError::ParseError] occurs.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct TextSpan {
+    /// The number of the line in the text of the proof, counting from 1 (and counting all lines,
+    /// like empty lines and directives).
+    pub line: usize,
+    /// The column (in characters, counting from 1) where the span starts.
+    pub start_column: usize,
+    /// The column (in characters, counting from 1) right after the end of the span.
+    pub end_column: usize,
+}
+
+/// The symbols that are used to write down connectives (and ⊥) when formulas are shown to the
+/// user, e.g. in error messages.
+///
+/// Note that there are no ASCII symbols for the quantifiers, so ∀ and ∃ are always written the
+/// same way.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum SymbolSet {
+    /// Use the symbol set in which the proof itself is written (see
+    /// [crate::formatter::detect_symbol_set]).
+    #[default]
+    Auto,
+    /// ∧, ∨, →, ↔, ¬ and ⊥.
+    Unicode,
+    /// `&`, `\/`, `->`, `<->`, `~` and `_|_`.
+    Ascii,
+    /// Like [SymbolSet::Unicode], but with ⊃ instead of → and ≡ instead of ↔.
+    Traditional,
+}
+
+/// Where the conclusion of a proof template may appear in a proof (see
+/// [CheckOptions::conclusion_placement]).
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum ConclusionPlacement {
+    /// The conclusion must be in the last line of the proof, so nothing may come after it.
+    #[default]
+    LastLine,
+    /// The conclusion may be in any line that is not inside a subproof. The proof may continue
+    /// after this line.
+    AnyTopLevelLine,
+}
+
+/// Options that change how a proof is checked and how the results are reported.
+///
+/// The options can be given as a string like `"symbols=ascii"`, see
+/// [crate::parser::parse_check_options]. The [Default] options are what [crate::check_proof]
+/// uses.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct CheckOptions {
+    /// The symbols that are used in the error messages.
+    pub symbols: SymbolSet,
+    /// Whether the derived equivalence rules (QE, Contra and Impl) may be used.
+    pub allow_derived_rules: bool,
+    /// Whether a subproof may be cited by its first line only (e.g. `→Intro: 5` instead of
+    /// `→Intro: 5-9`), in which case the checker figures out where the subproof ends.
+    pub allow_subproof_shorthand: bool,
+    /// Whether ↔Intro may cite its two subproofs in either order, and ↔Elim its two lines (e.g.
+    /// `↔Elim: 2, 1` where line 1 is the bi-implication), as in some textbooks. Otherwise, ↔Intro
+    /// for `P↔Q` cites the subproof from P to Q first, and ↔Elim cites the bi-implication first.
+    pub allow_any_bicond_order: bool,
+    /// Whether `t = t'` may be derived from a chain of equalities in one step, with `=Chain`.
+    pub allow_equality_chains: bool,
+    /// Where the conclusion of the proof template may appear in the proof. This only matters when
+    /// a proof is checked against a template.
+    pub conclusion_placement: ConclusionPlacement,
+    /// Whether the proof is a draft, in which inferences may be left without a justification for
+    /// now. Such lines are not checked (but they can be cited), and they are reported separately
+    /// from the mistakes, see [ProofResult::Unfinished].
+    pub drafting: bool,
+    /// Whether the proof may only use propositional logic: no quantifiers, no predicates with
+    /// arguments, no identity, no boxed constants and no rules for these. If it uses any of these,
+    /// then a fatal error is given which says so, instead of the usual (first-order) errors.
+    pub propositional_only: bool,
+    /// The greatest number of mistakes that is reported. If there are more, then only the first
+    /// ones are reported, together with a note that there are more. [None] means no limit.
+    pub max_errors: Option<usize>,
+    /// How much is told about each mistake that is reported.
+    pub feedback: FeedbackDetail,
+    /// Whether ¬ is a connective with its own rules, or an abbreviation of `φ → ⊥`.
+    pub negation: NegationConvention,
+    /// The order in which the mistakes are reported.
+    pub error_order: ErrorOrder,
+    /// Whether a rule may cite lines outside the subproof that it is used in.
+    pub reiteration: ReiterationPolicy,
+    /// Whether quantifiers may range over sentences, as in quantified propositional logic: with
+    /// this option, a quantifier over a name that is not one of the allowed variable names (like
+    /// `∀p (p → p)`) binds the atomic sentences with that name. The quantifier rules then
+    /// substitute sentences for it, and ∀Intro and ∃Elim introduce a new atomic sentence as
+    /// boxed constant, like `[q]`.
+    pub propositional_quantifiers: bool,
+}
+
+/// When the hints for a proof become more specific (see [crate::hints::hint]). Each field is the
+/// number of earlier attempts after which that kind of hint is given. Before the first of these,
+/// no hint is given at all.
+///
+/// The policy can be given as a string like `"family=1, rule=2, lines=3"`, see
+/// [crate::parser::parse_hint_policy]. The [Default] policy is the one in this example.
+#[cfg(feature = "tutoring")]
+#[derive(PartialEq, Debug, Clone)]
+pub struct HintPolicy {
+    /// After this many attempts, the hint says which family of rules to use (e.g. the rules for
+    /// ∧).
+    pub rule_family_after: usize,
+    /// After this many attempts, the hint says which rule to use (e.g. ∧Elim).
+    pub rule_after: usize,
+    /// After this many attempts, the hint says which rule to use, and which lines to cite.
+    pub citations_after: usize,
+}
+
+#[cfg(feature = "tutoring")]
+impl Default for HintPolicy {
+    fn default() -> Self {
+        HintPolicy {
+            rule_family_after: 1,
+            rule_after: 2,
+            citations_after: 3,
+        }
+    }
+}
+
+/// How much is told about each mistake in a proof (see [CheckOptions::feedback]). Fatal errors
+/// are always reported in full, since the proof cannot be checked at all then.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum FeedbackDetail {
+    /// The full error messages.
+    #[default]
+    Full,
+    /// Only the numbers of the lines that contain a mistake, without saying what the mistake is.
+    LineNumbers,
+}
+
+/// How negation is treated when a proof is checked (see [CheckOptions::negation]).
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum NegationConvention {
+    /// ¬ is a connective of its own, with the rules ¬Intro and ¬Elim.
+    #[default]
+    Primitive,
+    /// `¬φ` is short for `φ → ⊥`, as in courses that only have rules for → and ⊥. Every `¬φ` in
+    /// the proof (and in the proof template) is read as `φ → ⊥`, so that for example `¬A` is
+    /// derived with →Intro from a subproof that goes from `A` to `⊥`, and ⊥Intro may cite `A` and
+    /// `A → ⊥`. The rule ¬Intro cannot be used. ¬Elim is kept as the classical rule (double
+    /// negation elimination), which goes from `(φ → ⊥) → ⊥` (i.e. `¬¬φ`) to `φ`.
+    DefinedByBottom,
+}
+
+/// Whether a rule may cite lines outside the subproof that it is used in (see
+/// [CheckOptions::reiteration]). Textbooks differ on this.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum ReiterationPolicy {
+    /// Every line that is accessible may be cited directly, also when it is in an enclosing
+    /// subproof (or among the premises). Reit is then only needed to repeat a sentence.
+    #[default]
+    Direct,
+    /// Only Reit may cite lines outside the subproof that it is used in. Any other rule may only
+    /// cite lines in the same subproof (and subproofs directly inside it), so that a line from an
+    /// enclosing subproof must first be brought into the subproof with Reit.
+    Explicit,
+}
+
+/// The order in which the mistakes in a proof are reported (see [CheckOptions::error_order]).
+/// Mistakes that are not about one line (like a proof that does not match its template) come after
+/// the other mistakes of the same kind.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum ErrorOrder {
+    /// From the top of the proof to the bottom. Mistakes about the same line are ordered by their
+    /// error code.
+    #[default]
+    Line,
+    /// The most fundamental mistakes first (see [crate::error_codes::severity]), e.g. a subproof
+    /// that is not closed before a rule that is applied incorrectly. Mistakes of the same severity
+    /// are ordered from the top of the proof to the bottom.
+    Severity,
+    /// Grouped by their error code (see [crate::error_codes]), in alphabetical order of the codes.
+    /// Mistakes with the same code are ordered from the top of the proof to the bottom.
+    Code,
+}
+
+/// How closely a student's sentence should match a reference sentence (see
+/// [crate::formula_match::formulas_match] and [crate::parser::parse_match_policy]).
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum MatchPolicy {
+    /// The sentences should be the same, apart from spaces and redundant parentheses.
+    #[default]
+    Syntactic,
+    /// The sentences should be the same up to the names of the bound variables (α-equivalence),
+    /// so `∀x P(x)` matches `∀y P(y)`, but not `∀y P(a)`.
+    Alpha,
+    /// The sentences should be logically equivalent, which is checked with a truth table. This
+    /// only works for propositional sentences.
+    Equivalent,
+}
+
+/// What happens to the line labels when the line numbers of a proof are fixed (see
+/// [crate::parser::parse_line_labels] and [crate::labels]).
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum LineLabels {
+    /// A proof with labels keeps its labels, and a proof with line numbers keeps its line numbers.
+    #[default]
+    Keep,
+    /// The labels are replaced by the line numbers 1, 2, 3, ...
+    Numbers,
+    /// The line numbers (or the labels) are replaced by the labels `(a)`, `(b)`, `(c)`, ...
+    Letters,
 }
@@ -0,0 +1,82 @@
+/// A line or subproof is cited that is not accessible from the citing line, or a boxed constant
+/// is used outside its subproof.
+pub const SCOPE_VIOLATION: &str = "E_SCOPE_VIOLATION";
+
+/// A line or subproof is cited that does not exist, or it is cited in the wrong way.
+pub const BAD_CITATION: &str = "E_BAD_CITATION";
+
+/// An inference does not have a justification.
+pub const MISSING_JUSTIFICATION: &str = "E_MISSING_JUSTIFICATION";
+
+/// A rule is used that is not allowed with the options of the check, like a derived rule.
+pub const RULE_NOT_ALLOWED: &str = "E_RULE_NOT_ALLOWED";
+
+/// The proof as a whole is not structured correctly, e.g. it does not end at the top level.
+pub const STRUCTURE: &str = "E_STRUCTURE";
+
+/// A variable is not bound, a constant is quantified over, or a boxed constant is not a constant.
+pub const VARIABLES: &str = "E_VARIABLES";
+
+/// A name is used with different arities.
+pub const ARITY: &str = "E_ARITY";
+
+/// The proof does not match the proof template.
+pub const TEMPLATE_MISMATCH: &str = "E_TEMPLATE_MISMATCH";
+
+/// A proof rule is applied incorrectly.
+pub const RULE_MISAPPLIED: &str = "E_RULE_MISAPPLIED";
+
+/// The code of a fatal error.
+pub const FATAL: &str = "E_FATAL";
+
+/// The code of a submission in which no proof was found.
+pub const NO_PROOF: &str = "E_NO_PROOF";
+
+/// The code of a message that stands for mistakes that are not shown (see
+/// [crate::data::CheckOptions::feedback]). It is not a kind of mistake, so it cannot be expected
+/// with `%expect-error`.
+pub const HIDDEN: &str = "E_HIDDEN";
+
+/// The error codes from the most fundamental mistakes to the least fundamental ones, see
+/// [severity].
+const SEVERITY_ORDER: &[&str] = &[
+    NO_PROOF,
+    FATAL,
+    STRUCTURE,
+    VARIABLES,
+    ARITY,
+    SCOPE_VIOLATION,
+    BAD_CITATION,
+    RULE_NOT_ALLOWED,
+    MISSING_JUSTIFICATION,
+    RULE_MISAPPLIED,
+    TEMPLATE_MISMATCH,
+];
+
+/// Returns whether `code` is one of the known error codes.
+pub fn is_error_code(code: &str) -> bool {
+    SEVERITY_ORDER.contains(&code)
+}
+
+/// Returns how fundamental the mistakes with an error code are, where `0` is the most
+/// fundamental. A mistake in the structure of the proof comes before a mistake in the names in a
+/// sentence, which comes before a wrong citation, which comes before a rule that is applied
+/// incorrectly, and mistakes that only matter for the proof template come last.
+pub fn severity(code: &str) -> usize {
+    SEVERITY_ORDER.iter().position(|c| *c == code).unwrap_or(SEVERITY_ORDER.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_codes() {
+        assert!(severity(STRUCTURE) < severity(SCOPE_VIOLATION));
+        assert!(severity(RULE_MISAPPLIED) < severity(TEMPLATE_MISMATCH));
+        assert!(is_error_code("E_ARITY"));
+        assert!(is_error_code(FATAL));
+        assert!(is_error_code("E_NO_PROOF"));
+        assert!(!is_error_code("E_TYPO"));
+    }
+}
@@ -0,0 +1,196 @@
+use crate::data::*;
+use crate::formatter::{format_justification, format_term, format_wff, rule_name};
+use crate::parser;
+
+/// The names of the rules in forall x (the Calgary version, which is part of the Open Logic
+/// Project): for every rule, the name that [rule_name] gives it, and the name in forall x. The
+/// derived rules that forall x does not have keep their own name.
+///
+/// Notice that the rules for ¬ and ⊥ are named differently: deriving ⊥ from φ and ¬φ is ¬E in
+/// forall x, ⊥Elim is X (ex falso), and ¬Elim (from ¬¬φ to φ) is DNE.
+pub const FORALLX_RULE_NAMES: &[(&str, &str)] = &[
+    ("Reit", "R"),
+    ("∧Intro", "∧I"),
+    ("∧Elim", "∧E"),
+    ("∨Intro", "∨I"),
+    ("∨Elim", "∨E"),
+    ("→Intro", "→I"),
+    ("→Elim", "→E"),
+    ("↔Intro", "↔I"),
+    ("↔Elim", "↔E"),
+    ("¬Intro", "¬I"),
+    ("¬Elim", "DNE"),
+    ("⊥Intro", "¬E"),
+    ("⊥Elim", "X"),
+    ("=Intro", "=I"),
+    ("=Elim", "=E"),
+    ("∀Intro", "∀I"),
+    ("∀Elim", "∀E"),
+    ("∃Intro", "∃I"),
+    ("∃Elim", "∃E"),
+    ("QE", "CQ"),
+    ("Contra", "Contra"),
+    ("Impl", "Impl"),
+    ("=Chain", "=Chain"),
+];
+
+/// The justification of a premise in forall x.
+const PREMISE: &str = "PR";
+
+/// The justification of an assumption (a line without justification in a subproof) in forall x.
+const ASSUMPTION: &str = "AS";
+
+/// Exports a proof in the notation of forall x: the layout is the same as that of
+/// [crate::formatter::format_proof], but the rules have the names of forall x (see
+/// [FORALLX_RULE_NAMES]), ranges of lines are written with an en dash (like `2–4`), and premises
+/// and assumptions get the justification `PR` and `AS`. For example:
+///
+/// ```notrust
+/// 1 | A ∧ B    PR
+///   |----
+/// 2 | | C      AS
+///   | |----
+/// 3 | | A      ∧E 1
+/// 4 | C → A    →I 2–3
+/// ```
+///
+/// forall x has no boxed constants: there, ∀I does not use a subproof. A subproof with a boxed
+/// constant is exported as it is, like `[a]`.
+pub fn proof_to_forallx(proof: &[ProofLine]) -> String {
+    let width = proof.iter().filter_map(|l| l.line_num).max().unwrap_or(0).to_string().len();
+    let lines: Vec<String> = proof
+        .iter()
+        .map(|l| {
+            let line_num = l.line_num.map_or("".to_string(), |n| n.to_string());
+            let mut line = format!("{line_num:<width$} |{}", " |".repeat(l.depth - 1));
+            if l.is_fitch_bar_line {
+                line.push_str("----");
+            }
+            if let Some(c) = &l.constant_between_square_brackets {
+                line.push_str(&format!(" [{}]", format_term(c)));
+            }
+            if let Some(wff) = &l.sentence {
+                line.push(' ');
+                line.push_str(&format_wff(wff));
+            }
+            line
+        })
+        .collect();
+    let column = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) + 4;
+    let mut res: Vec<String> = vec![];
+    for (l, line) in proof.iter().zip(lines) {
+        let justification = match &l.justification {
+            Some(just) => Some(justification_to_forallx(just)),
+            None if l.line_num.is_none() => None,
+            None if l.depth == 1 => Some(PREMISE.to_string()),
+            None => Some(ASSUMPTION.to_string()),
+        };
+        match justification {
+            Some(just) => {
+                let pad = column - line.chars().count();
+                res.push(format!("{line}{}{just}", " ".repeat(pad)));
+            }
+            None => res.push(line.trim_end().to_string()),
+        }
+    }
+    res.join("\n")
+}
+
+/// Reads a proof in the notation of forall x (see [proof_to_forallx]) back. Every justification
+/// should be at the end of its line, with the name of a rule in [FORALLX_RULE_NAMES] followed by
+/// the cited lines; the justifications `PR` and `AS` may be left out. The proof is then parsed
+/// as usual, so that exporting a proof and reading it back gives the same [ProofLine]s.
+///
+/// If the proof cannot be parsed, an error message is returned.
+pub fn forallx_to_proof(text: &str) -> Result<Vec<ProofLine>, String> {
+    let lines: Vec<String> = text.lines().map(forallx_line_to_text).collect();
+    parser::parse_fitch_proof(&lines.join("\n"))
+        .map_err(|err| format!("the proof in forall x notation cannot be parsed: {err}"))
+}
+
+/* ------------------ PRIVATE -------------------- */
+
+/// Returns a justification in the notation of forall x, like `∨E 1, 2–3, 4–5`.
+fn justification_to_forallx(just: &Justification) -> String {
+    let rule = rule_name(just);
+    let name = FORALLX_RULE_NAMES.iter().find(|(r, _)| *r == rule).map_or(rule.as_str(), |r| r.1);
+    match format_justification(just).split_once(':') {
+        Some((_, cited)) => {
+            let cited: Vec<String> = cited.split(',').map(|c| c.trim().replace('-', "–")).collect();
+            format!("{name} {}", cited.join(", "))
+        }
+        None => name.to_string(),
+    }
+}
+
+/// Turns a line of a proof in forall x notation into a line of a proof in the usual plain text
+/// format, by rewriting the justification at the end of the line (if there is one) like
+/// `∨ Elim: 1, 2-3, 4-5`, and leaving out `PR` and `AS`.
+fn forallx_line_to_text(line: &str) -> String {
+    let line = line.trim_end();
+    // the cited lines are at the end of the line, and the name of the rule is right before them
+    let cited_len = line
+        .chars()
+        .rev()
+        .take_while(|c| c.is_ascii_digit() || [',', '-', '–', ' '].contains(c))
+        .map(char::len_utf8)
+        .sum::<usize>();
+    let (rest, cited) = line.split_at(line.len() - cited_len);
+    let Some((contents, name)) = rest.rsplit_once(' ') else {
+        return line.to_string();
+    };
+    let contents = contents.trim_end();
+    if cited.trim().is_empty() && [PREMISE, ASSUMPTION].contains(&name) {
+        return contents.to_string();
+    }
+    let Some((rule, _)) = FORALLX_RULE_NAMES.iter().find(|(_, n)| *n == name) else {
+        return line.to_string();
+    };
+    // all rules, except =I, cite at least one line
+    match (cited.trim(), *rule) {
+        ("", "=Intro") => format!("{contents}   {rule}"),
+        ("", _) => line.to_string(),
+        (cited, _) => format!("{contents}   {rule}: {}", cited.replace('–', "-")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forallx_roundtrip() {
+        let proof = "1 | ∀x (P(x) ∨ Q(x))
+  |----
+2 | | [a]
+  | |----
+3 | | P(a) ∨ Q(a)     ∀ Elim: 1
+  | | |
+4 | | | P(a)
+  | | |----
+5 | | | Q(a) ∨ P(a)   ∨ Intro: 4
+  | | |
+6 | | | Q(a)
+  | | |----
+7 | | | Q(a) ∨ P(a)   ∨ Intro: 6
+8 | | Q(a) ∨ P(a)     ∨ Elim: 3, 4-5, 6-7
+9 | ∀x (Q(x) ∨ P(x))  ∀ Intro: 2-8";
+        let lines = parser::parse_fitch_proof(proof).unwrap();
+        let forallx = proof_to_forallx(&lines);
+        assert!(forallx.starts_with("1 | ∀x (P(x) ∨ Q(x))"), "{forallx}");
+        assert!(forallx.contains("4 | | | P(a)"), "{forallx}");
+        assert!(forallx.contains("∨E 3, 4–5, 6–7\n9 |"), "{forallx}");
+        assert!(forallx.ends_with("∀I 2–8"), "{forallx}");
+        assert_eq!(forallx_to_proof(&forallx), Ok(lines));
+    }
+
+    #[test]
+    fn test_forallx_line_to_text() {
+        assert_eq!(forallx_line_to_text("1 | A ∧ B     PR"), "1 | A ∧ B");
+        assert_eq!(forallx_line_to_text("3 | ⊥         ¬E 1, 2"), "3 | ⊥   ⊥Intro: 1, 2");
+        assert_eq!(forallx_line_to_text("4 | a = a     =I"), "4 | a = a   =Intro");
+        assert_eq!(forallx_line_to_text("5 | B         X 3"), "5 | B   ⊥Elim: 3");
+        // a sentence that ends with the name of a rule, without a justification
+        assert_eq!(forallx_line_to_text("6 | A ∧ X"), "6 | A ∧ X");
+    }
+}
@@ -1,29 +1,54 @@
 use crate::data::*;
 use crate::formatter::format_wff;
+use crate::parser;
 use std::fmt::Write;
 
 /// Exports a proof to a string that can be put in a LaTeX document.
+///
+/// The export can be read back with [latex_to_proof], which gives the same [ProofLine]s. For this,
+/// the lines without line number that cannot be seen from the LaTeX are written as LaTeX comments
+/// (see [FITCH_BAR], [EMPTY_LINE] and [NO_FITCH_BAR]). In a proof as it is usually written, these
+/// are not needed: a Fitch bar below a `\hypo` line is drawn anyway, and an empty line between
+/// two subproofs is where one subproof is closed and the next one is opened.
 pub fn proof_to_latex(proof: &[ProofLine]) -> String {
     let mut prev_depth = 1;
     let mut is_hypo = true;
-    let proof_str = proof.iter().fold(String::new(), |mut output, l| {
-        if l.is_fitch_bar_line {
-            is_hypo = false;
+    let mut prev_is_hypo_line = false;
+    let mut proof_str = String::new();
+    for (i, l) in proof.iter().enumerate() {
+        if prev_is_hypo_line
+            && (l.depth != prev_depth || (l.line_num.is_none() && !l.is_fitch_bar_line))
+        {
+            let _ = writeln!(proof_str, "{NO_FITCH_BAR}");
         }
-        let part1 = if l.depth == prev_depth + 1 {
+        let below_hypo_line = prev_is_hypo_line && l.depth == prev_depth;
+        prev_is_hypo_line = false;
+        while l.depth > prev_depth {
             prev_depth += 1;
             is_hypo = true;
-            "\\open\n"
-        } else if l.depth == prev_depth - 1 {
+            proof_str.push_str("\\open\n");
+        }
+        while l.depth < prev_depth {
             prev_depth -= 1;
-            "\\close\n"
-        } else {
-            ""
-        };
-        if l.line_num.is_none() {
-            let _ = write!(output, "{}", part1);
-            return output;
+            proof_str.push_str("\\close\n");
+        }
+        if l.is_fitch_bar_line {
+            is_hypo = false;
+            if !below_hypo_line {
+                let _ = writeln!(proof_str, "{FITCH_BAR}");
+            }
+            continue;
         }
+        let Some(line_num) = l.line_num else {
+            let between_subproofs = i > 0
+                && proof[i - 1].depth > l.depth
+                && proof.get(i + 1).is_some_and(|next| next.depth > l.depth);
+            if !between_subproofs {
+                let _ = writeln!(proof_str, "{EMPTY_LINE}");
+            }
+            continue;
+        };
+        prev_is_hypo_line = is_hypo;
         let part2 = format!(
             "{}{{{}}}{{{}{}}}",
             if is_hypo {
@@ -31,9 +56,9 @@ pub fn proof_to_latex(proof: &[ProofLine]) -> String {
             } else {
                 "\\have"
             },
-            l.line_num.unwrap(),
+            line_num,
             match &l.constant_between_square_brackets {
-                Some(Term::Atomic(t)) => format!(" \\boxed{{{}}}~ ", t),
+                Some(Term::Atomic(t)) => format!(" \\boxed{{{}}}~ ", symbols_to_latex(t)),
                 _ => "".to_string(),
             },
             match &l.sentence {
@@ -42,12 +67,18 @@ pub fn proof_to_latex(proof: &[ProofLine]) -> String {
             },
         );
         let part3 = match &l.justification {
+            Some(Justification::AndIntro(_)) if l.cites_premises => "\\ai{Prem}".to_string(),
+            Some(Justification::EqualsChain(_)) if l.cites_premises => {
+                "\\by{$=\\,$Chain}{Prem}".to_string()
+            }
             Some(just) => justification_to_latex(just),
             _ => "".to_string(),
         };
-        let _ = writeln!(output, "{}{}{}", part1, part2, part3);
-        output
-    });
+        let _ = writeln!(proof_str, "{}{}", part2, part3);
+    }
+    if prev_is_hypo_line {
+        let _ = writeln!(proof_str, "{NO_FITCH_BAR}");
+    }
     format!("{}{}{}", "$\n\\begin{nd}\n", proof_str, "\\end{nd}\n$")
         .replace("  ", " ")
         .replace("{ ", "{")
@@ -55,16 +86,131 @@ pub fn proof_to_latex(proof: &[ProofLine]) -> String {
         .replace(" \\", "\\")
 }
 
-/* ------------------ PRIVATE -------------------- */
+/// The LaTeX comment for a Fitch bar that does not follow from the `\hypo` lines above it, e.g. at
+/// the top of a proof without premises.
+pub const FITCH_BAR: &str = "% ----";
+
+/// The LaTeX comment for an empty line (like `  | |`), except for an empty line between two
+/// subproofs.
+pub const EMPTY_LINE: &str = "% |";
+
+/// The LaTeX comment below the last `\hypo` line of the premises or of a subproof, if there is no
+/// Fitch bar below it.
+pub const NO_FITCH_BAR: &str = "% (no Fitch bar)";
+
+/// Reads a proof that was exported with [proof_to_latex] back, so that it gives the same
+/// [ProofLine]s as the original proof. The LaTeX is first turned into a proof in the usual plain
+/// text format again, which is then parsed as usual.
+///
+/// Every line of the LaTeX should be a line like `\have{3}{A\land B}\ai{1,2}`, `\open` or
+/// `\close`, or a LaTeX comment like [FITCH_BAR]; other comments, empty lines and the lines around
+/// the `nd` environment are skipped. If a line cannot be read, or the proof cannot be parsed, then
+/// an error message is returned.
+pub fn latex_to_proof(latex: &str) -> Result<Vec<ProofLine>, String> {
+    let mut depth = 1;
+    let mut below_hypo_line = false;
+    let mut after_close = false;
+    let mut lines: Vec<String> = vec![];
+    let bars = |depth: usize| "| ".repeat(depth);
+    for (i, line) in latex.lines().enumerate() {
+        let line = line.trim();
+        let is_comment = [FITCH_BAR, EMPTY_LINE, NO_FITCH_BAR].contains(&line);
+        if matches!(line, "" | "$" | "\\begin{nd}" | "\\end{nd}")
+            || line.starts_with('%') && !is_comment
+        {
+            continue;
+        }
+        let is_hypo_line = line.starts_with("\\hypo{");
+        // a Fitch bar below the last \hypo line is not written in the LaTeX
+        if below_hypo_line && !is_hypo_line && line != NO_FITCH_BAR {
+            lines.push(format!("  {}----", bars(depth - 1) + "|"));
+        }
+        below_hypo_line = is_hypo_line;
+        // neither is an empty line between two subproofs
+        if after_close && line == "\\open" {
+            lines.push(format!("  {}", bars(depth).trim_end()));
+        }
+        after_close = line == "\\close";
+        match line {
+            "\\open" => depth += 1,
+            "\\close" if depth > 1 => depth -= 1,
+            FITCH_BAR => lines.push(format!("  {}----", bars(depth - 1) + "|")),
+            EMPTY_LINE => lines.push(format!("  {}", bars(depth).trim_end())),
+            NO_FITCH_BAR => {}
+            _ => match latex_line_to_text(line) {
+                Some((line_num, contents)) => {
+                    lines.push(format!("{line_num} {}{contents}", bars(depth)))
+                }
+                None => return Err(format!("line {} of the LaTeX cannot be read: {line}", i + 1)),
+            },
+        }
+    }
+    if below_hypo_line {
+        lines.push(format!("  {}----", bars(depth - 1) + "|"));
+    }
+    parser::parse_fitch_proof(&lines.join("\n"))
+        .map_err(|err| format!("the proof in the LaTeX cannot be parsed: {err}"))
+}
+
+/// Converts a [Wff] to a LaTeX string (in math mode). This uses [format_wff] under the hood.
+pub fn wff_to_latex(wff: &Wff) -> String {
+    symbols_to_latex(&format_wff(wff))
+}
 
-/// Converts a [Wff] to a LaTeX string. This uses [format_wff] under the hood.
-fn wff_to_latex(wff: &Wff) -> String {
-    let formatted = format_wff(wff);
+/// The Greek letters that have a LaTeX command, which may be used in names (see
+/// [symbols_to_latex]).
+pub const GREEK_LETTERS: &[(char, &str)] = &[
+    ('α', "\\alpha"),
+    ('β', "\\beta"),
+    ('γ', "\\gamma"),
+    ('δ', "\\delta"),
+    ('ε', "\\epsilon"),
+    ('ζ', "\\zeta"),
+    ('η', "\\eta"),
+    ('θ', "\\theta"),
+    ('ι', "\\iota"),
+    ('κ', "\\kappa"),
+    ('λ', "\\lambda"),
+    ('μ', "\\mu"),
+    ('ν', "\\nu"),
+    ('ξ', "\\xi"),
+    ('π', "\\pi"),
+    ('ρ', "\\rho"),
+    ('σ', "\\sigma"),
+    ('ς', "\\varsigma"),
+    ('τ', "\\tau"),
+    ('υ', "\\upsilon"),
+    ('φ', "\\phi"),
+    ('χ', "\\chi"),
+    ('ψ', "\\psi"),
+    ('ω', "\\omega"),
+    ('Γ', "\\Gamma"),
+    ('Δ', "\\Delta"),
+    ('Θ', "\\Theta"),
+    ('Λ', "\\Lambda"),
+    ('Ξ', "\\Xi"),
+    ('Π', "\\Pi"),
+    ('Σ', "\\Sigma"),
+    ('Υ', "\\Upsilon"),
+    ('Φ', "\\Phi"),
+    ('Ψ', "\\Psi"),
+    ('Ω', "\\Omega"),
+];
 
+/// Replaces the logical symbols in a string by LaTeX commands (in math mode). The Greek letters in
+/// [GREEK_LETTERS] are replaced as well, by a group like `{\alpha}`, so that a name like `αβ` stays
+/// one name. Other letters (like `é`) are kept as they are.
+pub fn symbols_to_latex(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        match GREEK_LETTERS.iter().find(|(letter, _)| *letter == c) {
+            Some((_, command)) => res.push_str(&format!("{{{command}}}")),
+            None => res.push(c),
+        }
+    }
     // better too many spaces then not enough...
     // we will eliminate duplicate spaces later
-    formatted
-        .replace('∧', " \\land ")
+    res.replace('∧', " \\land ")
         .replace('∨', " \\lor ")
         .replace('¬', " \\neg ")
         .replace('→', " \\rightarrow ")
@@ -74,6 +220,109 @@ fn wff_to_latex(wff: &Wff) -> String {
         .replace('∃', "\\exists ")
 }
 
+/// Replaces the LaTeX commands for the logical symbols in a string by the symbols, the other way
+/// around as [symbols_to_latex].
+pub fn latex_to_symbols(s: &str) -> String {
+    let mut s = s.to_string();
+    for (letter, command) in GREEK_LETTERS {
+        s = s.replace(&format!("{{{command}}}"), &letter.to_string());
+    }
+    s.replace("\\leftrightarrow", " ↔ ")
+        .replace("\\rightarrow", " → ")
+        .replace("\\land", " ∧ ")
+        .replace("\\lor", " ∨ ")
+        .replace("\\neg", " ¬")
+        .replace("\\bot", " ⊥ ")
+        .replace("\\forall", "∀")
+        .replace("\\exists", "∃")
+}
+
+/* ------------------ PRIVATE -------------------- */
+
+/// Turns a line like `\have{3}{\boxed{a}~P(a)}\Ae{1}` of an exported proof back into its line
+/// number and the rest of the line as plain text, like `[a] P(a)   ∀ Elim: 1`. If the line cannot
+/// be read, [None] is returned.
+fn latex_line_to_text(line: &str) -> Option<(usize, String)> {
+    let rest = line.strip_prefix("\\hypo{").or(line.strip_prefix("\\have{"))?;
+    let (line_num, rest) = rest.split_once('}')?;
+    let (contents, just) = split_group(rest)?;
+    let mut text = match contents.strip_prefix("\\boxed") {
+        Some(contents) => {
+            let (constant, sentence) = split_group(contents)?;
+            let sentence = sentence.strip_prefix('~')?;
+            format!("[{}] {}", latex_to_symbols(constant), latex_to_symbols(sentence))
+        }
+        None => latex_to_symbols(contents),
+    };
+    if !just.is_empty() {
+        text.push_str("   ");
+        text.push_str(&latex_to_justification(just)?);
+    }
+    Some((line_num.parse().ok()?, text))
+}
+
+/// Turns a justification like `\oe{1,2-3,4-5}` or `\by{QE}{4}` (see [justification_to_latex])
+/// back into plain text, like `∨ Elim: 1,2-3,4-5` or `QE: 4`.
+fn latex_to_justification(just: &str) -> Option<String> {
+    let command = just.strip_prefix('\\')?;
+    let name_len = command.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(command.len());
+    let (command, args) = command.split_at(name_len);
+    let rule = match command {
+        "r" => "Reit",
+        "ai" => "∧ Intro",
+        "ae" => "∧ Elim",
+        "oi" => "∨ Intro",
+        "oe" => "∨ Elim",
+        "ni" => "¬ Intro",
+        "ne" => "¬ Elim",
+        "idi" if args.is_empty() => return Some("= Intro".to_string()),
+        "ide" => "= Elim",
+        "ii" => "→ Intro",
+        "ie" => "→ Elim",
+        "bci" => "↔ Intro",
+        "bce" => "↔ Elim",
+        "bi" => "⊥ Intro",
+        "be" => "⊥ Elim",
+        "Ai" => "∀ Intro",
+        "Ae" => "∀ Elim",
+        "Ei" => "∃ Intro",
+        "Ee" => "∃ Elim",
+        "by" => {
+            let (rule, args) = split_group(args)?;
+            let rule = match rule {
+                "QE" | "Contra" | "Impl" => rule,
+                "$=\\,$Chain" => "= Chain",
+                _ => return None,
+            };
+            let (args, "") = split_group(args)? else {
+                return None;
+            };
+            return Some(format!("{rule}: {args}"));
+        }
+        _ => return None,
+    };
+    let (args, "") = split_group(args)? else {
+        return None;
+    };
+    Some(format!("{rule}: {args}"))
+}
+
+/// If `s` starts with a group like `{...}` (which may contain other groups), then the contents of
+/// the group and the rest of `s` are returned.
+fn split_group(s: &str) -> Option<(&str, &str)> {
+    let s = s.strip_prefix('{')?;
+    let mut nesting = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' => nesting += 1,
+            '}' if nesting == 0 => return Some((&s[..i], &s[i + 1..])),
+            '}' => nesting -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
 /// Converts a [Justification] to a LaTeX string.
 fn justification_to_latex(just: &Justification) -> String {
     match just {
@@ -105,5 +354,75 @@ fn justification_to_latex(just: &Justification) -> String {
         Justification::ForallElim(n) => format!("\\Ae{{{n}}}"),
         Justification::ExistsIntro(n) => format!("\\Ei{{{n}}}"),
         Justification::ExistsElim(n, (a, b)) => format!("\\Ee{{{n},{a}-{b}}}"),
+        Justification::QuantifierExchange(n) => format!("\\by{{QE}}{{{n}}}"),
+        Justification::Contraposition(n) => format!("\\by{{Contra}}{{{n}}}"),
+        Justification::MaterialImplication(n) => format!("\\by{{Impl}}{{{n}}}"),
+        Justification::EqualsChain(ns) => format!(
+            "\\by{{$=\\,$Chain}}{{{}}}",
+            ns.iter().map(|n| n.to_string()).collect::<Vec<String>>().join(",")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::random_proof;
+
+    fn assert_roundtrip(proof: &str) {
+        let lines = parser::parse_fitch_proof(proof).unwrap();
+        let latex = proof_to_latex(&lines);
+        assert_eq!(latex_to_proof(&latex), Ok(lines), "{latex}");
+    }
+
+    #[test]
+    fn test_latex_roundtrip() {
+        let proof = "
+1 | A ∨ B
+2 | ∀x (P(x) ∧ C)
+3 | a = b
+  |----
+4 | | A
+  | |----
+5 | | B ∨ A          ∨ Intro: 4
+  |
+6 | | B
+  | |----
+7 | | B ∨ A          ∨ Intro: 6
+8 | B ∨ A            ∨ Elim: 1, 4-5, 6-7
+9 | | [c]
+  | |----
+10| | P(c) ∧ C       ∀ Elim: 2
+11| | P(c)           ∧ Elim: 10
+12| ∀y P(y)          ∀ Intro: 9-11
+13| (A ∨ B) ∧ a = b  ∧ Intro: Prem
+14| b = a            = Elim: 3, 3
+15| ¬¬⊥ → ⊥          Impl: 14";
+        assert_roundtrip(proof);
+        // a proof as it is usually written does not need any comments
+        let latex = proof_to_latex(&parser::parse_fitch_proof(proof).unwrap());
+        assert!(!latex.contains('%'), "{latex}");
+
+        // but other proofs do
+        assert_roundtrip("  |----\n1 | a = a  = Intro");
+        assert_roundtrip(
+            "1 | A\n2 | | B\n  | |----\n  |\n  |\n3 | | | C\n  | | |----\n4 | A  Reit: 1",
+        );
+        assert_roundtrip("1 | A\n2 | A  Reit: 1\n  |----\n  | | \n3 | | B");
+        assert_roundtrip("1 | A\n  |----\n  |----\n2 | | B\n3 | B  Reit: 2");
+        assert_roundtrip(
+            "1 | ∀α Σοφός(α)\n  |----\n2 | | [σβ]\n  | |----\n3 | | Σοφός(σβ)  ∀Elim: 1",
+        );
+        for seed in 0..100 {
+            assert_roundtrip(&random_proof(seed, (seed % 15) as usize));
+        }
+    }
+
+    #[test]
+    fn test_latex_to_proof_errors() {
+        assert!(latex_to_proof("\\have{1}{A}\\frobnicate{1}").is_err());
+        assert!(latex_to_proof("\\have{one}{A}").is_err());
+        assert!(latex_to_proof("\\hypo{1}{A}\n\\close").is_err());
+        assert!(latex_to_proof("\\hypo{1}{A\\land}").is_err());
     }
 }
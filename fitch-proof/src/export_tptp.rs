@@ -0,0 +1,175 @@
+use crate::data::*;
+use crate::formatter::format_wff;
+
+/// Exports a sequent (some premises and a conclusion) as a problem in the FOF (first-order form)
+/// language of TPTP, which automated theorem provers like E and Vampire read. The premises are
+/// axioms, named `premise_1`, `premise_2`, ..., and the conclusion is the conjecture, named
+/// `conclusion`. For example, the premise A ∧ B and the conclusion ∀x (P(x) → B) become
+///
+/// ```notrust
+/// fof(premise_1, axiom, ('A' & 'B')).
+/// fof(conclusion, conjecture, (! [X] : ('P'(X) => 'B'))).
+/// ```
+///
+/// In TPTP, the names of predicates start with a lowercase letter, and the names of variables with
+/// an uppercase letter. So the predicates and atomic sentences are quoted, like `'P'`, and the
+/// first letter of every bound variable is made uppercase. Constants and functions keep their
+/// name (quoted, if it is not a valid name in TPTP).
+///
+/// An error message is returned if a sentence quantifies over sentences (see
+/// [CheckOptions::propositional_quantifiers]), since that cannot be written in FOF.
+pub fn sequent_to_tptp(premises: &[Wff], conclusion: &Wff) -> Result<String, String> {
+    let mut res = vec![];
+    for (i, premise) in premises.iter().enumerate() {
+        res.push(format!("fof(premise_{}, axiom, {}).", i + 1, wff_to_tptp(premise, &mut vec![])?));
+    }
+    res.push(format!("fof(conclusion, conjecture, {}).", wff_to_tptp(conclusion, &mut vec![])?));
+    Ok(res.join("\n"))
+}
+
+/// Exports the premises and the conclusion of a proof (not the proof itself) as a TPTP problem,
+/// see [sequent_to_tptp]. The premises are the sentences above the first Fitch bar, and the
+/// conclusion is the last sentence outside the subproofs.
+///
+/// An error message is returned if the proof has no conclusion, or if the problem cannot be
+/// written in TPTP.
+pub fn proof_to_tptp(proof: &[ProofLine]) -> Result<String, String> {
+    let premises: Vec<Wff> = proof
+        .iter()
+        .take_while(|l| !l.is_fitch_bar_line)
+        .filter_map(|l| l.sentence.clone())
+        .collect();
+    let conclusion = proof
+        .iter()
+        .skip_while(|l| !l.is_fitch_bar_line)
+        .filter(|l| l.depth == 1)
+        .filter_map(|l| l.sentence.as_ref())
+        .last();
+    match conclusion {
+        Some(conclusion) => sequent_to_tptp(&premises, conclusion),
+        None => Err("the proof has no conclusion".to_string()),
+    }
+}
+
+/* ------------------ PRIVATE -------------------- */
+
+/// Writes a [Wff] in TPTP syntax, where `bound` are the variables that are bound around it.
+fn wff_to_tptp(wff: &Wff, bound: &mut Vec<String>) -> Result<String, String> {
+    let mut join = |wffs: &[Wff], op: &str| -> Result<String, String> {
+        let wffs = wffs.iter().map(|w| wff_to_tptp(w, bound)).collect::<Result<Vec<_>, _>>()?;
+        Ok(format!("({})", wffs.join(&format!(" {op} "))))
+    };
+    Ok(match wff {
+        Wff::Bottom => "$false".to_string(),
+        Wff::Atomic(name) => quote(name),
+        Wff::PredApp(name, args) => {
+            let args: Vec<String> = args.iter().map(|t| term_to_tptp(t, bound)).collect();
+            format!("{}({})", quote(name), args.join(","))
+        }
+        Wff::Equals(t1, t2) => format!("{} = {}", term_to_tptp(t1, bound), term_to_tptp(t2, bound)),
+        Wff::And(li) => join(li, "&")?,
+        Wff::Or(li) => join(li, "|")?,
+        Wff::Implies(w1, w2) => join(&[*w1.clone(), *w2.clone()], "=>")?,
+        Wff::Bicond(w1, w2) => join(&[*w1.clone(), *w2.clone()], "<=>")?,
+        Wff::Not(w) => format!("~ {}", wff_to_tptp(w, bound)?),
+        Wff::Forall(var, w) | Wff::Exists(var, w) => {
+            if var.starts_with(|c: char| c.is_uppercase()) {
+                return Err(format!(
+                    "{} quantifies over sentences, which cannot be written in TPTP",
+                    format_wff(wff)
+                ));
+            }
+            let quantifier = match wff {
+                Wff::Forall(..) => "!",
+                _ => "?",
+            };
+            bound.push(var.to_string());
+            let res = wff_to_tptp(w, bound);
+            bound.pop();
+            format!("({quantifier} [{}] : {})", variable(var), res?)
+        }
+    })
+}
+
+/// Writes a [Term] in TPTP syntax, where `bound` are the variables that are bound around it.
+fn term_to_tptp(term: &Term, bound: &[String]) -> String {
+    match term {
+        Term::Atomic(name) if bound.contains(name) => variable(name),
+        Term::Atomic(name) => functor(name),
+        Term::FuncApp(name, args) => {
+            let args: Vec<String> = args.iter().map(|t| term_to_tptp(t, bound)).collect();
+            format!("{}({})", functor(name), args.join(","))
+        }
+    }
+}
+
+/// Returns the name of a variable in TPTP: the same name, starting with an uppercase letter (or
+/// with `V`, if it does not start with an ASCII letter). Every character that is not an ASCII
+/// letter or digit is written as its code point between underscores, like `_3b1_` for α.
+fn variable(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_string(),
+            false => format!("_{:x}_", c as u32),
+        })
+        .collect();
+    match name.chars().next() {
+        Some(c) if c.is_ascii_alphabetic() => c.to_ascii_uppercase().to_string() + &name[1..],
+        _ => format!("V{name}"),
+    }
+}
+
+/// Returns the name of a constant or function in TPTP: the same name, quoted if it is not a valid
+/// name (a lowercase letter, followed by letters, digits and underscores).
+fn functor(name: &str) -> String {
+    let is_lower_word = name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    match is_lower_word {
+        true => name.to_string(),
+        false => quote(name),
+    }
+}
+
+/// Returns a name between single quotes, which is always a valid name in TPTP. Only printable
+/// ASCII characters may occur between quotes, so every other character is written as its code
+/// point between underscores, like in [variable]: `Σ` becomes `_3a3_`.
+fn quote(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '\\' | '\'' => format!("\\{c}"),
+            ' '..='~' => c.to_string(),
+            _ => format!("_{:x}_", c as u32),
+        })
+        .collect();
+    format!("'{name}'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_logical_expression_string;
+
+    #[test]
+    fn test_sequent_to_tptp() {
+        let wff = |s: &str| parse_logical_expression_string(s).unwrap();
+        assert_eq!(
+            sequent_to_tptp(
+                &[wff("∀x (P(x) → ∃y R(x, f(y)))"), wff("¬(a = b) ∨ ⊥")],
+                &wff("A ↔ (B ∧ C ∧ D)")
+            ),
+            Ok("fof(premise_1, axiom, (! [X] : ('P'(X) => (? [Y] : 'R'(X,f(Y)))))).\n\
+                fof(premise_2, axiom, (~ a = b | $false)).\n\
+                fof(conclusion, conjecture, ('A' <=> ('B' & 'C' & 'D')))."
+                .to_string())
+        );
+        assert!(sequent_to_tptp(&[], &wff("∀P (P → P)")).is_err());
+        assert_eq!(
+            sequent_to_tptp(&[], &wff("∀α Σοφός(α, σωκράτης)")),
+            Ok("fof(conclusion, conjecture, (! [V_3b1_] : '_3a3__3bf__3c6__3cc__3c2_'(V_3b1_,\
+                '_3c3__3c9__3ba__3c1__3ac__3c4__3b7__3c2_')))."
+                .to_string())
+        );
+    }
+}
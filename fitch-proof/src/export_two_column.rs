@@ -0,0 +1,131 @@
+use crate::data::*;
+use crate::export_to_latex::{symbols_to_latex, wff_to_latex};
+use crate::formatter::{format_line_justification, format_term, format_wff};
+use std::fmt::Write;
+
+/// Exports a proof in two columns: the sentences (with their line numbers, indented by the depth
+/// of the subproof they are in) and their justifications, as a LaTeX tabular or an HTML table.
+/// Unlike [crate::export_to_latex::proof_to_latex], there are no Fitch bars. Premises and
+/// assumptions get the justification `Premise` and `Assumption`.
+pub fn proof_to_two_columns(proof: &[ProofLine], options: &TwoColumnOptions) -> String {
+    let rows = rows(proof, options);
+    match options.format {
+        TwoColumnFormat::Latex => {
+            let column = |width: &Option<String>| match width {
+                Some(width) => format!("p{{{width}}}"),
+                None => "l".to_string(),
+            };
+            let mut res = format!(
+                "\\begin{{tabular}}{{{}{}}}\n",
+                column(&options.formula_width),
+                column(&options.justification_width)
+            );
+            for (number, depth, formula, justification) in rows {
+                let _ = writeln!(
+                    res,
+                    "{}{}${}$ & {} \\\\",
+                    number.map_or("".to_string(), |n| format!("{n}~")),
+                    "\\quad ".repeat(depth - 1),
+                    formula,
+                    justification
+                );
+            }
+            res.push_str("\\end{tabular}");
+            res
+        }
+        TwoColumnFormat::Html => {
+            let mut res = "<table class=\"two-column-proof\">\n".to_string();
+            if options.formula_width.is_some() || options.justification_width.is_some() {
+                let column = |width: &Option<String>| match width {
+                    Some(width) => format!("<col style=\"width: {width}\">"),
+                    None => "<col>".to_string(),
+                };
+                let _ = writeln!(
+                    res,
+                    "<colgroup>{}{}</colgroup>",
+                    column(&options.formula_width),
+                    column(&options.justification_width)
+                );
+            }
+            for (number, depth, formula, justification) in rows {
+                let _ = writeln!(
+                    res,
+                    "<tr><td>{}{}{}</td><td>{}</td></tr>",
+                    number.map_or("".to_string(), |n| format!("{n} ")),
+                    "&emsp;".repeat(depth - 1),
+                    formula,
+                    justification
+                );
+            }
+            res.push_str("</table>");
+            res
+        }
+    }
+}
+
+/* ------------------ PRIVATE -------------------- */
+
+/// Returns the rows of the two-column export of a proof: the formatted line number (if any), the
+/// depth, the sentence and the justification of every line with a line number, in the format of
+/// the options.
+fn rows(
+    proof: &[ProofLine],
+    options: &TwoColumnOptions,
+) -> Vec<(Option<String>, usize, String, String)> {
+    let latex = options.format == TwoColumnFormat::Latex;
+    let mut prev_depth = 1;
+    let mut is_hypo = true;
+    let mut res = vec![];
+    for l in proof {
+        if l.is_fitch_bar_line {
+            is_hypo = false;
+        }
+        if l.depth > prev_depth {
+            is_hypo = true;
+        }
+        prev_depth = l.depth;
+        let Some(n) = l.line_num else {
+            continue;
+        };
+        let number = match options.numbering {
+            Numbering::Plain => Some(format!("{n}.")),
+            Numbering::Parenthesized => Some(format!("({n})")),
+            Numbering::None => None,
+        };
+        let mut formula = match (&l.constant_between_square_brackets, latex) {
+            (Some(c), true) => format!("\\boxed{{{}}}~ ", format_term(c)),
+            (Some(c), false) => format!("[{}] ", format_term(c)),
+            (None, _) => "".to_string(),
+        };
+        match (&l.sentence, latex) {
+            (Some(wff), true) => formula.push_str(&wff_to_latex(wff)),
+            (Some(wff), false) => formula.push_str(&format_wff(wff)),
+            (None, _) => {}
+        }
+        let justification = match format_line_justification(l) {
+            _ if is_hypo && l.depth == 1 => "Premise".to_string(),
+            _ if is_hypo => "Assumption".to_string(),
+            Some(just) if latex => justification_to_latex(&just),
+            Some(just) => just,
+            None => "".to_string(),
+        };
+        let formula = formula.split_whitespace().collect::<Vec<_>>().join(" ");
+        res.push((number, l.depth, formula, justification));
+    }
+    res
+}
+
+/// Converts a formatted justification (see [format_line_justification]) to LaTeX (in text mode),
+/// by putting the logical symbols in math mode.
+fn justification_to_latex(just: &str) -> String {
+    just.chars()
+        .map(|c| {
+            let latex = symbols_to_latex(&c.to_string());
+            if latex == c.to_string() {
+                latex
+            } else {
+                format!("${}$", latex.trim())
+            }
+        })
+        .collect()
+}
@@ -74,6 +74,16 @@ pub fn fix_line_numbers(proof_lines: &mut [ProofLine]) {
                 Justification::ImpliesElim(n, m) => {
                     Justification::ImpliesElim(new_val(n), new_val(m))
                 }
+                Justification::QuantifierExchange(n) => {
+                    Justification::QuantifierExchange(new_val(n))
+                }
+                Justification::Contraposition(n) => Justification::Contraposition(new_val(n)),
+                Justification::MaterialImplication(n) => {
+                    Justification::MaterialImplication(new_val(n))
+                }
+                Justification::EqualsChain(ns) => {
+                    Justification::EqualsChain(ns.iter().map(new_val).collect())
+                }
             });
         }
     }
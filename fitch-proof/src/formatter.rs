@@ -10,8 +10,8 @@ pub fn format_proof(proof_lines: Vec<ProofLine>) -> String {
     let mut line_strings: Vec<String> = proof_lines
         .iter()
         .map(|pl| {
-            if pl.line_num.is_some() {
-                pl.line_num.unwrap().to_string()
+            if let Some(line_num) = pl.line_num {
+                line_num.to_string()
             } else {
                 "".to_string()
             }
@@ -31,28 +31,23 @@ pub fn format_proof(proof_lines: Vec<ProofLine>) -> String {
     }
 
     for (line, line_string) in zip(&proof_lines, &mut line_strings) {
-        if line.constant_between_square_brackets.is_some() {
-            line_string.push_str(" [");
-            line_string.push_str(match line.constant_between_square_brackets.as_ref().unwrap() {
-                Term::Atomic(str) => str,
-                _ => panic!(),
-            });
-            line_string.push(']');
+        if let Some(boxed_constant) = &line.constant_between_square_brackets {
+            line_string.push_str(&format!(" [{}]", format_term(boxed_constant)));
         }
     }
 
     for (line, line_string) in zip(&proof_lines, &mut line_strings) {
-        if line.sentence.is_some() {
+        if let Some(sentence) = &line.sentence {
             line_string.push(' ');
-            line_string.push_str(&format_wff(line.sentence.as_ref().unwrap()));
+            line_string.push_str(&format_wff(sentence));
         }
     }
 
     pad_to_same_length(&mut line_strings, 9);
 
     for (line, line_string) in zip(&proof_lines, &mut line_strings) {
-        if line.justification.is_some() {
-            line_string.push_str(&format_justification(line.justification.as_ref().unwrap()));
+        if let Some(justification) = format_line_justification(line) {
+            line_string.push_str(&justification);
         }
     }
 
@@ -118,6 +113,40 @@ pub fn format_term(term: &Term) -> String {
     }
 }
 
+/// Guesses which [SymbolSet] a proof is written in: [SymbolSet::Ascii] as soon as it contains an
+/// ASCII connective, [SymbolSet::Traditional] if it contains ⊃ or ≡, and [SymbolSet::Unicode]
+/// otherwise. This never returns [SymbolSet::Auto].
+pub fn detect_symbol_set(proof: &str) -> SymbolSet {
+    if ["->", "&", "/\\", "\\/", "~", "_|_"].iter().any(|sym| proof.contains(sym)) {
+        SymbolSet::Ascii
+    } else if proof.contains(['⊃', '≡']) {
+        SymbolSet::Traditional
+    } else {
+        SymbolSet::Unicode
+    }
+}
+
+/// Rewrites the (Unicode) connectives in a string, which may be a formula or a whole error
+/// message, with the symbols of the given [SymbolSet]. For [SymbolSet::Unicode] and
+/// [SymbolSet::Auto], the string is returned as is.
+pub fn render_symbols(s: &str, symbols: SymbolSet) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        match (symbols, c) {
+            (SymbolSet::Ascii, '∧') => res.push('&'),
+            (SymbolSet::Ascii, '∨') => res.push_str("\\/"),
+            (SymbolSet::Ascii, '→') => res.push_str("->"),
+            (SymbolSet::Ascii, '↔') => res.push_str("<->"),
+            (SymbolSet::Ascii, '¬') => res.push('~'),
+            (SymbolSet::Ascii, '⊥') => res.push_str("_|_"),
+            (SymbolSet::Traditional, '→') => res.push('⊃'),
+            (SymbolSet::Traditional, '↔') => res.push('≡'),
+            (_, c) => res.push(c),
+        }
+    }
+    res
+}
+
 /* ------------------ PRIVATE -------------------- */
 
 /// Given a slice of [String]s, this function modifies it by padding all strings with spaces so
@@ -131,8 +160,19 @@ fn pad_to_same_length(strings: &mut [String], extra: usize) {
     }
 }
 
+/// Makes a [String] out of the justification of a [ProofLine], if it has one. If the
+/// justification cites all premises with `Prem` (see [ProofLine::cites_premises]), then `Prem` is
+/// written instead of the line numbers of the premises.
+pub fn format_line_justification(line: &ProofLine) -> Option<String> {
+    let formatted = format_justification(line.justification.as_ref()?);
+    match formatted.split_once(':') {
+        Some((rule, _)) if line.cites_premises => Some(format!("{rule}: Prem")),
+        _ => Some(formatted),
+    }
+}
+
 /// Makes a [String] out of a [Justification].
-fn format_justification(just: &Justification) -> String {
+pub fn format_justification(just: &Justification) -> String {
     match just {
         Justification::Reit(n) => format!("Reit: {n}"),
         Justification::AndIntro(ns) => {
@@ -158,9 +198,23 @@ fn format_justification(just: &Justification) -> String {
         Justification::ForallElim(n) => format!("∀ Elim: {n}"),
         Justification::ExistsIntro(n) => format!("∃ Intro: {n}"),
         Justification::ExistsElim(n, (a, b)) => format!("∃ Elim: {n}, {a}-{b}"),
+        Justification::QuantifierExchange(n) => format!("QE: {n}"),
+        Justification::Contraposition(n) => format!("Contra: {n}"),
+        Justification::MaterialImplication(n) => format!("Impl: {n}"),
+        Justification::EqualsChain(ns) => {
+            format!("= Chain: {}", ns.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(", "))
+        }
     }
 }
 
+/// Returns the name of the rule of a [Justification], without the cited lines, as it is usually
+/// written, e.g. `∧Elim` or `Reit`.
+pub fn rule_name(just: &Justification) -> String {
+    let formatted = format_justification(just);
+    // unwrap works, since split always returns at least one part
+    formatted.split(':').next().unwrap().replace(' ', "")
+}
+
 /// This function removes any spaces at the end of a [String].
 fn remove_whitespace_at_end(s: &mut String) {
     let rpo = s.chars().collect::<Vec<_>>().iter().rposition(|c| c != &' ').unwrap();
@@ -0,0 +1,182 @@
+use crate::data::*;
+use std::collections::BTreeSet;
+
+/// The greatest number of different atomic sentences for which [MatchPolicy::Equivalent] is
+/// checked, since the truth table of a sentence with n atomic sentences has 2^n rows.
+pub const MAX_ATOMS: usize = 16;
+
+/// Returns whether a student's sentence matches a reference sentence, e.g. in an exercise where a
+/// sentence in English is translated into first-order logic. How closely the two should match is
+/// given by the [MatchPolicy].
+///
+/// An error message is returned if the policy is [MatchPolicy::Equivalent], but one of the
+/// sentences is not propositional, or the sentences have more than [MAX_ATOMS] different atomic
+/// sentences together.
+pub fn formulas_match(student: &Wff, reference: &Wff, policy: MatchPolicy) -> Result<bool, String> {
+    match policy {
+        MatchPolicy::Syntactic => Ok(student == reference),
+        MatchPolicy::Alpha => Ok(alpha_equivalent(student, reference, &mut vec![])),
+        MatchPolicy::Equivalent => {
+            if !is_propositional(student) || !is_propositional(reference) {
+                return Err(
+                    "logical equivalence can only be checked for propositional sentences, \
+                    without quantifiers, predicates with arguments and identity"
+                        .to_string(),
+                );
+            }
+            let mut atoms: BTreeSet<&str> = BTreeSet::new();
+            add_atoms(student, &mut atoms);
+            add_atoms(reference, &mut atoms);
+            if atoms.len() > MAX_ATOMS {
+                return Err(format!(
+                    "the sentences have {} different atomic sentences, but logical equivalence \
+                    can only be checked for at most {MAX_ATOMS}",
+                    atoms.len()
+                ));
+            }
+            let atoms: Vec<&str> = atoms.into_iter().collect();
+            Ok((0..1u32 << atoms.len()).all(|row| {
+                let value = |atom: &str| {
+                    let i = atoms.iter().position(|a| *a == atom).unwrap();
+                    row & (1 << i) != 0
+                };
+                evaluate(student, &value) == evaluate(reference, &value)
+            }))
+        }
+    }
+}
+
+/* ------------------ PRIVATE -------------------- */
+
+/// Returns whether two sentences are the same up to the names of the bound variables, like
+/// `∀x P(x)` and `∀y P(y)`. The variables that are bound around the sentences are in `bound`, as
+/// pairs of a variable in `wff1` and the corresponding variable in `wff2` (innermost last).
+fn alpha_equivalent<'a>(wff1: &'a Wff, wff2: &'a Wff, bound: &mut Vec<(&'a str, &'a str)>) -> bool {
+    match (wff1, wff2) {
+        (Wff::And(li1), Wff::And(li2)) | (Wff::Or(li1), Wff::Or(li2)) => {
+            li1.len() == li2.len()
+                && li1.iter().zip(li2).all(|(w1, w2)| alpha_equivalent(w1, w2, bound))
+        }
+        (Wff::Implies(a1, b1), Wff::Implies(a2, b2))
+        | (Wff::Bicond(a1, b1), Wff::Bicond(a2, b2)) => {
+            alpha_equivalent(a1, a2, bound) && alpha_equivalent(b1, b2, bound)
+        }
+        (Wff::Not(w1), Wff::Not(w2)) => alpha_equivalent(w1, w2, bound),
+        (Wff::Forall(v1, w1), Wff::Forall(v2, w2)) | (Wff::Exists(v1, w1), Wff::Exists(v2, w2)) => {
+            bound.push((v1, v2));
+            let res = alpha_equivalent(w1, w2, bound);
+            bound.pop();
+            res
+        }
+        (Wff::PredApp(p1, args1), Wff::PredApp(p2, args2)) => {
+            p1 == p2
+                && args1.len() == args2.len()
+                && args1.iter().zip(args2).all(|(t1, t2)| alpha_equivalent_terms(t1, t2, bound))
+        }
+        (Wff::Equals(s1, t1), Wff::Equals(s2, t2)) => {
+            alpha_equivalent_terms(s1, s2, bound) && alpha_equivalent_terms(t1, t2, bound)
+        }
+        _ => wff1 == wff2,
+    }
+}
+
+/// Returns whether two terms are the same up to the names of the bound variables, see
+/// [alpha_equivalent]. A bound name must be bound by corresponding quantifiers, and a free name
+/// must be the same name in both terms.
+fn alpha_equivalent_terms(term1: &Term, term2: &Term, bound: &[(&str, &str)]) -> bool {
+    match (term1, term2) {
+        (Term::Atomic(n1), Term::Atomic(n2)) => {
+            let binder1 = bound.iter().rposition(|(v1, _)| v1 == n1);
+            let binder2 = bound.iter().rposition(|(_, v2)| v2 == n2);
+            match (binder1, binder2) {
+                (None, None) => n1 == n2,
+                (i, j) => i == j,
+            }
+        }
+        (Term::FuncApp(f1, args1), Term::FuncApp(f2, args2)) => {
+            f1 == f2
+                && args1.len() == args2.len()
+                && args1.iter().zip(args2).all(|(t1, t2)| alpha_equivalent_terms(t1, t2, bound))
+        }
+        _ => false,
+    }
+}
+
+/// Returns whether a sentence is propositional: it has no quantifiers, no predicates with
+/// arguments and no identity.
+fn is_propositional(wff: &Wff) -> bool {
+    match wff {
+        Wff::And(li) | Wff::Or(li) => li.iter().all(is_propositional),
+        Wff::Implies(w1, w2) | Wff::Bicond(w1, w2) => is_propositional(w1) && is_propositional(w2),
+        Wff::Not(w) => is_propositional(w),
+        Wff::Bottom | Wff::Atomic(_) => true,
+        Wff::Forall(..) | Wff::Exists(..) | Wff::PredApp(..) | Wff::Equals(..) => false,
+    }
+}
+
+/// Adds the names of the atomic sentences in a propositional sentence to `atoms`.
+fn add_atoms<'a>(wff: &'a Wff, atoms: &mut BTreeSet<&'a str>) {
+    match wff {
+        Wff::And(li) | Wff::Or(li) => li.iter().for_each(|w| add_atoms(w, atoms)),
+        Wff::Implies(w1, w2) | Wff::Bicond(w1, w2) => {
+            add_atoms(w1, atoms);
+            add_atoms(w2, atoms);
+        }
+        Wff::Not(w) => add_atoms(w, atoms),
+        Wff::Atomic(name) => {
+            atoms.insert(name);
+        }
+        Wff::Bottom | Wff::Forall(..) | Wff::Exists(..) | Wff::PredApp(..) | Wff::Equals(..) => {}
+    }
+}
+
+/// Returns the truth value of a propositional sentence, where `value` gives the truth value of
+/// every atomic sentence.
+fn evaluate(wff: &Wff, value: &impl Fn(&str) -> bool) -> bool {
+    match wff {
+        Wff::And(li) => li.iter().all(|w| evaluate(w, value)),
+        Wff::Or(li) => li.iter().any(|w| evaluate(w, value)),
+        Wff::Implies(w1, w2) => !evaluate(w1, value) || evaluate(w2, value),
+        Wff::Bicond(w1, w2) => evaluate(w1, value) == evaluate(w2, value),
+        Wff::Not(w) => !evaluate(w, value),
+        Wff::Atomic(name) => value(name),
+        Wff::Bottom | Wff::Forall(..) | Wff::Exists(..) | Wff::PredApp(..) | Wff::Equals(..) => {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_logical_expression_string;
+
+    fn matches(student: &str, reference: &str, policy: MatchPolicy) -> Result<bool, String> {
+        let wff = |s: &str| parse_logical_expression_string(s).unwrap();
+        formulas_match(&wff(student), &wff(reference), policy)
+    }
+
+    #[test]
+    fn test_alpha_equivalence() {
+        let alpha = MatchPolicy::Alpha;
+        assert_eq!(matches("∀x ∃y R(x, y)", "∀y ∃x R(y, x)", alpha), Ok(true));
+        assert_eq!(matches("∀x ∃y R(x, y)", "∀x ∃y R(y, x)", alpha), Ok(false));
+        assert_eq!(matches("∀x (P(x) ∧ ∀x Q(x))", "∀y (P(y) ∧ ∀z Q(z))", alpha), Ok(true));
+        // free names should stay the same
+        assert_eq!(matches("∀x R(x, a)", "∀y R(y, a)", alpha), Ok(true));
+        assert_eq!(matches("∀x R(x, a)", "∀a R(a, a)", alpha), Ok(false));
+        assert_eq!(matches("∀x (f(x) = x)", "∀y (f(y) = y)", alpha), Ok(true));
+        assert_eq!(matches("∀x P(x)", "∀y P(y)", MatchPolicy::Syntactic), Ok(false));
+    }
+
+    #[test]
+    fn test_logical_equivalence() {
+        let equivalent = MatchPolicy::Equivalent;
+        assert_eq!(matches("¬(A ∧ B)", "¬A ∨ ¬B", equivalent), Ok(true));
+        assert_eq!(matches("A → B", "¬B → ¬A", equivalent), Ok(true));
+        assert_eq!(matches("A → B", "B → A", equivalent), Ok(false));
+        assert_eq!(matches("A ∨ ¬A", "¬⊥", equivalent), Ok(true));
+        assert_eq!(matches("A ∧ ¬A", "B ∧ ¬B", equivalent), Ok(true));
+        assert!(matches("P(a)", "P(a)", equivalent).is_err());
+    }
+}
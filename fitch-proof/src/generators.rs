@@ -0,0 +1,329 @@
+use crate::data::*;
+use crate::formatter;
+use crate::util::Rng;
+
+/// The propositional atoms, predicates (with their arities), function symbols (with their
+/// arities), constants and variables that the generators use. The variables are among the default
+/// allowed variable names, so generated proofs can be checked with [crate::check_proof] and
+/// `"x,y,z,u,v,w"`.
+const ATOMS: [&str; 3] = ["A", "B", "C"];
+const PREDICATES: [(&str, usize); 2] = [("P", 1), ("Q", 2)];
+const FUNCTIONS: [(&str, usize); 2] = [("f", 1), ("g", 2)];
+const CONSTANTS: [&str; 3] = ["a", "b", "c"];
+const VARIABLES: [&str; 3] = ["x", "y", "z"];
+
+/// Returns a random well-formed closed formula (so all its variables are bound), with at most
+/// `size` connectives and quantifiers. The same `seed` always gives the same formula.
+pub fn random_formula(seed: u64, size: usize) -> String {
+    formatter::format_wff(&random_wff(&mut Rng::new(seed), size, &[]))
+}
+
+/// Returns a random proof that is correct, in which about `size` rules are applied. The proof
+/// uses ∧Intro, ∧Elim, ∨Intro, →Intro (with nested subproofs), →Elim, =Intro, ∀Elim and Reit.
+/// The same `seed` always gives the same proof.
+///
+/// The proof is formatted, and uses the variables `x`, `y` and `z`, so it can be checked with
+/// [crate::check_proof] and the default variable names.
+pub fn random_proof(seed: u64, size: usize) -> String {
+    let mut builder = ProofBuilder {
+        rng: Rng::new(seed),
+        lines: vec![],
+        next_line_num: 1,
+    };
+    let mut scopes: Vec<Vec<(usize, Wff)>> = vec![vec![]];
+    for _ in 0..builder.rng.below(4) {
+        let premise = random_wff(&mut builder.rng, 3, &[]);
+        builder.push_line(1, premise, None, &mut scopes);
+    }
+    builder.push_fitch_bar_line(1);
+    builder.push_inferences(1, size.max(1), &mut scopes);
+    formatter::format_proof(builder.lines)
+}
+
+/* ------------------ PRIVATE -------------------- */
+
+/// Returns a random closed [Term], in which the variables `bound` may occur.
+fn random_term(rng: &mut Rng, depth: usize, bound: &[&str]) -> Term {
+    match rng.below(6) {
+        0 | 1 if depth > 0 => {
+            let (name, arity) = *rng.choose(&FUNCTIONS);
+            Term::FuncApp(
+                name.to_string(),
+                (0..arity).map(|_| random_term(rng, depth - 1, bound)).collect(),
+            )
+        }
+        2 | 3 if !bound.is_empty() => Term::Atomic(rng.choose(bound).to_string()),
+        _ => Term::Atomic(rng.choose(&CONSTANTS).to_string()),
+    }
+}
+
+/// Returns a random [Wff] with at most `size` connectives and quantifiers, in which the variables
+/// `bound` may occur (free).
+fn random_wff(rng: &mut Rng, size: usize, bound: &[&str]) -> Wff {
+    if size == 0 {
+        return match rng.below(5) {
+            0 => Wff::Bottom,
+            1 | 2 => {
+                let (name, arity) = *rng.choose(&PREDICATES);
+                Wff::PredApp(
+                    name.to_string(),
+                    (0..arity).map(|_| random_term(rng, 1, bound)).collect(),
+                )
+            }
+            3 => Wff::Equals(random_term(rng, 1, bound), random_term(rng, 1, bound)),
+            _ => Wff::Atomic(rng.choose(&ATOMS).to_string()),
+        };
+    }
+    let rem = size - 1;
+    match rng.below(7) {
+        0 => Wff::Not(Box::new(random_wff(rng, rem, bound))),
+        1 | 2 => {
+            // two or three parts, which share the remaining size
+            let first = rng.below(rem + 1);
+            let second = rng.below(rem - first + 1);
+            let mut sizes = vec![first, rem - first];
+            if rng.below(3) == 0 {
+                sizes = vec![first, second, rem - first - second];
+            }
+            let parts = sizes.into_iter().map(|s| random_wff(rng, s, bound)).collect();
+            if rng.below(2) == 0 {
+                Wff::And(parts)
+            } else {
+                Wff::Or(parts)
+            }
+        }
+        3 | 4 => {
+            let first = rng.below(rem + 1);
+            let w1 = Box::new(random_wff(rng, first, bound));
+            let w2 = Box::new(random_wff(rng, rem - first, bound));
+            if rng.below(2) == 0 {
+                Wff::Implies(w1, w2)
+            } else {
+                Wff::Bicond(w1, w2)
+            }
+        }
+        _ => {
+            // nested quantifiers over the same variable are not allowed
+            let unbound: Vec<&str> = VARIABLES.into_iter().filter(|v| !bound.contains(v)).collect();
+            if unbound.is_empty() {
+                return Wff::Not(Box::new(random_wff(rng, rem, bound)));
+            }
+            let var = *rng.choose(&unbound);
+            let bound: Vec<&str> = bound.iter().copied().chain([var]).collect();
+            let wff = Box::new(random_wff(rng, rem, &bound));
+            if rng.below(2) == 0 {
+                Wff::Forall(var.to_string(), wff)
+            } else {
+                Wff::Exists(var.to_string(), wff)
+            }
+        }
+    }
+}
+
+/// Replaces all (free) occurrences of the variable `var` in `wff` by `term`.
+fn substitute(wff: &Wff, var: &str, term: &Term) -> Wff {
+    fn substitute_term(t: &Term, var: &str, term: &Term) -> Term {
+        match t {
+            Term::Atomic(name) if name == var => term.clone(),
+            Term::Atomic(_) => t.clone(),
+            Term::FuncApp(name, args) => Term::FuncApp(
+                name.clone(),
+                args.iter().map(|a| substitute_term(a, var, term)).collect(),
+            ),
+        }
+    }
+    let sub = |w: &Wff| Box::new(substitute(w, var, term));
+    match wff {
+        Wff::And(li) => Wff::And(li.iter().map(|w| substitute(w, var, term)).collect()),
+        Wff::Or(li) => Wff::Or(li.iter().map(|w| substitute(w, var, term)).collect()),
+        Wff::Implies(w1, w2) => Wff::Implies(sub(w1), sub(w2)),
+        Wff::Bicond(w1, w2) => Wff::Bicond(sub(w1), sub(w2)),
+        Wff::Not(w) => Wff::Not(sub(w)),
+        Wff::Forall(v, _) | Wff::Exists(v, _) if v == var => wff.clone(),
+        Wff::Forall(v, w) => Wff::Forall(v.clone(), sub(w)),
+        Wff::Exists(v, w) => Wff::Exists(v.clone(), sub(w)),
+        Wff::Bottom | Wff::Atomic(_) => wff.clone(),
+        Wff::PredApp(name, args) => {
+            Wff::PredApp(name.clone(), args.iter().map(|a| substitute_term(a, var, term)).collect())
+        }
+        Wff::Equals(t1, t2) => {
+            Wff::Equals(substitute_term(t1, var, term), substitute_term(t2, var, term))
+        }
+    }
+}
+
+/// Builds a random correct proof, line by line (see [random_proof]).
+struct ProofBuilder {
+    rng: Rng,
+    lines: Vec<ProofLine>,
+    next_line_num: usize,
+}
+
+impl ProofBuilder {
+    /// Adds a line with a sentence. The line can then be cited from the innermost scope of
+    /// `scopes` (and the scopes inside it). Returns the line number of the new line.
+    fn push_line(
+        &mut self,
+        depth: usize,
+        sentence: Wff,
+        justification: Option<Justification>,
+        scopes: &mut [Vec<(usize, Wff)>],
+    ) -> usize {
+        let line_num = self.next_line_num;
+        self.next_line_num += 1;
+        // unwrap works, since there is always at least one scope
+        scopes.last_mut().unwrap().push((line_num, sentence.clone()));
+        self.lines.push(ProofLine {
+            line_num: Some(line_num),
+            depth,
+            is_fitch_bar_line: false,
+            sentence: Some(sentence),
+            justification,
+            constant_between_square_brackets: None,
+            cites_premises: false,
+        });
+        line_num
+    }
+
+    fn push_fitch_bar_line(&mut self, depth: usize) {
+        self.lines.push(ProofLine {
+            line_num: None,
+            depth,
+            is_fitch_bar_line: true,
+            sentence: None,
+            justification: None,
+            constant_between_square_brackets: None,
+            cites_premises: false,
+        });
+    }
+
+    /// Adds `steps` random inferences at the given depth.
+    fn push_inferences(&mut self, depth: usize, steps: usize, scopes: &mut Vec<Vec<(usize, Wff)>>) {
+        for _ in 0..steps {
+            self.push_inference(depth, scopes);
+        }
+    }
+
+    /// Adds one random inference at the given depth. If the randomly chosen rule cannot be
+    /// applied, then =Intro is used (which can always be applied).
+    fn push_inference(&mut self, depth: usize, scopes: &mut Vec<Vec<(usize, Wff)>>) {
+        let accessible: Vec<(usize, Wff)> = scopes.iter().flatten().cloned().collect();
+        let (sentence, justification) = match self.rng.below(9) {
+            0 | 1 if !accessible.is_empty() => {
+                let (n1, w1) = self.rng.choose(&accessible).clone();
+                let (n2, w2) = self.rng.choose(&accessible).clone();
+                (Wff::And(vec![w1, w2]), Justification::AndIntro(vec![n1, n2]))
+            }
+            2 => {
+                let conjunctions: Vec<&(usize, Wff)> =
+                    accessible.iter().filter(|(_, w)| matches!(w, Wff::And(_))).collect();
+                let Some((n, Wff::And(conjs))) = conjunctions.first().map(|c| (*c).clone()) else {
+                    return self.push_equals_intro(depth, scopes);
+                };
+                (self.rng.choose(&conjs).clone(), Justification::AndElim(n))
+            }
+            3 if !accessible.is_empty() => {
+                let (n, w) = self.rng.choose(&accessible).clone();
+                let other = random_wff(&mut self.rng, 2, &[]);
+                let disjuncts = if self.rng.below(2) == 0 {
+                    vec![w, other]
+                } else {
+                    vec![other, w]
+                };
+                (Wff::Or(disjuncts), Justification::OrIntro(n))
+            }
+            4 | 5 if depth < 4 => {
+                // sometimes assume an accessible sentence, so that →Elim can be applied later
+                let assumption = match self.rng.below(2) {
+                    0 if !accessible.is_empty() => self.rng.choose(&accessible).1.clone(),
+                    _ => random_wff(&mut self.rng, 2, &[]),
+                };
+                scopes.push(vec![]);
+                let begin = self.push_line(depth + 1, assumption.clone(), None, scopes);
+                self.push_fitch_bar_line(depth + 1);
+                let steps = 1 + self.rng.below(3);
+                self.push_inferences(depth + 1, steps, scopes);
+                // unwraps work, since the subproof contains at least one line
+                let (end, conclusion) = scopes.pop().unwrap().pop().unwrap();
+                (
+                    Wff::Implies(Box::new(assumption), Box::new(conclusion)),
+                    Justification::ImpliesIntro((begin, end)),
+                )
+            }
+            6 => {
+                let modus_ponens = accessible.iter().find_map(|(n, w)| match w {
+                    Wff::Implies(a, b) => accessible
+                        .iter()
+                        .find(|(_, w)| w == &**a)
+                        .map(|(m, _)| ((**b).clone(), Justification::ImpliesElim(*n, *m))),
+                    _ => None,
+                });
+                match modus_ponens {
+                    Some(inference) => inference,
+                    None => return self.push_equals_intro(depth, scopes),
+                }
+            }
+            7 => {
+                let universals: Vec<&(usize, Wff)> =
+                    accessible.iter().filter(|(_, w)| matches!(w, Wff::Forall(..))).collect();
+                let Some((n, Wff::Forall(var, wff))) = universals.first().map(|u| (*u).clone())
+                else {
+                    return self.push_equals_intro(depth, scopes);
+                };
+                let term = random_term(&mut self.rng, 1, &[]);
+                (substitute(&wff, &var, &term), Justification::ForallElim(n))
+            }
+            8 if depth > 1 => {
+                // reiterate a line from an enclosing scope
+                let outer: Vec<(usize, Wff)> =
+                    scopes[..scopes.len() - 1].iter().flatten().cloned().collect();
+                if outer.is_empty() {
+                    return self.push_equals_intro(depth, scopes);
+                }
+                let (n, w) = self.rng.choose(&outer).clone();
+                (w, Justification::Reit(n))
+            }
+            _ => return self.push_equals_intro(depth, scopes),
+        };
+        self.push_line(depth, sentence, Some(justification), scopes);
+    }
+
+    fn push_equals_intro(&mut self, depth: usize, scopes: &mut [Vec<(usize, Wff)>]) {
+        let term = random_term(&mut self.rng, 1, &[]);
+        let sentence = Wff::Equals(term.clone(), term);
+        self.push_line(depth, sentence, Some(Justification::EqualsIntro), scopes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    #[test]
+    fn test_parse_format_round_trip() {
+        for seed in 0..2000 {
+            let wff = random_wff(&mut Rng::new(seed), (seed % 12) as usize, &[]);
+            let formatted = formatter::format_wff(&wff);
+            let parsed = parser::parse_logical_expression_string(&formatted);
+            assert_eq!(parsed, Some(wff), "{formatted}");
+        }
+    }
+
+    #[test]
+    fn test_random_proofs_are_correct() {
+        for seed in 0..500 {
+            let proof = random_proof(seed, (seed % 15) as usize);
+            let res = crate::check_proof(&proof, "x,y,z,u,v,w");
+            assert_eq!(res, "The proof is correct!", "{proof}");
+            assert_eq!(crate::format_proof(&proof), proof);
+        }
+    }
+
+    #[test]
+    fn test_generators_are_deterministic() {
+        assert_eq!(random_formula(42, 8), random_formula(42, 8));
+        assert_eq!(random_proof(42, 8), random_proof(42, 8));
+        assert_ne!(random_proof(42, 8), random_proof(43, 8));
+    }
+}
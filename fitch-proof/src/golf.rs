@@ -0,0 +1,116 @@
+use crate::data::*;
+use crate::proof::*;
+
+/// The golf score of a correct proof in a proof-golf competition: how long the proof is and how
+/// deep its subproofs are nested, compared to the shortest known proof (see
+/// [ProofTemplate::minimal_length] and [ProofTemplate::minimal_depth]). Like in golf, a lower score
+/// is better. See [crate::golf_proof].
+#[derive(PartialEq, Debug, Clone)]
+pub struct GolfScore {
+    /// The number of lines with a line number, including the premises.
+    pub lines: usize,
+    /// The greatest number of subproofs that some line is in, so 0 if there are no subproofs.
+    pub nesting_depth: usize,
+    /// The number of lines of the shortest known proof.
+    pub minimal_length: usize,
+    /// The nesting depth of the shortest known proof, if it is known.
+    pub minimal_depth: Option<usize>,
+}
+
+impl GolfScore {
+    /// Computes the golf score of a proof. This does not check whether the proof is correct.
+    /// [None] is returned if the template does not give the length of the shortest known proof.
+    pub fn new(proof: &Proof, template: &ProofTemplate) -> Option<GolfScore> {
+        Some(GolfScore {
+            lines: proof.lines.iter().filter(|line| line.line_num.is_some()).count(),
+            nesting_depth: proof.lines.iter().map(|line| line.depth).max().unwrap_or(1) - 1,
+            minimal_length: template.minimal_length?,
+            minimal_depth: template.minimal_depth,
+        })
+    }
+
+    /// Returns the number of lines of the proof divided by the number of lines of the shortest
+    /// known proof, so 1 if the proof is just as long.
+    pub fn length_ratio(&self) -> f64 {
+        self.lines as f64 / self.minimal_length.max(1) as f64
+    }
+
+    /// Returns the nesting depth of the proof divided by the nesting depth of the shortest known
+    /// proof, where both are counted from 1 (so that a proof without subproofs can be compared).
+    /// This is 1 if the nesting depth of the shortest known proof is not known.
+    pub fn depth_ratio(&self) -> f64 {
+        match self.minimal_depth {
+            Some(min) => (self.nesting_depth + 1) as f64 / (min + 1) as f64,
+            None => 1.0,
+        }
+    }
+
+    /// Returns the golf score: 100 times the [GolfScore::length_ratio] times the
+    /// [GolfScore::depth_ratio]. So a proof that is just as long and just as deeply nested as the
+    /// shortest known proof scores 100 ("par"), a longer proof scores more and a shorter proof
+    /// scores less.
+    pub fn score(&self) -> f64 {
+        100.0 * self.length_ratio() * self.depth_ratio()
+    }
+
+    /// Returns the golf score as a JSON object, for example:
+    ///
+    /// `{"lines":4,"nesting_depth":1,"minimal_length":3,"minimal_depth":1,"length_ratio":1.333,
+    /// "depth_ratio":1.000,"score":133.3}`
+    ///
+    /// where `minimal_depth` is `null` if it is not known.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"lines\":{},\"nesting_depth\":{},\"minimal_length\":{},\"minimal_depth\":{},\
+            \"length_ratio\":{:.3},\"depth_ratio\":{:.3},\"score\":{:.1}}}",
+            self.lines,
+            self.nesting_depth,
+            self.minimal_length,
+            self.minimal_depth.map_or("null".to_string(), |min| min.to_string()),
+            self.length_ratio(),
+            self.depth_ratio(),
+            self.score()
+        )
+    }
+}
+
+/// Returns the rank of every golf score in a competition: 1 for the lowest (best) score, and so
+/// on. Equal scores share a rank, and the next rank is skipped, like 1, 2, 2, 4. The ranks are in
+/// the same order as the scores.
+pub fn rank_golf_scores(scores: &[GolfScore]) -> Vec<usize> {
+    let mut sorted: Vec<f64> = scores.iter().map(GolfScore::score).collect();
+    sorted.sort_by(f64::total_cmp);
+    scores.iter().map(|golf| 1 + sorted.partition_point(|other| *other < golf.score())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_golf_score() {
+        let golf = |lines: usize, nesting_depth: usize| GolfScore {
+            lines,
+            nesting_depth,
+            minimal_length: 4,
+            minimal_depth: Some(1),
+        };
+        assert_eq!(golf(4, 1).score(), 100.0);
+        assert_eq!(golf(6, 1).score(), 150.0);
+        assert_eq!(golf(4, 3).score(), 200.0);
+        let unknown_depth = GolfScore {
+            minimal_depth: None,
+            ..golf(2, 3)
+        };
+        assert_eq!(unknown_depth.score(), 50.0);
+        assert_eq!(
+            golf(6, 1).to_json(),
+            "{\"lines\":6,\"nesting_depth\":1,\"minimal_length\":4,\"minimal_depth\":1,\
+            \"length_ratio\":1.500,\"depth_ratio\":1.000,\"score\":150.0}"
+        );
+        assert_eq!(
+            rank_golf_scores(&[golf(6, 1), golf(4, 1), golf(6, 1), golf(8, 1)]),
+            vec![2, 1, 2, 4]
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use crate::checker;
+use crate::data::*;
+use crate::formatter;
+use std::collections::HashSet;
+
+/// Returns a hint for a proof that is not correct yet, which becomes more specific as the student
+/// makes more attempts. The hint is about the first line that needs a (different) justification
+/// (see [checker::suggest_justification]), and depending on the number of earlier `attempts` and
+/// the [HintPolicy], it says
+///
+/// 1. nothing yet, except after how many attempts a hint is given,
+/// 2. which family of rules to use (e.g. the rules for ∧),
+/// 3. which rule to use (e.g. ∧Elim), or
+/// 4. which rule to use, and which lines to cite (e.g. ∧Elim: 1).
+///
+/// If no single rule makes the line correct, then the hint says so (a step may be missing).
+pub fn hint(
+    proof_lines: Vec<ProofLine>,
+    allowed_variable_names: HashSet<String>,
+    options: CheckOptions,
+    policy: &HintPolicy,
+    attempts: usize,
+) -> String {
+    let (line_num, suggestion) =
+        match checker::suggest_justification(proof_lines, allowed_variable_names, options) {
+            Err(err) => return format!("Fatal error: {err}"),
+            Ok(None) => {
+                return "All lines of the proof are justified correctly, so there is no hint."
+                    .to_string()
+            }
+            Ok(Some(suggestion)) => suggestion,
+        };
+    let first_hint_after =
+        policy.rule_family_after.min(policy.rule_after).min(policy.citations_after);
+    if attempts < first_hint_after {
+        return match first_hint_after - attempts {
+            1 => "No hint yet: a hint is given after 1 more attempt.".to_string(),
+            n => format!("No hint yet: a hint is given after {n} more attempts."),
+        };
+    }
+    let Some(just) = suggestion else {
+        return format!(
+            "Line {line_num}: this line does not follow from the lines before it with a single \
+            rule. Maybe a step is missing before it?"
+        );
+    };
+    let rule = formatter::rule_name(&just);
+    if attempts >= policy.citations_after {
+        let formatted = formatter::format_justification(&just);
+        match formatted.split_once(':') {
+            Some((_, citations)) => format!("Line {line_num}: use {rule}:{citations}."),
+            None => format!("Line {line_num}: use {rule}."),
+        }
+    } else if attempts >= policy.rule_after {
+        format!("Line {line_num}: try {rule}.")
+    } else {
+        match rule_family(&just) {
+            Some(connective) => format!("Line {line_num}: try one of the rules for {connective}."),
+            None if matches!(just, Justification::Reit(_)) => format!(
+                "Line {line_num}: the sentence in this line already occurs in a line that it \
+                can cite."
+            ),
+            None => format!("Line {line_num}: try one of the derived rules."),
+        }
+    }
+}
+
+/* ------------------ PRIVATE -------------------- */
+
+/// Returns the connective or quantifier that the rule of a justification is about, e.g. `∧` for
+/// ∧Intro and ∧Elim. Reit and the derived rules are not about one connective, so for these, [None]
+/// is returned.
+fn rule_family(just: &Justification) -> Option<String> {
+    let rule = formatter::rule_name(just);
+    let family = rule.strip_suffix("Intro").or(rule.strip_suffix("Elim"))?;
+    Some(family.to_string())
+}
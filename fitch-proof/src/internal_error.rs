@@ -0,0 +1,147 @@
+use crate::data::{FatalError, ProofResult};
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+/// The greatest number of smaller inputs that [minimize_reproduction] tries, so that minimizing
+/// the input of a slow check on a long proof does not take forever.
+const MAX_REPRODUCTION_ATTEMPTS: usize = 200;
+
+/// Runs `check` on `input`, and returns its result. If `check` runs into a bug in the checker,
+/// i.e. it returns a [FatalError::Internal] or it panics, then a [FatalError::Internal] is
+/// returned, with a smaller input on which `check` still runs into a bug (see
+/// [minimize_reproduction]), so that the bug is easy to reproduce. Only the first panic is
+/// reported by the panic hook; the panics while the input is minimized are not.
+///
+/// Note that a panic can only be caught if the code is compiled with `panic = "unwind"`. This is
+/// the default for native code, but in WebAssembly (and in a release build of the CLI) a panic
+/// still aborts the program. That is why the checker returns a [FatalError::Internal] instead of
+/// panicking when it gets into a state that should be impossible.
+pub fn catch_internal_errors(input: &str, check: impl Fn(&str) -> ProofResult) -> ProofResult {
+    let fails = |input: &str| !matches!(catch_panic_silently(|| check(input)), Some(res) if !is_internal(&res));
+    match catch_panic(|| check(input)) {
+        Some(res) if !is_internal(&res) => res,
+        _ => ProofResult::FatalError(FatalError::Internal(minimize_reproduction(input, fails))),
+    }
+}
+
+/// Runs `f`, and returns its result, or [None] if it panics (see [catch_internal_errors] for
+/// when a panic can be caught).
+pub fn catch_panic<T>(f: impl FnOnce() -> T) -> Option<T> {
+    panic::catch_unwind(AssertUnwindSafe(f)).ok()
+}
+
+/// Given an `input` for which `fails(input)` is true, this function returns a (hopefully much
+/// shorter) input for which `fails` is still true, by leaving out lines of `input`. First, large
+/// blocks of lines are left out, and then smaller and smaller blocks, until no single line can be
+/// left out anymore, or [MAX_REPRODUCTION_ATTEMPTS] inputs have been tried.
+pub fn minimize_reproduction(input: &str, fails: impl Fn(&str) -> bool) -> String {
+    let mut lines: Vec<&str> = input.lines().collect();
+    let mut block_size = lines.len().div_ceil(2).max(1);
+    let mut attempts = 0;
+    loop {
+        let mut left_out_something = false;
+        let mut i = 0;
+        while i < lines.len() && lines.len() > 1 {
+            if attempts == MAX_REPRODUCTION_ATTEMPTS {
+                return lines.join("\n");
+            }
+            let end = (i + block_size).min(lines.len());
+            let candidate: Vec<&str> = [&lines[..i], &lines[end..]].concat();
+            attempts += 1;
+            if !candidate.is_empty() && fails(&candidate.join("\n")) {
+                lines = candidate;
+                left_out_something = true;
+            } else {
+                i = end;
+            }
+        }
+        if block_size == 1 && !left_out_something {
+            break;
+        }
+        if !left_out_something {
+            block_size = block_size.div_ceil(2);
+        }
+    }
+    lines.join("\n")
+}
+
+/* ------------------ PRIVATE -------------------- */
+
+thread_local! {
+    /// Whether a panic on this thread should be kept from the panic hook, see
+    /// [catch_panic_silently].
+    static SILENCE_PANICS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Makes sure that the panic hook skips the panics on threads that silence them, see
+/// [catch_panic_silently].
+static INSTALL_SILENCEABLE_HOOK: Once = Once::new();
+
+/// Same as [catch_panic], but a panic is not reported by the panic hook (which normally prints
+/// the panic message and a backtrace).
+fn catch_panic_silently<T>(f: impl FnOnce() -> T) -> Option<T> {
+    INSTALL_SILENCEABLE_HOOK.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !SILENCE_PANICS.get() {
+                hook(info);
+            }
+        }));
+    });
+    let silenced = SILENCE_PANICS.replace(true);
+    let res = catch_panic(f);
+    SILENCE_PANICS.set(silenced);
+    res
+}
+
+/// Returns whether the result of a check is a [FatalError::Internal].
+fn is_internal(res: &ProofResult) -> bool {
+    matches!(res, ProofResult::FatalError(FatalError::Internal(_)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimize_reproduction() {
+        let input = "1\n2\n3 X\n4\n5\n6 Y\n7\n8\n9\n10";
+        let fails = |s: &str| s.contains('X') && s.contains('Y');
+        assert_eq!(minimize_reproduction(input, fails), "3 X\n6 Y");
+        assert_eq!(minimize_reproduction("X", |s: &str| s.contains('X')), "X");
+
+        // the number of attempts is limited
+        let input: String = (0..1000).map(|i| format!("{i}\n")).collect();
+        let attempts = std::cell::Cell::new(0);
+        // only fails if all multiples of 7 are in it, which takes many attempts to find out
+        let fails = |s: &str| {
+            attempts.set(attempts.get() + 1);
+            s.lines().filter(|line| line.parse::<usize>().is_ok_and(|n| n % 7 == 0)).count() == 143
+        };
+        let res = minimize_reproduction(&input, fails);
+        assert_eq!(attempts.get(), MAX_REPRODUCTION_ATTEMPTS);
+        assert!(fails(&res));
+    }
+
+    #[test]
+    fn test_catch_internal_errors() {
+        let check = |s: &str| {
+            if s.contains("boom") {
+                panic!("this is a test");
+            }
+            ProofResult::Correct
+        };
+        assert!(matches!(catch_internal_errors("1 | A\n2 | B", check), ProofResult::Correct));
+        let res = catch_internal_errors("1 | A\n2 | boom\n3 | B", check);
+        assert!(matches!(res, ProofResult::FatalError(FatalError::Internal(s)) if s == "2 | boom"));
+
+        // an internal error that is returned (instead of a panic) is minimized as well
+        let check = |s: &str| match s.contains("bug") {
+            true => ProofResult::FatalError(FatalError::Internal(s.to_string())),
+            false => ProofResult::Correct,
+        };
+        let res = catch_internal_errors("1 | A\n2 | bug\n3 | B", check);
+        assert!(matches!(res, ProofResult::FatalError(FatalError::Internal(s)) if s == "2 | bug"));
+    }
+}
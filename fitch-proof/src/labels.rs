@@ -0,0 +1,274 @@
+use crate::parser;
+
+/// Replaces the labels of a proof whose lines are labeled instead of numbered, like
+///
+/// ```notrust
+/// (H1) | A ∧ B
+///      |----
+/// (a)  | A          ∧Elim: (H1)
+/// ```
+///
+/// by the line numbers 1, 2, ..., both in front of the lines and in the justifications (where a
+/// range like `(a)-(c)` becomes `1-3`). A label consists of letters, digits, `_` and `'` between
+/// parentheses. The proof with line numbers is returned, together with the labels: the label of
+/// line `n` is `labels[n - 1]`. A proof without labels is returned as it is, with no labels.
+///
+/// If a label is used for two lines, if a label is cited that no line has, or if some lines have
+/// a label and others a number, then an error message is returned.
+pub fn labels_to_numbers(proof: &str) -> Result<(String, Vec<String>), String> {
+    let mut labels: Vec<String> = vec![];
+    let mut has_numbers = false;
+    for line in proof.lines().filter(|line| !parser::is_directive(line)) {
+        let line = line.trim_start();
+        if line.starts_with(|c: char| c.is_ascii_digit()) {
+            has_numbers = true;
+        }
+        if let Some((label, _)) = split_label(line) {
+            if labels.iter().any(|l| l == label) {
+                return Err(format!("the label ({label}) is used for more than one line"));
+            }
+            labels.push(label.to_string());
+        }
+    }
+    if labels.is_empty() {
+        return Ok((proof.to_string(), labels));
+    }
+    if has_numbers {
+        return Err("some lines have a label and others have a line number, but a proof should \
+            use only one of the two"
+            .to_string());
+    }
+
+    let number = |label: &str| match labels.iter().position(|l| l == label) {
+        Some(i) => Ok((i + 1).to_string()),
+        None => Err(format!("the label ({label}) is cited, but there is no line with this label")),
+    };
+    let mut res = String::new();
+    for line in proof.split_inclusive('\n') {
+        if parser::is_directive(line) {
+            res.push_str(line);
+            continue;
+        }
+        let rest = line.trim_start();
+        res.push_str(&line[..line.len() - rest.len()]);
+        let rest = match split_label(rest) {
+            Some((label, rest)) => {
+                res.push_str(&number(label)?);
+                rest
+            }
+            None => rest,
+        };
+        let Some((contents, citations)) = rest.split_once(':') else {
+            res.push_str(rest);
+            continue;
+        };
+        res.push_str(contents);
+        res.push(':');
+        let mut citations = citations;
+        while let Some(start) = citations.find('(') {
+            res.push_str(&citations[..start]);
+            match split_label(&citations[start..]) {
+                Some((label, after)) => {
+                    res.push_str(&number(label)?);
+                    citations = after;
+                }
+                None => {
+                    res.push('(');
+                    citations = &citations[start + 1..];
+                }
+            }
+        }
+        res.push_str(citations);
+    }
+    Ok((res, labels))
+}
+
+/// Replaces the line numbers in a formatted proof (see [crate::formatter::format_proof]) by the
+/// labels, the other way around as [labels_to_numbers]: line `n` gets the label `labels[n - 1]`.
+/// The labels are padded to the same width, so that the Fitch bars stay aligned. Line numbers
+/// without a label are left as they are.
+pub fn numbers_to_labels(proof: &str, labels: &[String]) -> String {
+    if labels.is_empty() {
+        return proof.to_string();
+    }
+    let label = |number: &str| label_of(number, labels);
+    let lines: Vec<(Option<String>, &str)> = proof
+        .lines()
+        .map(|line| match line.split_once('|') {
+            Some((prefix, rest)) if !parser::is_directive(line) => {
+                let prefix = match prefix.trim() {
+                    "" => "".to_string(),
+                    number => label(number),
+                };
+                (Some(prefix), rest)
+            }
+            _ => (None, line),
+        })
+        .collect();
+    let width = lines
+        .iter()
+        .filter_map(|(prefix, _)| prefix.as_ref().map(|p| p.chars().count()))
+        .max()
+        .unwrap_or(0);
+    let lines: Vec<String> = lines
+        .into_iter()
+        .map(|(prefix, rest)| {
+            let Some(prefix) = prefix else {
+                return rest.to_string();
+            };
+            let mut line = format!("{prefix:width$} |");
+            match rest.split_once(':') {
+                Some((contents, citations)) => {
+                    line.push_str(contents);
+                    line.push(':');
+                    line.push_str(&map_numbers(citations, label));
+                }
+                None => line.push_str(rest),
+            }
+            line
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/// Replaces the line numbers in a message about a proof (like an error message of the proof
+/// checker) by the labels of the lines, see [labels_to_numbers]. Only numbers after words like
+/// `line`, `lines` and `subproof`, and after a `:` (like in `∧Elim: 1`) are replaced, and the
+/// numbers in a list or range after them, like `lines 1, 2 and 3` or `subproof 2-4`.
+pub fn numbers_to_labels_in_message(message: &str, labels: &[String]) -> String {
+    if labels.is_empty() {
+        return message.to_string();
+    }
+    let label = |number: &str| label_of(number, labels);
+    let mut res = String::new();
+    let mut after_line = false;
+    for word in message.split_inclusive(char::is_whitespace) {
+        let core = word.trim_end().trim_end_matches([',', '.', ':', ';', ')']);
+        if core.is_empty() {
+            res.push_str(word);
+            continue;
+        }
+        let is_number =
+            core.split('-').all(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+        if after_line && is_number {
+            res.push_str(&map_numbers(core, label));
+            res.push_str(&word[core.len()..]);
+        } else {
+            res.push_str(word);
+        }
+        after_line = match core.to_lowercase().as_str() {
+            "line" | "lines" | "subproof" | "subproofs" => true,
+            "and" | "or" | "to" => after_line,
+            _ if is_number => after_line,
+            _ => word.trim_end().ends_with(':'),
+        };
+    }
+    res
+}
+
+/// Returns the labels `a`, `b`, ..., `z`, `aa`, `ab`, ... for `n` lines.
+pub fn letter_labels(n: usize) -> Vec<String> {
+    (1..=n)
+        .map(|mut i| {
+            let mut label = vec![];
+            while i > 0 {
+                i -= 1;
+                label.push((b'a' + (i % 26) as u8) as char);
+                i /= 26;
+            }
+            label.into_iter().rev().collect()
+        })
+        .collect()
+}
+
+/* ------------------ PRIVATE -------------------- */
+
+/// If `s` starts with a label like `(H1)`, then the label (without the parentheses) and the rest
+/// of `s` are returned.
+fn split_label(s: &str) -> Option<(&str, &str)> {
+    let (label, rest) = s.strip_prefix('(')?.split_once(')')?;
+    let is_label = !label.is_empty()
+        && label.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '\'')
+        && !label.chars().all(|c| c.is_ascii_digit());
+    if is_label {
+        Some((label, rest))
+    } else {
+        None
+    }
+}
+
+/// Returns the label of the line with the given number, like `(H1)`. If there is no such line,
+/// then the number is returned as it is.
+fn label_of(number: &str, labels: &[String]) -> String {
+    let n = number.parse::<usize>().ok().and_then(|n| n.checked_sub(1));
+    match n.and_then(|n| labels.get(n)) {
+        Some(label) => format!("({label})"),
+        None => number.to_string(),
+    }
+}
+
+/// Applies `f` to every run of digits in `s`.
+fn map_numbers(s: &str, f: impl Fn(&str) -> String) -> String {
+    let mut res = String::new();
+    let mut number = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        if !number.is_empty() {
+            res.push_str(&f(&number));
+            number.clear();
+        }
+        res.push(c);
+    }
+    if !number.is_empty() {
+        res.push_str(&f(&number));
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labels_to_numbers() {
+        let proof =
+            "(H1) | A ∧ B\n     |----\n(a)  | A      ∧Elim: (H1)\n(b)  | A ∧ A  ∧Intro: (a), (a)";
+        let (numbered, labels) = labels_to_numbers(proof).unwrap();
+        assert_eq!(
+            numbered,
+            "1 | A ∧ B\n     |----\n2  | A      ∧Elim: 1\n3  | A ∧ A  ∧Intro: 2, 2"
+        );
+        assert_eq!(labels, vec!["H1", "a", "b"]);
+
+        assert_eq!(
+            labels_to_numbers("1 | A\n2 | A  Reit: 1"),
+            Ok(("1 | A\n2 | A  Reit: 1".to_string(), vec![]))
+        );
+        assert!(labels_to_numbers("(a) | A\n(a) | A  Reit: (a)").is_err());
+        assert!(labels_to_numbers("(a) | A\n(b) | A  Reit: (c)").is_err());
+        assert!(labels_to_numbers("(a) | A\n2 | A  Reit: (a)").is_err());
+    }
+
+    #[test]
+    fn test_numbers_to_labels() {
+        let labels = vec!["H1".to_string(), "a".to_string(), "b".to_string()];
+        let proof = "1 | A ∧ B\n  |----\n2 | A      ∧ Elim: 1\n3 | A ∧ A  ∧ Intro: 2, 2";
+        assert_eq!(
+            numbers_to_labels(proof, &labels),
+            "(H1) | A ∧ B\n     |----\n(a)  | A      ∧ Elim: (H1)\n(b)  | A ∧ A  ∧ Intro: (a), (a)"
+        );
+        assert_eq!(
+            numbers_to_labels_in_message("Line 3: the rule ∨ Elim: 1, 2-3 is used, but lines 1 and 2 and subproof 2-3 are", &labels),
+            "Line (b): the rule ∨ Elim: (H1), (a)-(b) is used, but lines (H1) and (a) and subproof (a)-(b) are"
+        );
+        assert_eq!(
+            numbers_to_labels_in_message("There are 2 mistakes in line 5.", &labels),
+            "There are 2 mistakes in line 5."
+        );
+        assert_eq!(letter_labels(28)[..3], ["a", "b", "c"]);
+        assert_eq!(letter_labels(28)[25..], ["z", "aa", "ab"]);
+    }
+}
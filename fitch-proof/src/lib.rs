@@ -1,19 +1,70 @@
 use wasm_bindgen::prelude::*;
+
+macro_rules! default_variable_names {
+    () => {
+        "x,y,z,u,v,w"
+    };
+}
+
+#[cfg(feature = "bundle")]
+mod bundle;
+mod cache;
 mod checker;
 mod data;
+pub mod error_codes;
+#[cfg(feature = "export")]
+mod export_forallx;
+#[cfg(feature = "export")]
 mod export_to_latex;
+#[cfg(feature = "export")]
+mod export_tptp;
+#[cfg(feature = "export")]
+mod export_two_column;
 mod fix_line_numbers;
 mod formatter;
+mod formula_match;
+#[cfg(any(test, feature = "testing"))]
+mod generators;
+mod golf;
+#[cfg(feature = "tutoring")]
+mod hints;
+mod internal_error;
+mod labels;
+#[cfg(feature = "tutoring")]
+mod lint;
+#[cfg(feature = "tutoring")]
+mod metrics;
 mod parser;
+mod profile;
 mod proof;
+mod rename;
+mod render;
+#[cfg(feature = "tutoring")]
+mod replay;
+#[cfg(feature = "tutoring")]
+mod score_card;
+#[cfg(feature = "tutoring")]
+mod summary;
+mod text_edits;
 mod util;
-use crate::data::{ProofResult, Wff};
+#[cfg(feature = "bundle")]
+pub use crate::bundle::{Exercise, ExerciseBundle};
+pub use crate::cache::CheckCache;
+pub use crate::data::{ConclusionPlacement, LineStatus, SymbolSet};
+#[cfg(feature = "testing")]
+pub use crate::generators::{random_formula, random_proof};
+pub use crate::golf::{rank_golf_scores, GolfScore};
+pub use crate::profile::CheckProfile;
+pub use crate::text_edits::{TextEdit, TextPosition};
 
-macro_rules! default_variable_names {
-    () => {
-        "x,y,z,u,v,w"
-    };
-}
+use crate::data::{
+    CheckOptions, ExpectedError, FatalError, FeedbackDetail, LineLabels, ProofError, ProofLine,
+    ProofResult, Wff,
+};
+use crate::render::DiagnosticsRenderer;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::iter::zip;
 
 /// Checks if a string is a fully correct proof.
 ///
@@ -26,12 +77,52 @@ macro_rules! default_variable_names {
 /// This function never panics.
 #[wasm_bindgen]
 pub fn check_proof(proof: &str, allowed_variable_names: &str) -> String {
-    let res = check_proof_to_proofresult(proof, allowed_variable_names);
-    match res {
-        ProofResult::Correct => "The proof is correct!".to_string(),
-        ProofResult::Error(errs) => errs.join("\n\n"),
-        ProofResult::FatalError(err) => format!("Fatal error: {err}"),
-    }
+    check_proof_with_options(proof, allowed_variable_names, "")
+}
+
+/// Same as [check_proof], but with a list of options (like `"symbols=ascii"`) that change how the
+/// proof is checked and how the results are reported. See [parser::parse_check_options] for the
+/// known options.
+///
+/// This function never panics.
+#[wasm_bindgen]
+pub fn check_proof_with_options(
+    proof: &str,
+    allowed_variable_names: &str,
+    options: &str,
+) -> String {
+    let (res, labels) = check_proof_with_labels(proof, None, allowed_variable_names, options);
+    labels::numbers_to_labels_in_message(&proof_result_to_string(res), &labels)
+}
+
+/// Same as [check_proof_with_options], but the result is rendered by the renderer with the given
+/// name, which is one of:
+///
+/// - `plain`: the messages, like [check_proof_with_options] returns them.
+/// - `full`: the messages, where every mistake about a line is followed by that line of the proof.
+/// - `json`: a JSON object with the verdict and the mistakes, with their line numbers and error
+///   codes, and the kind of a fatal error (e.g. whether the proof or the template is broken).
+/// - `sarif`: a SARIF 2.1.0 log, for code scanning tools and editors.
+/// - `html`: an HTML fragment with a list of the mistakes.
+///
+/// See [render] for the details. If there is no renderer with that name, then a fatal error is
+/// returned (as plain text). In a proof with labels instead of line numbers (see
+/// [labels::labels_to_numbers]), the messages use the labels, and the line numbers of the
+/// mistakes in the `json`, `sarif` and `html` output are the positions of the labeled lines.
+///
+/// This function never panics.
+#[wasm_bindgen]
+pub fn check_proof_with_renderer(
+    proof: &str,
+    allowed_variable_names: &str,
+    options: &str,
+    renderer: &str,
+) -> String {
+    let Some(renderer) = render::renderer(renderer) else {
+        return proof_result_to_string(unknown_renderer(renderer));
+    };
+    let (res, labels) = check_proof_with_labels(proof, None, allowed_variable_names, options);
+    renderer.render(&numbers_to_labels_in_proof_result(res, &labels), proof)
 }
 
 /// Checks if a string is a fully correct proof that matches a given proof template.
@@ -49,12 +140,283 @@ pub fn check_proof_with_template(
     template: Vec<String>,
     allowed_variable_names: &str,
 ) -> String {
-    let res = check_proof_to_proofresult_with_template(proof, &template, allowed_variable_names);
+    check_proof_with_template_and_options(proof, template, allowed_variable_names, "")
+}
+
+/// Same as [check_proof_with_template], but with a list of options, see
+/// [check_proof_with_options].
+///
+/// This function never panics.
+#[wasm_bindgen]
+pub fn check_proof_with_template_and_options(
+    proof: &str,
+    template: Vec<String>,
+    allowed_variable_names: &str,
+    options: &str,
+) -> String {
+    let (res, labels) =
+        check_proof_with_labels(proof, Some(&template), allowed_variable_names, options);
+    labels::numbers_to_labels_in_message(&proof_result_to_string(res), &labels)
+}
+
+/// Same as [check_proof_with_template_and_options], but the result is rendered by the renderer
+/// with the given name, see [check_proof_with_renderer].
+///
+/// This function never panics.
+#[wasm_bindgen]
+pub fn check_proof_with_template_and_renderer(
+    proof: &str,
+    template: Vec<String>,
+    allowed_variable_names: &str,
+    options: &str,
+    renderer: &str,
+) -> String {
+    let Some(renderer) = render::renderer(renderer) else {
+        return proof_result_to_string(unknown_renderer(renderer));
+    };
+    let (res, labels) =
+        check_proof_with_labels(proof, Some(&template), allowed_variable_names, options);
+    renderer.render(&numbers_to_labels_in_proof_result(res, &labels), proof)
+}
+
+/// Checks a proof like [check_proof_with_template_and_options] (or like
+/// [check_proof_with_options], if no template is given), and also returns the verdict: `correct`,
+/// `incorrect`, `unfinished` (for a draft, see [CheckOptions::drafting]), `no_proof`, `fatal` or
+/// `internal_error`. This is the same verdict as in the `json` renderer (see
+/// [check_proof_with_renderer]), so that a script does not have to read it from the message.
+///
+/// This function never panics.
+pub fn check_proof_with_verdict(
+    proof: &str,
+    template: Option<&[String]>,
+    allowed_variable_names: &str,
+    options: &str,
+) -> (String, &'static str) {
+    let (res, labels) = check_proof_with_labels(proof, template, allowed_variable_names, options);
+    let verdict = render::verdict(&res);
+    (labels::numbers_to_labels_in_message(&proof_result_to_string(res), &labels), verdict)
+}
+
+/// Checks a proof (against a template, if one is given) with a list of options, like
+/// [check_proof_with_options]. The lines of the proof may be labeled instead of numbered (see
+/// [labels::labels_to_numbers]); the labels are returned as well, but the messages in the
+/// [ProofResult] still use the line numbers.
+fn check_proof_with_labels(
+    proof: &str,
+    template: Option<&[String]>,
+    allowed_variable_names: &str,
+    options: &str,
+) -> (ProofResult, Vec<String>) {
+    let (proof, labels) = match labels::labels_to_numbers(proof) {
+        Ok(res) => res,
+        Err(err) => return (ProofResult::FatalError(FatalError::ParseError(err, None)), vec![]),
+    };
+    let proof = proof.as_str();
+    let res = match parser::parse_check_options(options) {
+        Ok(options) => {
+            let (symbols, max_errors, feedback) =
+                (options.symbols, options.max_errors, options.feedback);
+            let res = match template {
+                Some(template) => check_proof_to_proofresult_with_template(
+                    proof,
+                    template,
+                    allowed_variable_names,
+                    options,
+                ),
+                None => check_proof_to_proofresult(proof, allowed_variable_names, options),
+            };
+            let res = render_symbols_in_proof_result(res, proof, symbols);
+            limit_feedback_in_proof_result(res, max_errors, feedback)
+        }
+        Err(err) => ProofResult::FatalError(FatalError::ConfigError(err)),
+    };
+    (res, labels)
+}
+
+/// Parses a proof whose lines may be labeled instead of numbered (see
+/// [labels::labels_to_numbers]). The labels are returned as well, and are empty if the lines are
+/// numbered.
+#[cfg(any(feature = "export", feature = "tutoring"))]
+fn parse_labeled_fitch_proof(proof: &str) -> Result<(Vec<ProofLine>, Vec<String>), String> {
+    let (proof, labels) = labels::labels_to_numbers(proof)?;
+    Ok((parser::parse_fitch_proof(&proof)?, labels))
+}
+
+/// Returns a fatal error if the lines of a proof are labeled (see [labels::labels_to_numbers]),
+/// for the functions in which lines are also referred to outside the proof by their numbers.
+fn labeled_proof_error(proof: &str) -> Option<String> {
+    match labels::labels_to_numbers(proof) {
+        Ok((_, labels)) if !labels.is_empty() => Some(
+            "Fatal error: labeled proofs are not supported here, because the mistakes are \
+            identified by line numbers. Please number the lines of the proof instead."
+                .to_string(),
+        ),
+        _ => None,
+    }
+}
+
+/// Returns the fatal error for a renderer name that does not exist (see [render::renderer]).
+fn unknown_renderer(name: &str) -> ProofResult {
+    let names: Vec<&str> = render::RENDERERS.iter().map(|r| r.name()).collect();
+    ProofResult::FatalError(FatalError::ConfigError(format!(
+        "unknown renderer: {name}. The renderers are {}.",
+        names.join(", ")
+    )))
+}
+
+/// Checks only the structure of a proof (its "skeleton"), treating the sentences in it as opaque:
+/// the line numbers, the nesting of the subproofs, the justifications being there, the number of
+/// cited lines of each rule, and whether the cited lines and subproofs are accessible. This is
+/// meant for early feedback on a draft, and for exercises about the structure of a proof. The
+/// options are the same as for [check_proof_with_options].
+///
+/// If the structure is correct, then a string will be returned, saying that the structure of the
+/// proof is correct. Otherwise, a string is returned which contains a nice error message.
+///
+/// See also [parser::parse_fitch_proof_skeleton] and [checker::check_proof_skeleton].
+///
+/// This function never panics.
+#[wasm_bindgen]
+pub fn check_proof_skeleton(proof: &str, allowed_variable_names: &str, options: &str) -> String {
+    let res = match parser::parse_check_options(options) {
+        Ok(options) => {
+            let (symbols, max_errors, feedback) =
+                (options.symbols, options.max_errors, options.feedback);
+            let res = internal_error::catch_internal_errors(proof, |proof| {
+                match parsed_or_fatal(
+                    parser::parse_fitch_proof_skeleton(proof),
+                    allowed_variable_names,
+                ) {
+                    Ok((proof_lines, variable_names)) => {
+                        checker::check_proof_skeleton(proof_lines, variable_names, options.clone())
+                    }
+                    Err(res) => res,
+                }
+            });
+            let res = render_symbols_in_proof_result(res, proof, symbols);
+            limit_feedback_in_proof_result(res, max_errors, feedback)
+        }
+        Err(err) => ProofResult::FatalError(FatalError::ConfigError(err)),
+    };
     match res {
-        ProofResult::Correct => "The proof is correct!".to_string(),
-        ProofResult::Error(errs) => errs.join("\n\n"),
-        ProofResult::FatalError(err) => format!("Fatal error: {err}"),
+        ProofResult::Correct => "The structure of the proof is correct!".to_string(),
+        res => proof_result_to_string(res),
+    }
+}
+
+/// Checks if a string is a single correct inference of the form `premises ⊢ conclusion by RULE`,
+/// for example `A→B, A ⊢ B by →Elim`. This is meant for drill exercises on individual rules,
+/// where a full Fitch proof would only get in the way.
+///
+/// If the inference is correct, then a string will be returned, saying that the inference is
+/// correct. Otherwise, a string is returned which contains a nice error message.
+///
+/// See also [parser::parse_sequent] and [checker::check_sequent].
+///
+/// This function never panics.
+#[wasm_bindgen]
+pub fn check_sequent(sequent: &str, allowed_variable_names: &str) -> String {
+    let res = check_sequent_to_proofresult(sequent, allowed_variable_names);
+    match render_symbols_in_proof_result(res, sequent, SymbolSet::Auto) {
+        ProofResult::Correct => "The inference is correct!".to_string(),
+        res => proof_result_to_string(res),
+    }
+}
+
+/// Checks if a string is a single correct inference, and returns its evaluation in a
+/// [ProofResult]. See [check_sequent].
+///
+/// This function never panics.
+fn check_sequent_to_proofresult(sequent: &str, allowed_variable_names: &str) -> ProofResult {
+    if parser::is_empty_proof(sequent) {
+        return ProofResult::NoProof;
     }
+    internal_error::catch_internal_errors(sequent, |sequent| {
+        let sequent =
+            parser::parse_sequent(sequent).map_err(|err| FatalError::ParseError(err, None));
+        match parsed_or_fatal(sequent, allowed_variable_names) {
+            Ok((sequent, variable_names)) => checker::check_sequent(sequent, variable_names),
+            Err(res) => res,
+        }
+    })
+}
+
+/// Combines the result of parsing a proof (or a sequent) with the result of parsing the allowed
+/// variable names. If one of them fails, then the [ProofResult::FatalError] is returned: a
+/// [FatalError::ConfigError] if the allowed variable names are not valid, but the mistakes in the
+/// proof come first.
+fn parsed_or_fatal<T>(
+    parsed: Result<T, FatalError>,
+    allowed_variable_names: &str,
+) -> Result<(T, HashSet<String>), ProofResult> {
+    let parsed = parsed.map_err(ProofResult::FatalError)?;
+    match parser::parse_allowed_variable_names(allowed_variable_names) {
+        Ok(variable_names) => Ok((parsed, variable_names)),
+        Err(err) => Err(ProofResult::FatalError(FatalError::ConfigError(err))),
+    }
+}
+
+/// Checks whether a string is a well-formed sentence on its own (e.g. for an exercise where a
+/// sentence in English is translated into first-order logic), and if not, tells where and why.
+/// The result is a JSON object, for example:
+///
+/// `{"well_formed":false,"errors":[{"message":"this opening parenthesis is never closed",
+/// "start":2,"end":3}]}`
+///
+/// where `start` and `end` are the positions in `formula` (in UTF-16 code units, like in a
+/// JavaScript string) of the part of the sentence that the error is about. A sentence is not
+/// well-formed if it uses unknown symbols, if its parentheses are not balanced, if a quantifier
+/// has no variable, if it cannot be parsed, or if it has a free variable or quantifies over a name
+/// that is not a variable. See [parser::diagnose_logical_expression] for the details.
+///
+/// If the allowed variable names cannot be parsed, then the error is about the whole sentence.
+///
+/// This function never panics.
+#[wasm_bindgen]
+pub fn check_formula(formula: &str, allowed_variable_names: &str) -> String {
+    let errors = match parser::parse_allowed_variable_names(allowed_variable_names) {
+        Ok(variable_names) => {
+            parser::diagnose_logical_expression(formula, &variable_names).err().unwrap_or_default()
+        }
+        Err(err) => {
+            vec![(format!("the allowed variable names are not valid: {err}"), (0, formula.len()))]
+        }
+    };
+    let position = |i: usize| formula[..i].encode_utf16().count();
+    let errors: Vec<String> = errors
+        .iter()
+        .map(|(message, (start, end))| {
+            format!(
+                "{{\"message\":{},\"start\":{},\"end\":{}}}",
+                util::json_string(message),
+                position(*start),
+                position(*end)
+            )
+        })
+        .collect();
+    format!("{{\"well_formed\":{},\"errors\":[{}]}}", errors.is_empty(), errors.join(","))
+}
+
+/// Checks whether a student's sentence matches a reference sentence, e.g. to grade an exercise
+/// where a sentence in English is translated into first-order logic. The policy says how closely
+/// they should match: `"match=syntactic"` (the default), `"match=alpha"` (the same up to the names
+/// of the bound variables) or `"match=equivalent"` (logically equivalent, only for propositional
+/// sentences). See [data::MatchPolicy].
+///
+/// An error message is returned if one of the sentences or the policy cannot be parsed, or if the
+/// sentences cannot be compared with the policy (see [formula_match::formulas_match]).
+///
+/// This function never panics.
+#[wasm_bindgen]
+pub fn formulas_match(student: &str, reference: &str, policy: &str) -> Result<bool, String> {
+    let policy = parser::parse_match_policy(policy)?;
+    let Some(student) = parser::parse_logical_expression_string(student) else {
+        return Err("the student's sentence cannot be parsed".to_string());
+    };
+    let Some(reference) = parser::parse_logical_expression_string(reference) else {
+        return Err("the reference sentence cannot be parsed".to_string());
+    };
+    formula_match::formulas_match(&student, &reference, policy)
 }
 
 /// Checks if a string is a fully correct proof.
@@ -64,14 +426,19 @@ pub fn check_proof_with_template(
 /// See also [parser::parse_fitch_proof] and [checker::check_proof].
 ///
 /// This function never panics.
-fn check_proof_to_proofresult(proof: &str, allowed_variable_names: &str) -> ProofResult {
-    match (
-        parser::parse_fitch_proof(proof),
-        parser::parse_allowed_variable_names(allowed_variable_names),
-    ) {
-        (Ok(proof_lines), Ok(variable_names)) => checker::check_proof(proof_lines, variable_names),
-        (Err(err), _) | (_, Err(err)) => ProofResult::FatalError(err),
-    }
+fn check_proof_to_proofresult(
+    proof: &str,
+    allowed_variable_names: &str,
+    options: CheckOptions,
+) -> ProofResult {
+    internal_error::catch_internal_errors(proof, |proof| {
+        match parsed_or_fatal(parser::parse_fitch_proof_with_spans(proof), allowed_variable_names) {
+            Ok((proof_lines, variable_names)) => {
+                checker::check_proof(proof_lines, variable_names, options.clone())
+            }
+            Err(res) => res,
+        }
+    })
 }
 
 /// Checks if a string is a fully correct proof that matches a given proof template.
@@ -85,22 +452,628 @@ fn check_proof_to_proofresult_with_template(
     proof: &str,
     template: &[String],
     allowed_variable_names: &str,
+    options: CheckOptions,
 ) -> ProofResult {
-    match (
-        parser::parse_fitch_proof(proof),
+    internal_error::catch_internal_errors(proof, |proof| {
+        match parsed_or_fatal(parser::parse_fitch_proof_with_spans(proof), allowed_variable_names) {
+            Ok((proof_lines, variable_names)) => {
+                let template = match parser::parse_template(template) {
+                    Ok(template) => template,
+                    Err(err) => return template_parse_error(err),
+                };
+                let options = CheckOptions {
+                    propositional_only: options.propositional_only || template.propositional_only,
+                    ..options.clone()
+                };
+                checker::check_proof_with_template(
+                    proof_lines,
+                    template.sentences,
+                    variable_names,
+                    options,
+                )
+            }
+            Err(res) => res,
+        }
+    })
+}
+
+/// Returns the fatal error for a proof template that cannot be parsed (see
+/// [parser::parse_template]).
+fn template_parse_error(err: String) -> ProofResult {
+    ProofResult::FatalError(FatalError::TemplateError(format!(
+        "Some sentences in the template file could not be parsed. The problem is that {err}."
+    )))
+}
+
+/// Checks a proof like [check_proof_with_template_and_options] (or like
+/// [check_proof_with_options], if the template is empty), and also returns how long each phase of
+/// the check, each rule and each line took (see [CheckProfile]). This is meant for finding out
+/// what to optimize when checking very long proofs. The lines of the proof should be numbered, not
+/// labeled. The verdict is returned as well, like in [check_proof_with_verdict].
+///
+/// This function never panics.
+pub fn profile_proof(
+    proof: &str,
+    template: &[String],
+    allowed_variable_names: &str,
+    options: &str,
+) -> (String, &'static str, CheckProfile) {
+    let options = match parser::parse_check_options(options) {
+        Ok(options) => options,
+        Err(err) => {
+            let res = ProofResult::FatalError(FatalError::ConfigError(err));
+            let verdict = render::verdict(&res);
+            return (proof_result_to_string(res), verdict, CheckProfile::default());
+        }
+    };
+    let (symbols, max_errors, feedback) = (options.symbols, options.max_errors, options.feedback);
+    // the check is run again when it panics (see [internal_error::catch_internal_errors]), so
+    // only the profile of the last run is kept
+    let profile = RefCell::new(CheckProfile::default());
+    let res = internal_error::catch_internal_errors(proof, |proof| {
+        let mut run_profile = CheckProfile::default();
+        let start = profile::now();
+        let parsed = (
+            parsed_or_fatal(parser::parse_fitch_proof_with_spans(proof), allowed_variable_names),
+            (!template.is_empty()).then(|| parser::parse_template(template)),
+        );
+        run_profile.add_phase("parsing", profile::now() - start);
+        let res = match parsed {
+            (Err(res), _) => res,
+            (_, Some(Err(err))) => template_parse_error(err),
+            (Ok((proof_lines, variable_names)), template) => {
+                let template = template.and_then(Result::ok);
+                let options = CheckOptions {
+                    propositional_only: options.propositional_only
+                        || template.as_ref().is_some_and(|t| t.propositional_only),
+                    ..options.clone()
+                };
+                checker::check_proof_with_profile(
+                    proof_lines,
+                    template.map(|t| t.sentences),
+                    variable_names,
+                    options,
+                    &mut run_profile,
+                )
+            }
+        };
+        *profile.borrow_mut() = run_profile;
+        res
+    });
+    let res = render_symbols_in_proof_result(res, proof, symbols);
+    let res = limit_feedback_in_proof_result(res, max_errors, feedback);
+    let verdict = render::verdict(&res);
+    (proof_result_to_string(res), verdict, profile.into_inner())
+}
+
+/// Checks a proof like [check_proof_with_template_and_options] (or like
+/// [check_proof_with_options], if the template is empty), and, if the proof is correct, also
+/// returns its score in a proof-golf competition: its length and nesting depth compared to the
+/// shortest known proof, which the template gives with `# minimal proof length: 5` and
+/// (optionally) `# minimal nesting depth: 1` (see [parser::parse_template] and [GolfScore]).
+///
+/// The verdict is returned as well, like in [check_proof_with_verdict]. No golf score is returned
+/// if the proof is not correct, or if the template does not give the minimal proof length.
+///
+/// This function never panics.
+pub fn golf_proof(
+    proof: &str,
+    template: &[String],
+    allowed_variable_names: &str,
+    options: &str,
+) -> (String, &'static str, Option<GolfScore>) {
+    let (res, _) = check_proof_with_labels(
+        proof,
+        (!template.is_empty()).then_some(template),
+        allowed_variable_names,
+        options,
+    );
+    let verdict = render::verdict(&res);
+    if !matches!(res, ProofResult::Correct) {
+        return (proof_result_to_string(res), verdict, None);
+    }
+    let golf = match (
+        labels::labels_to_numbers(proof).and_then(|(proof, _)| parser::parse_fitch_proof(&proof)),
         parser::parse_allowed_variable_names(allowed_variable_names),
+        parser::parse_check_options(options),
+        parser::parse_template(template),
     ) {
-        (Ok(proof_lines), Ok(variable_names)) => {
-            let template_wffs: Vec<Wff> = template
-                .iter()
-                .filter_map(|s| parser::parse_logical_expression_string(s))
-                .collect();
-            if template_wffs.len() != template.len() {
-                return ProofResult::FatalError("Some sentences in the template file could not be parsed. If you see this as a student on Themis, please contact the course staff as soon as possible; something is wrong on our side. Thanks!".to_owned());
+        (Ok(lines), Ok(variable_names), Ok(options), Ok(template)) => {
+            proof::Proof::construct(lines, variable_names, options)
+                .ok()
+                .and_then(|proof| GolfScore::new(&proof, &template))
+        }
+        _ => None,
+    };
+    (proof_result_to_string(res), verdict, golf)
+}
+
+/// Checks a proof like [check_proof_with_template_and_options], and returns the result together
+/// with the golf score of the proof (see [golf_proof]), as a JSON object, for example:
+///
+/// `{"result":"The proof is correct!","golf":{"lines":4,"nesting_depth":1,...,"score":133.3}}`
+///
+/// where the golf score is like in [GolfScore::to_json], or `null` if there is none.
+///
+/// This function never panics.
+#[wasm_bindgen]
+pub fn check_proof_with_golf_score(
+    proof: &str,
+    template: Vec<String>,
+    allowed_variable_names: &str,
+    options: &str,
+) -> String {
+    let (result, _, golf) = golf_proof(proof, &template, allowed_variable_names, options);
+    format!(
+        "{{\"result\":{},\"golf\":{}}}",
+        util::json_string(&result),
+        golf.map_or("null".to_string(), |golf| golf.to_json())
+    )
+}
+
+/// Checks a proof like [check_proof_with_options], and returns the result together with how long
+/// the check took, as a JSON object, for example:
+///
+/// `{"result":"The proof is correct!","profile":{"total_ms":0.052,"phases":[...],...}}`
+///
+/// where the profile is like in [CheckProfile::to_json]. See [profile_proof].
+///
+/// This function never panics.
+#[wasm_bindgen]
+pub fn check_proof_with_profile(
+    proof: &str,
+    allowed_variable_names: &str,
+    options: &str,
+) -> String {
+    let (result, _, profile) = profile_proof(proof, &[], allowed_variable_names, options);
+    format!("{{\"result\":{},\"profile\":{}}}", util::json_string(&result), profile.to_json())
+}
+
+/// A proof that is being checked in chunks, so that a web page can show a progress bar (and the
+/// errors found so far) while a very long proof is being checked, without freezing the page.
+///
+/// Typical use from JavaScript: create a session, call [ProofCheckSession::check_next_lines]
+/// repeatedly (e.g. from `setTimeout`) until [ProofCheckSession::is_done] returns true, and then
+/// call [ProofCheckSession::finish] to get the same message that [check_proof] would give.
+#[wasm_bindgen]
+pub struct ProofCheckSession {
+    /// The check that is in progress, or the result of the check if it could not be started (e.g.
+    /// because of a fatal error). This is [None] once the session is finished.
+    check: Option<Result<checker::IncrementalCheck, ProofResult>>,
+    /// The proof and the allowed variable names, with which the proof is checked again to find a
+    /// small reproduction if the check runs into a bug (see [internal_error]).
+    proof: String,
+    allowed_variable_names: String,
+}
+
+#[wasm_bindgen]
+impl ProofCheckSession {
+    /// Starts checking a proof. This function never panics.
+    #[wasm_bindgen(constructor)]
+    pub fn new(proof: &str, allowed_variable_names: &str) -> ProofCheckSession {
+        // the check is started again when it panics (see [internal_error::catch_internal_errors]),
+        // so only the check of the last run is kept
+        let started = RefCell::new(None);
+        let res = internal_error::catch_internal_errors(proof, |proof| {
+            let proof_lines = parser::parse_fitch_proof_with_spans(proof);
+            match parsed_or_fatal(proof_lines, allowed_variable_names) {
+                Ok((proof_lines, variable_names)) => {
+                    let options = CheckOptions::default();
+                    match checker::IncrementalCheck::new(proof_lines, variable_names, options) {
+                        Ok(check) => {
+                            *started.borrow_mut() = Some(check);
+                            ProofResult::Correct
+                        }
+                        Err(res) => res,
+                    }
+                }
+                Err(res) => res,
+            }
+        });
+        let check = match (res, started.into_inner()) {
+            (ProofResult::Correct, Some(check)) => Ok(check),
+            (res, _) => Err(res),
+        };
+        ProofCheckSession {
+            check: Some(check),
+            proof: proof.to_string(),
+            allowed_variable_names: allowed_variable_names.to_string(),
+        }
+    }
+
+    /// Checks (at most) the next `max_lines` lines of the proof, and returns a JSON array with
+    /// one event per checked line with a justification, for example:
+    ///
+    /// `[{"line":3,"status":"correct"},{"line":4,"status":"error","message":"Line 4: ..."}]`
+    ///
+    /// If the check runs into a bug, then the session is done, and [ProofCheckSession::finish]
+    /// returns the internal error.
+    pub fn check_next_lines(&mut self, max_lines: usize) -> String {
+        let mut events: Vec<String> = vec![];
+        if let Some(Ok(check)) = &mut self.check {
+            let checked = internal_error::catch_panic(|| {
+                check.check_next_lines(max_lines, &mut |line_num, status| {
+                    events.push(match status {
+                        LineStatus::Correct => {
+                            format!("{{\"line\":{line_num},\"status\":\"correct\"}}")
+                        }
+                        LineStatus::Error(err) => format!(
+                            "{{\"line\":{line_num},\"status\":\"error\",\"message\":{}}}",
+                            util::json_string(err)
+                        ),
+                    })
+                })
+            });
+            if checked.is_none() {
+                // checking the whole proof again gives the internal error with a small input
+                let options = CheckOptions::default();
+                let res = match check_proof_to_proofresult(
+                    &self.proof,
+                    &self.allowed_variable_names,
+                    options,
+                ) {
+                    res @ ProofResult::FatalError(FatalError::Internal(_)) => res,
+                    _ => ProofResult::FatalError(FatalError::Internal(self.proof.clone())),
+                };
+                self.check = Some(Err(res));
+            }
+        }
+        format!("[{}]", events.join(","))
+    }
+
+    /// Returns the fraction (between 0 and 1) of the proof lines that have been checked.
+    pub fn progress(&self) -> f64 {
+        match &self.check {
+            Some(Ok(check)) if check.num_lines() > 0 => {
+                check.num_checked_lines() as f64 / check.num_lines() as f64
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// Returns whether all lines have been checked (or there is nothing left to check, e.g.
+    /// because of a fatal error).
+    pub fn is_done(&self) -> bool {
+        match &self.check {
+            Some(Ok(check)) => check.is_done(),
+            _ => true,
+        }
+    }
+
+    /// Finishes the check, and returns the same message that [check_proof] would return. After
+    /// calling this, the session cannot be used anymore (all functions act like the proof is
+    /// fully checked, and this function returns an empty string).
+    pub fn finish(&mut self) -> String {
+        match self.check.take() {
+            Some(Ok(check)) => proof_result_to_string(check.finish()),
+            Some(Err(res)) => proof_result_to_string(res),
+            None => "".to_string(),
+        }
+    }
+}
+
+/// Checks if a string is a fully correct proof, and calls `on_line_checked` for every proof line
+/// with a justification as soon as that line has been checked (with the line number, and the
+/// [LineStatus] of the line). Returns the same message as [check_proof].
+///
+/// This is the native counterpart of [ProofCheckSession].
+///
+/// This function never panics.
+pub fn check_proof_with_progress(
+    proof: &str,
+    allowed_variable_names: &str,
+    on_line_checked: &mut dyn FnMut(usize, &LineStatus),
+) -> String {
+    // the check is run again on smaller proofs when it panics (see
+    // [internal_error::catch_internal_errors]), so only the first run reports its progress
+    let on_line_checked = RefCell::new(Some(on_line_checked));
+    let res = internal_error::catch_internal_errors(proof, |proof| {
+        let mut on_line_checked = on_line_checked.borrow_mut().take();
+        match parsed_or_fatal(parser::parse_fitch_proof_with_spans(proof), allowed_variable_names) {
+            Ok((proof_lines, variable_names)) => checker::check_proof_with_progress(
+                proof_lines,
+                variable_names,
+                CheckOptions::default(),
+                &mut |line_num, status| {
+                    if let Some(on_line_checked) = &mut on_line_checked {
+                        on_line_checked(line_num, status);
+                    }
+                },
+            ),
+            Err(res) => res,
+        }
+    });
+    proof_result_to_string(res)
+}
+
+/// Rewrites the formulas in all messages of a [ProofResult] with the given [SymbolSet]. In case of
+/// [SymbolSet::Auto], the symbol set in which `input` is written is used, so that the user sees
+/// the same symbols that they typed.
+fn render_symbols_in_proof_result(
+    res: ProofResult,
+    input: &str,
+    symbols: SymbolSet,
+) -> ProofResult {
+    let symbols = match symbols {
+        SymbolSet::Auto => formatter::detect_symbol_set(input),
+        symbols => symbols,
+    };
+    match res {
+        ProofResult::Correct => ProofResult::Correct,
+        ProofResult::Error(errs) => ProofResult::Error(
+            errs.into_iter()
+                .map(|err| err.map_message(|err| formatter::render_symbols(err, symbols)))
+                .collect(),
+        ),
+        ProofResult::FatalError(err) => {
+            ProofResult::FatalError(err.map_message(|err| formatter::render_symbols(err, symbols)))
+        }
+        ProofResult::Unfinished(errs, unchecked_lines) => ProofResult::Unfinished(
+            errs.into_iter()
+                .map(|err| err.map_message(|err| formatter::render_symbols(err, symbols)))
+                .collect(),
+            unchecked_lines,
+        ),
+        ProofResult::NoProof => ProofResult::NoProof,
+    }
+}
+
+/// Limits how much the mistakes in a [ProofResult] give away, according to
+/// [CheckOptions::max_errors] and [CheckOptions::feedback]. Fatal errors are not changed.
+fn limit_feedback_in_proof_result(
+    res: ProofResult,
+    max_errors: Option<usize>,
+    feedback: FeedbackDetail,
+) -> ProofResult {
+    let hidden = |message: &str| ProofError::about_proof(error_codes::HIDDEN, message.to_string());
+    let limit = |errs: Vec<ProofError>| {
+        let mut errs = match feedback {
+            FeedbackDetail::Full => errs,
+            FeedbackDetail::LineNumbers => {
+                let mut line_errs: Vec<ProofError> = vec![];
+                for err in errs {
+                    let line_err = match err.line {
+                        Some(n) => ProofError::at_line(
+                            n,
+                            error_codes::HIDDEN,
+                            format!("Line {n}: there is a mistake in this line."),
+                        ),
+                        None => hidden("There is a mistake that is not about a single line."),
+                    };
+                    if !line_errs.contains(&line_err) {
+                        line_errs.push(line_err);
+                    }
+                }
+                line_errs
+            }
+        };
+        match max_errors {
+            Some(0) if !errs.is_empty() => {
+                vec![hidden("The proof contains mistakes, but they are not shown.")]
+            }
+            Some(max) if errs.len() > max => {
+                errs.truncate(max);
+                errs.push(hidden("There are more mistakes, which are not shown yet."));
+                errs
+            }
+            _ => errs,
+        }
+    };
+    match res {
+        ProofResult::Error(errs) => ProofResult::Error(limit(errs)),
+        ProofResult::Unfinished(errs, unchecked_lines) => {
+            ProofResult::Unfinished(limit(errs), unchecked_lines)
+        }
+        res => res,
+    }
+}
+
+/// Turns a [ProofResult] into the message that is shown to the user.
+fn proof_result_to_string(res: ProofResult) -> String {
+    render::PlainText.render(&res, "")
+}
+
+/// Returns the message of [ProofResult::NoProof], for the functions that do not check a proof,
+/// so that they treat a submission without a proof (see [parser::is_empty_proof]) the same way.
+fn no_proof_message() -> String {
+    proof_result_to_string(ProofResult::NoProof)
+}
+
+/// Replaces the line numbers in all messages of a [ProofResult] by the labels of the lines (see
+/// [labels::numbers_to_labels_in_message]).
+fn numbers_to_labels_in_proof_result(res: ProofResult, labels: &[String]) -> ProofResult {
+    let translate = |err: &str| labels::numbers_to_labels_in_message(err, labels);
+    let translate_all = |errs: Vec<ProofError>| -> Vec<ProofError> {
+        errs.into_iter().map(|err| err.map_message(translate)).collect()
+    };
+    match res {
+        ProofResult::Error(errs) => ProofResult::Error(translate_all(errs)),
+        ProofResult::FatalError(err) => ProofResult::FatalError(err.map_message(translate)),
+        ProofResult::Unfinished(errs, unchecked_lines) => {
+            ProofResult::Unfinished(translate_all(errs), unchecked_lines)
+        }
+        res @ (ProofResult::Correct | ProofResult::NoProof) => res,
+    }
+}
+
+/// Checks a deliberately broken proof (e.g. for a "find the mistake" exercise) against its
+/// `%expect-error` directives (see [parser::parse_directives]): the proof should have exactly the
+/// mistakes that the directives say, i.e. for every directive there should be one error with that
+/// error code (see [error_codes]) about that line, and there should be no other errors. The rules
+/// in `%allow` directives may be used in the proof. The options are the same as for
+/// [check_proof_with_options].
+///
+/// If the proof has exactly the expected mistakes, then a string is returned that says so.
+/// Otherwise, the expected mistakes that are missing and the unexpected errors are returned. The
+/// lines of the proof should be numbered, not labeled (see [labels::labels_to_numbers]).
+///
+/// This function never panics.
+#[wasm_bindgen]
+pub fn check_expected_errors(proof: &str, allowed_variable_names: &str, options: &str) -> String {
+    if let Some(err) = labeled_proof_error(proof) {
+        return err;
+    }
+    match verify_expected_errors(proof, allowed_variable_names, options) {
+        Ok(_) => "The proof has exactly the expected mistakes.".to_string(),
+        Err(problems) => problems.join("\n\n"),
+    }
+}
+
+/// Grades a student's answer to a "find the mistake" exercise: the proof is deliberately broken,
+/// and its `%expect-error` directives say which mistakes it has (see [check_expected_errors]).
+/// The student's answer lists the mistakes they found, one per line, as a line number and an
+/// error code (see [parser::parse_error_identification]), e.g. `4 E_SCOPE_VIOLATION`. The
+/// allowed variable names and the options are the ones with which the proof is checked, like in
+/// [check_expected_errors].
+///
+/// For every mistake in the answer, the feedback says whether it is correct, followed by how many
+/// of the mistakes in the proof are found and how many mistakes in the answer are wrong. If the
+/// answer is fully correct, a string is returned that says so. To keep the feedback from giving
+/// the mistakes away, an answer may not list more mistakes than the proof has.
+///
+/// A fatal error is returned if the proof does not have exactly the mistakes in its directives,
+/// or if a directive does not say which line the mistake is in (since then the exercise itself is
+/// wrong), or if the answer cannot be read.
+///
+/// This function never panics.
+#[wasm_bindgen]
+pub fn check_error_identification(
+    proof: &str,
+    student_answer: &str,
+    allowed_variable_names: &str,
+    options: &str,
+) -> String {
+    if let Some(err) = labeled_proof_error(proof) {
+        return err;
+    }
+    let expected = match verify_expected_errors(proof, allowed_variable_names, options) {
+        Ok(expected) => expected,
+        Err(problems) => {
+            return format!(
+                "Fatal error: the mistakes in this exercise do not match its %expect-error \
+                directives. If you see this as a student, please contact the course staff.\n\n{}",
+                problems.join("\n\n")
+            )
+        }
+    };
+    let Some(expected) = expected
+        .into_iter()
+        .map(|e| e.line.map(|line| (line, e.code)))
+        .collect::<Option<Vec<(usize, String)>>>()
+    else {
+        return "Fatal error: an %expect-error directive of this exercise does not say in which \
+            line the mistake is, so the mistake cannot be identified. If you see this as a \
+            student, please contact the course staff."
+            .to_string();
+    };
+    let answer = match parser::parse_error_identification(student_answer) {
+        Ok(answer) => answer,
+        Err(err) => return format!("Your answer could not be read: {err}."),
+    };
+    if answer.len() > expected.len() {
+        return format!(
+            "Your answer lists {} mistakes, but the proof has only {}. Please list at most {} \
+            mistakes.",
+            answer.len(),
+            expected.len(),
+            expected.len()
+        );
+    }
+
+    let mut found: Vec<bool> = vec![false; expected.len()];
+    let mut feedback: Vec<String> = vec![];
+    for (n, code) in &answer {
+        let matching =
+            (0..expected.len()).find(|i| !found[*i] && expected[*i] == (*n, code.clone()));
+        feedback.push(match matching {
+            Some(i) => {
+                found[i] = true;
+                format!("Line {n}: correct, this line has a mistake of type {code}.")
+            }
+            None if expected.contains(&(*n, code.clone())) => {
+                format!("Line {n}: this mistake of type {code} is already in your answer.")
             }
-            checker::check_proof_with_template(proof_lines, template_wffs, variable_names)
+            None if expected.iter().any(|(line, _)| line == n) => {
+                format!("Line {n}: this line has a mistake, but it is not of type {code}.")
+            }
+            None => format!("Line {n}: there is no mistake of type {code} in this line."),
+        });
+    }
+    let num_found = found.iter().filter(|f| **f).count();
+    let num_wrong = answer.len() - num_found;
+    if num_found == expected.len() && num_wrong == 0 {
+        return "Correct! You found all mistakes in the proof.".to_string();
+    }
+    feedback.push(format!(
+        "You found {num_found} of the {} mistakes in the proof, and {num_wrong} of the {} \
+        mistakes in your answer are wrong.",
+        expected.len(),
+        answer.len()
+    ));
+    feedback.join("\n")
+}
+
+/// Checks a proof against its `%expect-error` and `%allow` directives, see
+/// [check_expected_errors]. If the proof has exactly the expected mistakes, these are returned.
+/// Otherwise, the messages about the missing and the unexpected mistakes are returned, or the
+/// message about why the proof cannot be checked.
+fn verify_expected_errors(
+    proof: &str,
+    allowed_variable_names: &str,
+    options: &str,
+) -> Result<Vec<ExpectedError>, Vec<String>> {
+    let (directives, mut options) =
+        match (parser::parse_directives(proof), parser::parse_check_options(options)) {
+            (Ok(directives), Ok(options)) => (directives, options),
+            (Err(err), _) | (_, Err(err)) => return Err(vec![format!("Fatal error: {err}")]),
+        };
+    for rule in &directives.allowed_rules {
+        match rule.as_str() {
+            "=Chain" => options.allow_equality_chains = true,
+            _ => options.allow_derived_rules = true,
+        }
+    }
+    let symbols = options.symbols;
+    let res = check_proof_to_proofresult(proof, allowed_variable_names, options);
+    let errors: Vec<ProofError> = match render_symbols_in_proof_result(res, proof, symbols) {
+        ProofResult::Correct => vec![],
+        ProofResult::Error(errs) | ProofResult::Unfinished(errs, _) => errs,
+        res @ (ProofResult::NoProof | ProofResult::FatalError(FatalError::Internal(_))) => {
+            return Err(vec![proof_result_to_string(res)])
+        }
+        ProofResult::FatalError(err) => {
+            vec![ProofError::about_proof(error_codes::FATAL, format!("Fatal error: {err}"))]
         }
-        (Err(err), _) | (_, Err(err)) => ProofResult::FatalError(err),
+    };
+
+    let mut unexpected: Vec<bool> = vec![true; errors.len()];
+    let mut missing: Vec<String> = vec![];
+    for expected in &directives.expected_errors {
+        let found = errors.iter().enumerate().position(|(i, err)| {
+            unexpected[i]
+                && err.code == expected.code
+                && (expected.line.is_none() || expected.line == err.line)
+        });
+        match (found, expected.line) {
+            (Some(i), _) => unexpected[i] = false,
+            (None, Some(n)) => missing.push(format!(
+                "The proof should have a mistake of type {} in line {n}, but it does not.",
+                expected.code
+            )),
+            (None, None) => missing.push(format!(
+                "The proof should have a mistake of type {}, but it does not.",
+                expected.code
+            )),
+        }
+    }
+    let unexpected: Vec<String> = zip(errors, unexpected)
+        .filter(|(_, unexpected)| *unexpected)
+        .map(|(err, _)| format!("Unexpected mistake of type {}:\n{err}", err.code))
+        .collect();
+    if missing.is_empty() && unexpected.is_empty() {
+        Ok(directives.expected_errors)
+    } else {
+        Err([missing, unexpected].concat())
     }
 }
 
@@ -108,45 +1081,796 @@ fn check_proof_to_proofresult_with_template(
 ///
 /// This function never panics.
 pub fn proof_is_correct(proof: &str) -> bool {
-    matches!(check_proof_to_proofresult(proof, default_variable_names!()), ProofResult::Correct)
+    matches!(
+        check_proof_to_proofresult(proof, default_variable_names!(), CheckOptions::default()),
+        ProofResult::Correct
+    )
+}
+
+/// Returns whether a string is a single correct inference (see [check_sequent]).
+///
+/// This function never panics.
+pub fn sequent_is_correct(sequent: &str) -> bool {
+    matches!(check_sequent_to_proofresult(sequent, default_variable_names!()), ProofResult::Correct)
+}
+
+/// Returns the warnings for a proof: things that are allowed, but that are almost always a mistake,
+/// such as `∀x P(a)`. The warnings are separated by empty lines, just like error messages. If
+/// there are no warnings (or the proof cannot be parsed, or is not structured properly), then an
+/// empty string is returned. If there is no proof (see [parser::is_empty_proof]), then the message
+/// of [ProofResult::NoProof] is returned.
+///
+/// Warnings are not errors, so they are not part of the result of [check_proof]. If the lines of
+/// the proof are labeled (see [labels::labels_to_numbers]), then the warnings use the labels.
+///
+/// This function never panics.
+#[cfg(feature = "tutoring")]
+#[wasm_bindgen]
+pub fn lint_proof(proof: &str) -> String {
+    if parser::is_empty_proof(proof) {
+        return no_proof_message();
+    }
+    let Ok((lines, labels)) = parse_labeled_fitch_proof(proof) else {
+        return "".to_string();
+    };
+    let variable_names = match parser::parse_allowed_variable_names(default_variable_names!()) {
+        Ok(variable_names) => variable_names,
+        Err(err) => {
+            return proof_result_to_string(ProofResult::FatalError(FatalError::ConfigError(err)))
+        }
+    };
+    match proof::Proof::construct(lines, variable_names, CheckOptions::default()) {
+        Ok(proof_structure) => {
+            let warnings = labels::numbers_to_labels_in_message(
+                &lint::lint(&proof_structure).join("\n\n"),
+                &labels,
+            );
+            formatter::render_symbols(&warnings, formatter::detect_symbol_set(proof))
+        }
+        Err(_) => "".to_string(),
+    }
+}
+
+/// Returns a hint for a proof that is not correct yet, which becomes more specific as the student
+/// makes more attempts: first which family of rules to use, then which rule, and then which lines
+/// to cite (see [hints::hint]). `attempts` is the number of earlier attempts at this exercise,
+/// which the caller keeps track of. The policy says after how many attempts each kind of hint is
+/// given, e.g. `"family=1, rule=2, lines=3"` (see [parser::parse_hint_policy]); an empty policy
+/// gives these defaults. The options are the same as for [check_proof_with_options].
+///
+/// This function never panics.
+#[cfg(feature = "tutoring")]
+#[wasm_bindgen]
+pub fn proof_hint(
+    proof: &str,
+    allowed_variable_names: &str,
+    options: &str,
+    policy: &str,
+    attempts: usize,
+) -> String {
+    let (options, policy) =
+        match (parser::parse_check_options(options), parser::parse_hint_policy(policy)) {
+            (Ok(options), Ok(policy)) => (options, policy),
+            (Err(err), _) | (_, Err(err)) => return format!("Fatal error: {err}"),
+        };
+    let symbols = match options.symbols {
+        SymbolSet::Auto => formatter::detect_symbol_set(proof),
+        symbols => symbols,
+    };
+    // the hint is passed through a `ProofResult` to catch internal errors
+    let res = internal_error::catch_internal_errors(proof, |proof| {
+        match parsed_or_fatal(parser::parse_fitch_proof_with_spans(proof), allowed_variable_names) {
+            Ok((proof_lines, _)) if proof_lines.is_empty() => ProofResult::NoProof,
+            Ok((proof_lines, variable_names)) => {
+                let hint =
+                    hints::hint(proof_lines, variable_names, options.clone(), &policy, attempts);
+                ProofResult::Error(vec![ProofError::about_proof(error_codes::HIDDEN, hint)])
+            }
+            Err(res) => res,
+        }
+    });
+    match res {
+        ProofResult::Error(hint) => formatter::render_symbols(
+            &hint.iter().map(|h| h.message.as_str()).collect::<String>(),
+            symbols,
+        ),
+        res => proof_result_to_string(res),
+    }
+}
+
+/// Returns a "score card" for a correct proof, as a JSON object with the number of lines of the
+/// proof (and of the shortest known proof, if the template gives it, see
+/// [check_proof_with_template]), how often each rule is used, how deep the subproofs are nested,
+/// the warnings (see [lint_proof]) and a friendly summary of all this, for example:
+///
+/// `{"lines":3,"minimal_length":3,"rules":{"∧Elim":2},"nesting_depth":0,"warnings":[],
+/// "summary":"Well done! Your proof has 3 lines, just like the shortest proof we know of. ..."}`
+///
+/// The template may be empty, and the options are the same as for [check_proof_with_options].
+/// The lines of the proof may be labeled (see [labels::labels_to_numbers]), and then the warnings
+/// use the labels. If the proof is not correct, then an empty string is returned.
+///
+/// This function never panics.
+#[cfg(feature = "tutoring")]
+#[wasm_bindgen]
+pub fn proof_score_card(
+    proof: &str,
+    template: Vec<String>,
+    allowed_variable_names: &str,
+    options: &str,
+) -> String {
+    let (Ok(options), Ok(parsed_template)) =
+        (parser::parse_check_options(options), parser::parse_template(&template))
+    else {
+        return "".to_string();
+    };
+    let Ok((numbered, labels)) = labels::labels_to_numbers(proof) else {
+        return "".to_string();
+    };
+    let proof = numbered.as_str();
+    let res = if template.is_empty() {
+        check_proof_to_proofresult(proof, allowed_variable_names, options.clone())
+    } else {
+        check_proof_to_proofresult_with_template(
+            proof,
+            &template,
+            allowed_variable_names,
+            options.clone(),
+        )
+    };
+    if !matches!(res, ProofResult::Correct) {
+        return "".to_string();
+    }
+    // unwraps work, since the proof is correct
+    let lines = parser::parse_fitch_proof(proof).unwrap();
+    let variable_names = parser::parse_allowed_variable_names(allowed_variable_names).unwrap();
+    let symbols = match options.symbols {
+        SymbolSet::Auto => formatter::detect_symbol_set(proof),
+        symbols => symbols,
+    };
+    let proof_structure = proof::Proof::construct(lines, variable_names, options).unwrap();
+    let mut score_card =
+        score_card::ScoreCard::new(&proof_structure, parsed_template.minimal_length);
+    for warning in &mut score_card.warnings {
+        *warning = labels::numbers_to_labels_in_message(warning, &labels);
+    }
+    formatter::render_symbols(&score_card.to_json(), symbols)
+}
+
+/// Returns measures of how complex a sentence is, as a JSON object with, for every connective,
+/// the number of times it occurs, the greatest number of nested quantifiers, the number of atomic
+/// sentences and the size of the parse tree, for example:
+///
+/// `{"connectives":{"¬":1,"∧":2},"quantifier_depth":1,"atoms":3,"size":6}`
+///
+/// See [metrics::WffMetrics] for the details. If the sentence cannot be parsed, then an empty
+/// string is returned.
+///
+/// This function never panics.
+#[cfg(feature = "tutoring")]
+#[wasm_bindgen]
+pub fn wff_metrics(wff: &str) -> String {
+    match parser::parse_logical_expression_string(wff) {
+        Some(wff) => metrics::WffMetrics::new(&wff).to_json(),
+        None => "".to_string(),
+    }
 }
 
 /// Takes in a proof string as input, and tries to format that proof.
 ///
 /// If formatting succeeds, the formatted string is returned. If formatting fails, the original
-/// string is returned.
+/// string is returned. If there is no proof (see [parser::is_empty_proof]), then the message of
+/// [ProofResult::NoProof] is returned.
 ///
 /// This function never panics.
 #[wasm_bindgen]
 pub fn format_proof(proof: &str) -> String {
-    match parser::parse_fitch_proof(proof) {
-        Ok(lines) if !lines.is_empty() => formatter::format_proof(lines),
+    if parser::is_empty_proof(proof) {
+        return no_proof_message();
+    }
+    let Ok((numbered, labels)) = labels::labels_to_numbers(proof) else {
+        return proof.to_owned();
+    };
+    match parser::parse_fitch_proof(&numbered) {
+        Ok(lines) if !lines.is_empty() => {
+            let formatted = formatter::format_proof(lines);
+            with_directives(proof, labels::numbers_to_labels(&formatted, &labels))
+        }
         _ => proof.to_owned(),
     }
 }
 
-/// This function fixes the line numbers in a proof (in case they are not proper).
+/// Puts the directives of a proof (see [parser::parse_directives]) in front of the formatted
+/// proof, since the formatter only sees the proof lines.
+fn with_directives(proof: &str, formatted: String) -> String {
+    let directives: Vec<&str> =
+        proof.lines().filter(|line| parser::is_directive(line)).map(str::trim).collect();
+    if directives.is_empty() {
+        formatted
+    } else {
+        format!("{}\n{formatted}", directives.join("\n"))
+    }
+}
+
+/// Checks that formatting a proof (see [format_proof]) does not change its meaning: the proof is
+/// parsed, formatted and parsed again, and the two parsed proofs should be the same, line by line.
+/// Formatting the formatted proof again should not change it anymore either.
+///
+/// If this is all true, then `Ok(())` is returned. Otherwise, an error message that describes the
+/// first difference is returned. Since [format_proof] does not change a proof that cannot be
+/// parsed, such a proof is also fine, and so is a submission without a proof.
+///
+/// This function never panics.
+#[wasm_bindgen]
+pub fn roundtrip_check(proof: &str) -> Result<(), String> {
+    if parser::is_empty_proof(proof) {
+        return Ok(());
+    }
+    let Ok(lines) = parser::parse_fitch_proof(proof) else {
+        return Ok(());
+    };
+    let formatted = format_proof(proof);
+    let reparsed = match parser::parse_fitch_proof(&formatted) {
+        Ok(reparsed) => reparsed,
+        Err(err) => return Err(format!("the formatted proof cannot be parsed: {err}")),
+    };
+    let original_lines = proof.lines().filter(|s| !s.is_empty() && !parser::is_directive(s));
+    let formatted_lines = formatted.lines().filter(|s| !parser::is_directive(s));
+    for (i, ((line, reparsed_line), original)) in
+        zip(zip(&lines, &reparsed), zip(original_lines, formatted_lines)).enumerate()
+    {
+        let difference = if line.line_num != reparsed_line.line_num {
+            "line number"
+        } else if line.depth != reparsed_line.depth {
+            "depth"
+        } else if line.is_fitch_bar_line != reparsed_line.is_fitch_bar_line {
+            "Fitch bar"
+        } else if line.constant_between_square_brackets
+            != reparsed_line.constant_between_square_brackets
+        {
+            "boxed constant"
+        } else if line.sentence != reparsed_line.sentence {
+            "sentence"
+        } else if line.justification != reparsed_line.justification
+            || line.cites_premises != reparsed_line.cites_premises
+        {
+            "justification"
+        } else {
+            continue;
+        };
+        return Err(format!(
+            "line {} of the proof (`{}`) is formatted as `{}`, which has a different {difference}",
+            i + 1,
+            original.0.trim_end(),
+            original.1.trim_end()
+        ));
+    }
+    if lines.len() != reparsed.len() {
+        return Err(format!(
+            "the proof has {} lines, but the formatted proof has {} lines",
+            lines.len(),
+            reparsed.len()
+        ));
+    }
+    if format_proof(&formatted) != formatted {
+        return Err("formatting the formatted proof changes it again".to_string());
+    }
+    Ok(())
+}
+
+/// This function fixes the line numbers in a proof (in case they are not proper). A proof whose
+/// lines are labeled (like `(a)` or `(H1)`) instead of numbered keeps its labels.
 ///
 /// If fixing the line numbers succeeds, the fixed string is returned. If it fails, the original
-/// string is returned.
+/// string is returned. If there is no proof (see [parser::is_empty_proof]), then the message of
+/// [ProofResult::NoProof] is returned.
 ///
 /// This function never panics.
 #[wasm_bindgen]
 pub fn fix_line_numbers_in_proof(proof: &str) -> String {
-    match parser::parse_fitch_proof(proof) {
+    fix_line_numbers_with_options(proof, "")
+}
+
+/// Same as [fix_line_numbers_in_proof], but the option `labels` says what happens to the labels
+/// of the lines (see [parser::parse_line_labels]): `"labels=numbers"` replaces the labels by line
+/// numbers, and `"labels=letters"` numbers the lines `(a)`, `(b)`, `(c)`, ... instead.
+///
+/// If the options are not valid, or if fixing the line numbers fails, the original string is
+/// returned.
+///
+/// This function never panics.
+#[wasm_bindgen]
+pub fn fix_line_numbers_with_options(proof: &str, options: &str) -> String {
+    if parser::is_empty_proof(proof) {
+        return no_proof_message();
+    }
+    let (Ok(line_labels), Ok((numbered, labels))) =
+        (parser::parse_line_labels(options), labels::labels_to_numbers(proof))
+    else {
+        return proof.to_owned();
+    };
+    match parser::parse_fitch_proof(&numbered) {
         Ok(mut lines) if !lines.is_empty() => {
             fix_line_numbers::fix_line_numbers(&mut lines);
-            formatter::format_proof(lines)
+            let num_lines = lines.iter().filter(|l| l.line_num.is_some()).count();
+            let formatted = formatter::format_proof(lines);
+            let formatted = match line_labels {
+                LineLabels::Keep => labels::numbers_to_labels(&formatted, &labels),
+                LineLabels::Numbers => formatted,
+                LineLabels::Letters => {
+                    labels::numbers_to_labels(&formatted, &labels::letter_labels(num_lines))
+                }
+            };
+            with_directives(proof, formatted)
         }
         _ => proof.to_owned(),
     }
 }
 
+/// The changes to a proof document that an editor can ask for, see [document_edits].
+#[wasm_bindgen]
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum DocumentAction {
+    /// Format the proof, like [format_proof].
+    Format,
+    /// Fix the line numbers in the proof, like [fix_line_numbers_in_proof].
+    FixLineNumbers,
+}
+
+/// Applies a [DocumentAction] to a proof, but instead of returning the new proof, a list of
+/// (minimal) [TextEdit]s is returned which turn the original proof into the new one. This way, an
+/// editor can apply the changes without losing the cursor position or the undo history.
+///
+/// If the action fails (e.g. because the proof cannot be parsed), or there is no proof (see
+/// [parser::is_empty_proof]), no edits are returned.
+///
+/// This function never panics.
+pub fn document_edits(proof: &str, action: DocumentAction) -> Vec<TextEdit> {
+    if parser::is_empty_proof(proof) {
+        return vec![];
+    }
+    let new_proof = match action {
+        DocumentAction::Format => format_proof(proof),
+        DocumentAction::FixLineNumbers => fix_line_numbers_in_proof(proof),
+    };
+    if new_proof == proof {
+        return vec![];
+    }
+    text_edits::text_edits_for_reformatted_lines(proof, &new_proof)
+}
+
+/// Same as [document_edits], but returns the edits as a JSON array of LSP-style text edits, for
+/// example:
+///
+/// `[{"range":{"start":{"line":2,"character":5},"end":{"line":2,"character":5}},"newText":" "}]`
+///
+/// This function never panics.
+#[wasm_bindgen]
+pub fn document_edits_json(proof: &str, action: DocumentAction) -> String {
+    let edits: Vec<String> = document_edits(proof, action).iter().map(TextEdit::to_json).collect();
+    format!("[{}]", edits.join(","))
+}
+
+/// Renames the vocabulary (predicates, propositional atoms, function symbols and constants) of a
+/// proof and its template consistently, e.g. to make different variants of an exam question. The
+/// renaming is a comma-separated list of `old=new` pairs, like `"P=Q, Q=P, a=c"`. Names that are
+/// not mentioned stay the same, and variables are never renamed.
+///
+/// If it succeeds, the (formatted) renamed proof and the renamed template are returned. An error
+/// message is returned if the renaming is not consistent (for example, if it renames `a` to a
+/// constant `c` that is already used in the proof), if something cannot be parsed, or if the
+/// proof is correct (with respect to the template) but the renamed proof is not.
+///
+/// This function never panics.
+pub fn rename_vocabulary(
+    proof: &str,
+    template: &[String],
+    renaming: &str,
+    allowed_variable_names: &str,
+) -> Result<(String, Vec<String>), String> {
+    let renaming = parser::parse_renaming(renaming)?;
+    rename_vocabulary_by(proof, template, allowed_variable_names, |_, _, _| renaming)
+}
+
+/// Makes a variant of a proof and its template by renaming the vocabulary at random (see
+/// [rename::random_renaming]), like [rename_vocabulary]. The same `seed` always gives the same
+/// variant, so a variant can be made again later (e.g. for a regrade) from the same seed; see
+/// [variant_seed] for a seed per student.
+///
+/// This function never panics.
+pub fn random_variant(
+    proof: &str,
+    template: &[String],
+    seed: u64,
+    allowed_variable_names: &str,
+) -> Result<(String, Vec<String>), String> {
+    rename_vocabulary_by(proof, template, allowed_variable_names, |lines, wffs, vars| {
+        rename::random_renaming(lines, wffs, vars, seed)
+    })
+}
+
+/// Returns the seed of the variant of an exercise for a student (see [random_variant]), which is
+/// computed from the ID of the student and the ID of the exercise, so that every student gets
+/// their own variant of every exercise. The seed is the same on every platform, and with every
+/// version of this library.
+///
+/// This function never panics.
+#[wasm_bindgen]
+pub fn variant_seed(student_id: &str, exercise_id: &str) -> u64 {
+    util::fnv1a_hash(format!("{}\0{}", exercise_id.trim(), student_id.trim()).as_bytes())
+}
+
+/// Renames the vocabulary of a proof and its template (see [rename_vocabulary]), where the
+/// renaming is made by `renaming` from the parsed proof, the parsed template and the variables.
+fn rename_vocabulary_by<F>(
+    proof: &str,
+    template: &[String],
+    allowed_variable_names: &str,
+    renaming: F,
+) -> Result<(String, Vec<String>), String>
+where
+    F: FnOnce(&[ProofLine], &[Wff], &HashSet<String>) -> HashMap<String, String>,
+{
+    let variable_names = parser::parse_allowed_variable_names(allowed_variable_names)?;
+    let mut proof_lines = parser::parse_fitch_proof(proof)?;
+    let Ok(mut template_wffs) = parser::parse_template(template).map(|t| t.sentences) else {
+        return Err("some sentences in the template could not be parsed".to_string());
+    };
+    let renaming = renaming(&proof_lines, &template_wffs, &variable_names);
+    rename::rename_vocabulary(&mut proof_lines, &mut template_wffs, &renaming, &variable_names)?;
+
+    let new_proof = if proof_lines.is_empty() {
+        "".to_string()
+    } else {
+        formatter::format_proof(proof_lines)
+    };
+    // the comments in the template are kept as they are
+    let mut renamed_wffs = template_wffs.iter();
+    let new_template: Vec<String> = template
+        .iter()
+        .map(|line| match parser::template_comment(line) {
+            Some(_) => line.clone(),
+            // unwrap works, since every line that is not a comment is a sentence
+            None => formatter::format_wff(renamed_wffs.next().unwrap()),
+        })
+        .collect();
+    let is_correct = |proof: &str, template: &[String]| {
+        let options = CheckOptions::default();
+        let res = if template.is_empty() {
+            check_proof_to_proofresult(proof, allowed_variable_names, options)
+        } else {
+            check_proof_to_proofresult_with_template(
+                proof,
+                template,
+                allowed_variable_names,
+                options,
+            )
+        };
+        matches!(res, ProofResult::Correct)
+    };
+    if is_correct(proof, template) && !is_correct(&new_proof, &new_template) {
+        return Err("the proof is correct, but the renamed proof is not".to_string());
+    }
+    Ok((new_proof, new_template))
+}
+
+/// Checks a proof for an exercise in an exercise bundle (a `.fitchpack` file, see
+/// [ExerciseBundle]), with the template, allowed variable names and options of that exercise.
+/// The message is the same as for [check_proof_with_template_and_options].
+///
+/// This function never panics.
+#[cfg(feature = "bundle")]
+#[wasm_bindgen]
+pub fn check_proof_in_bundle(bundle: &str, exercise_id: &str, proof: &str) -> String {
+    match ExerciseBundle::from_json(bundle) {
+        Ok(bundle) => bundle.check_proof(exercise_id, proof),
+        Err(err) => proof_result_to_string(ProofResult::FatalError(FatalError::TemplateError(
+            format!("the exercise bundle could not be read: {err}"),
+        ))),
+    }
+}
+
+/// Checks that an exercise bundle is consistent (see [ExerciseBundle::validate]), and returns the
+/// problems with it, separated by empty lines. If there are no problems, an empty string is
+/// returned.
+///
+/// This function never panics.
+#[cfg(feature = "bundle")]
+#[wasm_bindgen]
+pub fn validate_bundle(bundle: &str) -> String {
+    match ExerciseBundle::from_json(bundle) {
+        Ok(bundle) => bundle.validate().join("\n\n"),
+        Err(err) => err,
+    }
+}
+
+/// Hides the solutions in an exercise bundle with a key (see [ExerciseBundle::hide_solutions]),
+/// and returns the new bundle. An error message is returned if the bundle cannot be read.
+///
+/// This function never panics.
+#[cfg(feature = "bundle")]
+#[wasm_bindgen]
+pub fn hide_solutions(bundle: &str, key: &str) -> Result<String, String> {
+    let mut bundle = ExerciseBundle::from_json(bundle)?;
+    bundle.hide_solutions(key);
+    Ok(bundle.to_json())
+}
+
+/// Returns the hidden solutions of an exercise in an exercise bundle, revealed with the key (see
+/// [Exercise::reveal_solutions]). `now` is the current time in seconds since the Unix epoch, e.g.
+/// `Date.now() / 1000` in JavaScript. Since the caller gives the time, the `unlock_after` time of
+/// the exercise does not keep anyone who has the key from revealing the solutions.
+///
+/// An error message is returned if the bundle cannot be read, there is no such exercise, the
+/// solutions cannot be revealed yet, or the key is not correct.
+///
+/// This function never panics.
+#[cfg(feature = "bundle")]
+#[wasm_bindgen]
+pub fn reveal_solution(
+    bundle: &str,
+    exercise_id: &str,
+    key: &str,
+    now: f64,
+) -> Result<Vec<String>, String> {
+    let bundle = ExerciseBundle::from_json(bundle)?;
+    let Some(exercise) = bundle.exercise(exercise_id) else {
+        return Err(format!("there is no exercise '{exercise_id}' in the bundle"));
+    };
+    exercise.reveal_solutions(key, now as u64)
+}
+
+#[cfg(feature = "export")]
 #[wasm_bindgen]
 pub fn export_to_latex(proof: &str) -> String {
-    match parser::parse_fitch_proof(proof) {
-        Ok(lines) if !lines.is_empty() => export_to_latex::proof_to_latex(&lines),
-        _ => "Failed to export to latex, because the proof could not be parsed or was empty."
-            .to_string(),
+    if parser::is_empty_proof(proof) {
+        return no_proof_message();
+    }
+    match parse_labeled_fitch_proof(proof) {
+        Ok((lines, _)) => export_to_latex::proof_to_latex(&lines),
+        Err(err) => {
+            format!("Failed to export to latex, because the proof could not be parsed: {err}")
+        }
+    }
+}
+
+/// Reads a proof that was exported with [export_to_latex] back, and returns it as a formatted
+/// proof (see [format_proof]). Exporting a proof and reading it back gives the same proof lines,
+/// see [export_to_latex::latex_to_proof], so solutions that are only kept as LaTeX can still be
+/// checked.
+///
+/// If the LaTeX cannot be read, then an error message is returned.
+///
+/// This function never panics.
+#[cfg(feature = "export")]
+#[wasm_bindgen]
+pub fn import_from_latex(latex: &str) -> Result<String, String> {
+    match export_to_latex::latex_to_proof(latex)? {
+        lines if lines.is_empty() => Err("there is no proof in the LaTeX".to_string()),
+        lines => Ok(formatter::format_proof(lines)),
+    }
+}
+
+/// Exports a proof in the notation of forall x (the textbook of the Open Logic Project), with the
+/// rule names of forall x and `PR` and `AS` for the premises and assumptions, so that exercises
+/// can be shared with courses that use forall x. See [export_forallx::proof_to_forallx] and
+/// [export_forallx::FORALLX_RULE_NAMES]. Labeled lines (see [labels::labels_to_numbers]) are
+/// numbered in the export.
+///
+/// This function never panics.
+#[cfg(feature = "export")]
+#[wasm_bindgen]
+pub fn export_to_forallx(proof: &str) -> String {
+    if parser::is_empty_proof(proof) {
+        return no_proof_message();
+    }
+    match parse_labeled_fitch_proof(proof) {
+        Ok((lines, _)) => export_forallx::proof_to_forallx(&lines),
+        Err(err) => format!("Failed to export, because the proof could not be parsed: {err}"),
+    }
+}
+
+/// Reads a proof in the notation of forall x (see [export_to_forallx]), and returns it as a
+/// formatted proof (see [format_proof]). See [export_forallx::forallx_to_proof].
+///
+/// If the proof cannot be read, then an error message is returned.
+///
+/// This function never panics.
+#[cfg(feature = "export")]
+#[wasm_bindgen]
+pub fn import_from_forallx(proof: &str) -> Result<String, String> {
+    match export_forallx::forallx_to_proof(proof)? {
+        lines if lines.is_empty() => Err("there is no proof in forall x notation".to_string()),
+        lines => Ok(formatter::format_proof(lines)),
+    }
+}
+
+/// Exports the premises and the conclusion of a proof (not the proof itself) as a problem in the
+/// TPTP language, so that exercise authors can check with an automated theorem prover (like E or
+/// Vampire) that the conclusion follows from the premises. See [export_tptp::proof_to_tptp].
+///
+/// If the proof cannot be parsed, or has no conclusion, or the problem cannot be written in TPTP,
+/// then an error message is returned.
+///
+/// This function never panics.
+#[cfg(feature = "export")]
+#[wasm_bindgen]
+pub fn export_to_tptp(proof: &str) -> Result<String, String> {
+    if parser::is_empty_proof(proof) {
+        return Err(no_proof_message());
+    }
+    export_tptp::proof_to_tptp(&parse_labeled_fitch_proof(proof)?.0)
+}
+
+/// Exports a template (see [check_proof_with_template]) as a problem in the TPTP language, like
+/// [export_to_tptp]: the last sentence of the template is the conjecture, and the others are the
+/// axioms.
+///
+/// If the template cannot be parsed or is empty, or the problem cannot be written in TPTP, then an
+/// error message is returned.
+///
+/// This function never panics.
+#[cfg(feature = "export")]
+#[wasm_bindgen]
+pub fn export_template_to_tptp(template: Vec<String>) -> Result<String, String> {
+    let template = parser::parse_template(&template)?;
+    match template.sentences.split_last() {
+        Some((conclusion, premises)) => export_tptp::sequent_to_tptp(premises, conclusion),
+        None => Err("the template is empty".to_string()),
+    }
+}
+
+/// Returns the steps in which a proof can be revealed one by one (e.g. to animate its
+/// construction during a lecture), as a JSON array of events in order: a line appears, a subproof
+/// is opened or closed, or a Fitch bar appears. Every line comes with what kind of line it is and
+/// the lines and subproofs that it cites, for example:
+///
+/// `[{"event":"line","line":1,"depth":1,"kind":"premise","boxed_constant":null,"sentence":"A ∧ B",
+/// "justification":null,"cited_lines":[],"cited_subproofs":[]},{"event":"fitch_bar","depth":1},
+/// ...]`
+///
+/// See [replay::replay_events] and [replay::ReplayEvent::to_json]. The sentences are written in
+/// the symbols that the proof is written in. If the lines of the proof are labeled (see
+/// [labels::labels_to_numbers]), then the events number them from 1 on. The proof does not need
+/// to be correct, but if it cannot be parsed, then an empty string is returned.
+///
+/// This function never panics.
+#[cfg(feature = "tutoring")]
+#[wasm_bindgen]
+pub fn proof_replay(proof: &str) -> String {
+    let Ok((lines, _)) = parse_labeled_fitch_proof(proof) else {
+        return "".to_string();
+    };
+    let events: Vec<String> =
+        replay::replay_events(&lines).iter().map(replay::ReplayEvent::to_json).collect();
+    let symbols = formatter::detect_symbol_set(proof);
+    formatter::render_symbols(&format!("[{}]", events.join(",")), symbols)
+}
+
+/// Exports a proof in two columns, the sentences and their justifications, as a LaTeX tabular or
+/// an HTML table. This is how solutions are often typeset, without Fitch bars: the subproofs are
+/// indented instead.
+///
+/// The options are given as a string like `"format=html, numbering=parens, formula_width=8cm"`,
+/// see [parser::parse_two_column_options]. By default, a LaTeX tabular with line numbers like
+/// `1.` is made, where the columns are as wide as their contents. Labeled lines (see
+/// [labels::labels_to_numbers]) are numbered in the export.
+#[cfg(feature = "export")]
+#[wasm_bindgen]
+pub fn export_two_column(proof: &str, options: &str) -> String {
+    if parser::is_empty_proof(proof) {
+        return no_proof_message();
+    }
+    let options = match parser::parse_two_column_options(options) {
+        Ok(options) => options,
+        Err(err) => return format!("Failed to export, because the options are not valid: {err}."),
+    };
+    match parse_labeled_fitch_proof(proof) {
+        Ok((lines, _)) => export_two_column::proof_to_two_columns(&lines, &options),
+        Err(err) => format!("Failed to export, because the proof could not be parsed: {err}"),
+    }
+}
+
+/// Returns a short outline of a proof in words, which can be pasted into the feedback on the
+/// proof, for example:
+///
+/// `The proof has 1 premise and ends with B ∨ A in line 6.`
+/// `Line 6 (B ∨ A) is proved by cases on line 1 (A ∨ B):`
+/// `  the first case assumes A in line 2 and derives B ∨ A in line 3;`
+/// `  ...`
+///
+/// See [summary::summarize_proof]. The sentences are written in the symbols that the proof is
+/// written in, and the lines are referred to by their labels if they are labeled (see
+/// [labels::labels_to_numbers]). The proof does not need to be correct, but if it cannot be
+/// parsed, then a message which says so is returned, and if there is no proof (see
+/// [parser::is_empty_proof]), then the message of [ProofResult::NoProof].
+///
+/// This function never panics.
+#[cfg(feature = "tutoring")]
+#[wasm_bindgen]
+pub fn summarize_proof(proof: &str) -> String {
+    if parser::is_empty_proof(proof) {
+        return no_proof_message();
+    }
+    match parse_labeled_fitch_proof(proof) {
+        Ok((lines, labels)) => {
+            let symbols = formatter::detect_symbol_set(proof);
+            let summary =
+                labels::numbers_to_labels_in_message(&summary::summarize_proof(&lines), &labels);
+            formatter::render_symbols(&summary, symbols)
+        }
+        Err(err) => {
+            format!("The proof could not be summarized, because it could not be parsed: {err}")
+        }
+    }
+}
+
+/// An entry point for fuzzers, for example a `cargo fuzz` target like this:
+///
+/// ```ignore
+/// fuzz_target!(|data: &[u8]| fitch_proof::fuzz_entry(data));
+/// ```
+///
+/// The bytes are read as a proof (anything after the first zero byte is read as the options, see
+/// [check_proof_with_options]) and the proof is given to all public functions that take a proof.
+/// It panics if one of them panics, or if one of them reports an internal error (see
+/// [FatalError::Internal]); otherwise, it does nothing.
+pub fn fuzz_entry(data: &[u8]) {
+    let data = String::from_utf8_lossy(data);
+    let (proof, options) = data.split_once('\0').unwrap_or((&data, ""));
+    let results = [
+        check_proof_with_options(proof, default_variable_names!(), options),
+        check_proof_with_template_and_options(
+            proof,
+            vec!["A".to_string(), "A".to_string()],
+            default_variable_names!(),
+            options,
+        ),
+        check_sequent(proof, default_variable_names!()),
+        check_proof_skeleton(proof, default_variable_names!(), options),
+        check_expected_errors(proof, default_variable_names!(), options),
+        check_error_identification(proof, options, default_variable_names!(), options),
+    ];
+    for res in results {
+        assert!(!res.starts_with("Internal error"), "{res}");
+    }
+    for renderer in render::RENDERERS {
+        check_proof_with_renderer(proof, default_variable_names!(), options, renderer.name());
+    }
+    check_proof_with_profile(proof, default_variable_names!(), options);
+    check_proof_with_progress(proof, default_variable_names!(), &mut |_, _| {});
+    check_proof_with_golf_score(
+        proof,
+        vec!["# minimal proof length: 1".to_string()],
+        default_variable_names!(),
+        options,
+    );
+    check_formula(proof, options);
+    let _ = formulas_match(proof, proof, options);
+    format_proof(proof);
+    fix_line_numbers_in_proof(proof);
+    fix_line_numbers_with_options(proof, "labels=letters");
+    document_edits(proof, DocumentAction::Format);
+    let _ = rename_vocabulary(proof, &[], "", default_variable_names!());
+    let _ = random_variant(proof, &[], 0, default_variable_names!());
+    #[cfg(feature = "export")]
+    {
+        export_to_latex(proof);
+        let _ = import_from_latex(proof);
+        let _ = import_from_latex(&export_to_latex(proof));
+        export_two_column(proof, options);
+        let _ = import_from_forallx(proof);
+        let _ = import_from_forallx(&export_to_forallx(proof));
+        let _ = export_to_tptp(proof);
+        let _ = export_template_to_tptp(proof.lines().map(str::to_string).collect());
+    }
+    #[cfg(feature = "tutoring")]
+    {
+        lint_proof(proof);
+        proof_score_card(proof, vec![], default_variable_names!(), options);
+        wff_metrics(proof);
+        proof_replay(proof);
+        summarize_proof(proof);
+        proof_hint(proof, default_variable_names!(), options, "", 3);
     }
 }
@@ -0,0 +1,252 @@
+use crate::data::*;
+use crate::formatter;
+use crate::proof::*;
+use crate::util;
+use std::collections::BTreeSet;
+use std::iter;
+
+/// Returns the warnings for a proof. A warning is about something that is allowed, but that is
+/// almost always a mistake (for example, `∀x P(a)`: the quantifier binds a variable that does not
+/// occur in the rest of the sentence). Warnings do not affect whether the proof is correct.
+///
+/// Like error messages, every warning starts with `Line <num>: `, and the warnings are sorted.
+///
+/// Note that some similar mistakes are not warnings, because the checker already rejects them:
+/// nested quantifiers over the same variable, and quantifying over a name that is not in the list
+/// of allowed variable names.
+pub fn lint(proof: &Proof) -> Vec<String> {
+    let mut warnings: Vec<String> = vec![];
+    for line in &proof.lines {
+        if let (Some(line_num), Some(wff)) = (line.line_num, &line.sentence) {
+            vacuous_quantifier_warnings(wff, line_num, &mut warnings);
+        }
+    }
+    warnings.extend(duplicate_line_warnings(proof));
+    warnings.extend(repeated_assumption_warnings(proof));
+    warnings.extend(duplicate_subproof_warnings(proof));
+    warnings.extend(unused_equals_intro_warnings(proof));
+    util::natural_sort(&mut warnings);
+    warnings
+}
+
+/* ------------------ PRIVATE -------------------- */
+
+/// Adds a warning to `warnings` for every quantifier in `wff` that binds a variable which does not
+/// occur (free) in the quantified part of the sentence.
+fn vacuous_quantifier_warnings(wff: &Wff, line_num: usize, warnings: &mut Vec<String>) {
+    match wff {
+        Wff::Forall(var, w) | Wff::Exists(var, w) => {
+            if !var_occurs_free_in_wff(var, w) {
+                let quantifier = if matches!(wff, Wff::Forall(..)) {
+                    "∀"
+                } else {
+                    "∃"
+                };
+                warnings.push(format!(
+                    "Line {line_num}: the quantifier {quantifier}{var} in {} has no effect, \
+                    because {var} does not occur in {}. Did you mean to write {var} somewhere?",
+                    formatter::format_wff(wff),
+                    formatter::format_wff(w)
+                ));
+            }
+            vacuous_quantifier_warnings(w, line_num, warnings);
+        }
+        Wff::And(li) | Wff::Or(li) => {
+            li.iter().for_each(|w| vacuous_quantifier_warnings(w, line_num, warnings))
+        }
+        Wff::Implies(w1, w2) | Wff::Bicond(w1, w2) => {
+            vacuous_quantifier_warnings(w1, line_num, warnings);
+            vacuous_quantifier_warnings(w2, line_num, warnings);
+        }
+        Wff::Not(w) => vacuous_quantifier_warnings(w, line_num, warnings),
+        Wff::Bottom | Wff::Atomic(_) | Wff::PredApp(..) | Wff::Equals(..) => {}
+    }
+}
+
+/// Returns a warning for every inference whose sentence is the same as the sentence of an earlier
+/// line that may be referenced from that inference. Such a line can be left out, since the earlier
+/// line can be cited instead (or reiterated, if it is needed inside a subproof). Lines that are
+/// already justified with Reit do not get a warning.
+fn duplicate_line_warnings(proof: &Proof) -> Vec<String> {
+    let mut warnings: Vec<String> = vec![];
+    for line in &proof.lines {
+        let (Some(line_num), Some(wff), Some(just)) =
+            (line.line_num, &line.sentence, &line.justification)
+        else {
+            continue;
+        };
+        let Some((referenceable_lines, _)) = proof.scope.get(line_num) else {
+            continue; // the line numbers are not in order
+        };
+        if matches!(just, Justification::Reit(_)) {
+            continue;
+        }
+        let earlier_line = referenceable_lines.iter().find(|n| {
+            proof.lines.iter().any(|l| l.line_num == Some(**n) && l.sentence.as_ref() == Some(wff))
+        });
+        if let Some(n) = earlier_line {
+            warnings.push(format!(
+                "Line {line_num}: the sentence in this line is the same as the sentence in line \
+                {n}, which may already be referenced here. You can cite line {n} instead (or use \
+                Reit: {n} if you need the sentence inside a subproof)."
+            ));
+        }
+    }
+    warnings
+}
+
+/// Returns a warning for every assumption of a subproof whose sentence is the same as the sentence
+/// of an earlier line that may be referenced from inside the subproof: a premise, an assumption of
+/// an enclosing subproof, or an inference. Such a line can be cited instead, and assuming it again
+/// is usually a misunderstanding, since the subproof then often cannot be closed with the rule
+/// that the student has in mind. Assumptions with a boxed constant do not get a warning.
+fn repeated_assumption_warnings(proof: &Proof) -> Vec<String> {
+    // for every open subproof (and the proof itself), the lines in it with a sentence, and whether
+    // they are a premise or an assumption
+    let mut levels: Vec<Vec<(usize, &Wff, bool)>> = vec![vec![]];
+    let mut is_hypo = true;
+    let mut warnings: Vec<String> = vec![];
+    for line in &proof.lines {
+        if line.depth > levels.len() {
+            is_hypo = true;
+        }
+        levels.resize_with(line.depth.max(1), Vec::new);
+        if line.is_fitch_bar_line {
+            is_hypo = false;
+        }
+        let (Some(line_num), Some(wff)) = (line.line_num, &line.sentence) else {
+            continue;
+        };
+        let earlier_line = match (is_hypo, line.depth, &line.constant_between_square_brackets) {
+            (true, 2.., None) => levels.iter().flatten().find(|(_, w, _)| *w == wff),
+            _ => None,
+        };
+        if let Some(&(n, _, earlier_is_hypo)) = earlier_line {
+            let (earlier, already) = match (earlier_is_hypo, levels[0].iter().any(|l| l.0 == n)) {
+                (true, true) => ("the premise", "This is already a premise"),
+                (true, false) => ("the assumption", "You already assumed this"),
+                (false, _) => ("the sentence", "You already derived this"),
+            };
+            warnings.push(format!(
+                "Line {line_num}: the assumption {} is the same as {earlier} in line {n}, which \
+                may already be referenced here. {already}, so you can cite line {n} instead of \
+                assuming it again. A subproof that assumes it again often cannot be closed the \
+                way you intend.",
+                formatter::format_wff(wff)
+            ));
+        }
+        levels.last_mut().unwrap().push((line_num, wff, is_hypo));
+    }
+    warnings
+}
+
+/// Returns a warning for every subproof that is identical to an earlier subproof that may be
+/// referenced from inside the later subproof: they have the same sentences and boxed constants,
+/// line by line.
+fn duplicate_subproof_warnings(proof: &Proof) -> Vec<String> {
+    let subproofs: BTreeSet<(usize, usize)> =
+        proof.scope.iter().flat_map(|(_, subproofs)| subproofs.iter().copied()).collect();
+    let contents = |(begin, end): (usize, usize)| {
+        let begin_index = proof.lines.iter().position(|l| l.line_num == Some(begin))?;
+        let end_index = proof.lines.iter().position(|l| l.line_num == Some(end))?;
+        let depth = proof.lines[begin_index].depth;
+        let lines: Vec<(usize, &Option<Wff>, &Option<Term>)> = proof.lines[begin_index..=end_index]
+            .iter()
+            .filter(|l| l.line_num.is_some())
+            .map(|l| (l.depth - depth, &l.sentence, &l.constant_between_square_brackets))
+            .collect();
+        Some(lines)
+    };
+    let mut warnings: Vec<String> = vec![];
+    for &(begin, end) in &subproofs {
+        // only lines with a justification have a scope, so we look at the scope of the last line
+        // of the subproof (and skip the subproofs inside the subproof itself)
+        let (Some((_, referenceable_subproofs)), Some(subproof)) =
+            (proof.scope.get(end), contents((begin, end)))
+        else {
+            continue;
+        };
+        let identical_earlier_subproof = referenceable_subproofs
+            .iter()
+            .filter(|(_, earlier_end)| *earlier_end < begin)
+            .find(|earlier| contents(**earlier).as_ref() == Some(&subproof));
+        if let Some((earlier_begin, earlier_end)) = identical_earlier_subproof {
+            warnings.push(format!(
+                "Line {begin}: the subproof {begin}-{end} is identical to the subproof \
+                {earlier_begin}-{earlier_end}, which may already be referenced here. You can cite \
+                {earlier_begin}-{earlier_end} instead."
+            ));
+        }
+    }
+    warnings
+}
+
+/// Returns a warning for every line with =Intro that is never cited. Such a line (`a = a`) can
+/// be left out of the proof.
+fn unused_equals_intro_warnings(proof: &Proof) -> Vec<String> {
+    let cited: BTreeSet<usize> =
+        proof.lines.iter().filter_map(|l| l.justification.as_ref()).flat_map(cited_lines).collect();
+    proof
+        .lines
+        .iter()
+        .filter(|l| l.justification == Some(Justification::EqualsIntro))
+        .filter_map(|l| l.line_num)
+        .filter(|n| !cited.contains(n))
+        .map(|n| {
+            format!(
+                "Line {n}: this =Intro line is never referenced, so it is not needed. Note that \
+                =Elim does not need a line like this when it replaces a term by itself."
+            )
+        })
+        .collect()
+}
+
+/// Returns the line numbers that are cited by a justification; for subproofs, its first and its
+/// last line.
+fn cited_lines(just: &Justification) -> Vec<usize> {
+    use Justification::*;
+    match just {
+        AndIntro(ns) | EqualsChain(ns) => ns.clone(),
+        AndElim(n)
+        | OrIntro(n)
+        | NotElim(n)
+        | BottomElim(n)
+        | ForallElim(n)
+        | ExistsIntro(n)
+        | Reit(n)
+        | QuantifierExchange(n)
+        | Contraposition(n)
+        | MaterialImplication(n) => vec![*n],
+        OrElim(n, subproofs) => {
+            iter::once(*n).chain(subproofs.iter().flat_map(|&(b, e)| [b, e])).collect()
+        }
+        NotIntro((b, e)) | ImpliesIntro((b, e)) | ForallIntro((b, e)) => vec![*b, *e],
+        BottomIntro(n1, n2) | ImpliesElim(n1, n2) | BicondElim(n1, n2) | EqualsElim(n1, n2) => {
+            vec![*n1, *n2]
+        }
+        BicondIntro((b1, e1), (b2, e2)) => vec![*b1, *e1, *b2, *e2],
+        ExistsElim(n, (b, e)) => vec![*n, *b, *e],
+        EqualsIntro => vec![],
+    }
+}
+
+/// Returns whether the variable `var` occurs free in [Wff] `wff`.
+fn var_occurs_free_in_wff(var: &str, wff: &Wff) -> bool {
+    fn var_occurs_in_term(var: &str, term: &Term) -> bool {
+        match term {
+            Term::Atomic(name) => name == var,
+            Term::FuncApp(_, args) => args.iter().any(|a| var_occurs_in_term(var, a)),
+        }
+    }
+    match wff {
+        Wff::Forall(v, w) | Wff::Exists(v, w) => v != var && var_occurs_free_in_wff(var, w),
+        Wff::And(li) | Wff::Or(li) => li.iter().any(|w| var_occurs_free_in_wff(var, w)),
+        Wff::Implies(w1, w2) | Wff::Bicond(w1, w2) => {
+            var_occurs_free_in_wff(var, w1) || var_occurs_free_in_wff(var, w2)
+        }
+        Wff::Not(w) => var_occurs_free_in_wff(var, w),
+        Wff::PredApp(_, args) => args.iter().any(|a| var_occurs_in_term(var, a)),
+        Wff::Equals(t1, t2) => var_occurs_in_term(var, t1) || var_occurs_in_term(var, t2),
+        Wff::Bottom | Wff::Atomic(_) => false,
+    }
+}
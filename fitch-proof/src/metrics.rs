@@ -0,0 +1,114 @@
+use crate::data::*;
+use crate::util;
+use std::collections::BTreeMap;
+
+/// Measures of how complex a single sentence is, e.g. to sort generated exercises by difficulty.
+#[derive(PartialEq, Debug)]
+pub struct WffMetrics {
+    /// For every connective (`∧`, `∨`, `→`, `↔`, `¬` and `⊥`) that occurs, the number of times it
+    /// occurs. A conjunction or disjunction of n sentences counts as n - 1 connectives.
+    pub connectives: BTreeMap<String, usize>,
+    /// The greatest number of quantifiers that some part of the sentence is in, so 0 if there are
+    /// no quantifiers.
+    pub quantifier_depth: usize,
+    /// The number of occurrences of atomic sentences (like `A`, `P(a)` and `a = b`, but not `⊥`).
+    pub atoms: usize,
+    /// The number of nodes of the parse tree, without the terms. A conjunction or disjunction of
+    /// any number of sentences is one node.
+    pub size: usize,
+}
+
+impl WffMetrics {
+    /// Computes the metrics of a sentence.
+    pub fn new(wff: &Wff) -> WffMetrics {
+        let mut metrics = WffMetrics {
+            connectives: BTreeMap::new(),
+            quantifier_depth: 0,
+            atoms: 0,
+            size: 0,
+        };
+        metrics.add(wff, 0);
+        metrics
+    }
+
+    /// Returns the metrics as a JSON object, for example:
+    ///
+    /// `{"connectives":{"¬":1,"∧":2},"quantifier_depth":1,"atoms":3,"size":6}`
+    pub fn to_json(&self) -> String {
+        let connectives: Vec<String> = self
+            .connectives
+            .iter()
+            .map(|(connective, count)| format!("{}:{count}", util::json_string(connective)))
+            .collect();
+        format!(
+            "{{\"connectives\":{{{}}},\"quantifier_depth\":{},\"atoms\":{},\"size\":{}}}",
+            connectives.join(","),
+            self.quantifier_depth,
+            self.atoms,
+            self.size,
+        )
+    }
+
+    /// Adds the metrics of `wff`, which is inside `depth` quantifiers.
+    fn add(&mut self, wff: &Wff, depth: usize) {
+        self.size += 1;
+        self.quantifier_depth = self.quantifier_depth.max(depth);
+        let mut connective = |symbol: &str, n: usize| {
+            *self.connectives.entry(symbol.to_string()).or_default() += n;
+        };
+        match wff {
+            Wff::And(wffs) | Wff::Or(wffs) => {
+                let symbol = match wff {
+                    Wff::And(_) => "∧",
+                    _ => "∨",
+                };
+                connective(symbol, wffs.len().saturating_sub(1));
+                for w in wffs {
+                    self.add(w, depth);
+                }
+            }
+            Wff::Implies(a, b) | Wff::Bicond(a, b) => {
+                let symbol = match wff {
+                    Wff::Implies(..) => "→",
+                    _ => "↔",
+                };
+                connective(symbol, 1);
+                self.add(a, depth);
+                self.add(b, depth);
+            }
+            Wff::Not(w) => {
+                connective("¬", 1);
+                self.add(w, depth);
+            }
+            Wff::Bottom => connective("⊥", 1),
+            Wff::Forall(_, w) | Wff::Exists(_, w) => self.add(w, depth + 1),
+            Wff::Atomic(_) | Wff::PredApp(..) | Wff::Equals(..) => self.atoms += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_logical_expression_string;
+
+    #[test]
+    fn test_wff_metrics() {
+        let wff =
+            parse_logical_expression_string("∀x (P(x) ∧ ¬∃y (Q(y) ∧ A ∧ x = y)) → ⊥").unwrap();
+        let metrics = WffMetrics::new(&wff);
+        assert_eq!(metrics.quantifier_depth, 2);
+        assert_eq!(metrics.atoms, 4);
+        assert_eq!(metrics.size, 11);
+        assert_eq!(
+            metrics.to_json(),
+            "{\"connectives\":{\"¬\":1,\"→\":1,\"∧\":3,\"⊥\":1},\"quantifier_depth\":2,\
+            \"atoms\":4,\"size\":11}"
+        );
+        let metrics = WffMetrics::new(&parse_logical_expression_string("A").unwrap());
+        assert_eq!(
+            metrics.to_json(),
+            "{\"connectives\":{},\"quantifier_depth\":0,\"atoms\":1,\"size\":1}"
+        );
+    }
+}
@@ -1,4 +1,5 @@
-use std::collections::HashSet;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::iter;
 use std::iter::from_fn;
 
@@ -11,22 +12,39 @@ use crate::data::*;
 ///
 /// For a specification of the grammar that is used for parsing, see the documentation of the
 /// functions [parse_proof_line] and [parse_logical_expr].
+///
+/// If there is no proof in the string (see [is_empty_proof]), then the vector is empty.
 pub fn parse_fitch_proof(proof: &str) -> Result<Vec<ProofLine>, String> {
-    let mut last_line_num = 0;
-    proof
-        .lines()
-        .filter(|s| !s.is_empty())
-        .map(|x| match lex(x) {
-            Ok(toks) => match parse_proof_line(&toks) {
-                Ok(line) => {
-                    last_line_num = line.line_num.unwrap_or(last_line_num);
-                    Ok(line)
-                }
-                Err(err) => Err(format!("parser failure near line {}: {}", last_line_num + 1, err)),
-            },
-            Err(err) => Err(format!("lexer failure near line {}: {}", last_line_num + 1, err)),
-        })
-        .collect()
+    parse_fitch_proof_lines(proof).collect()
+}
+
+/// Same as [parse_fitch_proof], but if the proof cannot be parsed, then a [FatalError] is returned,
+/// which says where in the text of the proof the parser failed (see [FatalError::ParseError]).
+pub fn parse_fitch_proof_with_spans(proof: &str) -> Result<Vec<ProofLine>, FatalError> {
+    parse_fitch_proof_lines_with(proof, parse_logical_expr).collect()
+}
+
+/// Same as [parse_fitch_proof], but the proof is parsed lazily, one line at a time: the returned
+/// iterator gives the [ProofLine]s (or an error message) in order. This way, a very long proof can
+/// be parsed without ever having all its tokens in memory at the same time; only the tokens of
+/// the current line are kept, in a buffer that is reused for every line.
+pub fn parse_fitch_proof_lines(
+    proof: &str,
+) -> impl Iterator<Item = Result<ProofLine, String>> + '_ {
+    parse_fitch_proof_lines_with(proof, parse_logical_expr)
+        .map(|line| line.map_err(|err| err.to_string()))
+}
+
+/// Same as [parse_fitch_proof_with_spans], but for checking only the structure of a proof (see
+/// [crate::checker::check_proof_skeleton]): the sentences are treated as opaque, so a sentence
+/// that cannot be parsed is not an error. Such a sentence is replaced by the atomic sentence `…`.
+///
+/// Note that the sentences still have to consist of valid tokens.
+pub fn parse_fitch_proof_skeleton(proof: &str) -> Result<Vec<ProofLine>, FatalError> {
+    parse_fitch_proof_lines_with(proof, |toks| {
+        Ok(parse_logical_expr(toks).unwrap_or(Wff::Atomic("…".to_string())))
+    })
+    .collect()
 }
 
 /// This function parses the list of strings that should be seen as a variable. This list should
@@ -56,7 +74,7 @@ pub fn parse_allowed_variable_names(allowed_var_names: &str) -> Result<HashSet<S
         let Some(Token::Name(var_name)) = rem_toks.first() else {
             return Err(err_str);
         };
-        if !var_name.chars().next().unwrap().is_ascii_lowercase() {
+        if !var_name.chars().next().unwrap().is_lowercase() {
             return Err("the list of allowed variable names could not be parsed: a variable name must start with a lowercase letter".to_string());
         }
         if allowed_variable_names.contains(var_name) {
@@ -74,6 +92,322 @@ pub fn parse_allowed_variable_names(allowed_var_names: &str) -> Result<HashSet<S
     Ok(allowed_variable_names)
 }
 
+/// This function parses a list of [CheckOptions]. This list should be a string slice like this:
+/// "symbols=ascii", i.e. a comma-separated list of `option=value` pairs. Options that are not
+/// mentioned keep their default value, so the empty string gives the default options.
+///
+/// The known options are:
+/// - `symbols`: one of `auto`, `unicode`, `ascii` or `traditional` (see [SymbolSet]).
+/// - `derived_rules`: `on` or `off` (see [CheckOptions::allow_derived_rules]).
+/// - `subproof_shorthand`: `on` or `off` (see [CheckOptions::allow_subproof_shorthand]).
+/// - `bicond_any_order`: `on` or `off` (see [CheckOptions::allow_any_bicond_order]).
+/// - `equality_chains`: `on` or `off` (see [CheckOptions::allow_equality_chains]).
+/// - `conclusion`: `last` or `anywhere` (see [ConclusionPlacement]).
+/// - `drafting`: `on` or `off` (see [CheckOptions::drafting]).
+/// - `propositional_only`: `on` or `off` (see [CheckOptions::propositional_only]).
+/// - `max_errors`: a number or `all` (see [CheckOptions::max_errors]).
+/// - `feedback`: `full` or `lines` (see [FeedbackDetail]).
+/// - `negation`: `primitive` or `bottom` (see [NegationConvention]).
+/// - `error_order`: `line`, `severity` or `code` (see [ErrorOrder]).
+/// - `reiteration`: `direct` or `explicit` (see [ReiterationPolicy]).
+/// - `propositional_quantifiers`: `on` or `off` (see [CheckOptions::propositional_quantifiers]).
+///
+/// If an option or value is unknown, an error message is returned.
+pub fn parse_check_options(options: &str) -> Result<CheckOptions, String> {
+    let mut check_options = CheckOptions::default();
+    for option in options.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let Some((key, value)) = option.split_once('=') else {
+            return Err(format!("the option '{option}' should be of the form 'option=value'"));
+        };
+        match (key.trim(), value.trim()) {
+            ("symbols", "auto") => check_options.symbols = SymbolSet::Auto,
+            ("symbols", "unicode") => check_options.symbols = SymbolSet::Unicode,
+            ("symbols", "ascii") => check_options.symbols = SymbolSet::Ascii,
+            ("symbols", "traditional") => check_options.symbols = SymbolSet::Traditional,
+            ("symbols", value) => return Err(format!("unknown symbol set: {value}")),
+            ("derived_rules", "on") => check_options.allow_derived_rules = true,
+            ("derived_rules", "off") => check_options.allow_derived_rules = false,
+            ("subproof_shorthand", "on") => check_options.allow_subproof_shorthand = true,
+            ("subproof_shorthand", "off") => check_options.allow_subproof_shorthand = false,
+            ("bicond_any_order", "on") => check_options.allow_any_bicond_order = true,
+            ("bicond_any_order", "off") => check_options.allow_any_bicond_order = false,
+            ("equality_chains", "on") => check_options.allow_equality_chains = true,
+            ("equality_chains", "off") => check_options.allow_equality_chains = false,
+            ("conclusion", "last") => {
+                check_options.conclusion_placement = ConclusionPlacement::LastLine
+            }
+            ("conclusion", "anywhere") => {
+                check_options.conclusion_placement = ConclusionPlacement::AnyTopLevelLine
+            }
+            ("conclusion", value) => {
+                return Err(format!("conclusion should be 'last' or 'anywhere', not '{value}'"))
+            }
+            ("drafting", "on") => check_options.drafting = true,
+            ("drafting", "off") => check_options.drafting = false,
+            ("propositional_only", "on") => check_options.propositional_only = true,
+            ("propositional_only", "off") => check_options.propositional_only = false,
+            ("max_errors", "all") => check_options.max_errors = None,
+            ("max_errors", value) => match value.parse::<usize>() {
+                Ok(n) => check_options.max_errors = Some(n),
+                Err(_) => {
+                    return Err(format!("max_errors should be a number or 'all', not '{value}'"))
+                }
+            },
+            ("feedback", "full") => check_options.feedback = FeedbackDetail::Full,
+            ("feedback", "lines") => check_options.feedback = FeedbackDetail::LineNumbers,
+            ("feedback", value) => {
+                return Err(format!("feedback should be 'full' or 'lines', not '{value}'"))
+            }
+            ("negation", "primitive") => check_options.negation = NegationConvention::Primitive,
+            ("negation", "bottom") => check_options.negation = NegationConvention::DefinedByBottom,
+            ("negation", value) => {
+                return Err(format!("negation should be 'primitive' or 'bottom', not '{value}'"))
+            }
+            ("error_order", "line") => check_options.error_order = ErrorOrder::Line,
+            ("error_order", "severity") => check_options.error_order = ErrorOrder::Severity,
+            ("error_order", "code") => check_options.error_order = ErrorOrder::Code,
+            ("error_order", value) => {
+                return Err(format!(
+                    "error_order should be 'line', 'severity' or 'code', not '{value}'"
+                ))
+            }
+            ("reiteration", "direct") => check_options.reiteration = ReiterationPolicy::Direct,
+            ("reiteration", "explicit") => check_options.reiteration = ReiterationPolicy::Explicit,
+            ("reiteration", value) => {
+                return Err(format!("reiteration should be 'direct' or 'explicit', not '{value}'"))
+            }
+            ("propositional_quantifiers", "on") => check_options.propositional_quantifiers = true,
+            ("propositional_quantifiers", "off") => check_options.propositional_quantifiers = false,
+            (
+                "derived_rules"
+                | "subproof_shorthand"
+                | "bicond_any_order"
+                | "equality_chains"
+                | "drafting"
+                | "propositional_only"
+                | "propositional_quantifiers",
+                value,
+            ) => return Err(format!("{key} should be 'on' or 'off', not '{value}'")),
+            (key, _) => return Err(format!("unknown option: {key}")),
+        }
+    }
+    Ok(check_options)
+}
+
+/// This function parses a [HintPolicy]. The policy should be a string slice like this:
+/// "family=1, rule=2, lines=3", i.e. a comma-separated list of `kind=attempts` pairs, where
+/// `family`, `rule` and `lines` set [HintPolicy::rule_family_after], [HintPolicy::rule_after] and
+/// [HintPolicy::citations_after]. Kinds that are not given keep their [Default] value.
+///
+/// If a kind is unknown or the number of attempts cannot be parsed, an error message is returned.
+#[cfg(feature = "tutoring")]
+pub fn parse_hint_policy(policy: &str) -> Result<HintPolicy, String> {
+    let mut hint_policy = HintPolicy::default();
+    for pair in policy.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let Some((kind, attempts)) = pair.split_once('=') else {
+            return Err(format!(
+                "'{pair}' in the hint policy should be of the form 'kind=attempts'"
+            ));
+        };
+        let Ok(attempts) = attempts.trim().parse::<usize>() else {
+            return Err(format!("'{}' in the hint policy is not a number", attempts.trim()));
+        };
+        match kind.trim() {
+            "family" => hint_policy.rule_family_after = attempts,
+            "rule" => hint_policy.rule_after = attempts,
+            "lines" => hint_policy.citations_after = attempts,
+            kind => {
+                return Err(format!(
+                    "unknown kind of hint: {kind} (it should be 'family', 'rule' or 'lines')"
+                ))
+            }
+        }
+    }
+    Ok(hint_policy)
+}
+
+/// This function parses the options of a two-column export (see [TwoColumnOptions]). The options
+/// should be a string slice like this: "format=html, numbering=parens, formula_width=8cm", i.e. a
+/// comma-separated list of `option=value` pairs, where
+///
+/// * `format` is `latex` or `html`,
+/// * `numbering` is `plain` (`1.`), `parens` (`(1)`) or `none`,
+/// * `formula_width` and `justification_width` are lengths like `8cm`, `40%` or `2.5in`.
+///
+/// Options that are not given get their [Default] value. If an option is unknown or has a wrong
+/// value, then an error message is returned.
+#[cfg(feature = "export")]
+pub fn parse_two_column_options(options: &str) -> Result<TwoColumnOptions, String> {
+    let mut two_column_options = TwoColumnOptions::default();
+    for option in options.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let Some((key, value)) = option.split_once('=') else {
+            return Err(format!("the option '{option}' should be of the form 'option=value'"));
+        };
+        let is_length = |value: &str| {
+            value.starts_with(|c: char| c.is_ascii_digit())
+                && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '%')
+        };
+        match (key.trim(), value.trim()) {
+            ("format", "latex") => two_column_options.format = TwoColumnFormat::Latex,
+            ("format", "html") => two_column_options.format = TwoColumnFormat::Html,
+            ("format", value) => {
+                return Err(format!("format should be 'latex' or 'html', not '{value}'"))
+            }
+            ("numbering", "plain") => two_column_options.numbering = Numbering::Plain,
+            ("numbering", "parens") => two_column_options.numbering = Numbering::Parenthesized,
+            ("numbering", "none") => two_column_options.numbering = Numbering::None,
+            ("numbering", value) => {
+                return Err(format!(
+                    "numbering should be 'plain', 'parens' or 'none', not '{value}'"
+                ))
+            }
+            ("formula_width", value) if is_length(value) => {
+                two_column_options.formula_width = Some(value.to_string())
+            }
+            ("justification_width", value) if is_length(value) => {
+                two_column_options.justification_width = Some(value.to_string())
+            }
+            ("formula_width" | "justification_width", value) => {
+                return Err(format!("{key} should be a length like '8cm', not '{value}'"))
+            }
+            (key, _) => return Err(format!("unknown option: {key}")),
+        }
+    }
+    Ok(two_column_options)
+}
+
+/// This function parses the options of [crate::fix_line_numbers_with_options]. The options should
+/// be a string slice like "labels=letters", where `labels` is `keep`, `numbers` or `letters` (see
+/// [LineLabels]). If no option is given, the labels are kept. If an option is unknown or has a
+/// wrong value, then an error message is returned.
+pub fn parse_line_labels(options: &str) -> Result<LineLabels, String> {
+    let mut line_labels = LineLabels::default();
+    for option in options.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let Some((key, value)) = option.split_once('=') else {
+            return Err(format!("the option '{option}' should be of the form 'option=value'"));
+        };
+        match (key.trim(), value.trim()) {
+            ("labels", "keep") => line_labels = LineLabels::Keep,
+            ("labels", "numbers") => line_labels = LineLabels::Numbers,
+            ("labels", "letters") => line_labels = LineLabels::Letters,
+            ("labels", value) => {
+                return Err(format!(
+                    "labels should be 'keep', 'numbers' or 'letters', not '{value}'"
+                ))
+            }
+            (key, _) => return Err(format!("unknown option: {key}")),
+        }
+    }
+    Ok(line_labels)
+}
+
+/// This function parses the policy of [crate::formulas_match]. The policy should be a string slice
+/// like "match=alpha", where `match` is `syntactic`, `alpha` or `equivalent` (see [MatchPolicy]).
+/// If no option is given, the sentences should match syntactically. If an option is unknown or has
+/// a wrong value, then an error message is returned.
+pub fn parse_match_policy(policy: &str) -> Result<MatchPolicy, String> {
+    let mut match_policy = MatchPolicy::default();
+    for option in policy.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let Some((key, value)) = option.split_once('=') else {
+            return Err(format!("the option '{option}' should be of the form 'option=value'"));
+        };
+        match (key.trim(), value.trim()) {
+            ("match", "syntactic") => match_policy = MatchPolicy::Syntactic,
+            ("match", "alpha") => match_policy = MatchPolicy::Alpha,
+            ("match", "equivalent") => match_policy = MatchPolicy::Equivalent,
+            ("match", value) => {
+                return Err(format!(
+                    "match should be 'syntactic', 'alpha' or 'equivalent', not '{value}'"
+                ))
+            }
+            (key, _) => return Err(format!("unknown option: {key}")),
+        }
+    }
+    Ok(match_policy)
+}
+
+/// This function parses a renaming of the vocabulary of a proof (see
+/// [crate::rename::rename_vocabulary]). The renaming should be a string slice like this:
+/// "P=Q, a=b", i.e. a comma-separated list of `old=new` pairs.
+///
+/// A predicate (or propositional atom) can only be renamed to a name that starts with an
+/// uppercase letter, and a constant (or function symbol) only to a name that starts with a
+/// lowercase letter. If a name is renamed twice, or if the renaming cannot be parsed, then an
+/// error message is returned.
+pub fn parse_renaming(renaming: &str) -> Result<HashMap<String, String>, String> {
+    let mut res: HashMap<String, String> = HashMap::new();
+    for pair in renaming.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let Some((old, new)) = pair.split_once('=') else {
+            return Err(format!("the renaming '{pair}' should be of the form 'old=new'"));
+        };
+        let name = |s: &str| match lex(s.trim()).as_deref() {
+            Ok([Token::Name(name)]) => Ok(name.clone()),
+            _ => Err(format!("'{}' in the renaming '{pair}' is not a name", s.trim())),
+        };
+        let (old, new) = (name(old)?, name(new)?);
+        // unwraps work, since names are never empty
+        if old.chars().next().unwrap().is_uppercase() != new.chars().next().unwrap().is_uppercase()
+        {
+            return Err(format!(
+                "{old} cannot be renamed to {new}: predicates should start with an uppercase \
+                letter, and constants and functions with a lowercase letter"
+            ));
+        }
+        if res.insert(old.clone(), new).is_some() {
+            return Err(format!("{old} is renamed more than once"));
+        }
+    }
+    Ok(res)
+}
+
+/// This function parses a proof template: one sentence per line, first the premises and then the
+/// conclusion. Lines that start with `#` are not sentences, but comments, except for a line like
+/// `# minimal proof length: 7`, which gives the number of lines of the shortest known proof, a
+/// line like `# minimal nesting depth: 1`, which gives how deep the subproofs of that proof are
+/// nested, and the line `# propositional only`, which says that the proof may only use
+/// propositional logic (see [CheckOptions::propositional_only]).
+///
+/// If a sentence, the minimal proof length or the minimal nesting depth cannot be parsed, an error
+/// message is returned.
+pub fn parse_template(template: &[String]) -> Result<ProofTemplate, String> {
+    let mut res = ProofTemplate {
+        sentences: vec![],
+        minimal_length: None,
+        minimal_depth: None,
+        propositional_only: false,
+    };
+    for line in template {
+        if template_comment(line) == Some("propositional only") {
+            res.propositional_only = true;
+        } else if let Some(comment) = template_comment(line) {
+            let key_value = comment.split_once(':').map(|(key, value)| (key.trim(), value));
+            if let Some(("minimal proof length", n)) = key_value {
+                let Ok(n) = n.trim().parse::<usize>() else {
+                    return Err(format!("'{}' is not a valid minimal proof length", n.trim()));
+                };
+                res.minimal_length = Some(n);
+            }
+            if let Some(("minimal nesting depth", n)) = key_value {
+                let Ok(n) = n.trim().parse::<usize>() else {
+                    return Err(format!("'{}' is not a valid minimal nesting depth", n.trim()));
+                };
+                res.minimal_depth = Some(n);
+            }
+        } else {
+            match parse_logical_expression_string(line) {
+                Some(wff) => res.sentences.push(wff),
+                None => return Err(format!("the sentence '{line}' could not be parsed")),
+            }
+        }
+    }
+    Ok(res)
+}
+
+/// If a line of a proof template is a comment (see [parse_template]), this function returns the
+/// comment without the `#` and the surrounding whitespace.
+pub fn template_comment(line: &str) -> Option<&str> {
+    line.trim().strip_prefix('#').map(str::trim)
+}
 
 /// This function parses a *logical expression* from a String.
 ///
@@ -99,6 +433,8 @@ pub fn parse_allowed_variable_names(allowed_var_names: &str) -> Result<HashSet<S
 ///          | ( <E1> )
 ///          | forall <VariableOrConstantName> <E3>
 ///          | exists <VariableOrConstantName> <E3>
+///          | forall <AtomicPropositionName> <E3>
+///          | exists <AtomicPropositionName> <E3>
 ///          | not <E3>
 ///          | bottom
 ///
@@ -113,21 +449,413 @@ pub fn parse_allowed_variable_names(allowed_var_names: &str) -> Result<HashSet<S
 /// <PredicateName> : some string starting with an UPPERCASE letter
 /// <AtomicPropositionName> : some string starting with an UPPERCASE letter
 /// ```
+///
+/// A name consists of letters only, which may be letters of any alphabet (so `Tall(x)`, `Φ` and
+/// `Σοφός(σωκράτης)` are fine), but its first letter should be uppercase or lowercase. So a name
+/// cannot start with a letter of a script without case, like the Chinese `中` (but `P中` is fine).
+///
+/// Besides the Unicode connectives, the connectives may be written in ASCII (`&` or `/\` for ∧,
+/// `\/` for ∨, `->` for →, `<->` for ↔, `~` for ¬ and `_|_` for ⊥), or as the traditional `⊃` for
+/// → and `≡` for ↔. The error messages use the same symbols as the proof (see [SymbolSet]).
+///
+/// A quantifier over an `<AtomicPropositionName>` (like `∀P (P → P)`) quantifies over sentences.
+/// Such sentences are only correct in a proof with [CheckOptions::propositional_quantifiers].
 pub fn parse_logical_expression_string(expr: &str) -> Option<Wff> {
     if let Ok(toks) = lex(expr) {
-        return match parse_logical_expr(&toks) {
-            Ok(expr) => Some(expr),
+        return parse_logical_expr(&toks).ok();
+    }
+    None
+}
+
+/// An error message about a part of a string, together with the byte range of that part.
+pub type SpannedError = (String, (usize, usize));
+
+/// Parses a single sentence like [parse_logical_expression_string], but if the sentence is not
+/// well-formed, this function tells where and why: every error message comes with the byte range
+/// in `expr` that it is about. The sentence is also not well-formed if it has a free variable, or
+/// a quantifier over a name that is not a variable (see [parse_allowed_variable_names]).
+///
+/// The errors are looked for in stages: symbols that are unknown or cannot occur in a sentence,
+/// then parentheses that are not balanced and quantifiers without a variable, then the first place
+/// where the sentence cannot be parsed, and finally the variables. Only the errors of the first
+/// stage that finds any are returned.
+pub fn diagnose_logical_expression(
+    expr: &str,
+    allowed_variable_names: &HashSet<String>,
+) -> Result<Wff, Vec<SpannedError>> {
+    let mut toks: Vec<Token> = vec![];
+    let mut spans: Vec<(usize, usize)> = vec![];
+    if let Err((err, span)) = lex_with_spans_into(expr, &mut toks, Some(&mut spans)) {
+        let err = match err.starts_with("invalid character") {
+            true => format!(
+                "the symbol {} is not known. The connectives are ¬, ∧, ∨, →, ↔ and ⊥ (or ~, &, \\/, \
+                ->, <-> and _|_), and the quantifiers are ∀ and ∃.",
+                &expr[span.0..span.1]
+            ),
+            false => err,
+        };
+        return Err(vec![(err, span)]);
+    }
+    if toks.is_empty() {
+        return Err(vec![("the sentence is empty".to_string(), (0, expr.len()))]);
+    }
+    let text = |i: usize| &expr[spans[i].0..spans[i].1];
+    let mut errors: Vec<SpannedError> = toks
+        .iter()
+        .enumerate()
+        .filter(|(_, tok)| {
+            matches!(
+                tok,
+                Token::Number(_)
+                    | Token::ConseqVertBar(_)
+                    | Token::Colon
+                    | Token::Dash
+                    | Token::LSqBracket
+                    | Token::RSqBracket
+                    | Token::Turnstile
+            )
+        })
+        .map(|(i, _)| (format!("the symbol {} cannot be used in a sentence", text(i)), spans[i]))
+        .collect();
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut open_parentheses: Vec<usize> = vec![];
+    for (i, tok) in toks.iter().enumerate() {
+        match tok {
+            Token::LPar => open_parentheses.push(i),
+            Token::RPar if open_parentheses.pop().is_none() => errors.push((
+                "this closing parenthesis has no matching opening parenthesis".to_string(),
+                spans[i],
+            )),
+            Token::Forall | Token::Exists => match toks.get(i + 1) {
+                Some(Token::Name(name)) if name.starts_with(|c: char| c.is_lowercase()) => {
+                    if i + 2 == toks.len() {
+                        errors.push((
+                            format!(
+                                "the quantifier {}{name} should be followed by the sentence \
+                                that it quantifies",
+                                text(i)
+                            ),
+                            (spans[i].0, spans[i + 1].1),
+                        ));
+                    }
+                }
+                _ => errors.push((
+                    format!(
+                        "the quantifier {} should be followed by the variable that it binds, \
+                        like {}x",
+                        text(i),
+                        text(i)
+                    ),
+                    spans[i],
+                )),
+            },
+            _ => {}
+        }
+    }
+    errors.extend(
+        open_parentheses
+            .into_iter()
+            .map(|i| ("this opening parenthesis is never closed".to_string(), spans[i])),
+    );
+    if !errors.is_empty() {
+        errors.sort_by_key(|(_, span)| *span);
+        return Err(errors);
+    }
+
+    let wff = match parse_e1(&toks) {
+        Some((wff, [])) => wff,
+        Some((_, rem_toks)) => {
+            let i = toks.len() - rem_toks.len();
+            let err = match rem_toks[0] {
+                Token::And | Token::Or | Token::Implies | Token::Bicond => format!(
+                    "the sentence cannot be read from the connective {} on: different \
+                    connectives need parentheses to show which one comes first, like \
+                    (A ∧ B) → C instead of A ∧ B → C",
+                    text(i)
+                ),
+                _ => format!("the sentence cannot be read from {} on", text(i)),
+            };
+            return Err(vec![(err, spans[i])]);
+        }
+        None => return Err(vec![("the sentence is not well-formed".to_string(), (0, expr.len()))]),
+    };
+
+    // the tokens in the scope of every quantifier: the variable, and the range of tokens after it
+    let scopes: Vec<(&str, usize, usize)> = toks
+        .iter()
+        .enumerate()
+        .filter(|(_, tok)| matches!(tok, Token::Forall | Token::Exists))
+        .filter_map(|(i, _)| match (&toks[i + 1], parse_e3(&toks[i + 2..])) {
+            (Token::Name(var), Some((_, rem_toks))) => {
+                Some((var.as_str(), i + 2, toks.len() - rem_toks.len()))
+            }
             _ => None,
+        })
+        .collect();
+    for (i, tok) in toks.iter().enumerate() {
+        let Token::Name(name) = tok else {
+            continue;
         };
+        let is_bound_variable = i > 0 && matches!(toks[i - 1], Token::Forall | Token::Exists);
+        if is_bound_variable && !allowed_variable_names.contains(name) {
+            errors.push((
+                format!(
+                    "the quantifier {}{name} quantifies over {name}, but {name} is not a \
+                    variable. You should quantify over a variable, not over a constant.",
+                    text(i - 1)
+                ),
+                (spans[i - 1].0, spans[i].1),
+            ));
+        }
+        let is_bound =
+            scopes.iter().any(|(var, begin, end)| var == name && (*begin..*end).contains(&i));
+        if !is_bound_variable
+            && !is_bound
+            && allowed_variable_names.contains(name)
+            && toks.get(i + 1) != Some(&Token::LPar)
+        {
+            errors.push((
+                format!(
+                    "the variable {name} is not bound by a quantifier (like ∀{name} or ∃{name}), \
+                    so this is not a sentence"
+                ),
+                spans[i],
+            ));
+        }
+    }
+    match errors.is_empty() {
+        true => Ok(wff),
+        false => Err(errors),
     }
-    None
+}
+
+/// This function parses a single inference (a *sequent*) from a string slice, for example
+/// `A→B, A ⊢ B by →Elim`.
+///
+/// The premises are separated by commas, and the rule is written without any citations: it is
+/// always applied to the premises, in the order in which they are written. Only rules that do not
+/// need a subproof can be used this way.
+///
+/// The grammar:
+///
+/// ```notrust
+/// <Sequent> ::= [ <E1> {, <E1>} ] ⊢ <E1> by <Rule>
+///
+/// <Rule> is any rule name, as used in <Justification> (see [parse_proof_line]), for example
+/// `∧Intro` or `Reit`.
+/// ```
+///
+/// If it succeeds, a [Sequent] is returned. Otherwise, a nice error message is returned.
+pub fn parse_sequent(sequent: &str) -> Result<Sequent, String> {
+    let toks = lex(sequent).map_err(|err| format!("lexer failure: {err}"))?;
+    let Some(turnstile_index) = toks.iter().position(|t| t == &Token::Turnstile) else {
+        return Err("an inference should be of the form \'premises ⊢ conclusion by rule\', \
+            but no ⊢ was found"
+            .to_string());
+    };
+    let Some(by_index) = toks.iter().rposition(|t| t == &Token::Name("by".to_string())) else {
+        return Err("an inference should be of the form \'premises ⊢ conclusion by rule\', \
+            but the word \'by\' was not found"
+            .to_string());
+    };
+    if by_index < turnstile_index {
+        return Err("the rule (after \'by\') should come after the conclusion".to_string());
+    }
+
+    let premises = if turnstile_index == 0 {
+        vec![]
+    } else {
+        split_at_top_level_commas(&toks[..turnstile_index])
+            .into_iter()
+            .enumerate()
+            .map(|(i, premise_toks)| {
+                parse_logical_expr(premise_toks)
+                    .map_err(|err| format!("failure when parsing premise {}: {}", i + 1, err))
+            })
+            .collect::<Result<Vec<Wff>, String>>()?
+    };
+    let conclusion = parse_logical_expr(&toks[turnstile_index + 1..by_index])
+        .map_err(|err| format!("failure when parsing the conclusion: {err}"))?;
+    let rule = parse_sequent_rule(&toks[by_index + 1..], premises.len())?;
+
+    Ok(Sequent {
+        premises,
+        conclusion,
+        rule,
+    })
+}
+
+/// This function parses the directives in a proof (see [ProofDirectives]). A directive is a line
+/// that starts with `%`, and has one of these forms:
+///
+/// - `%expect-error: <code>` or `%expect-error: <code> at line <num>`, where `<code>` is an
+///   error code like `E_SCOPE_VIOLATION` (see [crate::error_codes]);
+/// - `%allow: <rule> {, <rule>}`, where every `<rule>` is `QE`, `Contra`, `Impl` or `=Chain`.
+///
+/// All other lines are ignored. If a directive cannot be parsed, an error message is returned.
+pub fn parse_directives(proof: &str) -> Result<ProofDirectives, String> {
+    let mut directives = ProofDirectives::default();
+    for line in proof.lines().filter_map(|line| line.trim().strip_prefix('%')) {
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(format!("the directive '%{line}' should be of the form '%name: value'"));
+        };
+        match name.trim() {
+            "expect-error" => match value.split_whitespace().collect::<Vec<&str>>()[..] {
+                [code] | [code, "at", "line", _] if !crate::error_codes::is_error_code(code) => {
+                    return Err(format!("unknown error code in the directive '%{line}': {code}"))
+                }
+                [code] => directives.expected_errors.push(ExpectedError {
+                    code: code.to_string(),
+                    line: None,
+                }),
+                [code, "at", "line", n] if n.parse::<usize>().is_ok() => {
+                    directives.expected_errors.push(ExpectedError {
+                        code: code.to_string(),
+                        line: n.parse().ok(),
+                    })
+                }
+                _ => {
+                    return Err(format!(
+                        "the directive '%{line}' should be of the form \
+                        '%expect-error: <code>' or '%expect-error: <code> at line <number>'"
+                    ))
+                }
+            },
+            "allow" => {
+                for rule in value.split(',').map(str::trim) {
+                    if !["QE", "Contra", "Impl", "=Chain"].contains(&rule) {
+                        return Err(format!(
+                            "the rule '{rule}' in the directive '%{line}' cannot be allowed; \
+                            only QE, Contra, Impl and =Chain can"
+                        ));
+                    }
+                    directives.allowed_rules.push(rule.to_string());
+                }
+            }
+            name => return Err(format!("unknown directive: %{name}")),
+        }
+    }
+    Ok(directives)
+}
+
+/// This function parses a student's answer to a "find the mistake" exercise (see
+/// [crate::check_error_identification]): one mistake per line, given by the line number and the
+/// error code (see [crate::error_codes]), like `4 E_SCOPE_VIOLATION` or
+/// `line 4: E_SCOPE_VIOLATION`. The error codes may be written in lowercase, and empty lines are
+/// ignored. The mistakes are returned as pairs of a line number and an error code.
+///
+/// If a line of the answer cannot be parsed, an error message is returned.
+pub fn parse_error_identification(answer: &str) -> Result<Vec<(usize, String)>, String> {
+    let mut res = vec![];
+    for line in answer.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let without_prefix =
+            line.strip_prefix("line").or(line.strip_prefix("Line")).unwrap_or(line);
+        let words: Vec<&str> = without_prefix
+            .split(|c: char| c.is_whitespace() || c == ':' || c == ',')
+            .filter(|word| !word.is_empty())
+            .collect();
+        let [n, code] = words[..] else {
+            return Err(format!(
+                "'{line}' should be a line number followed by an error code, like \
+                '4 E_SCOPE_VIOLATION'"
+            ));
+        };
+        let Ok(n) = n.parse::<usize>() else {
+            return Err(format!("'{n}' in '{line}' is not a line number"));
+        };
+        let code = code.to_uppercase();
+        if !crate::error_codes::is_error_code(&code) {
+            return Err(format!("'{code}' in '{line}' is not an error code"));
+        }
+        res.push((n, code));
+    }
+    Ok(res)
+}
+
+/// Returns whether a line of a proof is a directive (see [parse_directives]), which is ignored
+/// when the proof is parsed.
+pub fn is_directive(line: &str) -> bool {
+    line.trim_start().starts_with('%')
+}
+
+/// Returns whether there is no proof in a string: the string is empty, or it only contains
+/// whitespace and directives (see [is_directive]).
+pub fn is_empty_proof(proof: &str) -> bool {
+    proof.lines().all(|line| line.trim().is_empty() || is_directive(line))
 }
 
 /* ----------------- PRIVATE -------------------*/
 
+/// Parses a proof lazily, like [parse_fitch_proof_lines], where the sentences are parsed with
+/// `parse_wff`. A line that cannot be parsed gives a [FatalError::ParseError] with the span of the
+/// character that could not be lexed, or else of the whole line, or a [FatalError::LimitExceeded]
+/// if a number in it is too big.
+fn parse_fitch_proof_lines_with(
+    proof: &str,
+    parse_wff: fn(&[Token]) -> Result<Wff, String>,
+) -> impl Iterator<Item = Result<ProofLine, FatalError>> + '_ {
+    let mut last_line_num = 0;
+    let mut toks: Vec<Token> = Vec::new();
+    // the line numbers of the premises, for `Prem` (see `expand_premise_block`)
+    let mut premises: Vec<usize> = vec![];
+    let mut after_premises = false;
+    // if there is no proof at all, then lines with only whitespace are not an error either
+    let no_proof = is_empty_proof(proof);
+    let lines = proof
+        .lines()
+        .enumerate()
+        .filter(move |(_, s)| !no_proof && !s.is_empty() && !is_directive(s));
+    lines.map(move |(i, x)| {
+        // the span of the bytes `start..end` of this line
+        let span = |start: usize, end: usize| TextSpan {
+            line: i + 1,
+            start_column: x[..start].chars().count() + 1,
+            end_column: x[..end].chars().count() + 1,
+        };
+        match lex_with_spans_into(x, &mut toks, None) {
+            Ok(()) => match expand_premise_block(&mut toks, &premises).and_then(|cites_premises| {
+                Ok((parse_proof_line(&toks, parse_wff)?, cites_premises))
+            }) {
+                Ok((mut line, cites_premises)) => {
+                    line.cites_premises = cites_premises;
+                    last_line_num = line.line_num.unwrap_or(last_line_num);
+                    if line.is_fitch_bar_line {
+                        after_premises = true;
+                    } else if !after_premises && line.depth == 1 {
+                        premises.extend(line.line_num);
+                    }
+                    Ok(line)
+                }
+                Err(err) => {
+                    let start = x.len() - x.trim_start().len();
+                    Err(FatalError::ParseError(
+                        format!("parser failure near line {}: {}", last_line_num + 1, err),
+                        Some(span(start, x.trim_end().len())),
+                    ))
+                }
+            },
+            Err((err, _)) if err == NUMBER_TOO_BIG => Err(FatalError::LimitExceeded(format!(
+                "lexer failure near line {}: {}",
+                last_line_num + 1,
+                err
+            ))),
+            Err((err, (start, end))) => Err(FatalError::ParseError(
+                format!("lexer failure near line {}: {}", last_line_num + 1, err),
+                Some(span(start, end)),
+            )),
+        }
+    })
+}
+
+/// The error message of the lexer for a number that is too big.
+const NUMBER_TOO_BIG: &str = "there was an integer bigger than 999999999";
+
 /// This is an enum containing tokens. The lexer converts a [String] to a vector of [Token]s, which
 /// can then be used by the parser.
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 enum Token {
     Name(String),
     LPar,
@@ -148,14 +876,38 @@ enum Token {
     Dash,
     LSqBracket,
     RSqBracket,
+    Turnstile,
 }
 
 /// Generate a list of [Token]s from a [String]. If the lexer fails, a nice error message is returned.
 fn lex(input: &str) -> Result<Vec<Token>, String> {
     let mut toks: Vec<Token> = Vec::new();
-    let mut input_iter = input.chars().peekable();
+    lex_into(input, &mut toks)?;
+    Ok(toks)
+}
+
+/// Same as [lex], but the [Token]s are put in `toks` (which is cleared first), so that the same
+/// buffer can be reused for many inputs.
+fn lex_into(input: &str, toks: &mut Vec<Token>) -> Result<(), String> {
+    lex_with_spans_into(input, toks, None).map_err(|(err, _)| err)
+}
 
-    while let Some(ch) = input_iter.next() {
+/// Same as [lex_into], but if `spans` is given, then the span (the byte range in `input`) of every
+/// [Token] is put in it as well. If the lexer fails, the error message comes with the byte range of
+/// the character that could not be lexed.
+fn lex_with_spans_into(
+    input: &str,
+    toks: &mut Vec<Token>,
+    mut spans: Option<&mut Vec<(usize, usize)>>,
+) -> Result<(), (String, (usize, usize))> {
+    toks.clear();
+    if let Some(spans) = spans.as_deref_mut() {
+        spans.clear();
+    }
+    let mut input_iter = input.char_indices().peekable();
+
+    while let Some((start, ch)) = input_iter.next() {
+        let num_toks = toks.len();
         match ch {
             ' ' => {} // ignore spaces
             '(' => toks.push(Token::LPar),
@@ -169,49 +921,100 @@ fn lex(input: &str) -> Result<Vec<Token>, String> {
             '\u{00AC}' => toks.push(Token::Not),
             ',' => toks.push(Token::Comma),
             '=' => toks.push(Token::Equals),
-            'a'..='z' | 'A'..='Z' => {
+            ch if is_name_start(ch) => {
                 let name = iter::once(ch)
-                    .chain(from_fn(|| input_iter.by_ref().next_if(|c| c.is_ascii_alphabetic())))
+                    .chain(from_fn(|| {
+                        input_iter.by_ref().next_if(|(_, c)| is_name_char(*c)).map(|(_, c)| c)
+                    }))
                     .collect::<String>();
                 toks.push(Token::Name(name));
             }
             '1'..='9' => {
-                let num: Result<usize, _> = iter::once(ch)
-                    .chain(from_fn(|| input_iter.by_ref().next_if(|c| c.is_ascii_digit())))
-                    .collect::<String>()
-                    .parse();
-                let err = "there was an integer bigger than 999999999".to_string();
-                if let Ok(n) = num {
-                    if n > 999999999 {
-                        return Err(err);
-                    }
-                    toks.push(Token::Number(n));
-                } else {
-                    return Err(err);
+                // unwraps work, since these are all digits
+                let mut n = ch.to_digit(10).unwrap() as usize;
+                while let Some((_, digit)) = input_iter.next_if(|(_, c)| c.is_ascii_digit()) {
+                    n = n.saturating_mul(10).saturating_add(digit.to_digit(10).unwrap() as usize);
+                }
+                if n > 999999999 {
+                    let end = input_iter.peek().map_or(input.len(), |(i, _)| *i);
+                    return Err((NUMBER_TOO_BIG.to_string(), (start, end)));
                 }
+                toks.push(Token::Number(n));
             }
             '|' => {
-                let num: usize = iter::once(ch)
-                    .chain(from_fn(|| input_iter.by_ref().next_if(|c| c == &'|' || c == &' ')))
-                    .filter(|c| c == &'|')
-                    .collect::<String>()
-                    .len();
+                let num: usize =
+                    1 + from_fn(|| input_iter.next_if(|(_, c)| c == &'|' || c == &' '))
+                        .filter(|(_, c)| c == &'|')
+                        .count();
                 toks.push(Token::ConseqVertBar(num));
             }
             ':' => toks.push(Token::Colon),
-            '-' => toks.push(Token::Dash),
+            '-' => match input_iter.next_if(|(_, c)| c == &'>') {
+                Some(_) => toks.push(Token::Implies),
+                None => toks.push(Token::Dash),
+            },
+            // the ASCII connectives `<->`, `/\`, `\/` and `_|_`
+            '<' | '/' | '\\' | '_' => {
+                let (rest, token) = match ch {
+                    '<' => ("->", Token::Bicond),
+                    '/' => ("\\", Token::And),
+                    '\\' => ("/", Token::Or),
+                    _ => ("|_", Token::Bottom),
+                };
+                for expected in rest.chars() {
+                    if input_iter.next_if(|(_, c)| *c == expected).is_none() {
+                        return Err(invalid_character(ch, start));
+                    }
+                }
+                toks.push(token);
+            }
+            '&' => toks.push(Token::And),
+            '~' => toks.push(Token::Not),
+            '\u{2283}' => toks.push(Token::Implies), // ⊃
+            '\u{2261}' => toks.push(Token::Bicond),  // ≡
             '[' => toks.push(Token::LSqBracket),
             ']' => toks.push(Token::RSqBracket),
             '⊥' => toks.push(Token::Bottom),
-            _ => {
-                let mut err: String = "invalid character found: ".to_owned();
-                err.push(ch);
-                return Err(err);
+            '⊢' => toks.push(Token::Turnstile),
+            ch if ch.is_alphabetic() => {
+                let err = format!(
+                    "invalid character found: {ch}. A name should start with an uppercase letter \
+                    (for a predicate or a sentence) or a lowercase letter (for a term), but {ch} \
+                    is neither"
+                );
+                return Err((err, (start, start + ch.len_utf8())));
             }
+            _ => return Err(invalid_character(ch, start)),
+        }
+        if let (Some(spans), true) = (spans.as_deref_mut(), toks.len() > num_toks) {
+            let end = input_iter.peek().map_or(input.len(), |(i, _)| *i);
+            // a `|` token swallows the spaces after it
+            let end = start + input[start..end].trim_end().len();
+            spans.push((start, end));
         }
     }
 
-    Ok(toks)
+    Ok(())
+}
+
+/// Returns the error of the lexer for a character `ch` at byte `start` that cannot be lexed: the
+/// error message, with the byte range of the character.
+fn invalid_character(ch: char, start: usize) -> (String, (usize, usize)) {
+    (format!("invalid character found: {ch}"), (start, start + ch.len_utf8()))
+}
+
+/// Returns whether a name (of a predicate, atomic sentence, function, constant or variable) may
+/// start with `c`: a letter that is uppercase or lowercase, in an alphabet that has both, like
+/// `P`, `é`, `Φ` or `α`. Whether it is uppercase decides what the name is (see
+/// [parse_logical_expression_string]), so a letter without case, like `中`, cannot start a name.
+fn is_name_start(c: char) -> bool {
+    c.is_uppercase() || c.is_lowercase()
+}
+
+/// Returns whether `c` may occur in a name after its first letter: any letter, in any alphabet.
+/// So a name can be descriptive, like `Tall` or `Λόγος`.
+fn is_name_char(c: char) -> bool {
+    c.is_alphabetic()
 }
 
 /// This function parses a *logical expression* from a list of [Token]s.
@@ -333,7 +1136,7 @@ fn parse_e3(toks: &[Token]) -> Option<(Wff, &[Token])> {
             None
         }
         Token::Forall => match toks.get(1)? {
-            Token::Name(name) if name.chars().next()?.is_lowercase() => {
+            Token::Name(name) => {
                 if let Some((wff, rem_toks)) = parse_e3(toks.get(2..)?) {
                     return Some((Wff::Forall(name.to_owned(), Box::new(wff)), rem_toks));
                 }
@@ -342,7 +1145,7 @@ fn parse_e3(toks: &[Token]) -> Option<(Wff, &[Token])> {
             _ => None,
         },
         Token::Exists => match toks.get(1)? {
-            Token::Name(name) if name.chars().next()?.is_lowercase() => {
+            Token::Name(name) => {
                 if let Some((wff, rem_toks)) = parse_e3(toks.get(2..)?) {
                     return Some((Wff::Exists(name.to_owned(), Box::new(wff)), rem_toks));
                 }
@@ -411,7 +1214,8 @@ fn parse_arg_list(toks: &[Token]) -> Option<(Vec<Term>, &[Token])> {
 ///                      | '|' { '|' } - { - }                                // fitch bar
 ///                      | '|' { '|' }                                        // empty line
 ///
-/// <ConstantName> : some string starting with lowercase letter
+/// <ConstantName> : some string starting with lowercase letter (or with an UPPERCASE letter
+///                  for a new atomic sentence, with the option propositional_quantifiers=on)
 ///
 /// <E1> is a full logical expression as parsed by the function parse_logical_expression_string();
 /// the grammar for <E1> is defined in logic_expr.parser.rs.
@@ -432,12 +1236,16 @@ fn parse_arg_list(toks: &[Token]) -> Option<(Vec<Term>, &[Token])> {
 ///                      | Not Elim: <num>
 ///                      | Equals Intro
 ///                      | Equals Elim: <num>, <num>
+///                      | Equals Chain: <num> {, <num>}
 ///                      | Bottom Intro: <num>, <num>
 ///                      | Bottom Elim: <num>
 ///                      | Forall Intro: <numrange>
 ///                      | Forall Elim: <num>
 ///                      | Exists Intro: <num>
 ///                      | Exists Elim: <num>, <numrange>
+///                      | QE: <num>                                   // derived rules
+///                      | Contra: <num>
+///                      | Impl: <num>
 ///
 /// ```
 ///
@@ -448,11 +1256,15 @@ fn parse_arg_list(toks: &[Token]) -> Option<(Vec<Term>, &[Token])> {
 /// then we parse the justification first. If the line ends with =Intro, then we also parse the
 /// justification first (=Intro is the only justification without colon). For the rest, everything
 /// can just be done normally from left to right.
-
-fn parse_proof_line(toks: &[Token]) -> Result<ProofLine, String> {
+///
+/// The sentence (`<E1>`) is parsed with `parse_wff`, which is normally [parse_logical_expr].
+fn parse_proof_line(
+    toks: &[Token],
+    parse_wff: fn(&[Token]) -> Result<Wff, String>,
+) -> Result<ProofLine, String> {
     if toks.contains(&Token::Colon)
         || (toks.last() == Some(&Token::Name("Intro".to_string())) // special check for =Intro
-            && toks.get(toks.len() - 2) == Some(&Token::Equals))
+            && toks.len().checked_sub(2).and_then(|i| toks.get(i)) == Some(&Token::Equals))
     {
         // we know that in this case, <FitchProofLine> ::= <num> '|' { '|' } <E1> <Justification>
         // since only a Justification can legally contain a colon token or end with =Intro
@@ -467,16 +1279,16 @@ fn parse_proof_line(toks: &[Token]) -> Result<ProofLine, String> {
         let toks_justification: &[Token];
         if let Token::Name(name) = &toks[colon_index - 1] {
             match name.as_str() {
-                "Reit" => {
+                "Reit" | "QE" | "Contra" | "Impl" => {
                     toks_before_justification = &toks[..colon_index - 1];
                     toks_justification = &toks[colon_index - 1..];
                 }
-                "Intro" | "Elim" => {
+                "Intro" | "Elim" | "Chain" => {
                     toks_before_justification = &toks[..colon_index - 2];
                     toks_justification = &toks[colon_index - 2..];
                 }
                 _ => {
-                    return Err(format!("failed to parse justification. Expected \'Reit\', \'Intro\' or \'Elim\', found \'{name}\'. Note that capitalization matters!"));
+                    return Err(format!("failed to parse justification. Expected \'Reit\', \'Intro\' or \'Elim\' (or one of the derived rules \'QE\', \'Contra\' or \'Impl\'), found \'{name}\'. Note that capitalization matters!"));
                 }
             }
 
@@ -489,7 +1301,7 @@ fn parse_proof_line(toks: &[Token]) -> Result<ProofLine, String> {
                 toks_before_justification.first(),
                 toks_before_justification.get(1),
                 parse_justification(toks_justification)?,
-                parse_logical_expr(toks_before_justification.get(2..).unwrap_or(&[]))?,
+                parse_wff(toks_before_justification.get(2..).unwrap_or(&[]))?,
             ) {
                 Ok(ProofLine {
                     line_num: Some(*line_num),
@@ -498,6 +1310,7 @@ fn parse_proof_line(toks: &[Token]) -> Result<ProofLine, String> {
                     sentence: Some(wff),
                     justification: Some(justific),
                     constant_between_square_brackets: None,
+                    cites_premises: false,
                 })
             } else {
                 Err("a line with an inference should always start with a line number (integer), followed by at least one vertical bar.".to_string())
@@ -511,7 +1324,7 @@ fn parse_proof_line(toks: &[Token]) -> Result<ProofLine, String> {
         //  <num> '|' { '|' } '[' <ConstantName> ']' [ <E1> ]
         //  '|' { '|' } - { - }
         //  '|' { '|' }
-        if toks.first().is_none() {
+        if toks.is_empty() {
             return Err("one proof line appears to be empty".to_string());
         }
         match toks.first().unwrap() {
@@ -528,9 +1341,6 @@ fn parse_proof_line(toks: &[Token]) -> Result<ProofLine, String> {
                 ) = (toks.get(2), toks.get(3), toks.get(4))
                 {
                     const_betw_sqbr = Some(Term::Atomic(name.to_string()));
-                    if !name.chars().next().unwrap_or('U').is_lowercase() {
-                        return Err("a boxed constant must be a constant; it should start with a lowercase letter".to_string());
-                    }
                     if toks.len() == 5 {
                         // this premise contains only a boxed constant, no further expression:
                         // early exit
@@ -541,6 +1351,7 @@ fn parse_proof_line(toks: &[Token]) -> Result<ProofLine, String> {
                             sentence: None,
                             justification: None,
                             constant_between_square_brackets: const_betw_sqbr,
+                            cites_premises: false,
                         });
                     }
                     5
@@ -558,7 +1369,7 @@ fn parse_proof_line(toks: &[Token]) -> Result<ProofLine, String> {
                         .to_string());
                 }
 
-                let wff = parse_logical_expr(toks.get(expression_start_index..).unwrap_or(&[]))?;
+                let wff = parse_wff(toks.get(expression_start_index..).unwrap_or(&[]))?;
 
                 Ok(ProofLine {
                     line_num: Some(*num),
@@ -567,6 +1378,7 @@ fn parse_proof_line(toks: &[Token]) -> Result<ProofLine, String> {
                     sentence: Some(wff),
                     justification: None,
                     constant_between_square_brackets: const_betw_sqbr,
+                    cites_premises: false,
                 })
             }
             Token::ConseqVertBar(depth) => {
@@ -579,6 +1391,7 @@ fn parse_proof_line(toks: &[Token]) -> Result<ProofLine, String> {
                         sentence: None,
                         justification: None,
                         constant_between_square_brackets: None,
+                        cites_premises: false,
                     })
                 } else {
                     Err("when you have a line without line number, then that line can only possibly contain some minuses to indicate a Fitch bar, but it may contain no other tokens than minuses after the vertical bar(s)".to_string())
@@ -594,14 +1407,28 @@ fn parse_proof_line(toks: &[Token]) -> Result<ProofLine, String> {
 
 /// Parse a justification, as specified by the grammar defined in the documentation for
 /// [parse_proof_line].
+///
+/// Wherever a <numrange> is expected, a single <num> is also accepted: it is parsed as the range
+/// `<num>-<num>`. This is the shorthand for citing the subproof that starts at line <num>; the
+/// checker figures out where that subproof ends (see [CheckOptions::allow_subproof_shorthand]).
 fn parse_justification(toks: &[Token]) -> Result<Justification, String> {
-    if toks.first().is_none() || toks.get(1).is_none() {
+    if toks.is_empty() || toks.get(1).is_none() {
         return Err("failure when parsing justification; it seems not to be there?".to_string());
     }
+    let toks = &expand_subproof_shorthands(toks)[..];
     match (&toks[0], &toks[1], toks.get(2), toks.get(3)) {
         (Token::Name(name), Token::Colon, Some(Token::Number(num)), None) if name == "Reit" => {
             Ok(Justification::Reit(*num))
         }
+        (Token::Name(name), Token::Colon, Some(Token::Number(num)), None) if name == "QE" => {
+            Ok(Justification::QuantifierExchange(*num))
+        }
+        (Token::Name(name), Token::Colon, Some(Token::Number(num)), None) if name == "Contra" => {
+            Ok(Justification::Contraposition(*num))
+        }
+        (Token::Name(name), Token::Colon, Some(Token::Number(num)), None) if name == "Impl" => {
+            Ok(Justification::MaterialImplication(*num))
+        }
         (Token::And, Token::Name(name), Some(Token::Colon), Some(Token::Number(num)))
             if name == "Intro" =>
         {
@@ -800,6 +1627,18 @@ fn parse_justification(toks: &[Token]) -> Result<Justification, String> {
                 Err(err_str)
             }
         }
+        (Token::Equals, Token::Name(name), Some(Token::Colon), Some(Token::Number(num)))
+            if name == "Chain" =>
+        {
+            let mut nums: Vec<usize> = vec![*num];
+            for pair in toks[4..].chunks(2) {
+                let [Token::Comma, Token::Number(next_num)] = pair else {
+                    return Err("failed to parse =Chain justification. It should be of this form: =Chain:<num>{,<num>}".to_string());
+                };
+                nums.push(*next_num);
+            }
+            Ok(Justification::EqualsChain(nums))
+        }
         (Token::Forall, Token::Name(name), Some(Token::Colon), Some(Token::Number(num1)))
             if name == "Intro" =>
         {
@@ -855,6 +1694,168 @@ fn parse_justification(toks: &[Token]) -> Result<Justification, String> {
     }
 }
 
+/// Replaces the keyword `Prem` in the justification tokens `toks` (of `∧Intro: Prem` or
+/// `=Chain: Prem`) by the line numbers of the premises, separated by commas, and returns whether
+/// this was done. `Prem` can only be the whole list of cited lines, of a rule that cites a list of
+/// lines; otherwise, an error message is returned.
+fn expand_premise_block(toks: &mut Vec<Token>, premises: &[usize]) -> Result<bool, String> {
+    let prem = Token::Name("Prem".to_string());
+    let Some(colon_index) = toks.iter().position(|t| t == &Token::Colon) else {
+        return Ok(false);
+    };
+    if !toks[colon_index + 1..].contains(&prem) {
+        return Ok(false);
+    }
+    let cites_list = match &toks[..colon_index] {
+        [.., Token::And, Token::Name(name)] => name == "Intro",
+        [.., Token::Equals, Token::Name(name)] => name == "Chain",
+        _ => false,
+    };
+    if !cites_list || toks[colon_index + 1..] != [prem] {
+        let err = "Prem (all premises) can only be cited on its own, by a rule that cites a list \
+            of lines, like this: ∧Intro: Prem or =Chain: Prem";
+        return Err(err.to_string());
+    }
+    if premises.is_empty() {
+        return Err("Prem cites all premises, but the proof has no premises".to_string());
+    }
+    toks.truncate(colon_index + 1);
+    for (i, n) in premises.iter().enumerate() {
+        if i > 0 {
+            toks.push(Token::Comma);
+        }
+        toks.push(Token::Number(*n));
+    }
+    Ok(true)
+}
+
+/// Replaces every single line number in the justification tokens `toks` at a position where a
+/// subproof should be cited by a range from that line number to itself. For example, the tokens of
+/// `→Intro: 3` become the tokens of `→Intro: 3-3`, and the tokens of `∨Elim: 1, 2, 4-5` become the
+/// tokens of `∨Elim: 1, 2-2, 4-5`.
+///
+/// Since this is done for every justification, the tokens are only copied if something is
+/// actually expanded.
+fn expand_subproof_shorthands(toks: &[Token]) -> Cow<'_, [Token]> {
+    let is_subproof_position = |i: usize| match (&toks[0], &toks[1]) {
+        (Token::Or, Token::Name(name)) if name == "Elim" => i >= 1,
+        (Token::Exists, Token::Name(name)) if name == "Elim" => i == 1,
+        (Token::Implies | Token::Bicond | Token::Not | Token::Forall, Token::Name(name)) => {
+            name == "Intro"
+        }
+        _ => false,
+    };
+    let Some(colon_index) = toks.iter().position(|t| t == &Token::Colon) else {
+        return Cow::Borrowed(toks);
+    };
+    let is_shorthand = |(i, citation): (usize, &[Token])| {
+        matches!(citation, [Token::Number(_)]) && is_subproof_position(i)
+    };
+    if !toks[colon_index + 1..].split(|t| t == &Token::Comma).enumerate().any(is_shorthand) {
+        return Cow::Borrowed(toks);
+    }
+    let mut res: Vec<Token> = toks[..=colon_index].to_vec();
+    for (i, citation) in toks[colon_index + 1..].split(|t| t == &Token::Comma).enumerate() {
+        if i > 0 {
+            res.push(Token::Comma);
+        }
+        match citation {
+            [Token::Number(n)] if is_subproof_position(i) => {
+                res.extend([Token::Number(*n), Token::Dash, Token::Number(*n)])
+            }
+            _ => res.extend_from_slice(citation),
+        }
+    }
+    Cow::Owned(res)
+}
+
+/// Splits a list of [Token]s at all commas that are not inside brackets. For example, the tokens
+/// of `P(a,b), Q` are split into the tokens of `P(a,b)` and the tokens of `Q`.
+fn split_at_top_level_commas(toks: &[Token]) -> Vec<&[Token]> {
+    let mut parts: Vec<&[Token]> = vec![];
+    let mut depth: usize = 0;
+    let mut part_start = 0;
+    for (i, tok) in toks.iter().enumerate() {
+        match tok {
+            Token::LPar => depth += 1,
+            Token::RPar => depth = depth.saturating_sub(1),
+            Token::Comma if depth == 0 => {
+                parts.push(&toks[part_start..i]);
+                part_start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&toks[part_start..]);
+    parts
+}
+
+/// Parses the rule of a sequent (see [parse_sequent]), and turns it into a [Justification] that
+/// cites the premises `1..=num_premises` in order.
+fn parse_sequent_rule(toks: &[Token], num_premises: usize) -> Result<Justification, String> {
+    let cited: Vec<usize> = (1..=num_premises).collect();
+    let exactly = |rule_name: &str, n: usize| {
+        if num_premises == n {
+            Ok(())
+        } else {
+            Err(format!(
+                "the rule {rule_name} needs exactly {n} premise(s), but {num_premises} premise(s) \
+                were given"
+            ))
+        }
+    };
+    let needs_subproof = |rule_name: &str| {
+        Err(format!(
+            "the rule {rule_name} needs a subproof, so it cannot be checked as a single inference"
+        ))
+    };
+    let rule_name = match toks {
+        [Token::Name(name)] => name.as_str(),
+        [_, Token::Name(name)] => name.as_str(),
+        _ => "",
+    };
+    match (toks, rule_name) {
+        ([Token::Name(_)], "Reit") => exactly("Reit", 1).map(|_| Justification::Reit(1)),
+        ([Token::And, _], "Intro") => {
+            if num_premises == 0 {
+                Err("the rule ∧Intro needs at least one premise".to_string())
+            } else {
+                Ok(Justification::AndIntro(cited))
+            }
+        }
+        ([Token::And, _], "Elim") => exactly("∧Elim", 1).map(|_| Justification::AndElim(1)),
+        ([Token::Or, _], "Intro") => exactly("∨Intro", 1).map(|_| Justification::OrIntro(1)),
+        ([Token::Or, _], "Elim") => needs_subproof("∨Elim"),
+        ([Token::Implies, _], "Intro") => needs_subproof("→Intro"),
+        ([Token::Implies, _], "Elim") => {
+            exactly("→Elim", 2).map(|_| Justification::ImpliesElim(1, 2))
+        }
+        ([Token::Bicond, _], "Intro") => needs_subproof("↔Intro"),
+        ([Token::Bicond, _], "Elim") => {
+            exactly("↔Elim", 2).map(|_| Justification::BicondElim(1, 2))
+        }
+        ([Token::Not, _], "Intro") => needs_subproof("¬Intro"),
+        ([Token::Not, _], "Elim") => exactly("¬Elim", 1).map(|_| Justification::NotElim(1)),
+        ([Token::Bottom, _], "Intro") => {
+            exactly("⊥Intro", 2).map(|_| Justification::BottomIntro(1, 2))
+        }
+        ([Token::Bottom, _], "Elim") => exactly("⊥Elim", 1).map(|_| Justification::BottomElim(1)),
+        ([Token::Equals, _], "Intro") => exactly("=Intro", 0).map(|_| Justification::EqualsIntro),
+        ([Token::Equals, _], "Elim") => {
+            exactly("=Elim", 2).map(|_| Justification::EqualsElim(1, 2))
+        }
+        ([Token::Forall, _], "Intro") => needs_subproof("∀Intro"),
+        ([Token::Forall, _], "Elim") => exactly("∀Elim", 1).map(|_| Justification::ForallElim(1)),
+        ([Token::Exists, _], "Intro") => {
+            exactly("∃Intro", 1).map(|_| Justification::ExistsIntro(1))
+        }
+        ([Token::Exists, _], "Elim") => needs_subproof("∃Elim"),
+        _ => Err("failed to parse the rule after \'by\'. Note that the proper capitalization \
+            is \'Intro\'/\'Elim\'/\'Reit\', and that no line numbers should be written."
+            .to_string()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1165,12 +2166,19 @@ mod tests {
             parse_justification(&lex("∨Elim:42,43-44,45-46,47-48").unwrap()),
             Ok(Justification::OrElim(42, vec![(43, 44), (45, 46), (47, 48)]))
         );
-        assert!(parse_justification(&lex("∨Elim:42,43-44,45-46,47,48").unwrap()).is_err());
+        // a single line number is the shorthand for a subproof starting at that line
+        assert_eq!(
+            parse_justification(&lex("∨Elim:42,43-44,45-46,47,48").unwrap()),
+            Ok(Justification::OrElim(42, vec![(43, 44), (45, 46), (47, 47), (48, 48)]))
+        );
         assert!(parse_justification(&lex("∨Elim:42,43-44,45-46-47-48").unwrap()).is_err());
         assert!(parse_justification(&lex("∨Elim:42-43-44,45-46,47-48").unwrap()).is_err());
         assert!(parse_justification(&lex("∨Elim-42,43-44,45-46,47-48").unwrap()).is_err());
         assert!(parse_justification(&lex("∨Elim:42,43-44,45-46,47-48,").unwrap()).is_err());
-        assert!(parse_justification(&lex("∨Elim:42,43-44,45-46,47-48,49").unwrap()).is_err());
+        assert_eq!(
+            parse_justification(&lex("∨Elim:42,43-44,45-46,47-48,49").unwrap()),
+            Ok(Justification::OrElim(42, vec![(43, 44), (45, 46), (47, 48), (49, 49)]))
+        );
         assert!(parse_justification(&lex("∨Elim:42,43-44,45-46,47-48,49-").unwrap()).is_err());
         assert!(parse_justification(&lex("∨Elim:42").unwrap()).is_err());
     }
@@ -1208,6 +2216,332 @@ mod tests {
         assert!((parse_justification(&lex("→Elim:42,43,").unwrap()).is_err()));
     }
 
+    #[test]
+    fn test_sequent_parser() {
+        assert_eq!(
+            parse_sequent("P(a,b), P(a,b)→Q ⊢ Q by →Elim"),
+            Ok(Sequent {
+                premises: vec![
+                    Wff::PredApp(
+                        "P".to_string(),
+                        vec![Term::Atomic("a".to_string()), Term::Atomic("b".to_string())]
+                    ),
+                    Wff::Implies(
+                        Box::new(Wff::PredApp(
+                            "P".to_string(),
+                            vec![Term::Atomic("a".to_string()), Term::Atomic("b".to_string())]
+                        )),
+                        Box::new(Wff::Atomic("Q".to_string()))
+                    ),
+                ],
+                conclusion: Wff::Atomic("Q".to_string()),
+                rule: Justification::ImpliesElim(1, 2),
+            })
+        );
+        assert_eq!(
+            parse_sequent("⊢ a=a by =Intro").map(|s| s.rule),
+            Ok(Justification::EqualsIntro)
+        );
+        assert!(parse_sequent("A, B ⊢ A∧B by ∧Intro: 1,2").is_err());
+        assert!(parse_sequent("A ⊢ A→A by →Intro").is_err());
+        assert!(parse_sequent("A, B ⊢ A by ∧Elim").is_err());
+        assert!(parse_sequent("A, B by ∧Intro").is_err());
+    }
+
+    #[test]
+    fn test_lexer_ascii_connectives() {
+        assert_eq!(lex("~A & B -> C\\/D <-> _|_"), lex("¬A ∧ B → C∨D ↔ ⊥"));
+        assert_eq!(lex("A /\\ B ⊃ (C ≡ D)"), lex("A ∧ B → (C ↔ D)"));
+        assert_eq!(lex("1-3"), Ok(vec![Token::Number(1), Token::Dash, Token::Number(3)]));
+        assert!(lex("A <- B").is_err());
+        let lex_error = |s: &str| {
+            let mut toks = vec![];
+            lex_with_spans_into(s, &mut toks, None).unwrap_err()
+        };
+        assert_eq!(lex_error("A <-B"), ("invalid character found: <".to_string(), (2, 3)));
+        assert_eq!(lex_error("A / B"), ("invalid character found: /".to_string(), (2, 3)));
+        assert_eq!(lex_error("_|"), ("invalid character found: _".to_string(), (0, 1)));
+    }
+
+    #[test]
+    fn test_subproof_shorthand_parser() {
+        let parse = |s: &str| parse_justification(&lex(s).unwrap());
+        assert_eq!(parse("→Intro: 3"), Ok(Justification::ImpliesIntro((3, 3))));
+        assert_eq!(parse("∨Elim: 1, 2, 4-5"), Ok(Justification::OrElim(1, vec![(2, 2), (4, 5)])));
+        assert_eq!(parse("∃Elim: 1, 2"), Ok(Justification::ExistsElim(1, (2, 2))));
+        assert_eq!(parse("↔Intro: 2, 4"), Ok(Justification::BicondIntro((2, 2), (4, 4))));
+        assert_eq!(parse("→Elim: 1, 2"), Ok(Justification::ImpliesElim(1, 2)));
+    }
+
+    #[test]
+    fn test_justification_parser_equals_chain() {
+        let parse = |s: &str| parse_justification(&lex(s).unwrap());
+        assert_eq!(parse("=Chain: 3, 1, 2"), Ok(Justification::EqualsChain(vec![3, 1, 2])));
+        assert_eq!(parse("=Chain: 3"), Ok(Justification::EqualsChain(vec![3])));
+        assert!(parse("=Chain: 3, 1,").is_err());
+        assert!(parse("=Chain: 3, 1-2").is_err());
+    }
+
+    #[test]
+    fn test_premise_block_parser() {
+        let lines = parse_fitch_proof("1 | A\n2 | B\n  |----\n3 | A ∧ B  ∧Intro: Prem").unwrap();
+        assert_eq!(lines[3].justification, Some(Justification::AndIntro(vec![1, 2])));
+        assert!(lines[3].cites_premises);
+        assert!(!lines[0].cites_premises);
+        // a predicate may still be called Prem
+        assert!(parse_fitch_proof("1 | Prem\n  |----\n2 | Prem  ∧Intro: Prem").is_ok());
+        assert!(parse_fitch_proof("1 | A\n  |----\n2 | A  Reit: Prem").is_err());
+        assert!(parse_fitch_proof("1 | A\n  |----\n2 | A ∧ A  ∧Intro: Prem, 1").is_err());
+        assert!(parse_fitch_proof("  |----\n1 | a = a  =Chain: Prem").is_err());
+    }
+
+    #[test]
+    fn test_parse_fitch_proof_lines() {
+        let mut proof = "1 | A\n  |----\n".to_string();
+        for n in 2..20000 {
+            proof.push_str(&format!("{n} | A  Reit: {}\n", n - 1));
+        }
+        let mut lines = parse_fitch_proof_lines(&proof);
+        assert_eq!(
+            lines.nth(19999),
+            Some(Ok(parse_proof_line(&lex("19999 | A Reit: 19998").unwrap(), parse_logical_expr)
+                .unwrap()))
+        );
+        assert_eq!(lines.next(), None);
+        assert_eq!(parse_fitch_proof(&proof).map(|lines| lines.len()), Ok(20000));
+
+        let mut lines = parse_fitch_proof_lines("1 | A\n  |----\n2 | A Reit: 1000000000\n3 | A");
+        assert!(lines.next().unwrap().is_ok());
+        assert!(lines.next().unwrap().is_ok());
+        assert_eq!(
+            lines.next(),
+            Some(Err(
+                "lexer failure near line 2: there was an integer bigger than 999999999".to_string()
+            ))
+        );
+        assert_eq!(lex("||  | |"), Ok(vec![Token::ConseqVertBar(4)]));
+    }
+
+    #[test]
+    fn test_renaming_parser() {
+        assert_eq!(parse_renaming(""), Ok(HashMap::new()));
+        assert_eq!(
+            parse_renaming(" P = Q, a=f "),
+            Ok(HashMap::from([
+                ("P".to_string(), "Q".to_string()),
+                ("a".to_string(), "f".to_string())
+            ]))
+        );
+        assert!(parse_renaming("P=q").is_err());
+        assert!(parse_renaming("P=Q, P=R").is_err());
+        assert!(parse_renaming("P").is_err());
+        assert!(parse_renaming("P=Q(a)").is_err());
+    }
+
+    #[test]
+    fn test_template_parser() {
+        let template = [
+            "A",
+            "# exercise 3",
+            " #  minimal proof length : 4",
+            "# minimal nesting depth: 0",
+            "A ∨ B",
+        ];
+        assert_eq!(
+            parse_template(&template.map(String::from)),
+            Ok(ProofTemplate {
+                sentences: vec![
+                    Wff::Atomic("A".to_string()),
+                    Wff::Or(vec![Wff::Atomic("A".to_string()), Wff::Atomic("B".to_string())])
+                ],
+                minimal_length: Some(4),
+                minimal_depth: Some(0),
+                propositional_only: false,
+            })
+        );
+        assert!(parse_template(&["#propositional only".to_string()]).unwrap().propositional_only);
+        assert!(parse_template(&["# minimal proof length: -1".to_string()]).is_err());
+        assert!(parse_template(&["# minimal nesting depth: one".to_string()]).is_err());
+        assert!(parse_template(&["A ∨".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_directives_parser() {
+        let proof = "%expect-error: E_SCOPE_VIOLATION at line 4\n1 | A\n % allow: QE, =Chain\n%expect-error: E_ARITY";
+        assert_eq!(
+            parse_directives(proof),
+            Ok(ProofDirectives {
+                expected_errors: vec![
+                    ExpectedError {
+                        code: "E_SCOPE_VIOLATION".to_string(),
+                        line: Some(4),
+                    },
+                    ExpectedError {
+                        code: "E_ARITY".to_string(),
+                        line: None,
+                    },
+                ],
+                allowed_rules: vec!["QE".to_string(), "=Chain".to_string()],
+            })
+        );
+        assert_eq!(parse_fitch_proof(proof).map(|lines| lines.len()), Ok(1));
+        assert!(parse_directives("%expect-error: E_TYPO").is_err());
+        assert!(parse_directives("%expect-error: E_ARITY at 4").is_err());
+        assert!(parse_directives("%allow: TautCon").is_err());
+        assert!(parse_directives("%hint: use ∧Elim").is_err());
+        assert!(parse_directives("%allow QE").is_err());
+        assert!(is_empty_proof(" \n\t\n% allow: QE\n"));
+        assert!(!is_empty_proof(proof));
+        assert_eq!(parse_fitch_proof(" \n\t\n% allow: QE\n"), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_error_identification_parser() {
+        assert_eq!(
+            parse_error_identification("4 E_SCOPE_VIOLATION\n\n line 2: e_arity"),
+            Ok(vec![(4, "E_SCOPE_VIOLATION".to_string()), (2, "E_ARITY".to_string())])
+        );
+        assert_eq!(parse_error_identification(""), Ok(vec![]));
+        assert!(parse_error_identification("E_ARITY").is_err());
+        assert!(parse_error_identification("four E_ARITY").is_err());
+        assert!(parse_error_identification("4 E_TYPO").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "tutoring")]
+    fn test_hint_policy_parser() {
+        assert_eq!(parse_hint_policy(""), Ok(HintPolicy::default()));
+        assert_eq!(
+            parse_hint_policy("family=0, lines = 5"),
+            Ok(HintPolicy {
+                rule_family_after: 0,
+                rule_after: 2,
+                citations_after: 5,
+            })
+        );
+        assert!(parse_hint_policy("rule=two").is_err());
+        assert!(parse_hint_policy("answer=9").is_err());
+        assert!(parse_hint_policy("rule").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "export")]
+    fn test_two_column_options_parser() {
+        assert_eq!(parse_two_column_options(""), Ok(TwoColumnOptions::default()));
+        assert_eq!(
+            parse_two_column_options("format=html, numbering=parens, justification_width=40%"),
+            Ok(TwoColumnOptions {
+                format: TwoColumnFormat::Html,
+                numbering: Numbering::Parenthesized,
+                formula_width: None,
+                justification_width: Some("40%".to_string()),
+            })
+        );
+        assert!(parse_two_column_options("format=pdf").is_err());
+        assert!(parse_two_column_options("formula_width=wide").is_err());
+        assert!(parse_two_column_options("formula_width=8cm}").is_err());
+        assert!(parse_two_column_options("colour=red").is_err());
+    }
+
+    #[test]
+    fn test_line_labels_parser() {
+        assert_eq!(parse_line_labels(""), Ok(LineLabels::Keep));
+        assert_eq!(parse_line_labels("labels = letters"), Ok(LineLabels::Letters));
+        assert_eq!(parse_line_labels("labels=numbers"), Ok(LineLabels::Numbers));
+        assert!(parse_line_labels("labels=roman").is_err());
+        assert!(parse_line_labels("numbers").is_err());
+    }
+
+    #[test]
+    fn test_check_options_parser() {
+        assert_eq!(parse_check_options(""), Ok(CheckOptions::default()));
+        assert_eq!(
+            parse_check_options(" symbols = ascii "),
+            Ok(CheckOptions {
+                symbols: SymbolSet::Ascii,
+                ..CheckOptions::default()
+            })
+        );
+        assert!(parse_check_options("symbols=greek").is_err());
+        assert!(parse_check_options("colors=on").is_err());
+        assert!(parse_check_options("symbols").is_err());
+        let options = parse_check_options("symbols=ascii, derived_rules=on").unwrap();
+        assert!(options.allow_derived_rules);
+        assert!(parse_check_options("derived_rules=yes").is_err());
+        assert!(parse_check_options("equality_chains=on").unwrap().allow_equality_chains);
+        assert!(parse_check_options("equality_chains=maybe").is_err());
+        assert!(parse_check_options("bicond_any_order=on").unwrap().allow_any_bicond_order);
+        assert!(parse_check_options("bicond_any_order=yes").is_err());
+        assert_eq!(
+            parse_check_options("conclusion=anywhere").unwrap().conclusion_placement,
+            ConclusionPlacement::AnyTopLevelLine
+        );
+        assert!(parse_check_options("conclusion=first").is_err());
+        assert!(parse_check_options("drafting=on").unwrap().drafting);
+        assert!(parse_check_options("propositional_only=on").unwrap().propositional_only);
+        assert!(parse_check_options("propositional_only=1").is_err());
+        assert!(
+            parse_check_options("propositional_quantifiers=on").unwrap().propositional_quantifiers
+        );
+        assert!(parse_check_options("propositional_quantifiers=yes").is_err());
+        assert_eq!(parse_check_options("max_errors=2").unwrap().max_errors, Some(2));
+        assert_eq!(parse_check_options("max_errors=2, max_errors=all").unwrap().max_errors, None);
+        assert!(parse_check_options("max_errors=-1").is_err());
+        assert_eq!(
+            parse_check_options("feedback=lines").unwrap().feedback,
+            FeedbackDetail::LineNumbers
+        );
+        assert!(parse_check_options("feedback=none").is_err());
+        assert_eq!(
+            parse_check_options("negation=bottom").unwrap().negation,
+            NegationConvention::DefinedByBottom
+        );
+        assert!(parse_check_options("negation=defined").is_err());
+        assert_eq!(
+            parse_check_options("error_order=severity").unwrap().error_order,
+            ErrorOrder::Severity
+        );
+        assert!(parse_check_options("error_order=random").is_err());
+        assert_eq!(
+            parse_check_options("reiteration=explicit").unwrap().reiteration,
+            ReiterationPolicy::Explicit
+        );
+        assert!(parse_check_options("reiteration=never").is_err());
+    }
+
+    #[test]
+    fn test_match_policy_parser() {
+        assert_eq!(parse_match_policy(""), Ok(MatchPolicy::Syntactic));
+        assert_eq!(parse_match_policy("match=alpha"), Ok(MatchPolicy::Alpha));
+        assert_eq!(parse_match_policy(" match = equivalent "), Ok(MatchPolicy::Equivalent));
+        assert!(parse_match_policy("match=semantic").is_err());
+        assert!(parse_match_policy("alpha").is_err());
+    }
+
+    #[test]
+    fn test_diagnose_logical_expression() {
+        let vars = HashSet::from(["x".to_string()]);
+        assert!(diagnose_logical_expression("∀x (P(x) ∨ x = a)", &vars).is_ok());
+        // the spans are byte ranges
+        assert_eq!(
+            diagnose_logical_expression("¬(A ∧ B", &vars),
+            Err(vec![("this opening parenthesis is never closed".to_string(), (2, 3))])
+        );
+        assert_eq!(
+            diagnose_logical_expression("P(x) ∧ ∃x Q(x)", &vars),
+            Err(vec![(
+                "the variable x is not bound by a quantifier (like ∀x or ∃x), so this is not a \
+                sentence"
+                    .to_string(),
+                (2, 3)
+            )])
+        );
+        assert_eq!(
+            diagnose_logical_expression("A |  B", &vars),
+            Err(vec![("the symbol | cannot be used in a sentence".to_string(), (2, 3))])
+        );
+    }
+
     #[test]
     fn test_parser_bug_infinite_loop_1() {
         let toks = lex("(f(g(a),=b)").unwrap();
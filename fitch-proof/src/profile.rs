@@ -0,0 +1,156 @@
+use crate::util;
+
+/// The number of lines that [CheckProfile::to_text] lists as the slowest lines.
+const NUM_SLOWEST_LINES: usize = 10;
+
+/// How long checking a proof took: per phase of the check, per rule and per line. This is meant
+/// for finding out what to optimize when checking very long (e.g. generated) proofs. All times are
+/// in milliseconds. See [crate::profile_proof].
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct CheckProfile {
+    /// The phases of the check (`parsing`, `structure and scope`, `template`, `rules` and
+    /// `proof-level checks`), in the order in which they ran, with how long each took. The phases
+    /// after a fatal error are missing.
+    pub phases: Vec<(String, f64)>,
+    /// For every rule that is used in the proof: the name of the rule, the number of lines that
+    /// use it and how long checking these lines took together, in the order in which the rules are
+    /// first used.
+    pub rules: Vec<(String, usize, f64)>,
+    /// For every line with a line number: the line number and how long checking the line took.
+    pub lines: Vec<(usize, f64)>,
+}
+
+impl CheckProfile {
+    /// Returns how long the whole check took.
+    pub fn total(&self) -> f64 {
+        self.phases.iter().map(|(_, ms)| ms).sum()
+    }
+
+    /// Returns the profile as a table for the terminal: the time of every phase, the time of every
+    /// rule (slowest first) and the slowest lines.
+    pub fn to_text(&self) -> String {
+        let mut res = vec![format!("Checked in {:.3} ms:", self.total())];
+        for (phase, ms) in &self.phases {
+            res.push(format!("  {phase:<24}{ms:>10.3} ms"));
+        }
+        if !self.rules.is_empty() {
+            res.push("\nRules (slowest first):".to_string());
+            let mut rules: Vec<&(String, usize, f64)> = self.rules.iter().collect();
+            rules.sort_by(|(_, _, ms1), (_, _, ms2)| ms2.total_cmp(ms1));
+            for (rule, count, ms) in rules {
+                let lines = match count {
+                    1 => "1 line".to_string(),
+                    n => format!("{n} lines"),
+                };
+                res.push(format!("  {rule:<12}{lines:>12}{ms:>10.3} ms"));
+            }
+        }
+        if !self.lines.is_empty() {
+            res.push("\nSlowest lines:".to_string());
+            let mut lines: Vec<&(usize, f64)> = self.lines.iter().collect();
+            lines.sort_by(|(_, ms1), (_, ms2)| ms2.total_cmp(ms1));
+            for (line_num, ms) in lines.into_iter().take(NUM_SLOWEST_LINES) {
+                res.push(format!("  line {line_num:<19}{ms:>10.3} ms"));
+            }
+        }
+        res.join("\n")
+    }
+
+    /// Returns the profile as a JSON object, for example:
+    ///
+    /// `{"total_ms":0.052,"phases":[{"phase":"parsing","ms":0.021},...],
+    /// "rules":[{"rule":"∧Elim","lines":2,"ms":0.004}],"lines":[{"line":1,"ms":0.001},...]}`
+    pub fn to_json(&self) -> String {
+        let phases: Vec<String> = self
+            .phases
+            .iter()
+            .map(|(phase, ms)| format!("{{\"phase\":{},\"ms\":{ms:.3}}}", util::json_string(phase)))
+            .collect();
+        let rules: Vec<String> = self
+            .rules
+            .iter()
+            .map(|(rule, count, ms)| {
+                format!("{{\"rule\":{},\"lines\":{count},\"ms\":{ms:.3}}}", util::json_string(rule))
+            })
+            .collect();
+        let lines: Vec<String> = self
+            .lines
+            .iter()
+            .map(|(line_num, ms)| format!("{{\"line\":{line_num},\"ms\":{ms:.3}}}"))
+            .collect();
+        format!(
+            "{{\"total_ms\":{:.3},\"phases\":[{}],\"rules\":[{}],\"lines\":[{}]}}",
+            self.total(),
+            phases.join(","),
+            rules.join(","),
+            lines.join(",")
+        )
+    }
+
+    /// Adds a phase of the check that took `ms` milliseconds.
+    pub(crate) fn add_phase(&mut self, phase: &str, ms: f64) {
+        self.phases.push((phase.to_string(), ms));
+    }
+
+    /// Adds a line (with its rule, if it has a justification) that took `ms` milliseconds to
+    /// check.
+    pub(crate) fn add_line(&mut self, line_num: usize, rule: Option<String>, ms: f64) {
+        self.lines.push((line_num, ms));
+        let Some(rule) = rule else {
+            return;
+        };
+        match self.rules.iter_mut().find(|(r, _, _)| *r == rule) {
+            Some((_, count, total)) => {
+                *count += 1;
+                *total += ms;
+            }
+            None => self.rules.push((rule, 1, ms)),
+        }
+    }
+}
+
+/// Returns the current time in milliseconds, counted from some fixed moment. Only differences
+/// between two of these times are meaningful.
+///
+/// In WebAssembly, [std::time::Instant] is not available, so the clock of the browser is used
+/// instead. If there is no such clock (e.g. in a web worker), this is always 0.
+pub(crate) fn now() -> f64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        web_sys::window().and_then(|w| w.performance()).map_or(0.0, |p| p.now())
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use std::sync::OnceLock;
+        use std::time::Instant;
+        static START: OnceLock<Instant> = OnceLock::new();
+        START.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_profile() {
+        let mut profile = CheckProfile::default();
+        profile.add_phase("parsing", 1.5);
+        profile.add_line(1, None, 0.25);
+        profile.add_line(2, Some("∧Elim".to_string()), 0.5);
+        profile.add_line(3, Some("∧Elim".to_string()), 1.0);
+        profile.add_phase("rules", 2.0);
+        assert_eq!(profile.total(), 3.5);
+        assert_eq!(profile.rules, vec![("∧Elim".to_string(), 2, 1.5)]);
+        assert_eq!(
+            profile.to_json(),
+            "{\"total_ms\":3.500,\"phases\":[{\"phase\":\"parsing\",\"ms\":1.500},\
+            {\"phase\":\"rules\",\"ms\":2.000}],\"rules\":[{\"rule\":\"∧Elim\",\"lines\":2,\
+            \"ms\":1.500}],\"lines\":[{\"line\":1,\"ms\":0.250},{\"line\":2,\"ms\":0.500},\
+            {\"line\":3,\"ms\":1.000}]}"
+        );
+        let text = profile.to_text();
+        assert!(text.starts_with("Checked in 3.500 ms:"), "{text}");
+        assert!(text.contains("\nSlowest lines:\n  line 3 "), "{text}");
+    }
+}
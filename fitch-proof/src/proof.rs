@@ -1,4 +1,5 @@
 use crate::data::*;
+use crate::formatter;
 use std::collections::HashSet;
 
 /// [Scope] is a type which stores scoping information (like which lines can reference which
@@ -36,6 +37,11 @@ pub struct Proof {
     pub units: Vec<ProofUnit>,
     ///  a field which contains the set of strings that should be seen as a variable.
     pub allowed_variable_names: HashSet<String>,
+    ///  a field which contains the [CheckOptions] with which the proof should be checked.
+    pub options: CheckOptions,
+    ///  a field which contains the number of premises if the proof is made from a [Sequent] (see
+    /// [crate::checker::check_sequent]), so that the lines are named after the sequent.
+    pub sequent_premises: Option<usize>,
 }
 
 /// An enum that is useful to look at the structure of a proof. This is useful for example when you
@@ -65,19 +71,37 @@ impl Proof {
     /// proof is not even half-well-structured, and further analysis is impossible. After
     /// [Proof::construct]ing the proof, you should [Proof::is_fully_correct]() it. The combination of these two things
     /// allows you to assess the correctness of a proof.
+    ///
+    /// The error is a [FatalError::ParseError], or a [FatalError::Internal] (with the formatted
+    /// proof) if the scope of the lines cannot be determined, which would be a bug.
     pub fn construct(
-        proof_lines: Vec<ProofLine>,
+        mut proof_lines: Vec<ProofLine>,
         allowed_variable_names: HashSet<String>,
-    ) -> Result<Proof, String> {
-        let units = Self::lines_to_units(&proof_lines)?;
-        Self::is_half_well_structured(&units)?; // check if proof is HALF-well-structured
-        let scope = Self::determine_scope(&units);
+        options: CheckOptions,
+    ) -> Result<Proof, FatalError> {
+        let parse_error = |err| FatalError::ParseError(err, None);
+        let units = Self::lines_to_units(&proof_lines).map_err(parse_error)?;
+        // check if proof is HALF-well-structured
+        Self::is_half_well_structured(&units).map_err(parse_error)?;
+        if options.propositional_only {
+            Self::is_propositional(&proof_lines).map_err(parse_error)?;
+        }
+        if options.negation == NegationConvention::DefinedByBottom {
+            for line in &mut proof_lines {
+                line.sentence = line.sentence.as_ref().map(Self::expand_negations);
+            }
+        }
+        let Some(scope) = Self::determine_scope(&units) else {
+            return Err(FatalError::Internal(formatter::format_proof(proof_lines)));
+        };
 
         Ok(Proof {
             lines: proof_lines,
             scope,
             units,
             allowed_variable_names,
+            options,
+            sequent_premises: None,
         })
     }
 
@@ -114,8 +138,9 @@ impl Proof {
         Ok(units)
     }
 
-    /// This function computes the [Scope] of a proof.
-    fn determine_scope(units: &[ProofUnit]) -> Scope {
+    /// This function computes the [Scope] of a proof. Returns [None] if the units do not have the
+    /// structure of a half-well-structured proof (see [Proof::is_half_well_structured]).
+    fn determine_scope(units: &[ProofUnit]) -> Option<Scope> {
         let last_line_number: usize = units
             .iter()
             .filter_map(|u| match u {
@@ -123,8 +148,7 @@ impl Proof {
                 | ProofUnit::NumberedProofLineWithJustification(num) => Some(*num),
                 _ => None,
             })
-            .last()
-            .unwrap();
+            .next_back()?;
         let mut scope: Scope = vec![(vec![], vec![]); last_line_number + 1];
         for i in 0..units.len() {
             if let ProofUnit::NumberedProofLineWithJustification(num) = units[i] {
@@ -136,55 +160,46 @@ impl Proof {
 
                 for j in (0..i).rev() {
                     match units[j] {
-                        ProofUnit::SubproofOpen => {
-                            if depth > 0 {
-                                depth -= 1;
-                                let subproof_begin;
-                                if let ProofUnit::NumberedProofLineWithoutJustificationWithoutBoxedConstant(
-                                    s_begin,
-                                ) = units[j + 1]
-                                {
-                                    subproof_begin = s_begin;
-                                } else if let ProofUnit::NumberedProofLineThatIntroducesBoxedConstant(
-                                    s_begin,
-                                ) = units[j + 1]
-                                {
-                                    subproof_begin = s_begin;
-                                } else {
-                                    panic!("This really should not happen. This is a mistake by the developer. Please contact me if you get this.");
-                                }
-                                let subproof_end = stack.pop().expect("This is a mistake by the developer. Please contact me if you get this.");
-                                if stack.is_empty() {
-                                    scope[num].1.push((subproof_begin, subproof_end));
-                                }
-                            }
-                        }
-                        ProofUnit::SubproofClose => {
-                            depth += 1;
-                            if let ProofUnit::NumberedProofLineWithJustification(subproof_end) =
-                                units[j - 1]
-                            {
-                                stack.push(subproof_end);
-                            } else if let ProofUnit::NumberedProofLineWithoutJustificationWithoutBoxedConstant(
-                                subproof_end,
-                            ) = units[j - 1]
+                        ProofUnit::SubproofOpen if depth > 0 => {
+                            depth -= 1;
+                            let subproof_begin;
+                            if let ProofUnit::NumberedProofLineWithoutJustificationWithoutBoxedConstant(
+                                s_begin,
+                            ) = units[j + 1]
                             {
-                                stack.push(subproof_end);
+                                subproof_begin = s_begin;
                             } else if let ProofUnit::NumberedProofLineThatIntroducesBoxedConstant(
-                                subproof_end,
-                            ) = units[j - 1]
+                                s_begin,
+                            ) = units[j + 1]
                             {
-                                stack.push(subproof_end);
+                                subproof_begin = s_begin;
                             } else {
-                                panic!("This really should not happen. This is a mistake by the developer. Please contact me if you get this.");
+                                return None;
+                            }
+                            let subproof_end = stack.pop()?;
+                            if stack.is_empty() {
+                                scope.get_mut(num)?.1.push((subproof_begin, subproof_end));
+                            }
+                        }
+                        ProofUnit::SubproofClose => {
+                            depth += 1;
+                            match units[..j].last()? {
+                                ProofUnit::NumberedProofLineWithJustification(subproof_end)
+                                | ProofUnit::NumberedProofLineWithoutJustificationWithoutBoxedConstant(
+                                    subproof_end,
+                                )
+                                | ProofUnit::NumberedProofLineThatIntroducesBoxedConstant(
+                                    subproof_end,
+                                ) => stack.push(*subproof_end),
+                                _ => return None,
                             }
                         }
                         ProofUnit::NumberedProofLineWithJustification(ref_num)
                         | ProofUnit::NumberedProofLineThatIntroducesBoxedConstant(ref_num)
-                        | ProofUnit::NumberedProofLineWithoutJustificationWithoutBoxedConstant(ref_num) => {
-                            if depth == 0 {
-                                scope[num].0.push(ref_num);
-                            }
+                        | ProofUnit::NumberedProofLineWithoutJustificationWithoutBoxedConstant(
+                            ref_num,
+                        ) if depth == 0 => {
+                            scope.get_mut(num)?.0.push(ref_num);
                         }
                         _ => {}
                     }
@@ -192,7 +207,7 @@ impl Proof {
             }
         }
 
-        scope
+        Some(scope)
     }
 
     /// This function checks if a proof is HALF-well-structured.
@@ -393,4 +408,105 @@ impl Proof {
         Ok(()) // nice, proof is HALF-well-structured. we can now perform further analysis without
                // yielding a fatal error.
     }
+
+    /// This function checks that a proof only uses propositional logic (see
+    /// [CheckOptions::propositional_only]). If it does not, then a friendly error message about
+    /// the first line that uses first-order logic is returned.
+    fn is_propositional(proof_lines: &[ProofLine]) -> Result<(), String> {
+        fn first_order_part(wff: &Wff) -> Option<&'static str> {
+            match wff {
+                Wff::Forall(..) | Wff::Exists(..) => Some("quantifiers (∀ and ∃)"),
+                Wff::PredApp(..) => Some("predicates with arguments (like P(a))"),
+                Wff::Equals(..) => Some("identity (=)"),
+                Wff::And(wffs) | Wff::Or(wffs) => wffs.iter().find_map(first_order_part),
+                Wff::Implies(wff1, wff2) | Wff::Bicond(wff1, wff2) => {
+                    first_order_part(wff1).or_else(|| first_order_part(wff2))
+                }
+                Wff::Not(wff) => first_order_part(wff),
+                Wff::Bottom | Wff::Atomic(_) => None,
+            }
+        }
+
+        for line in proof_lines {
+            let Some(line_num) = line.line_num else {
+                continue;
+            };
+            let part = match (&line.sentence, &line.justification) {
+                _ if line.constant_between_square_brackets.is_some() => {
+                    Some("boxed constants".to_string())
+                }
+                (Some(wff), _) if first_order_part(wff).is_some() => {
+                    first_order_part(wff).map(str::to_string)
+                }
+                (
+                    _,
+                    Some(
+                        just @ (Justification::ForallIntro(_)
+                        | Justification::ForallElim(_)
+                        | Justification::ExistsIntro(_)
+                        | Justification::ExistsElim(..)
+                        | Justification::EqualsIntro
+                        | Justification::EqualsElim(..)
+                        | Justification::EqualsChain(_)
+                        | Justification::QuantifierExchange(_)),
+                    ),
+                ) => Some(format!("the rule {}", formatter::rule_name(just))),
+                _ => None,
+            };
+            if let Some(part) = part {
+                return Err(format!(
+                    "Line {line_num}: this exercise is propositional-only, so {part} cannot be \
+                    used. Only atomic sentences (like A), the connectives and ⊥ are allowed."
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces every `¬φ` in a sentence by `φ → ⊥` (see [NegationConvention::DefinedByBottom]).
+    pub fn expand_negations(wff: &Wff) -> Wff {
+        let expand = |wff: &Wff| Box::new(Self::expand_negations(wff));
+        match wff {
+            Wff::Not(wff) => Wff::Implies(expand(wff), Box::new(Wff::Bottom)),
+            Wff::And(wffs) => Wff::And(wffs.iter().map(Self::expand_negations).collect()),
+            Wff::Or(wffs) => Wff::Or(wffs.iter().map(Self::expand_negations).collect()),
+            Wff::Implies(wff1, wff2) => Wff::Implies(expand(wff1), expand(wff2)),
+            Wff::Bicond(wff1, wff2) => Wff::Bicond(expand(wff1), expand(wff2)),
+            Wff::Forall(var, wff) => Wff::Forall(var.clone(), expand(wff)),
+            Wff::Exists(var, wff) => Wff::Exists(var.clone(), expand(wff)),
+            Wff::Bottom | Wff::Atomic(_) | Wff::PredApp(..) | Wff::Equals(..) => wff.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_determine_scope() {
+        use ProofUnit::*;
+        let units = [
+            NumberedProofLineWithoutJustificationWithoutBoxedConstant(1),
+            FitchBarLine,
+            SubproofOpen,
+            NumberedProofLineWithoutJustificationWithoutBoxedConstant(2),
+            SubproofClose,
+            NumberedProofLineWithJustification(3),
+        ];
+        let scope = Proof::determine_scope(&units).unwrap();
+        assert_eq!(scope[3], (vec![1], vec![(2, 2)]));
+
+        // units that cannot come from a half-well-structured proof give no scope, not a panic
+        assert_eq!(Proof::determine_scope(&[]), None);
+        let units = [
+            SubproofOpen,
+            NumberedProofLineWithJustification(1),
+            SubproofClose,
+            NumberedProofLineWithJustification(2),
+        ];
+        assert_eq!(Proof::determine_scope(&units), None);
+        let units = [SubproofClose, NumberedProofLineWithJustification(1)];
+        assert_eq!(Proof::determine_scope(&units), None);
+    }
 }
@@ -0,0 +1,289 @@
+use crate::data::*;
+use crate::util::Rng;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// This function renames the vocabulary (predicates, propositional atoms, function symbols and
+/// constants) in a vector of [ProofLine]s and in a proof template, according to `renaming`, which
+/// maps old names to new names. Names that are not in `renaming` stay the same, and variables
+/// (i.e. the names in `variable_names`) are never renamed.
+///
+/// Before anything is renamed, it is checked that the renaming is consistent: it may not rename
+/// two names to the same new name, it may not rename a name to a name that is already used (and
+/// not renamed itself), and it may not involve variables. If it is not consistent, an error
+/// message is returned and nothing is changed.
+pub fn rename_vocabulary(
+    proof_lines: &mut [ProofLine],
+    template: &mut [Wff],
+    renaming: &HashMap<String, String>,
+    variable_names: &HashSet<String>,
+) -> Result<(), String> {
+    let mut vocabulary: BTreeSet<String> = BTreeSet::new();
+    for line in &*proof_lines {
+        if let Some(wff) = &line.sentence {
+            vocabulary_of_wff(wff, variable_names, &mut vocabulary);
+        }
+        if let Some(Term::Atomic(name)) = &line.constant_between_square_brackets {
+            vocabulary.insert(name.clone());
+        }
+    }
+    for wff in &*template {
+        vocabulary_of_wff(wff, variable_names, &mut vocabulary);
+    }
+    check_renaming(renaming, &vocabulary, variable_names)?;
+
+    for line in &mut *proof_lines {
+        if let Some(wff) = &mut line.sentence {
+            rename_in_wff(wff, renaming, variable_names);
+        }
+        if let Some(term) = &mut line.constant_between_square_brackets {
+            rename_in_term(term, renaming, variable_names);
+        }
+    }
+    for wff in &mut *template {
+        rename_in_wff(wff, renaming, variable_names);
+    }
+    Ok(())
+}
+
+/// Returns a random renaming of the vocabulary of a proof and its template, which can be given
+/// to [rename_vocabulary] to make a variant of an exercise. Only names of the same kind are
+/// swapped: propositional atoms, constants, and predicates and function symbols of the same arity.
+/// Names may also be replaced by unused names: atoms by `A` to `H`, predicates by `P` to `W`,
+/// function symbols by `f` to `h` and constants by `a` to `e`. Names that are used as two
+/// different kinds (e.g. as a predicate of two different arities) are not renamed.
+///
+/// The same `seed` always gives the same renaming.
+pub fn random_renaming(
+    proof_lines: &[ProofLine],
+    template: &[Wff],
+    variable_names: &HashSet<String>,
+    seed: u64,
+) -> HashMap<String, String> {
+    let mut kinds: BTreeMap<String, BTreeSet<NameKind>> = BTreeMap::new();
+    for line in proof_lines {
+        if let Some(wff) = &line.sentence {
+            kinds_of_names_in_wff(wff, variable_names, &mut kinds);
+        }
+        if let Some(term) = &line.constant_between_square_brackets {
+            kinds_of_names_in_term(term, variable_names, &mut kinds);
+        }
+    }
+    for wff in template {
+        kinds_of_names_in_wff(wff, variable_names, &mut kinds);
+    }
+    let mut names_of_kind: BTreeMap<NameKind, Vec<String>> = BTreeMap::new();
+    for (name, kinds_of_name) in &kinds {
+        if let (Some(kind), 1) = (kinds_of_name.first(), kinds_of_name.len()) {
+            names_of_kind.entry(*kind).or_default().push(name.clone());
+        }
+    }
+
+    let mut rng = Rng::new(seed);
+    let mut unavailable: HashSet<String> = kinds.into_keys().collect();
+    unavailable.extend(variable_names.iter().cloned());
+    let mut renaming = HashMap::new();
+    for (kind, names) in names_of_kind {
+        let fresh_names = match kind {
+            NameKind::Atom => 'A'..='H',
+            NameKind::Predicate(_) => 'P'..='W',
+            NameKind::Function(_) => 'f'..='h',
+            NameKind::Constant => 'a'..='e',
+        };
+        let mut new_names = names.clone();
+        for fresh in fresh_names.map(String::from) {
+            if unavailable.insert(fresh.clone()) {
+                new_names.push(fresh);
+            }
+        }
+        rng.shuffle(&mut new_names);
+        renaming.extend(names.into_iter().zip(new_names));
+    }
+    renaming
+}
+
+/* ------------------ PRIVATE -------------------- */
+
+/// Checks that `renaming` is consistent (see [rename_vocabulary]), given the names that are used
+/// in the proof and the template.
+fn check_renaming(
+    renaming: &HashMap<String, String>,
+    vocabulary: &BTreeSet<String>,
+    variable_names: &HashSet<String>,
+) -> Result<(), String> {
+    // sorted, so that the error messages do not depend on the order of a HashMap
+    let mut pairs: Vec<(&String, &String)> = renaming.iter().collect();
+    pairs.sort();
+    let mut new_names: HashMap<&String, &String> = HashMap::new();
+    for (old, new) in pairs {
+        if variable_names.contains(old) || variable_names.contains(new) {
+            let var = if variable_names.contains(old) {
+                old
+            } else {
+                new
+            };
+            return Err(format!("{var} is a variable, and variables cannot be renamed"));
+        }
+        if let Some(other_old) = new_names.insert(new, old) {
+            return Err(format!("both {other_old} and {old} are renamed to {new}"));
+        }
+        if vocabulary.contains(new) && !renaming.contains_key(new) {
+            return Err(format!(
+                "{old} cannot be renamed to {new}, because {new} is already used in the proof"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Adds all names of predicates, propositional atoms, function symbols and constants that occur in
+/// `wff` to `vocabulary`.
+fn vocabulary_of_wff(
+    wff: &Wff,
+    variable_names: &HashSet<String>,
+    vocabulary: &mut BTreeSet<String>,
+) {
+    fn vocabulary_of_term(
+        term: &Term,
+        variable_names: &HashSet<String>,
+        vocabulary: &mut BTreeSet<String>,
+    ) {
+        match term {
+            Term::Atomic(name) => {
+                if !variable_names.contains(name) {
+                    vocabulary.insert(name.clone());
+                }
+            }
+            Term::FuncApp(name, args) => {
+                vocabulary.insert(name.clone());
+                args.iter().for_each(|t| vocabulary_of_term(t, variable_names, vocabulary));
+            }
+        }
+    }
+    match wff {
+        Wff::And(li) | Wff::Or(li) => {
+            li.iter().for_each(|w| vocabulary_of_wff(w, variable_names, vocabulary))
+        }
+        Wff::Implies(w1, w2) | Wff::Bicond(w1, w2) => {
+            vocabulary_of_wff(w1, variable_names, vocabulary);
+            vocabulary_of_wff(w2, variable_names, vocabulary);
+        }
+        Wff::Not(w) | Wff::Forall(_, w) | Wff::Exists(_, w) => {
+            vocabulary_of_wff(w, variable_names, vocabulary)
+        }
+        Wff::Bottom => {}
+        Wff::Atomic(name) => {
+            vocabulary.insert(name.clone());
+        }
+        Wff::PredApp(name, args) => {
+            vocabulary.insert(name.clone());
+            args.iter().for_each(|t| vocabulary_of_term(t, variable_names, vocabulary));
+        }
+        Wff::Equals(t1, t2) => {
+            vocabulary_of_term(t1, variable_names, vocabulary);
+            vocabulary_of_term(t2, variable_names, vocabulary);
+        }
+    }
+}
+
+/// Renames the name `name` (of anything but a variable) in place, if it should be renamed.
+fn rename_name(name: &mut String, renaming: &HashMap<String, String>) {
+    if let Some(new) = renaming.get(name) {
+        name.clone_from(new);
+    }
+}
+
+/// Renames the function symbols and constants in `term` in place.
+fn rename_in_term(term: &mut Term, renaming: &HashMap<String, String>, vars: &HashSet<String>) {
+    match term {
+        Term::Atomic(name) => {
+            if !vars.contains(name) {
+                rename_name(name, renaming);
+            }
+        }
+        Term::FuncApp(name, args) => {
+            rename_name(name, renaming);
+            args.iter_mut().for_each(|t| rename_in_term(t, renaming, vars));
+        }
+    }
+}
+
+/// Renames the predicates, propositional atoms, function symbols and constants in `wff` in place.
+fn rename_in_wff(wff: &mut Wff, renaming: &HashMap<String, String>, vars: &HashSet<String>) {
+    match wff {
+        Wff::And(li) | Wff::Or(li) => li.iter_mut().for_each(|w| rename_in_wff(w, renaming, vars)),
+        Wff::Implies(w1, w2) | Wff::Bicond(w1, w2) => {
+            rename_in_wff(w1, renaming, vars);
+            rename_in_wff(w2, renaming, vars);
+        }
+        Wff::Not(w) | Wff::Forall(_, w) | Wff::Exists(_, w) => rename_in_wff(w, renaming, vars),
+        Wff::Bottom => {}
+        Wff::Atomic(name) => rename_name(name, renaming),
+        Wff::PredApp(name, args) => {
+            rename_name(name, renaming);
+            args.iter_mut().for_each(|t| rename_in_term(t, renaming, vars));
+        }
+        Wff::Equals(t1, t2) => {
+            rename_in_term(t1, renaming, vars);
+            rename_in_term(t2, renaming, vars);
+        }
+    }
+}
+
+/// The kinds of names in the vocabulary, see [random_renaming]. The [usize]s are arities.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+enum NameKind {
+    Atom,
+    Predicate(usize),
+    Function(usize),
+    Constant,
+}
+
+/// Adds the kinds of the function symbols and constants in `term` to `kinds`.
+fn kinds_of_names_in_term(
+    term: &Term,
+    vars: &HashSet<String>,
+    kinds: &mut BTreeMap<String, BTreeSet<NameKind>>,
+) {
+    match term {
+        Term::Atomic(name) => {
+            if !vars.contains(name) {
+                kinds.entry(name.clone()).or_default().insert(NameKind::Constant);
+            }
+        }
+        Term::FuncApp(name, args) => {
+            kinds.entry(name.clone()).or_default().insert(NameKind::Function(args.len()));
+            args.iter().for_each(|t| kinds_of_names_in_term(t, vars, kinds));
+        }
+    }
+}
+
+/// Adds the kinds of the predicates, propositional atoms, function symbols and constants in `wff`
+/// to `kinds`.
+fn kinds_of_names_in_wff(
+    wff: &Wff,
+    vars: &HashSet<String>,
+    kinds: &mut BTreeMap<String, BTreeSet<NameKind>>,
+) {
+    match wff {
+        Wff::And(li) | Wff::Or(li) => li.iter().for_each(|w| kinds_of_names_in_wff(w, vars, kinds)),
+        Wff::Implies(w1, w2) | Wff::Bicond(w1, w2) => {
+            kinds_of_names_in_wff(w1, vars, kinds);
+            kinds_of_names_in_wff(w2, vars, kinds);
+        }
+        Wff::Not(w) | Wff::Forall(_, w) | Wff::Exists(_, w) => {
+            kinds_of_names_in_wff(w, vars, kinds)
+        }
+        Wff::Bottom => {}
+        Wff::Atomic(name) => {
+            kinds.entry(name.clone()).or_default().insert(NameKind::Atom);
+        }
+        Wff::PredApp(name, args) => {
+            kinds.entry(name.clone()).or_default().insert(NameKind::Predicate(args.len()));
+            args.iter().for_each(|t| kinds_of_names_in_term(t, vars, kinds));
+        }
+        Wff::Equals(t1, t2) => {
+            kinds_of_names_in_term(t1, vars, kinds);
+            kinds_of_names_in_term(t2, vars, kinds);
+        }
+    }
+}
@@ -0,0 +1,393 @@
+use crate::data::*;
+use crate::error_codes;
+use crate::labels;
+use crate::parser;
+use crate::util;
+use std::fmt::Write;
+
+/// Turns the result of checking a proof into the output that is shown to the user, or that is read
+/// by some other tool. Every renderer has a name, by which it can be selected (see [renderer]).
+pub trait DiagnosticsRenderer {
+    /// The name of the renderer, like `plain` or `json`.
+    fn name(&self) -> &'static str;
+
+    /// Renders the result of checking `proof`. The proof is only used to point to the lines of
+    /// its text that the mistakes are about.
+    fn render(&self, res: &ProofResult, proof: &str) -> String;
+}
+
+/// The messages as they are shown to a student, e.g. `Line 3: the rule ...`, separated by empty
+/// lines. This is what [crate::check_proof] returns.
+pub struct PlainText;
+
+/// Like [PlainText], but every mistake about a line is followed by that line as it is written in
+/// the proof, and the number of that line in the text of the proof (counting from 1, including
+/// the Fitch bars and empty lines).
+pub struct FullText;
+
+/// A JSON object with the verdict (`correct`, `incorrect`, `unfinished`, `no_proof`, `fatal` or
+/// `internal_error`), the mistakes and the lines that are not checked yet (see
+/// [CheckOptions::drafting]), for example:
+///
+/// `{"verdict":"incorrect","fatal_kind":null,"errors":[{"line":3,"text_line":4,
+/// "code":"E_RULE_MISAPPLIED","message":"Line 3: ..."}],"unchecked_lines":[]}`
+///
+/// where `line` is the line number that a mistake is about (in a proof with labels instead of line
+/// numbers, the position of the labeled line, see [labels::labels_to_numbers]), and `text_line`
+/// the number of that line in the text of the proof (see [FullText]). Both are `null` if the
+/// mistake is not about a single line, except that `text_line` is the line that could not be
+/// parsed for a [FatalError::ParseError]. The `code` is the error code (see [crate::error_codes]), which is
+/// `null` for an internal error. For a fatal error, `fatal_kind` says whose mistake it is (see
+/// [FatalError::kind]), so that the message can be sent to the right person.
+pub struct Json;
+
+/// A SARIF 2.1.0 log, for code scanning tools and editors. Every mistake is a result with its
+/// error code as `ruleId`, and every line that is not checked yet a result with level `note`. The
+/// locations refer to the proof as `proof.txt`; for a [FatalError::ParseError], the location is
+/// the part of the line that could not be parsed.
+pub struct Sarif;
+
+/// An HTML fragment: a `<div class="proof-result ...">` with the verdict as second class (see
+/// [Json]), and a list of the mistakes in it, in which every mistake has its line number and
+/// error code as `data-line` and `data-code` attributes.
+pub struct Html;
+
+/// All renderers, see [renderer].
+pub const RENDERERS: &[&dyn DiagnosticsRenderer] = &[&PlainText, &FullText, &Json, &Sarif, &Html];
+
+/// Returns the renderer with the given name: `plain`, `full`, `json`, `sarif` or `html`.
+pub fn renderer(name: &str) -> Option<&'static dyn DiagnosticsRenderer> {
+    RENDERERS.iter().copied().find(|r| r.name() == name)
+}
+
+/// All verdicts, see [verdict].
+pub const VERDICTS: &[&str] =
+    &["correct", "incorrect", "unfinished", "no_proof", "fatal", "internal_error"];
+
+/// Returns the verdict of a [ProofResult]: `correct`, `incorrect`, `unfinished`, `no_proof`,
+/// `fatal` or `internal_error`.
+pub fn verdict(res: &ProofResult) -> &'static str {
+    match res {
+        ProofResult::Correct => "correct",
+        ProofResult::Error(_) => "incorrect",
+        ProofResult::Unfinished(..) => "unfinished",
+        ProofResult::NoProof => "no_proof",
+        ProofResult::FatalError(FatalError::Internal(_)) => "internal_error",
+        ProofResult::FatalError(_) => "fatal",
+    }
+}
+
+impl DiagnosticsRenderer for PlainText {
+    fn name(&self) -> &'static str {
+        "plain"
+    }
+
+    fn render(&self, res: &ProofResult, _proof: &str) -> String {
+        match res {
+            ProofResult::Correct => "The proof is correct!".to_string(),
+            ProofResult::Error(errs) => join_errors(errs),
+            ProofResult::Unfinished(errs, unchecked_lines) if errs.is_empty() => format!(
+                "There are no mistakes in the lines with a justification. {}",
+                unchecked_note(unchecked_lines)
+            ),
+            ProofResult::Unfinished(errs, unchecked_lines) => {
+                format!("{}\n\n{}", join_errors(errs), unchecked_note(unchecked_lines))
+            }
+            ProofResult::NoProof | ProofResult::FatalError(_) => single_message(res),
+        }
+    }
+}
+
+impl DiagnosticsRenderer for FullText {
+    fn name(&self) -> &'static str {
+        "full"
+    }
+
+    fn render(&self, res: &ProofResult, proof: &str) -> String {
+        let with_lines = |errs: &[ProofError]| -> Vec<ProofError> {
+            errs.iter()
+                .map(|err| match err.line.and_then(|n| text_line(proof, n)) {
+                    Some((i, line)) => err.clone().map_message(|message| {
+                        format!("{message}\n  --> line {i} of the proof:\n      {}", line.trim())
+                    }),
+                    None => err.clone(),
+                })
+                .collect()
+        };
+        match res {
+            ProofResult::Error(errs) => PlainText.render(&ProofResult::Error(with_lines(errs)), ""),
+            ProofResult::Unfinished(errs, unchecked_lines) => PlainText
+                .render(&ProofResult::Unfinished(with_lines(errs), unchecked_lines.clone()), ""),
+            res => PlainText.render(res, proof),
+        }
+    }
+}
+
+impl DiagnosticsRenderer for Json {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn render(&self, res: &ProofResult, proof: &str) -> String {
+        let optional = |n: Option<usize>| n.map_or("null".to_string(), |n| n.to_string());
+        let span_line = fatal_span(res).map(|span| span.line);
+        let errors: Vec<String> = diagnostics(res)
+            .iter()
+            .map(|(line, code, message)| {
+                format!(
+                    "{{\"line\":{},\"text_line\":{},\"code\":{},\"message\":{}}}",
+                    optional(*line),
+                    optional(line.and_then(|n| text_line(proof, n)).map(|(i, _)| i).or(span_line)),
+                    code.map_or("null".to_string(), util::json_string),
+                    util::json_string(message)
+                )
+            })
+            .collect();
+        let unchecked_lines: Vec<String> =
+            unchecked_lines(res).iter().map(|n| n.to_string()).collect();
+        let fatal_kind = match res {
+            ProofResult::FatalError(err) => util::json_string(err.kind()),
+            _ => "null".to_string(),
+        };
+        format!(
+            "{{\"verdict\":\"{}\",\"fatal_kind\":{fatal_kind},\"errors\":[{}],\
+            \"unchecked_lines\":[{}]}}",
+            verdict(res),
+            errors.join(","),
+            unchecked_lines.join(",")
+        )
+    }
+}
+
+impl DiagnosticsRenderer for Sarif {
+    fn name(&self) -> &'static str {
+        "sarif"
+    }
+
+    fn render(&self, res: &ProofResult, proof: &str) -> String {
+        let result = |rule: Option<&str>, level: &str, message: &str, line: Option<usize>| {
+            let mut result = "{".to_string();
+            if let Some(rule) = rule {
+                let _ = write!(result, "\"ruleId\":{},", util::json_string(rule));
+            }
+            let _ = write!(
+                result,
+                "\"level\":\"{level}\",\"message\":{{\"text\":{}}}",
+                util::json_string(message)
+            );
+            let region = match (line.and_then(|n| text_line(proof, n)), fatal_span(res)) {
+                (Some((i, _)), _) => Some(format!("{{\"startLine\":{i}}}")),
+                (None, Some(span)) => Some(format!(
+                    "{{\"startLine\":{},\"startColumn\":{},\"endColumn\":{}}}",
+                    span.line, span.start_column, span.end_column
+                )),
+                (None, None) => None,
+            };
+            if let Some(region) = region {
+                let _ = write!(
+                    result,
+                    ",\"locations\":[{{\"physicalLocation\":{{\"artifactLocation\":\
+                    {{\"uri\":\"proof.txt\"}},\"region\":{region}}}}}]"
+                );
+            }
+            result.push('}');
+            result
+        };
+        let results: Vec<String> = diagnostics(res)
+            .iter()
+            .map(|(line, code, message)| result(*code, "error", message, *line))
+            .chain(
+                unchecked_lines(res)
+                    .iter()
+                    .map(|n| result(None, "note", &unchecked_note(&[*n]), Some(*n))),
+            )
+            .collect();
+        format!(
+            "{{\"$schema\":\"https://json.schemastore.org/sarif-2.1.0.json\",\"version\":\"2.1.0\",\
+            \"runs\":[{{\"tool\":{{\"driver\":{{\"name\":\"fitch-proof\"}}}},\"results\":[{}]}}]}}",
+            results.join(",")
+        )
+    }
+}
+
+impl DiagnosticsRenderer for Html {
+    fn name(&self) -> &'static str {
+        "html"
+    }
+
+    fn render(&self, res: &ProofResult, _proof: &str) -> String {
+        let mut html = format!("<div class=\"proof-result {}\">\n", verdict(res));
+        match res {
+            ProofResult::Correct => html.push_str("<p>The proof is correct!</p>\n"),
+            ProofResult::NoProof | ProofResult::FatalError(_) => {
+                let _ = writeln!(html, "<p>{}</p>", html_escape(&single_message(res)));
+            }
+            ProofResult::Error(_) | ProofResult::Unfinished(..) => {
+                html.push_str("<ul>\n");
+                for (line, code, message) in diagnostics(res) {
+                    html.push_str("<li class=\"error\"");
+                    if let Some(line) = line {
+                        let _ = write!(html, " data-line=\"{line}\"");
+                    }
+                    if let Some(code) = code {
+                        let _ = write!(html, " data-code=\"{code}\"");
+                    }
+                    let _ = writeln!(html, "><pre>{}</pre></li>", html_escape(&message));
+                }
+                html.push_str("</ul>\n");
+                if let [_, ..] = unchecked_lines(res) {
+                    let _ = writeln!(html, "<p>{}</p>", unchecked_note(unchecked_lines(res)));
+                }
+            }
+        }
+        html.push_str("</div>");
+        html
+    }
+}
+
+/* ------------------ PRIVATE -------------------- */
+
+/// Returns the mistakes in a [ProofResult]: the line number that it is about (if any), the error
+/// code (if any) and the message.
+fn diagnostics(res: &ProofResult) -> Vec<(Option<usize>, Option<&'static str>, String)> {
+    match res {
+        ProofResult::Correct => vec![],
+        ProofResult::Error(errs) | ProofResult::Unfinished(errs, _) => {
+            errs.iter().map(|err| (err.line, Some(err.code), err.to_string())).collect()
+        }
+        ProofResult::NoProof => vec![(None, Some(error_codes::NO_PROOF), single_message(res))],
+        ProofResult::FatalError(FatalError::Internal(_)) => vec![(None, None, single_message(res))],
+        ProofResult::FatalError(_) => vec![(None, Some(error_codes::FATAL), single_message(res))],
+    }
+}
+
+/// Returns the messages of the mistakes as they are shown to a student, separated by empty lines.
+fn join_errors(errs: &[ProofError]) -> String {
+    errs.iter().map(|err| err.to_string()).collect::<Vec<String>>().join("\n\n")
+}
+
+/// Returns the part of the text of the proof that a fatal error is about, see
+/// [FatalError::ParseError].
+fn fatal_span(res: &ProofResult) -> Option<TextSpan> {
+    match res {
+        ProofResult::FatalError(FatalError::ParseError(_, span)) => *span,
+        _ => None,
+    }
+}
+
+/// Returns the line numbers of the lines that are not checked yet, see
+/// [ProofResult::Unfinished].
+fn unchecked_lines(res: &ProofResult) -> &[usize] {
+    match res {
+        ProofResult::Unfinished(_, unchecked_lines) => unchecked_lines,
+        _ => &[],
+    }
+}
+
+/// Returns the message of a submission without a proof, or of a fatal or internal error.
+fn single_message(res: &ProofResult) -> String {
+    match res {
+        ProofResult::NoProof => "No proof found: the proof is empty, or it only contains \
+            whitespace and directives."
+            .to_string(),
+        ProofResult::FatalError(err @ FatalError::Internal(_)) => format!("Internal error: {err}"),
+        ProofResult::FatalError(err) => format!("Fatal error: {err}"),
+        _ => "".to_string(),
+    }
+}
+
+/// Returns the note that says that some lines are not checked yet, because they do not have a
+/// justification.
+fn unchecked_note(unchecked_lines: &[usize]) -> String {
+    match unchecked_lines {
+        [n] => format!("Line {n} does not have a justification yet, so it is not checked."),
+        [ns @ .., last] => format!(
+            "Lines {} and {last} do not have a justification yet, so they are not checked.",
+            ns.iter().map(|n| n.to_string()).collect::<Vec<String>>().join(", ")
+        ),
+        [] => "".to_string(),
+    }
+}
+
+/// Returns the number (counting from 1) and the text of the line in the text of the proof that
+/// has line number `line_num`, if there is such a line. In a proof with labels, this is the line
+/// that gets line number `line_num` (see [labels::labels_to_numbers]).
+fn text_line(proof: &str, line_num: usize) -> Option<(usize, &str)> {
+    let numbered = match labels::labels_to_numbers(proof) {
+        Ok((numbered, labels)) if !labels.is_empty() => numbered,
+        _ => proof.to_string(),
+    };
+    let i = numbered.lines().position(|line| {
+        let Some(rest) = line.trim_start().strip_prefix(&line_num.to_string()) else {
+            return false;
+        };
+        !rest.starts_with(|c: char| c.is_ascii_digit()) && !parser::is_directive(line)
+    })?;
+    Some((i + 1, proof.lines().nth(i)?))
+}
+
+/// Escapes the characters that have a special meaning in HTML.
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renderers() {
+        let proof = "1 | A\n  |----\n2 | B   Reit: 1";
+        let res = ProofResult::Error(vec![ProofError::at_line(
+            2,
+            error_codes::RULE_MISAPPLIED,
+            "Line 2: the proof rule Reit is used, but ...".to_string(),
+        )]);
+        assert_eq!(PlainText.render(&res, proof), "Line 2: the proof rule Reit is used, but ...");
+        assert_eq!(
+            FullText.render(&res, proof),
+            "Line 2: the proof rule Reit is used, but ...\n  --> line 3 of the proof:\n      \
+            2 | B   Reit: 1"
+        );
+        assert_eq!(
+            Json.render(&res, proof),
+            "{\"verdict\":\"incorrect\",\"fatal_kind\":null,\"errors\":[{\"line\":2,\
+            \"text_line\":3,\"code\":\"E_RULE_MISAPPLIED\",\"message\":\"Line 2: the proof rule \
+            Reit is used, but ...\"}],\"unchecked_lines\":[]}"
+        );
+        assert!(Sarif.render(&res, proof).contains(
+            "{\"ruleId\":\"E_RULE_MISAPPLIED\",\"level\":\"error\",\"message\":{\"text\":\"Line \
+            2: the proof rule Reit is used, but ...\"},\"locations\":[{\"physicalLocation\":\
+            {\"artifactLocation\":{\"uri\":\"proof.txt\"},\"region\":{\"startLine\":3}}}]}"
+        ));
+        assert_eq!(
+            Html.render(
+                &ProofResult::FatalError(FatalError::ConfigError("a < b".to_string())),
+                proof
+            ),
+            "<div class=\"proof-result fatal\">\n<p>Fatal error: a &lt; b</p>\n</div>"
+        );
+        let span = TextSpan {
+            line: 3,
+            start_column: 1,
+            end_column: 5,
+        };
+        let res = ProofResult::FatalError(FatalError::ParseError("oops".to_string(), Some(span)));
+        assert_eq!(
+            Json.render(&res, proof),
+            "{\"verdict\":\"fatal\",\"fatal_kind\":\"parse_error\",\"errors\":[{\"line\":null,\
+            \"text_line\":3,\"code\":\"E_FATAL\",\"message\":\"Fatal error: oops\"}],\
+            \"unchecked_lines\":[]}"
+        );
+        assert!(Sarif
+            .render(&res, proof)
+            .contains("\"region\":{\"startLine\":3,\"startColumn\":1,\"endColumn\":5}"));
+        assert_eq!(text_line("10 | A\n1 | B", 1), Some((2, "1 | B")));
+        assert_eq!(
+            text_line("(a) | A\n    |----\n(b) | A  Reit: (a)", 2),
+            Some((3, "(b) | A  Reit: (a)"))
+        );
+        assert_eq!(renderer("sarif").map(|r| r.name()), Some("sarif"));
+        assert!(renderer("xml").is_none());
+    }
+}
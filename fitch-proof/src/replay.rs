@@ -0,0 +1,152 @@
+use crate::checker::cited_lines_and_subproofs;
+use crate::data::*;
+use crate::formatter;
+use crate::util;
+
+/// One step in the construction of a proof, see [replay_events].
+#[derive(PartialEq, Debug)]
+pub enum ReplayEvent {
+    /// A subproof is opened. The associated [usize] is the depth of the lines inside it.
+    OpenSubproof(usize),
+    /// A subproof is closed. The associated [usize] is the depth of the lines inside it.
+    CloseSubproof(usize),
+    /// The Fitch bar below the premises or the assumption(s) of a subproof appears. The associated
+    /// [usize] is its depth.
+    FitchBar(usize),
+    /// A line with a line number appears.
+    Line {
+        line_num: usize,
+        depth: usize,
+        kind: LineKind,
+        /// The boxed constant that the line introduces, if any.
+        boxed_constant: Option<String>,
+        sentence: Option<String>,
+        justification: Option<String>,
+        /// The lines that the justification cites.
+        cited_lines: Vec<usize>,
+        /// The subproofs that the justification cites, as (first line, last line).
+        cited_subproofs: Vec<(usize, usize)>,
+    },
+}
+
+/// What kind of line a [ReplayEvent::Line] is.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum LineKind {
+    /// A premise of the proof (above the first Fitch bar).
+    Premise,
+    /// An assumption of a subproof (above the Fitch bar of the subproof).
+    Assumption,
+    /// Any other line, whether it has a justification or not.
+    Inference,
+}
+
+/// Returns the steps in which a proof can be shown one by one, e.g. to animate its construction
+/// in a lecture: the lines appear from top to bottom, and the subproofs are opened and closed
+/// around them. Every line comes with the lines and subproofs that it cites, so that these can be
+/// highlighted when the line appears. Any subproofs that are still open after the last line are
+/// closed at the end.
+///
+/// The proof does not need to be correct.
+pub fn replay_events(proof: &[ProofLine]) -> Vec<ReplayEvent> {
+    let mut events = vec![];
+    let mut prev_depth = 1;
+    let mut is_hypo = true;
+    for line in proof {
+        while line.depth > prev_depth {
+            prev_depth += 1;
+            is_hypo = true;
+            events.push(ReplayEvent::OpenSubproof(prev_depth));
+        }
+        while line.depth < prev_depth {
+            events.push(ReplayEvent::CloseSubproof(prev_depth));
+            prev_depth -= 1;
+        }
+        if let Some(line_num) = line.line_num {
+            let kind = match (is_hypo, line.depth) {
+                (true, 1) => LineKind::Premise,
+                (true, _) => LineKind::Assumption,
+                (false, _) => LineKind::Inference,
+            };
+            let (cited_lines, cited_subproofs) =
+                line.justification.as_ref().map(cited_lines_and_subproofs).unwrap_or_default();
+            events.push(ReplayEvent::Line {
+                line_num,
+                depth: line.depth,
+                kind,
+                boxed_constant: line
+                    .constant_between_square_brackets
+                    .as_ref()
+                    .map(formatter::format_term),
+                sentence: line.sentence.as_ref().map(formatter::format_wff),
+                justification: formatter::format_line_justification(line),
+                cited_lines,
+                cited_subproofs,
+            });
+        }
+        if line.is_fitch_bar_line {
+            is_hypo = false;
+            events.push(ReplayEvent::FitchBar(line.depth));
+        }
+    }
+    while prev_depth > 1 {
+        events.push(ReplayEvent::CloseSubproof(prev_depth));
+        prev_depth -= 1;
+    }
+    events
+}
+
+impl ReplayEvent {
+    /// Returns the event as a JSON object, for example:
+    ///
+    /// `{"event":"open_subproof","depth":2}` or
+    ///
+    /// `{"event":"line","line":3,"depth":2,"kind":"inference","boxed_constant":null,
+    /// "sentence":"A","justification":"∧ Elim: 1","cited_lines":[1],"cited_subproofs":[]}`
+    ///
+    /// where `kind` is `premise`, `assumption` or `inference`, and the cited subproofs are
+    /// written like `[[2,4]]`.
+    pub fn to_json(&self) -> String {
+        let optional =
+            |s: &Option<String>| s.as_deref().map_or("null".to_string(), util::json_string);
+        match self {
+            ReplayEvent::OpenSubproof(depth) => {
+                format!("{{\"event\":\"open_subproof\",\"depth\":{depth}}}")
+            }
+            ReplayEvent::CloseSubproof(depth) => {
+                format!("{{\"event\":\"close_subproof\",\"depth\":{depth}}}")
+            }
+            ReplayEvent::FitchBar(depth) => {
+                format!("{{\"event\":\"fitch_bar\",\"depth\":{depth}}}")
+            }
+            ReplayEvent::Line {
+                line_num,
+                depth,
+                kind,
+                boxed_constant,
+                sentence,
+                justification,
+                cited_lines,
+                cited_subproofs,
+            } => {
+                let kind = match kind {
+                    LineKind::Premise => "premise",
+                    LineKind::Assumption => "assumption",
+                    LineKind::Inference => "inference",
+                };
+                let cited_lines: Vec<String> = cited_lines.iter().map(|n| n.to_string()).collect();
+                let cited_subproofs: Vec<String> =
+                    cited_subproofs.iter().map(|(a, b)| format!("[{a},{b}]")).collect();
+                format!(
+                    "{{\"event\":\"line\",\"line\":{line_num},\"depth\":{depth},\"kind\":\"{kind}\",\
+                    \"boxed_constant\":{},\"sentence\":{},\"justification\":{},\
+                    \"cited_lines\":[{}],\"cited_subproofs\":[{}]}}",
+                    optional(boxed_constant),
+                    optional(sentence),
+                    optional(justification),
+                    cited_lines.join(","),
+                    cited_subproofs.join(","),
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use crate::formatter;
+use crate::lint;
+use crate::proof::*;
+use crate::util;
+use std::collections::BTreeMap;
+
+/// Statistics about a single proof, which can be shown to a student as a "score card" after they
+/// finished the proof: how long the proof is (compared to the shortest known proof, if the
+/// template gives its length), which rules are used, how deep the subproofs are nested, and the
+/// style warnings (see [lint::lint]).
+#[derive(PartialEq, Debug)]
+pub struct ScoreCard {
+    /// The number of lines with a line number, including the premises.
+    pub lines: usize,
+    /// The number of lines of the shortest known proof, see [crate::parser::parse_template].
+    pub minimal_length: Option<usize>,
+    /// For every rule that is used, the number of times it is used.
+    pub rules: BTreeMap<String, usize>,
+    /// The greatest number of subproofs that some line is in, so 0 if there are no subproofs.
+    pub nesting_depth: usize,
+    pub warnings: Vec<String>,
+}
+
+impl ScoreCard {
+    /// Computes the score card of a proof. This does not check whether the proof is correct.
+    pub fn new(proof: &Proof, minimal_length: Option<usize>) -> ScoreCard {
+        let mut rules: BTreeMap<String, usize> = BTreeMap::new();
+        for just in proof.lines.iter().filter_map(|line| line.justification.as_ref()) {
+            *rules.entry(formatter::rule_name(just)).or_default() += 1;
+        }
+        ScoreCard {
+            lines: proof.lines.iter().filter(|line| line.line_num.is_some()).count(),
+            minimal_length,
+            rules,
+            nesting_depth: proof.lines.iter().map(|line| line.depth).max().unwrap_or(1) - 1,
+            warnings: lint::lint(proof),
+        }
+    }
+
+    /// Returns a friendly summary of the score card, in a few sentences.
+    pub fn summary(&self) -> String {
+        let lines = plural(self.lines, "line", "lines");
+        let mut summary = match self.minimal_length {
+            Some(min) if self.lines < min => format!(
+                "Well done! Your proof has {lines}, so it is even shorter than the shortest proof \
+                we know of, which has {}.",
+                plural(min, "line", "lines")
+            ),
+            Some(min) if self.lines == min => format!(
+                "Well done! Your proof has {lines}, just like the shortest proof we know of."
+            ),
+            Some(min) => format!(
+                "Well done! Your proof has {lines}. The shortest proof we know of has {}.",
+                plural(min, "line", "lines")
+            ),
+            None => format!("Well done! Your proof has {lines}."),
+        };
+        let rules = plural(self.rules.len(), "different rule", "different rules");
+        summary.push_str(&match self.nesting_depth {
+            0 => format!(" It uses {rules}, and no subproofs."),
+            depth => format!(
+                " It uses {rules}, and subproofs that are nested at most {} deep.",
+                plural(depth, "level", "levels")
+            ),
+        });
+        if !self.warnings.is_empty() {
+            let warnings = plural(self.warnings.len(), "style warning", "style warnings");
+            summary.push_str(&format!(" There is still room for improvement: {warnings}."));
+        }
+        summary
+    }
+
+    /// Returns the score card as a JSON object, for example:
+    ///
+    /// `{"lines":4,"minimal_length":3,"rules":{"∧Elim":2,"∧Intro":1},"nesting_depth":0,
+    /// "warnings":[],"summary":"Well done! ..."}`
+    ///
+    /// where `minimal_length` is `null` if it is not known.
+    pub fn to_json(&self) -> String {
+        let rules: Vec<String> = self
+            .rules
+            .iter()
+            .map(|(rule, count)| format!("{}:{count}", util::json_string(rule)))
+            .collect();
+        let warnings: Vec<String> = self.warnings.iter().map(|w| util::json_string(w)).collect();
+        format!(
+            "{{\"lines\":{},\"minimal_length\":{},\"rules\":{{{}}},\"nesting_depth\":{},\
+            \"warnings\":[{}],\"summary\":{}}}",
+            self.lines,
+            self.minimal_length.map_or("null".to_string(), |min| min.to_string()),
+            rules.join(","),
+            self.nesting_depth,
+            warnings.join(","),
+            util::json_string(&self.summary()),
+        )
+    }
+}
+
+/* ------------------ PRIVATE -------------------- */
+
+/// Returns `n` followed by the singular or the plural, e.g. `1 line` or `3 lines`.
+fn plural(n: usize, singular: &str, plural: &str) -> String {
+    format!(
+        "{n} {}",
+        if n == 1 {
+            singular
+        } else {
+            plural
+        }
+    )
+}
@@ -0,0 +1,206 @@
+use crate::checker::cited_lines_and_subproofs;
+use crate::data::*;
+use crate::formatter::{format_term, format_wff, rule_name};
+
+/// Returns a short outline of a proof in words, e.g. as a first draft of the feedback on a
+/// student's proof. Every step that uses subproofs (like →Intro or ∨Elim) is described in one
+/// sentence, and below it (indented) the steps that use subproofs inside these subproofs, and so
+/// on. The outline ends with the other rules that are used, and how often. For example:
+///
+/// ```notrust
+/// The proof has 1 premise and ends with B ∨ A in line 6.
+/// Line 6 (B ∨ A) is proved by cases on line 1 (A ∨ B):
+///   the first case assumes A in line 2 and derives B ∨ A in line 3;
+///   the second case assumes B in line 4 and derives B ∨ A in line 5.
+/// The other steps use ∨Intro (2 times).
+/// ```
+///
+/// The proof does not need to be correct: the outline says what the proof does, not whether it
+/// does it correctly.
+pub fn summarize_proof(proof: &[ProofLine]) -> String {
+    let num_premises = proof
+        .iter()
+        .take_while(|l| !l.is_fitch_bar_line)
+        .filter(|l| l.depth == 1 && l.line_num.is_some())
+        .count();
+    let premises = match num_premises {
+        0 => "no premises".to_string(),
+        1 => "1 premise".to_string(),
+        n => format!("{n} premises"),
+    };
+    let last = proof.iter().rev().find(|l| l.line_num.is_some() && l.sentence.is_some());
+    let mut res = match last.and_then(|l| l.line_num) {
+        Some(n) => {
+            vec![format!("The proof has {premises} and ends with {}.", sentence_at(proof, n))]
+        }
+        None => vec![format!("The proof has {premises} and no other sentences.")],
+    };
+
+    let header_len = res.len();
+    outline(proof, 1, (1, usize::MAX), 0, &mut res);
+    let has_outline = res.len() > header_len;
+
+    let mut rules: Vec<(String, usize)> = vec![];
+    for just in proof.iter().filter_map(|l| l.justification.as_ref()) {
+        if !cited_lines_and_subproofs(just).1.is_empty() {
+            continue;
+        }
+        let rule = rule_name(just);
+        match rules.iter_mut().find(|(r, _)| *r == rule) {
+            Some((_, count)) => *count += 1,
+            None => rules.push((rule, 1)),
+        }
+    }
+    let rules: Vec<String> = rules
+        .into_iter()
+        .map(|(rule, count)| match count {
+            1 => format!("{rule} (once)"),
+            n => format!("{rule} ({n} times)"),
+        })
+        .collect();
+    if let Some((last_rule, other_rules)) = rules.split_last() {
+        let rules = match other_rules {
+            [] => last_rule.clone(),
+            _ => format!("{} and {last_rule}", other_rules.join(", ")),
+        };
+        if has_outline {
+            res.push(format!("The other steps use {rules}."));
+        } else {
+            res.push(format!("The steps use {rules}."));
+        }
+    }
+    res.join("\n")
+}
+
+/* ------------------ PRIVATE -------------------- */
+
+/// Adds the description of every step at `depth` between the lines `first` and `last` (inclusive)
+/// that uses subproofs to `res`, each followed by the outline of its subproofs. The descriptions
+/// are indented by `indent` levels.
+fn outline(
+    proof: &[ProofLine],
+    depth: usize,
+    (first, last): (usize, usize),
+    indent: usize,
+    res: &mut Vec<String>,
+) {
+    for line in proof.iter().filter(|l| l.depth == depth) {
+        let (Some(n), Some(just)) = (line.line_num, &line.justification) else {
+            continue;
+        };
+        if n < first || n > last {
+            continue;
+        }
+        let subproofs = cited_lines_and_subproofs(just).1;
+        if subproofs.is_empty() {
+            continue;
+        }
+        let step = format!("Line {n} ({})", sentence_or_blank(proof, n));
+        let pad = "  ".repeat(indent);
+        match just {
+            Justification::ImpliesIntro((a, b)) => res.push(format!(
+                "{pad}{step} is proved by assuming {} and deriving {}.",
+                sentence_at(proof, *a),
+                sentence_at(proof, *b)
+            )),
+            Justification::NotIntro((a, b)) => res.push(format!(
+                "{pad}{step} is proved by contradiction: assuming {} leads to {}.",
+                sentence_at(proof, *a),
+                sentence_at(proof, *b)
+            )),
+            Justification::ForallIntro((a, b)) => res.push(format!(
+                "{pad}{step} is proved for an arbitrary {} by deriving {}.",
+                sentence_at(proof, *a),
+                sentence_at(proof, *b)
+            )),
+            Justification::ExistsElim(m, (a, b)) => res.push(format!(
+                "{pad}{step} is proved by taking a witness for line {m} ({}): assuming {}, it \
+                derives {}.",
+                sentence_or_blank(proof, *m),
+                sentence_at(proof, *a),
+                sentence_at(proof, *b)
+            )),
+            Justification::BicondIntro((a, b), (c, d)) => {
+                res.push(format!("{pad}{step} is proved in both directions:"));
+                res.push(format!(
+                    "{pad}  from {} to {};",
+                    sentence_at(proof, *a),
+                    sentence_at(proof, *b)
+                ));
+                res.push(format!(
+                    "{pad}  from {} to {}.",
+                    sentence_at(proof, *c),
+                    sentence_at(proof, *d)
+                ));
+            }
+            Justification::OrElim(m, cases) => {
+                res.push(format!(
+                    "{pad}{step} is proved by cases on line {m} ({}):",
+                    sentence_or_blank(proof, *m)
+                ));
+                for (i, (a, b)) in cases.iter().enumerate() {
+                    let end = match cases.len() - i {
+                        1 => ".",
+                        _ => ";",
+                    };
+                    res.push(format!(
+                        "{pad}  the {} case assumes {} and derives {}{end}",
+                        ordinal(i + 1),
+                        sentence_at(proof, *a),
+                        sentence_at(proof, *b)
+                    ));
+                }
+            }
+            _ => res.push(format!("{pad}{step} is proved with {}.", rule_name(just))),
+        }
+        for (a, b) in subproofs {
+            // the depth only increases, so this always ends
+            let first_line = proof.iter().find(|l| l.line_num == Some(a));
+            if let Some(l) = first_line.filter(|l| l.depth > depth) {
+                outline(proof, l.depth, (a, b), indent + 1, res);
+            }
+        }
+    }
+}
+
+/// Returns the boxed constant (if any) and the sentence in line `n`, followed by the line number,
+/// like `[a] P(a) in line 4`. If there is no line `n`, then only the line number is returned.
+fn sentence_at(proof: &[ProofLine], n: usize) -> String {
+    let Some(line) = proof.iter().find(|l| l.line_num == Some(n)) else {
+        return format!("line {n}");
+    };
+    let contents = match (&line.constant_between_square_brackets, &line.sentence) {
+        (Some(c), Some(wff)) => format!("[{}] {}", format_term(c), format_wff(wff)),
+        (Some(c), None) => format_term(c),
+        (None, Some(wff)) => format_wff(wff),
+        (None, None) => return format!("line {n}"),
+    };
+    format!("{contents} in line {n}")
+}
+
+/// Returns the sentence in line `n`, or `...` if there is no such sentence.
+fn sentence_or_blank(proof: &[ProofLine], n: usize) -> String {
+    proof
+        .iter()
+        .find(|l| l.line_num == Some(n))
+        .and_then(|l| l.sentence.as_ref())
+        .map_or("...".to_string(), format_wff)
+}
+
+/// Returns `first`, `second`, ... for `1`, `2`, ..., and `11th`, `12th`, ... from `11` on.
+fn ordinal(i: usize) -> String {
+    const ORDINALS: [&str; 10] = [
+        "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth",
+        "tenth",
+    ];
+    if let Some(ordinal) = ORDINALS.get(i.saturating_sub(1)) {
+        return ordinal.to_string();
+    }
+    let suffix = match (i % 10, i % 100) {
+        (1, r) if r != 11 => "st",
+        (2, r) if r != 12 => "nd",
+        (3, r) if r != 13 => "rd",
+        _ => "th",
+    };
+    format!("{i}{suffix}")
+}
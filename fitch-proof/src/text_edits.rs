@@ -0,0 +1,234 @@
+use crate::util;
+
+/// A position in a text document, in the same way as the Language Server Protocol does it: a
+/// zero-based line number, and a zero-based offset in that line, counted in UTF-16 code units
+/// (which is also how JavaScript counts the length of strings).
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct TextPosition {
+    pub line: usize,
+    pub character: usize,
+}
+
+/// A change to a text document: the text between [TextEdit::start] and [TextEdit::end] should be
+/// replaced by [TextEdit::new_text].
+///
+/// A list of [TextEdit]s (as returned by [text_edits_for_reformatted_lines]) never contains
+/// overlapping edits, and all positions in it refer to the *original* document, so the edits can
+/// be applied at once (like an LSP `WorkspaceEdit`), or one by one starting from the last one.
+#[derive(PartialEq, Debug, Clone)]
+pub struct TextEdit {
+    pub start: TextPosition,
+    pub end: TextPosition,
+    pub new_text: String,
+}
+
+impl TextEdit {
+    /// Returns this edit as a JSON object, in the shape of an LSP `TextEdit`:
+    /// `{"range":{"start":{"line":0,"character":2},"end":{...}},"newText":"..."}`.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"range\":{{\"start\":{{\"line\":{},\"character\":{}}},\"end\":{{\"line\":{},\"character\":{}}}}},\"newText\":{}}}",
+            self.start.line,
+            self.start.character,
+            self.end.line,
+            self.end.character,
+            util::json_string(&self.new_text)
+        )
+    }
+}
+
+/// Given a document `old` and the result `new` of reformatting it, this function returns a list
+/// of small [TextEdit]s that turn `old` into `new`.
+///
+/// This function assumes that `new` was made from `old` like the formatter does it: every line of
+/// `old` that is not empty corresponds to one line of `new` (in the same order), and the empty
+/// lines of `old` are removed. Since only the parts of the lines that actually change are
+/// replaced, an editor that applies these edits can keep the cursor position, the undo history,
+/// and so on. If `old` and `new` do not correspond in this way, then one edit replacing the whole
+/// document is returned.
+pub fn text_edits_for_reformatted_lines(old: &str, new: &str) -> Vec<TextEdit> {
+    let old_lines: Vec<&str> = old.split('\n').collect();
+    let new_lines: Vec<&str> = new.split('\n').collect();
+    let mapped_old_lines: Vec<usize> =
+        (0..old_lines.len()).filter(|i| !matches!(old_lines[*i], "" | "\r")).collect();
+
+    let end_of_document = TextPosition {
+        line: old_lines.len() - 1,
+        character: utf16_len(old_lines[old_lines.len() - 1]),
+    };
+    if mapped_old_lines.len() != new_lines.len() || mapped_old_lines.is_empty() {
+        if old == new {
+            return vec![];
+        }
+        return vec![TextEdit {
+            start: TextPosition {
+                line: 0,
+                character: 0,
+            },
+            end: end_of_document,
+            new_text: new.to_string(),
+        }];
+    }
+
+    let mut edits: Vec<TextEdit> = vec![];
+
+    // empty lines before the first non-empty line
+    if mapped_old_lines[0] > 0 {
+        edits.push(TextEdit {
+            start: TextPosition {
+                line: 0,
+                character: 0,
+            },
+            end: TextPosition {
+                line: mapped_old_lines[0],
+                character: 0,
+            },
+            new_text: "".to_string(),
+        });
+    }
+
+    for (k, &i) in mapped_old_lines.iter().enumerate() {
+        if let Some(edit) = line_edit(i, old_lines[i], new_lines[k]) {
+            edits.push(edit);
+        }
+        // empty lines between this line and the next non-empty line (or the end of the document)
+        let next = mapped_old_lines.get(k + 1).copied();
+        if next.map_or(i + 1 < old_lines.len(), |next| next > i + 1) {
+            edits.push(TextEdit {
+                start: TextPosition {
+                    line: i,
+                    character: utf16_len(old_lines[i]),
+                },
+                end: match next {
+                    Some(next) => TextPosition {
+                        line: next - 1,
+                        character: 0,
+                    },
+                    None => end_of_document,
+                },
+                new_text: "".to_string(),
+            })
+        }
+    }
+
+    merge_adjacent_edits(edits)
+}
+
+/* ------------------ PRIVATE -------------------- */
+
+/// Returns the smallest edit that turns line number `line` with contents `old` into `new`, or
+/// [None] if they are already equal.
+fn line_edit(line: usize, old: &str, new: &str) -> Option<TextEdit> {
+    if old == new {
+        return None;
+    }
+    let old_chars: Vec<char> = old.chars().collect();
+    let new_chars: Vec<char> = new.chars().collect();
+    let prefix_len = old_chars.iter().zip(&new_chars).take_while(|(a, b)| a == b).count();
+    let suffix_len = old_chars[prefix_len..]
+        .iter()
+        .rev()
+        .zip(new_chars[prefix_len..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let utf16_offset = |chars: &[char]| chars.iter().map(|c| c.len_utf16()).sum::<usize>();
+    Some(TextEdit {
+        start: TextPosition {
+            line,
+            character: utf16_offset(&old_chars[..prefix_len]),
+        },
+        end: TextPosition {
+            line,
+            character: utf16_offset(&old_chars[..old_chars.len() - suffix_len]),
+        },
+        new_text: new_chars[prefix_len..new_chars.len() - suffix_len].iter().collect(),
+    })
+}
+
+/// Merges edits that touch each other (the end of one is the start of the next) into one edit,
+/// so that the resulting list certainly contains no edits sharing a position.
+fn merge_adjacent_edits(edits: Vec<TextEdit>) -> Vec<TextEdit> {
+    let mut merged: Vec<TextEdit> = vec![];
+    for edit in edits {
+        match merged.last_mut() {
+            Some(last) if last.end == edit.start => {
+                last.end = edit.end;
+                last.new_text.push_str(&edit.new_text);
+            }
+            _ => merged.push(edit),
+        }
+    }
+    merged
+}
+
+/// Returns the length of a string in UTF-16 code units.
+fn utf16_len(s: &str) -> usize {
+    s.chars().map(|c| c.len_utf16()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Applies edits (as returned by [text_edits_for_reformatted_lines]) to a document.
+    fn apply_edits(doc: &str, edits: &[TextEdit]) -> String {
+        let offset = |pos: &TextPosition| -> usize {
+            let line_start: usize =
+                doc.split('\n').take(pos.line).map(|l| l.len() + 1).sum::<usize>();
+            let line = doc.split('\n').nth(pos.line).unwrap();
+            let mut units = 0;
+            let mut bytes = 0;
+            for c in line.chars() {
+                if units >= pos.character {
+                    break;
+                }
+                units += c.len_utf16();
+                bytes += c.len_utf8();
+            }
+            line_start + bytes
+        };
+        let mut res = doc.to_string();
+        for edit in edits.iter().rev() {
+            res.replace_range(offset(&edit.start)..offset(&edit.end), &edit.new_text);
+        }
+        res
+    }
+
+    #[test]
+    fn test_text_edits_1() {
+        let old = "\n1 | A\n  |---\n2 | A   Reit:1\n";
+        let new = "1 | A\n  |----\n2 | A Reit: 1";
+        let edits = text_edits_for_reformatted_lines(old, new);
+        assert_eq!(apply_edits(old, &edits), new);
+        assert_eq!(
+            edits[0],
+            TextEdit {
+                start: TextPosition {
+                    line: 0,
+                    character: 0
+                },
+                end: TextPosition {
+                    line: 1,
+                    character: 0
+                },
+                new_text: "".to_string()
+            }
+        );
+        assert_eq!(edits.len(), 4);
+    }
+
+    #[test]
+    fn test_text_edits_2() {
+        let old = "1 | ∀x P(x)\r\n\r\n\n  |---\n2 | P(a)  ∀Elim:1";
+        let new = "1 | ∀x P(x)\n  |----\n2 | P(a)  ∀ Elim: 1";
+        assert_eq!(apply_edits(old, &text_edits_for_reformatted_lines(old, new)), new);
+        assert_eq!(text_edits_for_reformatted_lines(new, new), vec![]);
+    }
+
+    #[test]
+    fn test_text_edits_fallback() {
+        let old = "1 | A\n2 | B";
+        let new = "1 | A";
+        assert_eq!(apply_edits(old, &text_edits_for_reformatted_lines(old, new)), new);
+    }
+}
@@ -1,4 +1,8 @@
+#[cfg(any(test, feature = "tutoring"))]
 use std::cmp::Ordering;
+#[cfg(any(test, feature = "bundle"))]
+use std::collections::BTreeMap;
+#[cfg(any(test, feature = "tutoring"))]
 use std::iter::{self, from_fn};
 
 /// Sort a list of strings in a "human-friendly way". See examples...
@@ -13,10 +17,11 @@ use std::iter::{self, from_fn};
 ///      ["helloh", "hello2", "hello", "hello11", "hello1", "hello100", "42", "hello1000"];
 ///  let sorted =
 ///      ["42", "hello", "hello1", "hello2", "hello11", "hello100", "hello1000", "helloh"];
-
+///
 ///  natural_sort(&mut unsorted);
 ///  assert_eq!(sorted, unsorted);
 /// ```
+#[cfg(any(test, feature = "tutoring"))]
 pub fn natural_sort<T: AsRef<str>>(strings: &mut [T]) {
     strings.sort_by(|s1, s2| {
         // I'm pretty sure this is a total order relation ;)
@@ -54,6 +59,214 @@ pub fn natural_sort<T: AsRef<str>>(strings: &mut [T]) {
     })
 }
 
+/// Turns a string into a JSON string literal (including the surrounding double quotes), escaping
+/// all characters that need to be escaped.
+pub fn json_string(s: &str) -> String {
+    let mut res = String::with_capacity(s.len() + 2);
+    res.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c if (c as u32) < 0x20 => res.push_str(&format!("\\u{:04x}", c as u32)),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+/// The inverse of [json_string]: turns a JSON string literal (including the surrounding double
+/// quotes) back into a string. Returns [None] if `s` is not a JSON string literal.
+pub fn parse_json_string(s: &str) -> Option<String> {
+    let mut chars = s.strip_prefix('"')?.strip_suffix('"')?.chars();
+    let mut res = String::with_capacity(s.len());
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                '"' => res.push('"'),
+                '\\' => res.push('\\'),
+                '/' => res.push('/'),
+                'n' => res.push('\n'),
+                'r' => res.push('\r'),
+                't' => res.push('\t'),
+                'u' => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    res.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+                }
+                _ => return None,
+            },
+            '"' => return None,
+            c => res.push(c),
+        }
+    }
+    Some(res)
+}
+
+/// Splits the JSON string literal at the start of `s` (including the surrounding double quotes)
+/// from the rest of `s`. Returns [None] if `s` does not start with a string literal that ends.
+pub fn split_json_string(s: &str) -> Option<(&str, &str)> {
+    // the string ends at the first double quote that is not escaped
+    let mut escaped = false;
+    let end = s.strip_prefix('"')?.find(|c| {
+        let is_end = c == '"' && !escaped;
+        escaped = c == '\\' && !escaped;
+        is_end
+    })? + 2;
+    Some(s.split_at(end))
+}
+
+/// A JSON value, as read by [parse_json]. Numbers are kept as they are written, since the JSON
+/// files that are read by this crate only contain small integers (if any).
+#[cfg(any(test, feature = "bundle"))]
+#[derive(PartialEq, Debug, Clone)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(BTreeMap<String, JsonValue>),
+}
+
+/// The greatest depth of the arrays and objects in a JSON document that [parse_json] reads, so
+/// that a deeply nested document cannot overflow the stack.
+#[cfg(any(test, feature = "bundle"))]
+const MAX_JSON_DEPTH: usize = 128;
+
+/// Parses a JSON document. If `s` is not valid JSON (or an object contains the same key twice, or
+/// the arrays and objects are nested too deeply), an error message is returned.
+#[cfg(any(test, feature = "bundle"))]
+pub fn parse_json(s: &str) -> Result<JsonValue, String> {
+    let mut rest = s.trim_start();
+    let value = parse_json_value(&mut rest, 0)?;
+    if !rest.trim().is_empty() {
+        return Err(json_error(rest.trim_start(), "expected the end of the JSON document"));
+    }
+    Ok(value)
+}
+
+/// Parses one JSON value at the start of `rest` (which does not start with whitespace), and moves
+/// `rest` to after the value and the whitespace after it. The value is nested in `depth` arrays and
+/// objects.
+#[cfg(any(test, feature = "bundle"))]
+fn parse_json_value(rest: &mut &str, depth: usize) -> Result<JsonValue, String> {
+    if depth >= MAX_JSON_DEPTH && (rest.starts_with('{') || rest.starts_with('[')) {
+        return Err(json_error(rest, "the arrays and objects are nested too deeply"));
+    }
+    let value = if let Some(after) = rest.strip_prefix('{') {
+        *rest = after.trim_start();
+        let mut object = BTreeMap::new();
+        while !rest.starts_with('}') {
+            if !object.is_empty() {
+                *rest =
+                    rest.strip_prefix(',').ok_or_else(|| json_error(rest, "expected , or }"))?;
+                *rest = rest.trim_start();
+            }
+            let JsonValue::String(key) = parse_json_value(rest, depth + 1)? else {
+                return Err(json_error(rest, "expected a string as the key of an object"));
+            };
+            *rest = rest.strip_prefix(':').ok_or_else(|| json_error(rest, "expected :"))?;
+            *rest = rest.trim_start();
+            let value = parse_json_value(rest, depth + 1)?;
+            if object.insert(key.clone(), value).is_some() {
+                return Err(format!(
+                    "the key {} occurs more than once in an object",
+                    json_string(&key)
+                ));
+            }
+        }
+        *rest = &rest[1..];
+        JsonValue::Object(object)
+    } else if let Some(after) = rest.strip_prefix('[') {
+        *rest = after.trim_start();
+        let mut array = vec![];
+        while !rest.starts_with(']') {
+            if !array.is_empty() {
+                *rest =
+                    rest.strip_prefix(',').ok_or_else(|| json_error(rest, "expected , or ]"))?;
+                *rest = rest.trim_start();
+            }
+            array.push(parse_json_value(rest, depth + 1)?);
+        }
+        *rest = &rest[1..];
+        JsonValue::Array(array)
+    } else if rest.starts_with('"') {
+        let (literal, after) =
+            split_json_string(rest).ok_or_else(|| json_error(rest, "unterminated string"))?;
+        let s = parse_json_string(literal).ok_or_else(|| json_error(rest, "invalid string"))?;
+        *rest = after;
+        JsonValue::String(s)
+    } else {
+        let end = rest.find(|c: char| !(c.is_ascii_alphanumeric() || "+-.".contains(c)));
+        let (word, after) = rest.split_at(end.unwrap_or(rest.len()));
+        let value = match word {
+            "null" => JsonValue::Null,
+            "true" => JsonValue::Bool(true),
+            "false" => JsonValue::Bool(false),
+            _ if word.parse::<f64>().is_ok() => JsonValue::Number(word.to_string()),
+            _ => return Err(json_error(rest, "expected a JSON value")),
+        };
+        *rest = after;
+        value
+    };
+    *rest = rest.trim_start();
+    Ok(value)
+}
+
+/// An error message for a JSON document that cannot be parsed, which shows where the problem is.
+#[cfg(any(test, feature = "bundle"))]
+fn json_error(rest: &str, problem: &str) -> String {
+    match rest.chars().take(20).collect::<String>() {
+        near if near.is_empty() => format!("invalid JSON: {problem}, but the document ends"),
+        near => format!("invalid JSON: {problem}, near '{near}'"),
+    }
+}
+
+/// The 64-bit FNV-1a hash of some bytes. Unlike the hashers in the standard library, this hash
+/// is guaranteed to stay the same between versions of Rust, so it can be stored in files.
+pub fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| (hash ^ *b as u64).wrapping_mul(0x100000001b3))
+}
+
+/// A small pseudo-random number generator (xorshift64*). It is not good enough for anything
+/// serious, but it is good enough for generating test cases and variants of exercises, and it is
+/// the same on all platforms, so the same seed always gives the same results.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        // the state should never be zero
+        Rng(seed.wrapping_mul(0x9e3779b97f4a7c15) | 1)
+    }
+
+    /// Returns a random number in `0..n`.
+    ///
+    /// PRECONDITION (panics otherwise): n > 0
+    pub fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        (self.0.wrapping_mul(0x2545f4914f6cdd1d) >> 32) as usize % n
+    }
+
+    /// Returns a random element of a non-empty slice.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn choose<'a, T>(&mut self, elements: &'a [T]) -> &'a T {
+        &elements[self.below(elements.len())]
+    }
+
+    /// Puts the elements of a slice in a random order (with the Fisher-Yates shuffle).
+    pub fn shuffle<T>(&mut self, elements: &mut [T]) {
+        for i in (1..elements.len()).rev() {
+            elements.swap(i, self.below(i + 1));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -67,4 +280,64 @@ mod tests {
         natural_sort(&mut unsorted);
         assert_eq!(sorted, unsorted);
     }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("Line 1: \"∧\"\n\\"), "\"Line 1: \\\"∧\\\"\\n\\\\\"");
+    }
+
+    #[test]
+    fn test_parse_json_string() {
+        for s in ["", "Line 1: \"∧\"\n\\", "a\u{1}b\tc\r"] {
+            assert_eq!(parse_json_string(&json_string(s)).as_deref(), Some(s));
+        }
+        assert_eq!(parse_json_string("\"a\\u0041\""), Some("aA".to_string()));
+        assert_eq!(parse_json_string("abc"), None);
+        assert_eq!(parse_json_string("\"a\"b\""), None);
+        assert_eq!(parse_json_string("\"a\\\""), None);
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let json = parse_json(
+            " {\"a\": [1, -2.5e3, true, null], \"b\" : {\"c\":\"d\\\"e\\\\\"}, \"f\":[]} ",
+        );
+        assert_eq!(
+            json,
+            Ok(JsonValue::Object(BTreeMap::from([
+                (
+                    "a".to_string(),
+                    JsonValue::Array(vec![
+                        JsonValue::Number("1".to_string()),
+                        JsonValue::Number("-2.5e3".to_string()),
+                        JsonValue::Bool(true),
+                        JsonValue::Null
+                    ])
+                ),
+                (
+                    "b".to_string(),
+                    JsonValue::Object(BTreeMap::from([(
+                        "c".to_string(),
+                        JsonValue::String("d\"e\\".to_string())
+                    )]))
+                ),
+                ("f".to_string(), JsonValue::Array(vec![])),
+            ])))
+        );
+        for invalid in
+            ["", "{", "[1,]", "[1 2]", "{\"a\":1,\"a\":2}", "{a:1}", "\"abc", "nul", "1 2"]
+        {
+            assert!(parse_json(invalid).is_err(), "{invalid}");
+        }
+        let nested = |depth| "[".repeat(depth) + &"]".repeat(depth);
+        assert!(parse_json(&nested(MAX_JSON_DEPTH)).is_ok());
+        assert!(parse_json(&nested(MAX_JSON_DEPTH + 1)).is_err());
+        assert!(parse_json(&"{\"a\":".repeat(100_000)).is_err());
+    }
+
+    #[test]
+    fn test_fnv1a_hash() {
+        assert_eq!(fnv1a_hash(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a_hash(b"a"), 0xaf63dc4c8601ec8c);
+    }
 }
@@ -2595,3 +2595,1885 @@ fn test_exists_elim_10(){
 ";
     assert!(proof_is_not_correct_ultra_pedantic(proof));
 }
+#[test]
+fn test_sequent_1() {
+    assert!(fitch_proof::sequent_is_correct("A, B ⊢ A ∧ B by ∧Intro"));
+    assert!(!fitch_proof::sequent_is_correct("A, B ⊢ B ∧ A by ∧Intro"));
+    assert!(fitch_proof::sequent_is_correct("A ∧ B ⊢ B by ∧Elim"));
+    assert!(fitch_proof::sequent_is_correct("⊢ a = a by =Intro"));
+    assert!(!fitch_proof::sequent_is_correct("⊢ a = b by =Intro"));
+}
+#[test]
+fn test_sequent_2() {
+    // the order of the premises does not matter for rules citing two lines
+    assert!(fitch_proof::sequent_is_correct("A→B, A ⊢ B by →Elim"));
+    assert!(fitch_proof::sequent_is_correct("A, A→B ⊢ B by →Elim"));
+    assert!(fitch_proof::sequent_is_correct("¬A, A ⊢ ⊥ by ⊥Intro"));
+    assert!(!fitch_proof::sequent_is_correct("A, A→B ⊢ A by →Elim"));
+}
+#[test]
+fn test_sequent_3() {
+    assert!(fitch_proof::sequent_is_correct("∀x P(x,f(x)) ⊢ P(a,f(a)) by ∀Elim"));
+    assert!(fitch_proof::sequent_is_correct("P(a,b) ⊢ ∃x P(x,b) by ∃Intro"));
+    assert!(!fitch_proof::sequent_is_correct("P(a,b) ⊢ ∃x P(x,x) by ∃Intro"));
+    // rules that need a subproof are not supported
+    assert!(!fitch_proof::sequent_is_correct("A ⊢ A→A by →Intro"));
+}
+#[test]
+fn test_sequent_error_message() {
+    assert_eq!(
+        fitch_proof::check_sequent("A ∧ B ⊢ C by ∧Elim", "x,y,z"),
+        "the justification ∧Elim: 1 is used, but none of the conjuncts in premise 1 is \
+        identical to the sentence found in the conclusion."
+    );
+    assert_eq!(
+        fitch_proof::check_sequent("A, B ⊢ A ∧ C by ∧Intro", "x,y,z"),
+        "the rule ∧Intro is used, but the 2'th conjunct of the sentence in that line is not the \
+        same as the sentence found in premise 2 (the 2'th line referenced in the justification)."
+    );
+    assert_eq!(
+        fitch_proof::check_sequent("P(x) ⊢ ∃x P(x) by ∃Intro", "x,y,z"),
+        "Premise 1: this line contains unbound variables."
+    );
+}
+#[test]
+fn test_progress_events() {
+    let proof = "
+1 | A ∧ B
+  |----
+2 | A           ∧Elim: 1
+3 | C           ∧Elim: 1
+4 | B ∧ A       ∧Intro: 2, 1
+";
+    let mut events: Vec<(usize, fitch_proof::LineStatus)> = vec![];
+    let res = fitch_proof::check_proof_with_progress(proof, "x,y,z", &mut |line, status| {
+        events.push((line, status.clone()))
+    });
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0], (2, fitch_proof::LineStatus::Correct));
+    assert!(matches!(events[1], (3, fitch_proof::LineStatus::Error(_))));
+    assert!(matches!(events[2], (4, fitch_proof::LineStatus::Error(_))));
+    assert_eq!(res, fitch_proof::check_proof(proof, "x,y,z"));
+}
+#[test]
+fn test_progress_session() {
+    let proof = "
+1 | A ∧ B
+  |----
+2 | A           ∧Elim: 1
+3 | C           ∧Elim: 1
+";
+    let mut session = fitch_proof::ProofCheckSession::new(proof, "x,y,z");
+    assert_eq!(session.check_next_lines(3), r#"[{"line":2,"status":"correct"}]"#);
+    assert!(!session.is_done());
+    assert!(session.check_next_lines(3).contains(r#""line":3,"status":"error""#));
+    assert!(session.is_done());
+    assert_eq!(session.finish(), fitch_proof::check_proof(proof, "x,y,z"));
+}
+
+#[test]
+fn test_document_edits() {
+    use fitch_proof::{DocumentAction, TextEdit, TextPosition};
+    let proof = &fitch_proof::format_proof("1 | A ∧ B\n  |----\n7 | A     ∧Elim:1");
+    assert_eq!(fitch_proof::document_edits(proof, DocumentAction::Format), vec![]);
+    assert_eq!(
+        fitch_proof::document_edits(proof, DocumentAction::FixLineNumbers),
+        vec![TextEdit {
+            start: TextPosition {
+                line: 2,
+                character: 0
+            },
+            end: TextPosition {
+                line: 2,
+                character: 1
+            },
+            new_text: "2".to_string()
+        }]
+    );
+    assert_eq!(
+        fitch_proof::document_edits_json("1 | A\n  |---", DocumentAction::Format),
+        r#"[{"range":{"start":{"line":1,"character":6},"end":{"line":1,"character":6}},"newText":"-"}]"#
+    );
+    assert_eq!(fitch_proof::document_edits_json("1 | A (", DocumentAction::Format), "[]");
+}
+
+#[test]
+fn test_ascii_symbols() {
+    let proof = "
+1 | A -> B
+2 | ~B
+  |----
+3 | B           ->Elim: 2, 2
+4 | A & B       &Intro: 1, 2
+";
+    let msg = fitch_proof::check_proof(proof, "x,y,z");
+    assert!(msg.contains("->Elim") && msg.contains("&Intro"), "{msg}");
+    assert!(!msg.contains(['→', '∧']), "{msg}");
+    let msg = fitch_proof::check_proof_with_options(proof, "x,y,z", "symbols=traditional");
+    assert!(msg.contains("⊃Elim") && !msg.contains("->"), "{msg}");
+    assert!(fitch_proof::check_proof_with_options(proof, "x,y,z", "symbols=foo")
+        .starts_with("Fatal error"));
+    proof_is_correct_ultra_pedantic(
+        "
+1 | A -> B
+2 | A
+  |----
+3 | B           ->Elim: 1, 2
+4 | _|_ \\/ B    \\/Intro: 3
+",
+    );
+}
+
+#[test]
+fn test_derived_rules() {
+    let proof = "
+1 | ¬∀x P(x)
+2 | A → ¬∃x Q(x)
+3 | B → C
+  |----
+4 | ∃x ¬P(x)                QE: 1
+5 | A → ∀x ¬Q(x)            QE: 2
+6 | ¬C → ¬B                 Contra: 3
+7 | ¬B ∨ C                  Impl: 3
+8 | B → C                   Impl: 7
+";
+    let correct = "The proof is correct!";
+    assert_eq!(fitch_proof::check_proof_with_options(proof, "x,y,z", "derived_rules=on"), correct);
+    let msg = fitch_proof::check_proof(proof, "x,y,z");
+    assert!(msg.contains("Line 4: the derived rule QE is used, but derived rules are not allowed"));
+    let formatted = fitch_proof::format_proof(proof);
+    assert!(formatted.contains("QE: 1") && formatted.contains("Contra: 3"));
+
+    let wrong = "
+1 | ¬∀x P(x)
+2 | B → C
+  |----
+3 | ∀x ¬P(x)                QE: 1
+4 | ¬B → ¬C                 Contra: 2
+5 | ¬C ∨ B                  Impl: 2
+";
+    let msg = fitch_proof::check_proof_with_options(wrong, "x,y,z", "derived_rules=on");
+    assert!(msg.contains("Line 3: the rule QE: 1 is used"), "{msg}");
+    assert!(msg.contains("Line 4: the rule Contra: 2 is used"), "{msg}");
+    assert!(msg.contains("Line 5: the rule Impl: 2 is used"), "{msg}");
+}
+
+#[test]
+fn test_or_elim_three_cases() {
+    let cases = |disjunction: &str| {
+        format!(
+            "
+1 | {disjunction}
+  |----
+2 | | A
+  | |----
+3 | | C ∨ B ∨ A           ∨Intro: 2
+  |
+4 | | B
+  | |----
+5 | | C ∨ B ∨ A           ∨Intro: 4
+  |
+6 | | C
+  | |----
+7 | | C ∨ B ∨ A           ∨Intro: 6
+8 | C ∨ B ∨ A             ∨Elim: 1, 2-3, 4-5, 6-7
+"
+        )
+    };
+    proof_is_correct_ultra_pedantic(&cases("A ∨ B ∨ C"));
+    proof_is_correct_ultra_pedantic(&cases("A ∨ (B ∨ C)"));
+    proof_is_correct_ultra_pedantic(&cases("(A ∨ B) ∨ C"));
+    proof_is_not_correct_ultra_pedantic(&cases("(A ∨ C) ∨ B"));
+    proof_is_not_correct_ultra_pedantic(&cases("A ∨ B"));
+}
+
+#[test]
+fn test_subproof_shorthand() {
+    let proof = "
+1 | A ∨ B
+  |----
+2 | | A
+  | |----
+3 | | B ∨ A       ∨Intro: 2
+  |
+4 | | B
+  | |----
+5 | | B ∨ A       ∨Intro: 4
+6 | B ∨ A         ∨Elim: 1, 2, 4
+7 | | C
+  | |----
+8 | | C           Reit: 7
+9 | C → C         →Intro: 7
+";
+    let correct = "The proof is correct!";
+    let options = "subproof_shorthand=on";
+    assert_eq!(fitch_proof::check_proof_with_options(proof, "x,y,z", options), correct);
+    let msg = fitch_proof::check_proof(proof, "x,y,z");
+    assert!(msg.contains("like this: 2-3"), "{msg}");
+    assert!(msg.contains("Line 9: the subproof that starts at line 7 is referenced"), "{msg}");
+    assert!(!msg.contains("4-5"), "{msg}");
+
+    // a subproof that consists of only one line can still be cited by that one line
+    proof_is_correct_ultra_pedantic(
+        "
+1 | | A
+  | |----
+2 | A → A         →Intro: 1
+",
+    );
+}
+
+#[test]
+fn test_lint_vacuous_quantifier() {
+    let proof = "
+1 | ∀x P(a)
+2 | ∃y ∀x (R(x) ∧ Q(b))
+3 | ∀x ∃y R(x, y)
+  |----
+4 | P(a)          ∀Elim: 1
+";
+    proof_is_correct_ultra_pedantic(proof);
+    let warnings = fitch_proof::lint_proof(proof);
+    assert!(
+        warnings.starts_with("Line 1: the quantifier ∀x in ∀x P(a) has no effect"),
+        "{warnings}"
+    );
+    assert!(warnings.contains("Line 2: the quantifier ∃y"), "{warnings}");
+    assert!(!warnings.contains("quantifier ∀x in ∀x (R(x)"), "{warnings}");
+    assert!(!warnings.contains("Line 3"), "{warnings}");
+    assert!(!warnings.contains("Line 4"), "{warnings}");
+    assert_eq!(fitch_proof::lint_proof("1 | ∀x P(x)"), "");
+}
+
+#[test]
+fn test_lint_duplicates() {
+    let proof = "
+1 | A ∧ B
+  |----
+2 | A             ∧Elim: 1
+3 | | C
+  | |----
+4 | | A           ∧Elim: 1
+5 | | A           Reit: 2
+6 | C → A         →Intro: 3-5
+7 | | C
+  | |----
+8 | | A           ∧Elim: 1
+9 | | A           Reit: 2
+10| C → A         →Intro: 7-9
+11| A             ∧Elim: 1
+";
+    proof_is_correct_ultra_pedantic(proof);
+    let warnings = fitch_proof::lint_proof(proof);
+    assert!(
+        warnings
+            .contains("Line 4: the sentence in this line is the same as the sentence in line 2"),
+        "{warnings}"
+    );
+    assert!(!warnings.contains("Line 5:"), "{warnings}");
+    assert!(
+        warnings.contains("Line 7: the subproof 7-9 is identical to the subproof 3-5"),
+        "{warnings}"
+    );
+    assert!(
+        warnings
+            .contains("Line 10: the sentence in this line is the same as the sentence in line 6"),
+        "{warnings}"
+    );
+    assert!(
+        warnings
+            .contains("Line 11: the sentence in this line is the same as the sentence in line 2"),
+        "{warnings}"
+    );
+    // line numbers that are not in order should not be a problem
+    let _ =
+        fitch_proof::lint_proof("1 | A\n  |----\n9 | A  Reit: 1\n2 | A  Reit: 9\n3 | A  ∧Intro: 9");
+}
+
+#[test]
+fn test_closed_subproof_diagram() {
+    let proof = "
+1 | A
+  |----
+2 | | B
+  | |----
+3 | | A ∧ B         ∧Intro: 1, 2
+4 | | B             Reit: 2
+5 | | B ∧ A         ∧Intro: 2, 1
+6 | B → (B ∧ A)     →Intro: 2-5
+7 | A ∧ B           Reit: 3
+";
+    let msg = fitch_proof::check_proof(proof, "x,y,z");
+    let expected = "Line 7: line 3 is referenced in the justification, but this is not allowed, \
+        because line 3 is inside an already closed subproof.
+2 | | B      <-- the subproof starts here
+3 | | A ∧ B  <-- line 3 is inside the subproof
+  | | ⋮
+5 | | B ∧ A  <-- the subproof ends here
+  | ⋮
+7 | A ∧ B    <-- line 7 is outside the subproof, so it cannot reference line 3";
+    assert_eq!(msg, expected);
+}
+
+#[test]
+fn test_equality_chains() {
+    let proof = "
+1 | a = b
+2 | c = b
+3 | c = d
+  |----
+4 | a = d           =Chain: 1, 2, 3
+5 | d = a           =Chain: 3, 2, 1
+6 | a = a           =Chain: 1, 1
+";
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "equality_chains=on");
+    assert_eq!(res, "The proof is correct!");
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "");
+    assert!(res.contains("Line 4: the rule =Chain is used, but chains of equalities"), "{res}");
+
+    let proof = "
+1 | a = b
+2 | c = d
+  |----
+3 | a = d           =Chain: 1, 2
+4 | a = c           =Chain: 1
+5 | P(a)            =Chain: 1
+";
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "equality_chains=on");
+    assert!(res.contains("Line 3: the rule =Chain is used, but the equality in line 2 does not continue the chain, because it does not contain b"), "{res}");
+    assert!(res.contains("Line 4: the rule =Chain is used, but the chain of equalities leads from a to b, not to c"), "{res}");
+    assert!(
+        res.contains(
+            "Line 5: the rule =Chain is used, but the sentence in this line is not of the form"
+        ),
+        "{res}"
+    );
+}
+
+#[test]
+fn test_lint_unused_equals_intro() {
+    let proof = "
+1 | P(a)
+  |----
+2 | a = a           =Intro
+3 | b = b           =Intro
+4 | (b = b) ∧ P(a)   ∧Intro: 3, 1
+";
+    proof_is_correct_ultra_pedantic(proof);
+    let warnings = fitch_proof::lint_proof(proof);
+    assert!(warnings.contains("Line 2: this =Intro line is never referenced"), "{warnings}");
+    assert!(!warnings.contains("Line 3:"), "{warnings}");
+}
+
+#[test]
+fn test_conclusion_placement() {
+    let template = vec!["A ∧ B".to_string(), "A".to_string()];
+    let check = |proof: &str, options: &str| {
+        fitch_proof::check_proof_with_template_and_options(
+            proof,
+            template.clone(),
+            "x,y,z",
+            options,
+        )
+    };
+    let proof = "
+1 | A ∧ B
+  |----
+2 | A             ∧Elim: 1
+3 | B             ∧Elim: 1
+";
+    let res = check(proof, "");
+    assert_eq!(res, "Line 2: the conclusion is reached in this line, but the proof continues after it. The conclusion should be the last line of the proof.");
+    assert_eq!(check(proof, "conclusion=anywhere"), "The proof is correct!");
+    assert_eq!(check(proof, "conclusion=last"), res);
+
+    let proof = "
+1 | A ∧ B
+  |----
+2 | | C
+  | |----
+3 | | A           ∧Elim: 1
+4 | C → A         →Intro: 2-3
+";
+    let res = check(proof, "conclusion=anywhere");
+    assert!(
+        res.contains("The conclusion in the proof template is not reached in your proof"),
+        "{res}"
+    );
+    let res = check(proof, "");
+    assert!(
+        res.contains(
+            "The conclusion of your proof does not match the conclusion in the proof template."
+        ),
+        "{res}"
+    );
+
+    // a conclusion that is one of the premises is reached as well
+    let template = vec!["A".to_string(), "B".to_string(), "A".to_string()];
+    let proof = "1 | A\n2 | B\n  |----\n3 | B ∧ A  ∧Intro: 2, 1";
+    let check = |options| {
+        fitch_proof::check_proof_with_template_and_options(
+            proof,
+            template.clone(),
+            "x,y,z",
+            options,
+        )
+    };
+    assert_eq!(check("conclusion=anywhere"), "The proof is correct!");
+    let res = check("");
+    assert!(res.starts_with("Line 1: the conclusion is reached in this line"), "{res}");
+}
+
+#[test]
+fn test_rename_vocabulary() {
+    let proof = "
+1 | ∀x (P(x) → Q(f(x)))
+2 | P(a) ∧ R
+  |----
+3 | P(a)                ∧Elim: 2
+4 | P(a) → Q(f(a))      ∀Elim: 1
+5 | Q(f(a))             →Elim: 4, 3
+6 | | [b]
+  | |----
+7 | | b = b             =Intro
+8 | ∀x (x = x)          ∀Intro: 6-7
+9 | Q(f(a)) ∧ ∀x (x = x)  ∧Intro: 5, 8
+";
+    let template: Vec<String> =
+        ["∀x (P(x) → Q(f(x)))", "P(a) ∧ R", "Q(f(a)) ∧ ∀x (x = x)"].map(String::from).to_vec();
+    proof_is_correct_ultra_pedantic(proof);
+
+    let (new_proof, new_template) =
+        fitch_proof::rename_vocabulary(proof, &template, "P=Q, Q=P, f=g, a=c, b=d, R=S", "x,y,z")
+            .unwrap();
+    assert_eq!(new_template, ["∀x (Q(x) → P(g(x)))", "Q(c) ∧ S", "P(g(c)) ∧ ∀x (x=x)"]);
+    assert!(new_proof.contains("6 | | [d]"), "{new_proof}");
+    assert!(new_proof.contains("5 | P(g(c))"), "{new_proof}");
+    assert_eq!(
+        fitch_proof::check_proof_with_template(&new_proof, new_template, "x,y,z"),
+        "The proof is correct!"
+    );
+
+    let rename =
+        |renaming: &str| fitch_proof::rename_vocabulary(proof, &template, renaming, "x,y,z");
+    assert_eq!(
+        rename("a=b"),
+        Err("a cannot be renamed to b, because b is already used in the proof".to_string())
+    );
+    assert_eq!(rename("a=c, b=c"), Err("both a and b are renamed to c".to_string()));
+    assert_eq!(rename("a=x"), Err("x is a variable, and variables cannot be renamed".to_string()));
+    assert!(rename("a=C").is_err());
+    assert!(rename("a=b, b=a").is_ok());
+}
+
+#[test]
+fn test_fuzz_entry() {
+    fitch_proof::fuzz_entry(b"1 | A\n  |----\n2 | A  Reit: 1");
+    fitch_proof::fuzz_entry(b"1 | A\n  |----\n2 | | B\n3 | A  \xff\xfe\0derived_rules=on");
+    fitch_proof::fuzz_entry(b"A, B \xe2\x8a\xa2 A \xe2\x88\xa7 B by \xe2\x88\xa7Intro\0\0");
+    fitch_proof::fuzz_entry(b"");
+    // a line that is just the name of a rule
+    fitch_proof::fuzz_entry(b"1 | A\n  |---\nIntro");
+}
+
+#[test]
+fn test_roundtrip_check() {
+    let proofs = [
+        "1 | A ∧ B\n  |----\n2 | A             ∧Elim: 1",
+        "1|A&B\n|---\n\n2|A  &Elim:1\n3 | A \\/ C  \\/Intro:2",
+        "1 | ∀x (P(x) → Q(x))\n  |---\n2 | | [a] P(a)\n  | |---\n3 | | Q(a)  →Elim: 4, 2\n4 | ∀x Q(x)  ∀Intro: 2-3",
+        "1|¬¬(a=b)\n|--\n2| | A\n| |--\n3 | | A  Reit:2\n4|A→A  →Intro:2-3",
+        "garbage ∧∧",
+        "",
+    ];
+    for proof in proofs {
+        assert_eq!(fitch_proof::roundtrip_check(proof), Ok(()), "{proof}");
+    }
+}
+
+#[test]
+fn test_drafting() {
+    let proof = "
+1 | A ∧ B
+  |----
+2 | A
+3 | B             ∧Elim: 1
+4 | B ∧ A         ∧Intro: 3, 2
+5 | | C
+  | |----
+6 | | A
+7 | C → A         →Intro: 5-6
+";
+    let res = fitch_proof::check_proof(proof, "x,y,z");
+    assert!(res.contains("Line 2: missing justification"), "{res}");
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "drafting=on");
+    assert_eq!(res, "There are no mistakes in the lines with a justification. Lines 2 and 6 do not have a justification yet, so they are not checked.");
+    let (res, verdict) = fitch_proof::check_proof_with_verdict(proof, None, "x,y,z", "drafting=on");
+    assert!(res.starts_with("There are no mistakes"), "{res}");
+    assert_eq!(verdict, "unfinished");
+    let (_, verdict) = fitch_proof::check_proof_with_verdict(proof, None, "x,y,z", "");
+    assert_eq!(verdict, "incorrect");
+
+    let proof = "
+1 | A ∧ B
+  |----
+2 | A
+3 | C             ∧Elim: 1
+";
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "drafting=on");
+    assert!(res.starts_with("Line 3: "), "{res}");
+    assert!(
+        res.ends_with("\n\nLine 2 does not have a justification yet, so it is not checked."),
+        "{res}"
+    );
+    let proof = "1 | A ∧ B\n  |----\n2 | A  ∧Elim: 1";
+    assert_eq!(
+        fitch_proof::check_proof_with_options(proof, "x,y,z", "drafting=on"),
+        "The proof is correct!"
+    );
+}
+
+#[test]
+fn test_proof_score_card() {
+    let proof = "
+1 | A ∧ B
+  |----
+2 | | C
+  | |----
+3 | | A         ∧Elim: 1
+4 | C → A       →Intro: 2-3
+5 | A           ∧Elim: 1
+6 | A ∧ (C → A) ∧Intro: 5, 4
+";
+    let template: Vec<String> =
+        ["A ∧ B", "# from the second exercise sheet", "# minimal proof length: 5", "A ∧ (C → A)"]
+            .map(String::from)
+            .to_vec();
+    assert_eq!(
+        fitch_proof::check_proof_with_template(proof, template.clone(), "x,y,z"),
+        "The proof is correct!"
+    );
+    let res = fitch_proof::proof_score_card(proof, template.clone(), "x,y,z", "");
+    assert!(res.starts_with("{\"lines\":6,\"minimal_length\":5,\"rules\":{\"→Intro\":1,\"∧Elim\":2,\"∧Intro\":1},\"nesting_depth\":1,\"warnings\":[],"), "{res}");
+    assert!(res.ends_with("\"summary\":\"Well done! Your proof has 6 lines. The shortest proof we know of has 5 lines. It uses 3 different rules, and subproofs that are nested at most 1 level deep.\"}"), "{res}");
+
+    let proof = "1 | A & B\n  |----\n2 | A  &Elim: 1";
+    let res = fitch_proof::proof_score_card(proof, vec![], "x,y,z", "");
+    assert_eq!(res, "{\"lines\":2,\"minimal_length\":null,\"rules\":{\"&Elim\":1},\"nesting_depth\":0,\"warnings\":[],\"summary\":\"Well done! Your proof has 2 lines. It uses 1 different rule, and no subproofs.\"}");
+
+    let proof = "1 | A ∧ B\n  |----\n2 | a = a  =Intro\n3 | A  ∧Elim: 1";
+    let template: Vec<String> =
+        ["A ∧ B", "A", "#minimal proof length: 3"].map(String::from).to_vec();
+    let res = fitch_proof::proof_score_card(proof, template, "x,y,z", "");
+    assert!(res.contains("\"warnings\":[\"Line 2: "), "{res}");
+    assert!(res.contains("Your proof has 3 lines, just like the shortest proof we know of. It uses 2 different rules, and no subproofs. There is still room for improvement: 1 style warning."), "{res}");
+    let template: Vec<String> =
+        ["A ∧ B", "A", "# minimal proof length: 4"].map(String::from).to_vec();
+    let res = fitch_proof::proof_score_card(proof, template, "x,y,z", "");
+    assert!(
+        res.contains(
+            "so it is even shorter than the shortest proof we know of, which has 4 lines."
+        ),
+        "{res}"
+    );
+    assert_eq!(
+        fitch_proof::proof_score_card("1 | A\n  |----\n2 | B  Reit: 1", vec![], "x,y,z", ""),
+        ""
+    );
+    let template: Vec<String> = ["A", "# minimal proof length: few"].map(String::from).to_vec();
+    let res = fitch_proof::check_proof_with_template("1 | A", template, "x,y,z");
+    assert!(res.contains("Some sentences in the template file could not be parsed."), "{res}");
+}
+
+#[test]
+fn test_exercise_bundle() {
+    let bundle = r##"{
+  "title": "Week 1",
+  "exercises": [
+    {
+      "id": "1.1",
+      "statement": "Show that A follows from A ∧ B.",
+      "template": ["A ∧ B", "# minimal proof length: 2", "A"],
+      "solutions": ["1 | A ∧ B\n  |----\n2 | A  ∧Elim: 1"]
+    },
+    {
+      "id": "1.2",
+      "template": ["¬∀x P(x)", "∃x ¬P(x)"],
+      "variables": "x",
+      "options": "derived_rules=on",
+      "solutions": ["1 | ¬∀x P(x)\n  |----\n2 | ∃x ¬P(x)  QE: 1"]
+    }
+  ]
+}"##;
+    assert_eq!(fitch_proof::validate_bundle(bundle), "");
+    let proof = "1 | A ∧ B\n  |----\n2 | A  ∧Elim: 1";
+    assert_eq!(fitch_proof::check_proof_in_bundle(bundle, "1.1", proof), "The proof is correct!");
+    let res = fitch_proof::check_proof_in_bundle(bundle, "1.2", proof);
+    assert!(res.starts_with("The premises of your proof do not match"), "{res}");
+    let res = fitch_proof::check_proof_in_bundle(bundle, "1.3", proof);
+    assert_eq!(res, "Fatal error: there is no exercise '1.3' in the bundle");
+    let res = fitch_proof::check_proof_in_bundle("{\"exercises\": [", "1.1", proof);
+    assert!(
+        res.starts_with("Fatal error: the exercise bundle could not be read: invalid JSON"),
+        "{res}"
+    );
+
+    let bundle = fitch_proof::ExerciseBundle::from_json(bundle).unwrap();
+    assert_eq!(bundle.title, "Week 1");
+    assert_eq!(
+        bundle.exercise("1.2").map(|exercise| exercise.options.as_str()),
+        Some("derived_rules=on")
+    );
+
+    let mut broken = bundle.clone();
+    broken.exercises[1].id = "1.1".to_string();
+    broken.exercises[1].options = "".to_string();
+    broken.exercises[0].template[1] = "# minimal proof length: 3".to_string();
+    assert_eq!(
+        broken.validate(),
+        [
+            "exercise '1.1': solution 1 has 2 lines, but the template says that the shortest proof has 3 lines",
+            "there is more than one exercise '1.1'",
+            "exercise '1.1': solution 1 is not correct:\nLine 2: the derived rule QE is used, but derived rules are not allowed here. Use the basic Intro and Elim rules instead."
+        ]
+    );
+    let mut broken = bundle.clone();
+    broken.exercises[0].template = vec!["# no sentences".to_string()];
+    broken.exercises[1].allowed_variable_names = "X".to_string();
+    let problems = broken.validate();
+    assert_eq!(problems.len(), 2, "{problems:?}");
+    assert_eq!(problems[0], "exercise '1.1': the template does not have a conclusion");
+    assert!(problems[1].starts_with("exercise '1.2': the list of allowed variable names"));
+}
+
+#[test]
+fn test_hidden_solutions() {
+    let solution = "1 | A ∧ B\n  |----\n2 | A  ∧Elim: 1";
+    let bundle = format!(
+        "{{\"exercises\": [{{\"id\": \"1\", \"template\": [\"A ∧ B\", \"A\"], \"solutions\": [{:?}], \
+        \"unlock_after\": \"2026-11-01T23:59:00Z\"}}]}}",
+        solution
+    );
+    let hidden = fitch_proof::hide_solutions(&bundle, "secret").unwrap();
+    assert!(!hidden.contains("∧Elim"), "{hidden}");
+    assert!(hidden.contains("\"hidden_solutions\": [\""), "{hidden}");
+    assert_eq!(fitch_proof::validate_bundle(&hidden), "");
+
+    let after_deadline = 1793577541.0;
+    assert_eq!(
+        fitch_proof::reveal_solution(&hidden, "1", "secret", after_deadline),
+        Ok(vec![solution.to_string()])
+    );
+    assert_eq!(
+        fitch_proof::reveal_solution(&hidden, "1", "guess", after_deadline),
+        Err("the key is not correct".to_string())
+    );
+    assert_eq!(
+        fitch_proof::reveal_solution(&hidden, "1", "secret", after_deadline - 1.0),
+        Err("the solutions of exercise '1' cannot be revealed before 2026-11-01T23:59:00Z"
+            .to_string())
+    );
+    assert!(fitch_proof::reveal_solution(&hidden, "2", "secret", after_deadline).is_err());
+
+    let bundle = fitch_proof::ExerciseBundle::from_json(&hidden).unwrap();
+    assert_eq!(fitch_proof::ExerciseBundle::from_json(&bundle.to_json()), Ok(bundle.clone()));
+
+    // a hidden solution that is changed, or moved to another exercise, cannot be revealed
+    let mut changed = bundle.clone();
+    let hidden_solution = &mut changed.exercises[0].hidden_solutions[0];
+    let last = if hidden_solution.ends_with('0') {
+        "1"
+    } else {
+        "0"
+    };
+    hidden_solution.replace_range(hidden_solution.len() - 1.., last);
+    let wrong_key = Err("the key is not correct".to_string());
+    assert_eq!(changed.exercises[0].reveal_solutions("secret", after_deadline as u64), wrong_key);
+    let mut moved = bundle;
+    moved.exercises[0].id = "2".to_string();
+    assert_eq!(moved.exercises[0].reveal_solutions("secret", after_deadline as u64), wrong_key);
+}
+
+#[test]
+fn test_propositional_only() {
+    let proof = "1 | A ∧ B\n  |----\n2 | A  ∧Elim: 1";
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "propositional_only=on");
+    assert_eq!(res, "The proof is correct!");
+
+    let proof = "1 | ∀x P(x)\n  |----\n2 | P(a)  ∀Elim: 1";
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "propositional_only=on");
+    assert_eq!(res, "Fatal error: Line 1: this exercise is propositional-only, so quantifiers (∀ and ∃) cannot be used. Only atomic sentences (like A), the connectives and ⊥ are allowed.");
+    assert_eq!(fitch_proof::check_proof(proof, "x,y,z"), "The proof is correct!");
+
+    let proof = "1 | A\n  |----\n2 | A ∧ P(b)  ∧Intro: 1, 1";
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "propositional_only=on");
+    assert!(res.starts_with("Fatal error: Line 2: this exercise is propositional-only, so predicates with arguments (like P(a)) cannot be used."), "{res}");
+
+    let proof = "1 | A\n  |----\n2 | A  =Intro";
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "propositional_only=on");
+    assert!(res.starts_with("Fatal error: Line 2: this exercise is propositional-only, so the rule =Intro cannot be used."), "{res}");
+
+    let proof = "1 | A\n  |----\n2 | | [c]\n  | |----\n3 | | A  Reit: 1\n4 | A  Reit: 1";
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "propositional_only=on");
+    assert!(res.starts_with("Fatal error: Line 2: this exercise is propositional-only, so boxed constants cannot be used."), "{res}");
+
+    let template = ["# propositional only", "a = a"].map(String::from).to_vec();
+    let res =
+        fitch_proof::check_proof_with_template("  |----\n1 | a = a  =Intro", template, "x,y,z");
+    assert!(res.starts_with("Fatal error: Line 1: this exercise is propositional-only, so identity (=) cannot be used."), "{res}");
+}
+
+#[test]
+fn test_check_proof_skeleton() {
+    // the sentences do not matter, and may even be unparsable
+    let proof = "
+1 | A ∧
+  |----
+2 | | C
+  | |----
+3 | | whatever  ∧Elim: 1
+4 | C → A       →Intro: 2-3
+5 | B           ∨Elim: 1, 2-3, 2-3
+";
+    assert_eq!(
+        fitch_proof::check_proof_skeleton(proof, "x,y,z", ""),
+        "The structure of the proof is correct!"
+    );
+    assert!(!fitch_proof::check_proof(proof, "x,y,z").contains("correct"));
+
+    let proof = "
+1 | A
+  |----
+2 | | C
+  | |----
+3 | | B  ∧Elim: 1
+4 | D  ∧Elim: 3
+5 | E  →Intro: 2-4
+6 | F  Reit: 7
+7 | G
+";
+    let res = fitch_proof::check_proof_skeleton(proof, "x,y,z", "");
+    assert!(res.starts_with("Line 4: line 3 is referenced in the justification, but this is not allowed, because line 3 is inside an already closed subproof."), "{res}");
+    assert!(res.contains("\n\nLine 5: the referenced subproof 2-4 "), "{res}");
+    assert!(res.contains("\n\nLine 6: line 7 is referenced in the justification, but this is not allowed, because line 7 does not come before line 6."), "{res}");
+    assert!(res.ends_with("\n\nLine 7: missing justification"), "{res}");
+
+    let res = fitch_proof::check_proof_skeleton(proof, "x,y,z", "drafting=on");
+    assert!(
+        res.ends_with("Line 7 does not have a justification yet, so it is not checked."),
+        "{res}"
+    );
+
+    let proof = "1 | A\n  |----\n2 | | B\n  |----\n3 | C  Reit: 1";
+    assert!(fitch_proof::check_proof_skeleton(proof, "x,y,z", "").starts_with("Fatal error"));
+}
+
+#[test]
+fn test_check_expected_errors() {
+    let proof = "
+%expect-error: E_SCOPE_VIOLATION at line 4
+1 | A
+  |----
+2 | | B
+  | |----
+3 | | A  Reit: 1
+4 | A  Reit: 3
+";
+    assert_eq!(
+        fitch_proof::check_expected_errors(proof, "x,y,z", ""),
+        "The proof has exactly the expected mistakes."
+    );
+    // the directives are ignored when the proof is checked normally
+    assert!(fitch_proof::check_proof(proof, "x,y,z").starts_with("Line 4: line 3 is referenced"));
+    assert!(fitch_proof::format_proof(proof)
+        .starts_with("%expect-error: E_SCOPE_VIOLATION at line 4\n1 | A\n"));
+    assert_eq!(fitch_proof::roundtrip_check(proof), Ok(()));
+
+    let proof = "
+%expect-error: E_SCOPE_VIOLATION at line 3
+%expect-error: E_MISSING_JUSTIFICATION
+1 | A
+  |----
+2 | A ∧ A  ∧Intro: 1
+";
+    let res = fitch_proof::check_expected_errors(proof, "x,y,z", "");
+    assert_eq!(res, "The proof should have a mistake of type E_SCOPE_VIOLATION in line 3, but it does not.\n\nThe proof should have a mistake of type E_MISSING_JUSTIFICATION, but it does not.\n\nUnexpected mistake of type E_RULE_MISAPPLIED:\nLine 2: the rule ∧Intro is used, but the number of conjuncts (2) of the sentence in line 2 is not equal to the number of referenced proof lines (1).");
+
+    let proof = "%allow: QE\n%expect-error: E_RULE_MISAPPLIED at line 2\n1 | ¬∀x P(x)\n  |----\n2 | ∃x P(x)  QE: 1";
+    assert_eq!(
+        fitch_proof::check_expected_errors(proof, "x,y,z", ""),
+        "The proof has exactly the expected mistakes."
+    );
+    let proof = "%expect-error: E_FATAL\n1 | A\n  |----\n3 | A  Reit: 1";
+    assert_eq!(
+        fitch_proof::check_expected_errors(proof, "x,y,z", ""),
+        "The proof has exactly the expected mistakes."
+    );
+    let res = fitch_proof::check_expected_errors("%allow: TautCon\n1 | A", "x,y,z", "");
+    assert!(res.starts_with("Fatal error: the rule 'TautCon' in the directive"), "{res}");
+
+    // the error code does not depend on the symbols in which the message is written
+    let proof = "%expect-error: E_RULE_NOT_ALLOWED at line 4\n1 | ¬B\n  |----\n2 | | B\n  | |----\n3 | | ⊥  ⊥Intro: 2, 1\n4 | ¬B  ¬Intro: 2-3";
+    assert_eq!(
+        fitch_proof::check_expected_errors(proof, "x,y,z", "negation=bottom,symbols=ascii"),
+        "The proof has exactly the expected mistakes."
+    );
+}
+
+#[test]
+fn test_check_error_identification() {
+    let proof = "
+%expect-error: E_SCOPE_VIOLATION at line 4
+%expect-error: E_MISSING_JUSTIFICATION at line 5
+1 | A
+  |----
+2 | | B
+  | |----
+3 | | A  Reit: 1
+4 | A  Reit: 3
+5 | A
+";
+    let check = |answer: &str| fitch_proof::check_error_identification(proof, answer, "x,y,z", "");
+    assert_eq!(
+        check("4 E_SCOPE_VIOLATION\nline 5: e_missing_justification"),
+        "Correct! You found all mistakes in the proof."
+    );
+    assert_eq!(check("4 E_SCOPE_VIOLATION"), "Line 4: correct, this line has a mistake of type E_SCOPE_VIOLATION.\nYou found 1 of the 2 mistakes in the proof, and 0 of the 1 mistakes in your answer are wrong.");
+    assert_eq!(check("4 E_BAD_CITATION\n3 E_SCOPE_VIOLATION"), "Line 4: this line has a mistake, but it is not of type E_BAD_CITATION.\nLine 3: there is no mistake of type E_SCOPE_VIOLATION in this line.\nYou found 0 of the 2 mistakes in the proof, and 2 of the 2 mistakes in your answer are wrong.");
+    // finding a mistake twice does not count twice
+    assert_eq!(check("4 E_SCOPE_VIOLATION\n4 E_SCOPE_VIOLATION"), "Line 4: correct, this line has a mistake of type E_SCOPE_VIOLATION.\nLine 4: this mistake of type E_SCOPE_VIOLATION is already in your answer.\nYou found 1 of the 2 mistakes in the proof, and 1 of the 2 mistakes in your answer are wrong.");
+    // listing every mistake in every line does not give the mistakes away
+    let guesses: Vec<String> = (1..=5)
+        .flat_map(|n| {
+            ["E_SCOPE_VIOLATION", "E_MISSING_JUSTIFICATION"].map(|code| format!("{n} {code}"))
+        })
+        .collect();
+    assert_eq!(
+        check(&guesses.join("\n")),
+        "Your answer lists 10 mistakes, but the proof has only 2. Please list at most 2 mistakes."
+    );
+    assert!(check("line four").starts_with("Your answer could not be read: "));
+    // an exercise whose directives are wrong
+    let res = fitch_proof::check_error_identification(
+        "%expect-error: E_ARITY at line 1\n1 | A",
+        "1 E_ARITY",
+        "x,y,z",
+        "",
+    );
+    assert!(res.starts_with("Fatal error: the mistakes in this exercise do not match"), "{res}");
+    // a mistake without a line cannot be identified
+    let res = fitch_proof::check_error_identification(
+        &proof.replace("E_SCOPE_VIOLATION at line 4", "E_SCOPE_VIOLATION"),
+        "4 E_SCOPE_VIOLATION",
+        "x,y,z",
+        "",
+    );
+    assert!(
+        res.starts_with(
+            "Fatal error: an %expect-error directive of this exercise does not say in which line"
+        ),
+        "{res}"
+    );
+    // an exercise with options
+    let proof = "%expect-error: E_RULE_NOT_ALLOWED at line 4\n1 | ¬B\n  |----\n2 | | B\n  | |----\n3 | | ⊥  ⊥Intro: 2, 1\n4 | ¬B  ¬Intro: 2-3";
+    let check = |options| {
+        fitch_proof::check_error_identification(proof, "4 E_RULE_NOT_ALLOWED", "x", options)
+    };
+    assert_eq!(check("negation=bottom"), "Correct! You found all mistakes in the proof.");
+    assert!(check("").starts_with("Fatal error: the mistakes in this exercise do not match"));
+}
+
+#[test]
+fn test_export_two_column() {
+    let proof = "
+1 | P ∧ Q
+  |----
+2 | | R
+  | |----
+3 | | P  ∧Elim: 1
+4 | R → P  →Intro: 2-3
+";
+    assert_eq!(
+        fitch_proof::export_two_column(proof, ""),
+        "\\begin{tabular}{ll}
+1.~$P \\land Q$ & Premise \\\\
+2.~\\quad $R$ & Assumption \\\\
+3.~\\quad $P$ & $\\land$ Elim: 1 \\\\
+4.~$R \\rightarrow P$ & $\\rightarrow$ Intro: 2-3 \\\\
+\\end{tabular}"
+    );
+    assert_eq!(
+        fitch_proof::export_two_column(proof, "format=html, numbering=parens, formula_width=6cm"),
+        "<table class=\"two-column-proof\">
+<colgroup><col style=\"width: 6cm\"><col></colgroup>
+<tr><td>(1) P ∧ Q</td><td>Premise</td></tr>
+<tr><td>(2) &emsp;R</td><td>Assumption</td></tr>
+<tr><td>(3) &emsp;P</td><td>∧ Elim: 1</td></tr>
+<tr><td>(4) R → P</td><td>→ Intro: 2-3</td></tr>
+</table>"
+    );
+    let res = fitch_proof::export_two_column(
+        "1 | [a]\n  |----\n2 | a = a  =Intro",
+        "numbering=none, justification_width=3cm",
+    );
+    assert_eq!(res, "\\begin{tabular}{lp{3cm}}\n$\\boxed{a}~$ & Premise \\\\\n$a=a$ & = Intro \\\\\n\\end{tabular}");
+    assert!(fitch_proof::export_two_column(proof, "format=pdf")
+        .starts_with("Failed to export, because the options"));
+    assert!(fitch_proof::export_two_column("1 | A ∧", "")
+        .starts_with("Failed to export, because the proof could not be parsed"));
+}
+
+#[test]
+fn test_wff_metrics() {
+    assert_eq!(
+        fitch_proof::wff_metrics("¬(A ∧ B) ∨ ∀x ∃y R(x, y)"),
+        "{\"connectives\":{\"¬\":1,\"∧\":1,\"∨\":1},\"quantifier_depth\":2,\"atoms\":3,\"size\":8}"
+    );
+    // the other symbol sets can be used as well
+    assert_eq!(fitch_proof::wff_metrics("A -> B"), fitch_proof::wff_metrics("A → B"));
+    assert_eq!(fitch_proof::wff_metrics("A ∧"), "");
+}
+
+#[test]
+fn test_feedback_policy() {
+    let proof = "
+1 | A ∧ B
+  |----
+2 | C  ∧Elim: 1
+3 | D  ∧Elim: 1
+4 | B  ∧Elim: 5
+";
+    let full = fitch_proof::check_proof(proof, "x,y,z");
+    assert_eq!(full.split("\n\n").count(), 3, "{full}");
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "max_errors=1");
+    assert_eq!(
+        res,
+        format!(
+            "{}\n\nThere are more mistakes, which are not shown yet.",
+            full.split("\n\n").next().unwrap()
+        )
+    );
+    assert_eq!(fitch_proof::check_proof_with_options(proof, "x,y,z", "max_errors=3"), full);
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "feedback=lines");
+    assert_eq!(res, "Line 2: there is a mistake in this line.\n\nLine 3: there is a mistake in this line.\n\nLine 4: there is a mistake in this line.");
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "feedback=lines, max_errors=0");
+    assert_eq!(res, "The proof contains mistakes, but they are not shown.");
+    // correct proofs and fatal errors are not affected
+    let options = "feedback=lines, max_errors=0";
+    assert_eq!(
+        fitch_proof::check_proof_with_options("1 | A\n  |----\n2 | A  Reit: 1", "x,y,z", options),
+        "The proof is correct!"
+    );
+    assert!(fitch_proof::check_proof_with_options(
+        "1 | A\n  |----\n3 | A  Reit: 1",
+        "x,y,z",
+        options
+    )
+    .starts_with("Fatal error: "));
+    // the policy also applies when checking against a template, as for the exercises in a bundle
+    let res = fitch_proof::check_proof_with_template_and_options(
+        proof,
+        vec!["A ∧ B".to_string(), "B".to_string()],
+        "x,y,z",
+        options,
+    );
+    assert_eq!(res, "The proof contains mistakes, but they are not shown.");
+}
+
+#[test]
+fn test_proof_hint() {
+    let proof = "
+1 | A ∧ B
+2 | B → C
+  |----
+3 | B  ∧Intro: 1
+4 | C
+";
+    let hint = |attempts| fitch_proof::proof_hint(proof, "x,y,z", "", "", attempts);
+    assert_eq!(hint(0), "No hint yet: a hint is given after 1 more attempt.");
+    assert_eq!(hint(1), "Line 3: try one of the rules for ∧.");
+    assert_eq!(hint(2), "Line 3: try ∧Elim.");
+    assert_eq!(hint(3), "Line 3: use ∧Elim: 1.");
+    assert_eq!(hint(10), "Line 3: use ∧Elim: 1.");
+    // after fixing line 3, the hint is about line 4, which has no justification yet
+    let proof = proof.replace("∧Intro", "∧Elim");
+    assert_eq!(fitch_proof::proof_hint(&proof, "x,y,z", "", "", 3), "Line 4: use →Elim: 2,3.");
+    assert_eq!(
+        fitch_proof::proof_hint(&proof, "x,y,z", "symbols=ascii", "rule=0", 0),
+        "Line 4: try ->Elim."
+    );
+
+    let res = fitch_proof::proof_hint(
+        "1 | A\n2 | B → C\n  |----\n3 | C  →Elim: 1, 2",
+        "x,y,z",
+        "",
+        "family=0, rule=5",
+        0,
+    );
+    assert_eq!(res, "Line 3: this line does not follow from the lines before it with a single rule. Maybe a step is missing before it?");
+    let proof = "
+1 | A ∨ B
+  |----
+2 | | A
+  | |----
+3 | | B ∨ A  ∨Intro: 2
+  |
+4 | | B
+  | |----
+5 | | B ∨ A  ∨Intro: 4
+6 | B ∨ A
+";
+    assert_eq!(
+        fitch_proof::proof_hint(proof, "x,y,z", "", "", 3),
+        "Line 6: use ∨Elim: 1, 2-3, 4-5."
+    );
+    let res = fitch_proof::proof_hint("1 | A\n  |----\n2 | A  Reit: 1", "x,y,z", "", "", 3);
+    assert_eq!(res, "All lines of the proof are justified correctly, so there is no hint.");
+    assert!(fitch_proof::proof_hint("1 | A\n  |----\n3 | A", "x,y,z", "", "", 3)
+        .starts_with("Fatal error: "));
+    assert!(
+        fitch_proof::proof_hint("1 | A", "x,y,z", "", "hints=all", 3).starts_with("Fatal error: ")
+    );
+}
+
+#[test]
+fn test_proof_replay() {
+    let proof = "
+1 | A
+  |----
+2 | | B
+  | |----
+3 | | A  Reit: 1
+4 | B -> A  ->Intro: 2-3
+";
+    let events = [
+        r#"{"event":"line","line":1,"depth":1,"kind":"premise","boxed_constant":null,"sentence":"A","justification":null,"cited_lines":[],"cited_subproofs":[]}"#,
+        r#"{"event":"fitch_bar","depth":1}"#,
+        r#"{"event":"open_subproof","depth":2}"#,
+        r#"{"event":"line","line":2,"depth":2,"kind":"assumption","boxed_constant":null,"sentence":"B","justification":null,"cited_lines":[],"cited_subproofs":[]}"#,
+        r#"{"event":"fitch_bar","depth":2}"#,
+        r#"{"event":"line","line":3,"depth":2,"kind":"inference","boxed_constant":null,"sentence":"A","justification":"Reit: 1","cited_lines":[1],"cited_subproofs":[]}"#,
+        r#"{"event":"close_subproof","depth":2}"#,
+        r#"{"event":"line","line":4,"depth":1,"kind":"inference","boxed_constant":null,"sentence":"B -> A","justification":"-> Intro: 2-3","cited_lines":[],"cited_subproofs":[[2,3]]}"#,
+    ];
+    assert_eq!(fitch_proof::proof_replay(proof), format!("[{}]", events.join(",")));
+
+    // subproofs that are still open at the end are closed
+    let res = fitch_proof::proof_replay("1 | A\n  |----\n2 | | [a]\n  | |----");
+    assert!(res.contains(r#""kind":"assumption","boxed_constant":"a","sentence":null"#), "{res}");
+    assert!(
+        res.ends_with(r#"{"event":"fitch_bar","depth":2},{"event":"close_subproof","depth":2}]"#),
+        "{res}"
+    );
+    assert_eq!(fitch_proof::proof_replay("1 | A ∧"), "");
+}
+
+#[test]
+fn test_citing_premises_as_a_block() {
+    let proof = "
+1 | A
+2 | B
+3 | C
+  |----
+4 | | D
+  | |----
+5 | | A ∧ B ∧ C  ∧Intro: Prem
+6 | D → (A ∧ B ∧ C)  →Intro: 4-5
+";
+    assert_eq!(fitch_proof::check_proof(proof, "x,y,z"), "The proof is correct!");
+    assert!(
+        fitch_proof::format_proof(proof).contains("5 | | A ∧ B ∧ C             ∧ Intro: Prem\n")
+    );
+    assert_eq!(fitch_proof::roundtrip_check(proof), Ok(()));
+    assert!(fitch_proof::export_to_latex(proof).contains("\\have{5}{A\\land B\\land C}\\ai{Prem}"));
+    assert!(fitch_proof::export_two_column(proof, "format=html").contains("<td>∧ Intro: Prem</td>"));
+    assert!(fitch_proof::proof_replay(proof)
+        .contains(r#""justification":"∧ Intro: Prem","cited_lines":[1,2,3]"#));
+    // the premises are still renumbered, but they are written as Prem again
+    let fixed = fitch_proof::fix_line_numbers_in_proof(&proof.replace("6 |", "7 |"));
+    assert!(fixed.contains("∧ Intro: Prem"));
+
+    let res = fitch_proof::check_proof(&proof.replace("A ∧ B ∧ C  ∧", "A ∧ C  ∧"), "x,y,z");
+    assert!(res.starts_with("Line 5: "), "{res}");
+    let proof = "1 | a = b\n2 | b = c\n  |----\n3 | a = c  =Chain: Prem";
+    assert_eq!(
+        fitch_proof::check_proof_with_options(proof, "x,y,z", "equality_chains=on"),
+        "The proof is correct!"
+    );
+    let res = fitch_proof::check_proof("1 | A\n  |----\n2 | A  Reit: Prem", "x,y,z");
+    assert!(res.contains("Prem (all premises) can only be cited on its own"), "{res}");
+}
+
+#[test]
+fn test_negation_defined_by_bottom() {
+    let proof = "
+1 | A → B
+2 | ¬B
+  |----
+3 | | A
+  | |----
+4 | | B  →Elim: 1, 3
+5 | | ⊥  →Elim: 2, 4
+6 | ¬A  →Intro: 3-5
+";
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "negation=bottom");
+    assert_eq!(res, "The proof is correct!");
+    // ¬A and A → ⊥ are the same sentence, and ⊥Intro may cite B and B → ⊥
+    let proof = proof.replace("6 | ¬A", "6 | A → ⊥").replace("→Elim: 2, 4", "⊥Intro: 4, 2");
+    let res = fitch_proof::check_proof_with_options(&proof, "x,y,z", "negation=bottom");
+    assert_eq!(res, "The proof is correct!");
+    let template = ["A → B", "¬B", "¬A"].map(String::from).to_vec();
+    let res = fitch_proof::check_proof_with_template_and_options(
+        &proof,
+        template.clone(),
+        "x,y,z",
+        "negation=bottom",
+    );
+    assert_eq!(res, "The proof is correct!");
+    let res = fitch_proof::check_proof_with_template(&proof, template, "x,y,z");
+    assert!(res.contains("does not match the conclusion in the proof template"), "{res}");
+
+    let proof = "1 | ¬B\n  |----\n2 | | B\n  | |----\n3 | | ⊥  ⊥Intro: 2, 1\n4 | ¬B  ¬Intro: 2-3";
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "negation=bottom");
+    assert_eq!(res, "Line 4: the rule ¬Intro is used, but here ¬φ is short for φ → ⊥, so there is no rule ¬Intro. Use →Intro with a subproof that ends with ⊥ instead.");
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "negation=primitive");
+    assert_eq!(res, "The proof is correct!");
+
+    // ¬Elim is double negation elimination, so classical proofs are still possible
+    let proof = "1 | (A → ⊥) → ⊥\n  |----\n2 | A  ¬Elim: 1";
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "negation=bottom");
+    assert_eq!(res, "The proof is correct!");
+    let proof = "1 | ¬¬A\n  |----\n2 | A  ¬Elim: 1";
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "negation=bottom");
+    assert_eq!(res, "The proof is correct!");
+    let proof = "1 | A\n  |----\n2 | ¬¬A  ¬Elim: 1";
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "negation=bottom");
+    assert_eq!(res, "Line 2: ¬Elim can only be used to go from ¬¬P to P, not the other way around");
+    let proof = "1 | A → ⊥\n  |----\n2 | A  ¬Elim: 1";
+    let res = fitch_proof::check_proof_with_options(proof, "x,y,z", "negation=bottom");
+    assert_eq!(res, "Line 2: ¬Elim is used improperly");
+
+    // the derived rules still see the negations
+    let proof = "1 | A → B\n  |----\n2 | ¬B → (A → ⊥)  Contra: 1";
+    let res =
+        fitch_proof::check_proof_with_options(proof, "x,y,z", "negation=bottom, derived_rules=on");
+    assert_eq!(res, "The proof is correct!");
+}
+
+#[test]
+fn test_summarize_proof() {
+    let proof = "
+1 | A ∨ B
+  |----
+2 | | A
+  | |----
+3 | | B ∨ A  ∨Intro: 2
+4 | | B
+  | |----
+5 | | B ∨ A  ∨Intro: 4
+6 | B ∨ A  ∨Elim: 1, 2-3, 4-5
+";
+    assert_eq!(
+        fitch_proof::summarize_proof(proof),
+        "The proof has 1 premise and ends with B ∨ A in line 6.
+Line 6 (B ∨ A) is proved by cases on line 1 (A ∨ B):
+  the first case assumes A in line 2 and derives B ∨ A in line 3;
+  the second case assumes B in line 4 and derives B ∨ A in line 5.
+The other steps use ∨Intro (2 times)."
+    );
+
+    let proof = "
+1 | ∀x (P(x) -> Q(x))
+  |----
+2 | | [c]
+  | |----
+3 | | | P(c)
+  | | |----
+4 | | | P(c) -> Q(c)  ∀Elim: 1
+5 | | | Q(c)  -> Elim: 4, 3
+6 | | P(c) -> Q(c)  -> Intro: 3-5
+7 | ∀x (P(x) -> Q(x))  ∀Intro: 2-6
+";
+    assert_eq!(
+        fitch_proof::summarize_proof(proof),
+        "The proof has 1 premise and ends with ∀x (P(x) -> Q(x)) in line 7.
+Line 7 (∀x (P(x) -> Q(x))) is proved for an arbitrary c in line 2 by deriving P(c) -> Q(c) in line 6.
+  Line 6 (P(c) -> Q(c)) is proved by assuming P(c) in line 3 and deriving Q(c) in line 5.
+The other steps use ∀Elim (once) and ->Elim (once)."
+    );
+
+    let res = fitch_proof::summarize_proof("1 | A\n  |----\n2 | A ∧ A  ∧Intro: 1, 1");
+    assert_eq!(
+        res,
+        "The proof has 1 premise and ends with A ∧ A in line 2.\nThe steps use ∧Intro (once)."
+    );
+    let res = fitch_proof::summarize_proof("1 | A ∧");
+    assert!(res.starts_with("The proof could not be summarized"), "{res}");
+}
+
+#[test]
+fn test_random_variant() {
+    let proof = "
+1 | ∀x (P(x) → Q(f(x)))
+2 | P(a) ∧ R
+  |----
+3 | P(a)                ∧Elim: 2
+4 | P(a) → Q(f(a))      ∀Elim: 1
+5 | Q(f(a))             →Elim: 4, 3
+";
+    let template: Vec<String> =
+        ["∀x (P(x) → Q(f(x)))", "P(a) ∧ R", "Q(f(a))"].map(String::from).to_vec();
+    let seed = fitch_proof::variant_seed("s1234567", "exercise 3");
+    assert_eq!(seed, fitch_proof::variant_seed(" s1234567 ", "exercise 3"));
+    assert_ne!(seed, fitch_proof::variant_seed("s7654321", "exercise 3"));
+    assert_ne!(seed, fitch_proof::variant_seed("s1234567", "exercise 4"));
+
+    let variant = fitch_proof::random_variant(proof, &template, seed, "x,y,z").unwrap();
+    assert_eq!(fitch_proof::random_variant(proof, &template, seed, "x,y,z"), Ok(variant.clone()));
+    let (new_proof, new_template) = variant;
+    assert_eq!(
+        fitch_proof::check_proof_with_template(&new_proof, new_template, "x,y,z"),
+        "The proof is correct!"
+    );
+    // the variables stay the same, and the structure of the sentences too
+    assert!(new_proof.starts_with("1 | ∀x ("), "{new_proof}");
+    let variants: std::collections::HashSet<_> = (0..20)
+        .map(|seed| fitch_proof::random_variant(proof, &template, seed, "x,y,z").unwrap().0)
+        .collect();
+    assert!(variants.len() > 10);
+}
+
+#[test]
+fn test_line_labels() {
+    let proof = "
+(H1) | A ∧ B
+     |----
+(a)  | B          ∧Elim: (H1)
+(b)  | A          ∧Elim: (H1)
+(c)  | B ∧ A      ∧Intro: (a), (b)
+";
+    assert_eq!(fitch_proof::check_proof(proof, "x"), "The proof is correct!");
+    assert_eq!(
+        fitch_proof::check_proof(&proof.replace("(a), (b)", "(b), (b)"), "x"),
+        "Line (c): the rule ∧Intro is used, but the 1'th conjunct of the sentence in that line is \
+        not the same as the sentence found in line (b) (the 1'th line referenced in the \
+        justification)."
+    );
+    assert_eq!(
+        fitch_proof::check_proof(&proof.replace("(a), (b)", "(a), (d)"), "x"),
+        "Fatal error: the label (d) is cited, but there is no line with this label"
+    );
+    assert_eq!(
+        fitch_proof::check_proof(&proof.replace("(a)  | B", "3 | B"), "x"),
+        "Fatal error: some lines have a label and others have a line number, but a proof should \
+        use only one of the two"
+    );
+    let json = fitch_proof::check_proof_with_renderer(
+        &proof.replace("(a), (b)", "(b), (b)"),
+        "x",
+        "",
+        "json",
+    );
+    assert!(json.contains("\"line\":4,\"text_line\":6,\"code\":\"E_RULE_MISAPPLIED\""), "{json}");
+    assert!(json.contains("\"message\":\"Line (c): the rule ∧Intro is used"), "{json}");
+
+    let formatted = "
+(H1) | A ∧ B
+     |----
+(a)  | B             ∧ Elim: (H1)
+(b)  | A             ∧ Elim: (H1)
+(c)  | B ∧ A         ∧ Intro: (a), (b)";
+    assert_eq!(fitch_proof::format_proof(proof), formatted.trim_start());
+    assert_eq!(fitch_proof::fix_line_numbers_in_proof(proof), formatted.trim_start());
+
+    let numbered = fitch_proof::fix_line_numbers_with_options(proof, "labels=numbers");
+    assert_eq!(
+        numbered,
+        "1 | A ∧ B\n  |----\n2 | B             ∧ Elim: 1\n3 | A             ∧ Elim: 1\n\
+        4 | B ∧ A         ∧ Intro: 2, 3"
+    );
+    let lettered = "(a) | A ∧ B\n    |----\n(b) | B             ∧ Elim: (a)\n\
+        (c) | A             ∧ Elim: (a)\n(d) | B ∧ A         ∧ Intro: (b), (c)";
+    assert_eq!(fitch_proof::fix_line_numbers_with_options(proof, "labels=letters"), lettered);
+    assert_eq!(fitch_proof::fix_line_numbers_with_options(&numbered, "labels=letters"), lettered);
+    assert_eq!(fitch_proof::fix_line_numbers_with_options(proof, "labels=roman"), proof);
+}
+
+#[test]
+fn test_line_labels_in_other_functions() {
+    let proof = "
+(H1) | A ∧ B
+     |----
+(a)  | B          ∧Elim: (H1)
+(b)  | A          ∧Elim: (H1)
+(c)  | B ∧ A      ∧Intro: (a), (b)
+";
+    let numbered = "
+1 | A ∧ B
+  |----
+2 | B          ∧Elim: 1
+3 | A          ∧Elim: 1
+4 | B ∧ A      ∧Intro: 2, 3
+";
+    assert_eq!(fitch_proof::export_to_latex(proof), fitch_proof::export_to_latex(numbered));
+    assert_eq!(fitch_proof::export_to_forallx(proof), fitch_proof::export_to_forallx(numbered));
+    assert_eq!(fitch_proof::export_to_tptp(proof), fitch_proof::export_to_tptp(numbered));
+    assert_eq!(
+        fitch_proof::export_two_column(proof, ""),
+        fitch_proof::export_two_column(numbered, "")
+    );
+    assert_eq!(fitch_proof::proof_replay(proof), fitch_proof::proof_replay(numbered));
+    let summary = fitch_proof::summarize_proof(proof);
+    assert!(summary.contains("ends with B ∧ A in line (c)"), "{summary}");
+
+    let warned = proof.replace("| B          ∧Elim", "| ∀x B       ∧Elim");
+    let warnings = fitch_proof::lint_proof(&warned);
+    assert!(warnings.starts_with("Line (a): "), "{warnings}");
+    let score_card = fitch_proof::proof_score_card(proof, vec![], "x", "");
+    assert!(score_card.starts_with("{\"lines\":4,"), "{score_card}");
+
+    let expected = "Fatal error: labeled proofs are not supported here";
+    assert!(fitch_proof::check_expected_errors(proof, "x", "").starts_with(expected));
+    assert!(fitch_proof::check_error_identification(proof, "(c) E_RULE_MISAPPLIED", "x", "")
+        .starts_with(expected));
+}
+
+#[test]
+fn test_import_from_latex() {
+    let proof = "
+1 | ∀x (P(x) → Q(x))
+2 | P(a)
+  |----
+3 | P(a) → Q(a)        ∀Elim: 1
+4 | Q(a)               →Elim: 3, 2
+5 | | [b]
+  | |----
+6 | | b = b            =Intro
+7 | ∀y (y = y)         ∀Intro: 5-6
+";
+    let latex = fitch_proof::export_to_latex(proof);
+    let imported = fitch_proof::import_from_latex(&latex).unwrap();
+    assert_eq!(imported, fitch_proof::format_proof(proof));
+    assert_eq!(fitch_proof::check_proof(&imported, "x,y"), "The proof is correct!");
+
+    assert_eq!(
+        fitch_proof::import_from_latex(
+            "$\n\\begin{nd}\n\\hypo{1}{A}\n\\have{2}{A}\\reit{1}\n\\end{nd}\n$"
+        ),
+        Err("line 4 of the LaTeX cannot be read: \\have{2}{A}\\reit{1}".to_string())
+    );
+    assert!(fitch_proof::import_from_latex("$\n\\begin{nd}\n\\end{nd}\n$").is_err());
+}
+
+#[test]
+fn test_error_order() {
+    let proof = "
+1 | A ∧ B
+  |----
+2 | B               ∧Elim: 1
+3 | | C
+  | |----
+4 | | C             Reit: 3
+5 | C → C           →Intro: 3-4
+6 | A               ∧Elim: 9
+7 | C               Reit: 4
+8 | A ∨ C           ∨Intro: 1
+";
+    let error_lines = |order: &str| {
+        let res =
+            fitch_proof::check_proof_with_options(proof, "x", &format!("error_order={order}"));
+        res.split("\n\n").map(|err| err.split(':').next().unwrap().to_string()).collect::<Vec<_>>()
+    };
+    // line 6 has a bad citation, line 7 a scope violation and line 8 a misapplied rule
+    assert_eq!(error_lines("line"), ["Line 6", "Line 7", "Line 8"]);
+    assert_eq!(error_lines("severity"), ["Line 7", "Line 6", "Line 8"]);
+    assert_eq!(error_lines("code"), ["Line 6", "Line 8", "Line 7"]);
+}
+
+#[test]
+fn test_bicond_any_order() {
+    let proof = "
+1 | A→B
+2 | B→A
+  | ---
+3 | | B
+  | | ---
+4 | | A     →Elim: 2,3
+  |
+5 | | A
+  | | ---
+6 | | B     →Elim: 1,5
+7 | A↔B     ↔Intro: 3-4, 5-6
+";
+    let res = fitch_proof::check_proof(proof, "x");
+    assert!(res.contains("Line 7: ↔Intro is used with the subproofs in the wrong order"), "{res}");
+    assert!(res.contains("like this: ↔Intro: 5-6, 3-4"), "{res}");
+    let res = fitch_proof::check_proof_with_options(proof, "x", "bicond_any_order=on");
+    assert!(res.starts_with("The proof is correct"), "{res}");
+
+    let res = fitch_proof::check_proof(&proof.replace("3-4, 5-6", "3-4, 3-4"), "x");
+    assert!(res.contains("the subproof 3-4 goes from B to A"), "{res}");
+
+    let proof = "
+1 | A ↔ B
+2 | A
+  | ---
+3 | B     ↔Elim: 2, 1
+";
+    let res = fitch_proof::check_proof(proof, "x");
+    assert!(
+        res.contains("the bi-implication should be cited first, like this: ↔Elim: 1, 2"),
+        "{res}"
+    );
+    let res = fitch_proof::check_proof_with_options(proof, "x", "bicond_any_order=on");
+    assert!(res.starts_with("The proof is correct"), "{res}");
+
+    let res = fitch_proof::check_proof(
+        &proof.replace("3 | B     ↔Elim: 2, 1", "3 | A     ↔Elim: 1, 2"),
+        "x",
+    );
+    assert!(res.contains("is used from left to right (from A in line 2), so it gives B"), "{res}");
+}
+
+#[test]
+fn test_reiteration_policy() {
+    let proof = "
+1 | A
+  | ---
+2 | | B
+  | | ---
+3 | | A ∧ B     ∧Intro: 1, 2
+4 | B → (A ∧ B) →Intro: 2-3
+";
+    let res = fitch_proof::check_proof(proof, "x");
+    assert!(res.starts_with("The proof is correct"), "{res}");
+    let res = fitch_proof::check_proof_with_options(proof, "x", "reiteration=explicit");
+    assert!(
+        res.contains(
+            "Line 3: line 1 is cited, but it is outside the subproof that line 3 is in, and here \
+            lines outside the subproof can only be cited with Reit."
+        ),
+        "{res}"
+    );
+    let res = fitch_proof::check_proof_with_renderer(proof, "x", "reiteration=explicit", "json");
+    assert!(res.contains("\"code\":\"E_SCOPE_VIOLATION\""), "{res}");
+
+    let proof = "
+1 | A
+  | ---
+2 | | B
+  | | ---
+3 | | A         Reit: 1
+4 | | A ∧ B     ∧Intro: 3, 2
+5 | B → (A ∧ B) →Intro: 2-4
+";
+    let res = fitch_proof::check_proof_with_options(proof, "x", "reiteration=explicit");
+    assert!(res.starts_with("The proof is correct"), "{res}");
+}
+
+#[test]
+fn test_lint_repeated_assumption() {
+    let proof = "
+1 | A
+  |----
+2 | | B
+  | |----
+3 | | | A
+  | | |----
+4 | | | B         Reit: 2
+5 | | A → B       →Intro: 3-4
+6 | B → (A → B)   →Intro: 2-5
+7 | B → (A → B)   Reit: 6
+8 | | B → (A → B)
+  | |----
+9 | | | B
+  | | |----
+10| | | B         Reit: 9
+11| | B → B       →Intro: 9-10
+12| (B → (A → B)) → (B → B)   →Intro: 8-11
+";
+    let warnings = fitch_proof::lint_proof(proof);
+    assert!(warnings.contains("Line 3: the assumption A is the same as the premise in line 1, which may already be referenced here. This is already a premise"), "{warnings}");
+    assert!(
+        warnings
+            .contains("Line 8: the assumption B → (A → B) is the same as the sentence in line 6"),
+        "{warnings}"
+    );
+    assert!(!warnings.contains("Line 9:"), "{warnings}");
+
+    let proof = "
+1 | A
+  |----
+2 | | B
+  | |----
+3 | | | B
+  | | |----
+4 | | | A         Reit: 1
+5 | | B → A       →Intro: 3-4
+6 | B → (B → A)   →Intro: 2-5
+";
+    let warnings = fitch_proof::lint_proof(proof);
+    assert!(warnings.contains("Line 3: the assumption B is the same as the assumption in line 2, which may already be referenced here. You already assumed this, so you can cite line 2 instead"), "{warnings}");
+}
+
+#[test]
+fn test_check_formula() {
+    let vars = "x,y";
+    let ok = "{\"well_formed\":true,\"errors\":[]}";
+    assert_eq!(fitch_proof::check_formula("∀x (P(x) → Q(x))", vars), ok);
+    assert_eq!(fitch_proof::check_formula("¬(A ∧ B) ↔ (¬A ∨ ¬B)", vars), ok);
+    assert_eq!(
+        fitch_proof::check_formula("∀x (P(x)", vars),
+        "{\"well_formed\":false,\"errors\":[{\"message\":\"this opening parenthesis is never \
+        closed\",\"start\":3,\"end\":4}]}"
+    );
+    let res = fitch_proof::check_formula("P(x))", vars);
+    assert!(res.contains("no matching opening parenthesis\",\"start\":4,\"end\":5"), "{res}");
+    let res = fitch_proof::check_formula("∀ P(x)", vars);
+    assert!(res.contains("followed by the variable that it binds, like ∀x\",\"start\":0"), "{res}");
+    let res = fitch_proof::check_formula("A % B", vars);
+    assert!(res.contains("the symbol % is not known"), "{res}");
+    let res = fitch_proof::check_formula("A ∧ B → C", vars);
+    assert!(res.contains("from the connective → on"), "{res}");
+    let res = fitch_proof::check_formula("∀x P(x) ∧ Q(x)", vars);
+    assert!(res.contains("the variable x is not bound by a quantifier"), "{res}");
+    assert!(res.contains("\"start\":12,\"end\":13"), "{res}");
+    let res = fitch_proof::check_formula("∀a P(a)", vars);
+    assert!(res.contains("but a is not a variable"), "{res}");
+    let res = fitch_proof::check_formula("", vars);
+    assert!(res.contains("the sentence is empty"), "{res}");
+    let res = fitch_proof::check_formula("A", "");
+    assert!(res.contains("the allowed variable names are not valid"), "{res}");
+}
+
+#[test]
+fn test_formulas_match() {
+    assert_eq!(fitch_proof::formulas_match("∀x (P(x) → Q(x))", "∀x(P(x)→Q(x))", ""), Ok(true));
+    assert_eq!(fitch_proof::formulas_match("∀y (P(y) → Q(y))", "∀x (P(x) → Q(x))", ""), Ok(false));
+    assert_eq!(
+        fitch_proof::formulas_match("∀y (P(y) → Q(y))", "∀x (P(x) → Q(x))", "match=alpha"),
+        Ok(true)
+    );
+    assert_eq!(fitch_proof::formulas_match("A → B", "¬A ∨ B", "match=alpha"), Ok(false));
+    assert_eq!(fitch_proof::formulas_match("A → B", "¬A ∨ B", "match=equivalent"), Ok(true));
+    assert!(fitch_proof::formulas_match("∀x P(x)", "∀x P(x)", "match=equivalent").is_err());
+    assert!(fitch_proof::formulas_match("A ∧", "A", "").is_err());
+    assert!(fitch_proof::formulas_match("A", "A", "match=maybe").is_err());
+}
+
+#[test]
+fn test_check_proof_with_renderer() {
+    let proof = "1 | A ∧ B\n  |----\n2 | B      ∧Elim: 1\n3 | C      ∧Elim: 1\n";
+    let res = fitch_proof::check_proof_with_renderer(proof, "x", "", "plain");
+    assert_eq!(res, fitch_proof::check_proof_with_options(proof, "x", ""));
+    let res = fitch_proof::check_proof_with_renderer(proof, "x", "", "full");
+    assert!(res.contains("--> line 4 of the proof:\n      3 | C      ∧Elim: 1"), "{res}");
+    let res = fitch_proof::check_proof_with_renderer(proof, "x", "", "json");
+    assert!(res.starts_with("{\"verdict\":\"incorrect\""), "{res}");
+    assert!(res.contains("\"line\":3"), "{res}");
+    let res = fitch_proof::check_proof_with_renderer(proof, "x", "", "sarif");
+    assert!(res.contains("\"version\":\"2.1.0\""), "{res}");
+    let res = fitch_proof::check_proof_with_renderer(proof, "x", "", "html");
+    assert!(res.starts_with("<div class=\"proof-result incorrect\">"), "{res}");
+    let res = fitch_proof::check_proof_with_renderer(proof, "x", "", "yaml");
+    assert!(res.starts_with("Fatal error: unknown renderer"), "{res}");
+    assert!(res.contains("plain, full, json, sarif, html"), "{res}");
+}
+
+#[test]
+fn test_profile_proof() {
+    let proof =
+        "1 | A ∧ B\n  |----\n2 | B      ∧Elim: 1\n3 | A      ∧Elim: 1\n4 | B ∧ A  ∧Intro: 2, 3\n";
+    let template = ["A ∧ B".to_string(), "B ∧ A".to_string()];
+    let (result, verdict, profile) = fitch_proof::profile_proof(proof, &template, "x", "");
+    assert_eq!(result, "The proof is correct!");
+    assert_eq!(verdict, "correct");
+    let phases: Vec<&str> = profile.phases.iter().map(|(phase, _)| phase.as_str()).collect();
+    assert_eq!(
+        phases,
+        ["parsing", "structure and scope", "template", "rules", "proof-level checks"]
+    );
+    let rules: Vec<(&str, usize)> =
+        profile.rules.iter().map(|(r, n, _)| (r.as_str(), *n)).collect();
+    assert_eq!(rules, [("∧Elim", 2), ("∧Intro", 1)]);
+    let lines: Vec<usize> = profile.lines.iter().map(|(n, _)| *n).collect();
+    assert_eq!(lines, [1, 2, 3, 4]);
+    // the result is the same as without profiling
+    let (result, _, _) = fitch_proof::profile_proof(proof, &[], "x", "symbols=ascii");
+    assert_eq!(result, fitch_proof::check_proof_with_options(proof, "x", "symbols=ascii"));
+    let (result, verdict, profile) = fitch_proof::profile_proof("1 | A ∧\n  |----", &[], "x", "");
+    assert!(result.starts_with("Fatal error"), "{result}");
+    assert_eq!(verdict, "fatal");
+    assert_eq!(profile.phases.len(), 1);
+
+    let res = fitch_proof::check_proof_with_profile(proof, "x", "");
+    assert!(res.starts_with("{\"result\":\"The proof is correct!\",\"profile\":{\"total_ms\":"));
+    assert!(res.contains("{\"rule\":\"∧Intro\",\"lines\":1,"), "{res}");
+}
+
+#[test]
+fn test_no_proof() {
+    let no_proof =
+        "No proof found: the proof is empty, or it only contains whitespace and directives.";
+    for proof in ["", "\n\n", "  \n\t\n", "% allow: QE\n   "] {
+        assert_eq!(fitch_proof::check_proof(proof, "x"), no_proof);
+        assert_eq!(
+            fitch_proof::check_proof_with_template(proof, vec!["A".into(), "A".into()], "x"),
+            no_proof
+        );
+        assert_eq!(fitch_proof::check_proof_skeleton(proof, "x", ""), no_proof);
+        assert_eq!(fitch_proof::check_proof_with_progress(proof, "x", &mut |_, _| {}), no_proof);
+        assert_eq!(fitch_proof::check_expected_errors(proof, "x", ""), no_proof);
+        assert_eq!(fitch_proof::proof_hint(proof, "x", "", "", 3), no_proof);
+        let res = fitch_proof::check_proof_with_renderer(proof, "x", "", "json");
+        assert!(res.starts_with("{\"verdict\":\"no_proof\""), "{res}");
+        assert!(res.contains("\"code\":\"E_NO_PROOF\""), "{res}");
+        assert_eq!(fitch_proof::check_sequent(proof, "x"), no_proof);
+        assert_eq!(fitch_proof::lint_proof(proof), no_proof);
+        assert_eq!(fitch_proof::format_proof(proof), no_proof);
+        assert_eq!(fitch_proof::fix_line_numbers_in_proof(proof), no_proof);
+        assert_eq!(fitch_proof::export_to_latex(proof), no_proof);
+        assert_eq!(fitch_proof::export_to_forallx(proof), no_proof);
+        assert_eq!(fitch_proof::export_to_tptp(proof), Err(no_proof.to_string()));
+        assert_eq!(fitch_proof::export_two_column(proof, ""), no_proof);
+        assert_eq!(fitch_proof::summarize_proof(proof), no_proof);
+        assert_eq!(fitch_proof::document_edits(proof, fitch_proof::DocumentAction::Format), vec![]);
+        assert_eq!(fitch_proof::roundtrip_check(proof), Ok(()));
+        assert_eq!(fitch_proof::proof_replay(proof), "[]");
+    }
+    // a line with only whitespace inside a proof is still a mistake
+    let res = fitch_proof::check_proof("1 | A\n   \n  |---", "x");
+    assert!(res.starts_with("Fatal error"), "{res}");
+}
+
+#[test]
+fn test_propositional_quantifiers() {
+    let options = "propositional_quantifiers=on";
+    let proof = "
+1 | ∀P (P → P)
+  | ---
+2 | (A ∧ B) → (A ∧ B)    ∀Elim: 1
+3 | ∃Q (Q → (A ∧ B))     ∃Intro: 2
+";
+    let res = fitch_proof::check_proof_with_options(proof, "x", options);
+    assert!(res.starts_with("The proof is correct"), "{res}");
+    let res = fitch_proof::check_proof(proof, "x");
+    assert!(
+        res.contains("Line 1: P is an atomic sentence, and you can only quantify over"),
+        "{res}"
+    );
+    let res = fitch_proof::check_proof_with_renderer(proof, "x", "", "json");
+    assert!(res.contains("\"code\":\"E_VARIABLES\""), "{res}");
+
+    let proof = "
+1 | ∀P (P → P)
+  | ---
+2 | A → B    ∀Elim: 1
+";
+    let res = fitch_proof::check_proof_with_options(proof, "x", options);
+    assert!(
+        res.contains("Line 2: the rule ∀Elim:1 is used, but the sentence in this line"),
+        "{res}"
+    );
+
+    let proof = "
+  | ---
+1 | | [Q]
+  | | ---
+2 | | | Q
+  | | | ---
+3 | | | Q          Reit: 2
+4 | | Q → Q        →Intro: 2-3
+5 | ∀P (P → P)     ∀Intro: 1-4
+";
+    let res = fitch_proof::check_proof_with_options(proof, "x", options);
+    assert!(res.starts_with("The proof is correct"), "{res}");
+    let res = fitch_proof::check_proof(proof, "x");
+    assert!(res.contains("Line 1: a boxed constant must be a constant"), "{res}");
+
+    let proof = "
+1 | ∃P (P ∧ A)
+  | ---
+2 | | [Q] Q ∧ A
+  | | ---
+3 | | A            ∧Elim: 2
+4 | A              ∃Elim: 1, 2-3
+5 | Q              ∃Elim: 1, 2-3
+";
+    let res = fitch_proof::check_proof_with_options(proof, "x", options);
+    assert!(!res.contains("Line 4:"), "{res}");
+    assert!(res.contains("Line 5: it is not allowed to use a boxed constant outside"), "{res}");
+
+    // the sentence that is substituted cannot be captured by a quantifier
+    let proof = "
+1 | ∀P ∀x (P → Q(x))
+  | ---
+2 | ∀x (R(x) → Q(x))    ∀Elim: 1
+";
+    let res = fitch_proof::check_proof_with_options(proof, "x", options);
+    assert!(res.contains("Line 2: the rule ∀Elim:1 is used"), "{res}");
+}
+
+#[test]
+fn test_golf_proof() {
+    let template: Vec<String> =
+        ["A ∧ B", "B ∧ A", "# minimal proof length: 4", "# minimal nesting depth: 0"]
+            .map(String::from)
+            .to_vec();
+    let proof = "
+1 | A ∧ B
+  |---
+2 | B        ∧Elim: 1
+3 | A        ∧Elim: 1
+4 | A        Reit: 3
+5 | B ∧ A    ∧Intro: 2, 4
+";
+    let (res, verdict, golf) = fitch_proof::golf_proof(proof, &template, "x", "");
+    assert!(res.starts_with("The proof is correct"), "{res}");
+    assert_eq!(verdict, "correct");
+    let golf = golf.unwrap();
+    assert_eq!((golf.lines, golf.nesting_depth, golf.score()), (5, 0, 125.0));
+    assert_eq!(
+        fitch_proof::check_proof_with_golf_score(proof, template.clone(), "x", ""),
+        "{\"result\":\"The proof is correct!\",\"golf\":{\"lines\":5,\"nesting_depth\":0,\
+        \"minimal_length\":4,\"minimal_depth\":0,\"length_ratio\":1.250,\"depth_ratio\":1.000,\
+        \"score\":125.0}}"
+    );
+
+    // no golf score without the minimal proof length, or for an incorrect proof
+    let (_, _, golf) = fitch_proof::golf_proof(proof, &template[..2], "x", "");
+    assert_eq!(golf, None);
+    let (res, verdict, golf) =
+        fitch_proof::golf_proof("1 | A ∧ B\n  |---\n2 | B ∧ A  Reit: 1", &template, "x", "");
+    assert!(!res.starts_with("The proof is correct"), "{res}");
+    assert_eq!(verdict, "incorrect");
+    assert_eq!(golf, None);
+}
+
+#[test]
+fn test_forallx_export() {
+    let proof = "
+1 | A ∧ B
+  |---
+2 | | ¬A
+  | |---
+3 | | A         ∧Elim: 1
+4 | | ⊥         ⊥Intro: 3, 2
+5 | ¬¬A         ¬Intro: 2-4
+6 | A           ¬Elim: 5
+";
+    let forallx = fitch_proof::export_to_forallx(proof);
+    assert_eq!(
+        forallx,
+        "1 | A ∧ B    PR
+  |----
+2 | | ¬A     AS
+  | |----
+3 | | A      ∧E 1
+4 | | ⊥      ¬E 3, 2
+5 | ¬¬A      ¬I 2–4
+6 | A        DNE 5"
+    );
+    assert_eq!(
+        fitch_proof::import_from_forallx(&forallx),
+        Ok(fitch_proof::format_proof(proof.trim()))
+    );
+    assert!(fitch_proof::import_from_forallx("1 | A   PR\n  |----\n2 | B   MP 1").is_err());
+}
+
+#[test]
+fn test_tptp_export() {
+    let proof = "
+1 | ∀x (P(x) → Q(x))
+2 | P(a)
+  |---
+3 | P(a) → Q(a)    ∀Elim: 1
+4 | Q(a)           →Elim: 3, 2
+";
+    assert_eq!(
+        fitch_proof::export_to_tptp(proof),
+        Ok("fof(premise_1, axiom, (! [X] : ('P'(X) => 'Q'(X)))).
+fof(premise_2, axiom, 'P'(a)).
+fof(conclusion, conjecture, 'Q'(a))."
+            .to_string())
+    );
+    assert_eq!(
+        fitch_proof::export_template_to_tptp(vec!["A ∨ B".to_string(), "B ∨ A".to_string()]),
+        Ok("fof(premise_1, axiom, ('A' | 'B')).\nfof(conclusion, conjecture, ('B' | 'A'))."
+            .to_string())
+    );
+    assert!(fitch_proof::export_to_tptp("1 | A\n  |---").is_err());
+}
+
+#[test]
+fn test_fatal_error_kinds() {
+    let kind = |template: Vec<String>, proof: &str, vars: &str, options: &str| {
+        let res = fitch_proof::check_proof_with_template_and_renderer(
+            proof, template, vars, options, "json",
+        );
+        assert!(res.starts_with("{\"verdict\":\"fatal\",\"fatal_kind\":"), "{res}");
+        res.split('"').nth(7).unwrap().to_string()
+    };
+    let proof = "1 | A\n  |----\n2 | A   Reit: 1";
+    let template = || vec!["A".to_string(), "A".to_string()];
+    assert_eq!(kind(template(), "1 | A\n\n  |----\n2 | A & ", "x", ""), "parse_error");
+    assert_eq!(kind(template(), "1 | A\n  |----\n  | |\n2 | A   Reit: 1", "x", ""), "parse_error");
+    assert_eq!(kind(template(), proof, "x", "symbols=hieroglyphs"), "config_error");
+    assert_eq!(kind(template(), proof, "X", ""), "config_error");
+    assert_eq!(kind(vec!["A &".to_string()], proof, "x", ""), "template_error");
+    assert_eq!(kind(vec![], proof, "x", ""), "template_error");
+    assert_eq!(
+        kind(template(), "1 | A\n  |----\n2 | A   Reit: 1000000000", "x", ""),
+        "limit_exceeded"
+    );
+
+    // the line that cannot be parsed is given by its number in the text of the proof
+    let res = fitch_proof::check_proof_with_renderer("1 | A\n\n  |----\n2 | A & ", "x", "", "json");
+    assert!(res.contains("\"line\":null,\"text_line\":4,\"code\":\"E_FATAL\""), "{res}");
+    let proof_with_dollar = "1 | A\n  |----\n2 | A $ B  Reit: 1";
+    let res = fitch_proof::check_proof_with_renderer(proof_with_dollar, "x", "", "sarif");
+    let region = "\"region\":{\"startLine\":3,\"startColumn\":7,\"endColumn\":8}";
+    assert!(res.contains(region), "{res}");
+    let res = fitch_proof::check_proof_with_renderer(proof, "x", "", "json");
+    assert!(res.contains("\"fatal_kind\":null"), "{res}");
+
+    // a broken template is not the student's mistake
+    let res = fitch_proof::check_proof_with_template(proof, vec![], "x");
+    assert_eq!(
+        res,
+        "Fatal error: The proof template is empty. This is a mistake in the exercise, not in your \
+        proof. If you see this as a student, please contact the course staff."
+    );
+}
+
+#[test]
+fn test_unicode_names() {
+    let proof = "1 | ∀x (Σοφός(x) → Θνητός(x))
+2 | Σοφός(σωκράτης)
+  |----
+3 | Σοφός(σωκράτης) → Θνητός(σωκράτης)   ∀ Elim: 1
+4 | Θνητός(σωκράτης)                      → Elim: 3, 2";
+    assert_eq!(fitch_proof::check_proof(proof, "x"), "The proof is correct!");
+    let greek = proof.replace("∀x", "∀α").replace("(x)", "(α)");
+    assert_eq!(fitch_proof::check_proof(&greek, "α,β"), "The proof is correct!");
+    let tall = "1 | ∀x Tall(x)\n  |----\n2 | Tall(émile)  ∀ Elim: 1";
+    assert_eq!(fitch_proof::check_proof(tall, "x"), "The proof is correct!");
+
+    let formatted = fitch_proof::import_from_forallx(&fitch_proof::export_to_forallx(&greek));
+    assert_eq!(fitch_proof::check_proof(&formatted.unwrap(), "α"), "The proof is correct!");
+    let latex = fitch_proof::export_to_latex(&greek);
+    assert!(latex.contains("{\\alpha}"), "{latex}");
+    let formatted = fitch_proof::import_from_latex(&latex);
+    assert_eq!(fitch_proof::check_proof(&formatted.unwrap(), "α"), "The proof is correct!");
+
+    let tptp = fitch_proof::export_to_tptp(&greek).unwrap();
+    let conclusion = "fof(conclusion, conjecture, '_398__3bd__3b7__3c4__3cc__3c2_'(\
+        '_3c3__3c9__3ba__3c1__3ac__3c4__3b7__3c2_')).";
+    assert!(tptp.ends_with(conclusion), "{tptp}");
+    assert!(tptp.is_ascii(), "{tptp}");
+
+    let chinese = "1 | P中(a)\n  |----\n2 | P中(a)  Reit: 1";
+    assert_eq!(fitch_proof::check_proof(chinese, "x"), "The proof is correct!");
+    let res = fitch_proof::check_proof(&chinese.replace("P中", "中"), "x");
+    assert!(res.contains("A name should start with an uppercase letter"), "{res}");
+}